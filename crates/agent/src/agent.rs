@@ -5,6 +5,7 @@ pub mod context_store;
 pub mod history_store;
 pub mod thread;
 pub mod thread_store;
+pub mod tool_permissions;
 pub mod tool_use;
 
 pub use context::{AgentContext, ContextId, ContextLoadResult};
@@ -45,8 +45,10 @@ impl AgentProfile {
                 .map(|profile| profile.enable_all_context_servers)
                 .unwrap_or_default(),
             context_servers: base_profile
-                .map(|profile| profile.context_servers)
+                .as_ref()
+                .map(|profile| profile.context_servers.clone())
                 .unwrap_or_default(),
+            terminal_network: base_profile.map_or(true, |profile| profile.terminal_network),
         };
 
         update_settings_file::<AgentSettings>(fs, cx, {
@@ -251,6 +253,7 @@ mod tests {
                     tools: IndexMap::default(),
                     enable_all_context_servers: false,
                     context_servers: IndexMap::from_iter([("mcp".into(), context_server_preset())]),
+                    terminal_network: true,
                 },
             );
             AgentSettings::override_global(agent_settings, cx);
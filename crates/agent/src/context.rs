@@ -2,9 +2,12 @@ use crate::thread::Thread;
 use assistant_context::AssistantContext;
 use assistant_tool::outline;
 use collections::HashSet;
+use context_server::ContextServerId;
+use context_server::types::{ResourceContentsType, ResourcesReadParams, requests::ResourcesRead};
 use futures::future;
 use futures::{FutureExt, future::Shared};
 use gpui::{App, AppContext as _, ElementId, Entity, SharedString, Task};
+use http_client::Url;
 use icons::IconName;
 use language::{Buffer, ParseStatus};
 use language_model::{LanguageModelImage, LanguageModelRequestMessage, MessageContent};
@@ -32,6 +35,7 @@ pub enum ContextKind {
     TextThread,
     Rules,
     Image,
+    McpResource,
 }
 
 impl ContextKind {
@@ -46,6 +50,7 @@ impl ContextKind {
             ContextKind::TextThread => IconName::MessageBubbles,
             ContextKind::Rules => RULES_ICON,
             ContextKind::Image => IconName::Image,
+            ContextKind::McpResource => IconName::Server,
         }
     }
 }
@@ -66,6 +71,7 @@ pub enum AgentContextHandle {
     TextThread(TextThreadContextHandle),
     Rules(RulesContextHandle),
     Image(ImageContext),
+    McpResource(McpResourceContextHandle),
 }
 
 impl AgentContextHandle {
@@ -80,6 +86,7 @@ impl AgentContextHandle {
             Self::TextThread(context) => context.context_id,
             Self::Rules(context) => context.context_id,
             Self::Image(context) => context.context_id,
+            Self::McpResource(context) => context.context_id,
         }
     }
 
@@ -101,6 +108,7 @@ pub enum AgentContext {
     TextThread(TextThreadContext),
     Rules(RulesContext),
     Image(ImageContext),
+    McpResource(McpResourceContext),
 }
 
 impl AgentContext {
@@ -121,6 +129,9 @@ impl AgentContext {
             }
             AgentContext::Rules(context) => AgentContextHandle::Rules(context.handle.clone()),
             AgentContext::Image(context) => AgentContextHandle::Image(context.clone()),
+            AgentContext::McpResource(context) => {
+                AgentContextHandle::McpResource(context.handle.clone())
+            }
         }
     }
 }
@@ -779,6 +790,107 @@ impl ImageContext {
     }
 }
 
+/// Context provided by a resource of a context server.
+///
+/// The resource is read from the server each time the context is loaded, so that its contents are
+/// up to date.
+#[derive(Debug, Clone)]
+pub struct McpResourceContextHandle {
+    pub server_id: ContextServerId,
+    pub uri: SharedString,
+    pub name: SharedString,
+    pub context_id: ContextId,
+}
+
+#[derive(Debug, Clone)]
+pub struct McpResourceContext {
+    pub handle: McpResourceContextHandle,
+    pub text: SharedString,
+}
+
+impl McpResourceContextHandle {
+    pub fn eq_for_key(&self, other: &Self) -> bool {
+        self.server_id == other.server_id && self.uri == other.uri
+    }
+
+    pub fn hash_for_key<H: Hasher>(&self, state: &mut H) {
+        self.server_id.hash(state);
+        self.uri.hash(state);
+    }
+
+    pub fn lookup_key(server_id: ContextServerId, uri: SharedString) -> AgentContextKey {
+        AgentContextKey(AgentContextHandle::McpResource(McpResourceContextHandle {
+            server_id,
+            uri,
+            name: "".into(),
+            context_id: ContextId::for_lookup(),
+        }))
+    }
+
+    pub(crate) fn load(
+        self,
+        project: &Entity<Project>,
+        cx: &App,
+    ) -> Task<Option<(AgentContext, Vec<Entity<Buffer>>)>> {
+        let Some(server) = project
+            .read(cx)
+            .context_server_store()
+            .read(cx)
+            .get_running_server(&self.server_id)
+        else {
+            log::error!(
+                "context server {} is not running, so {} can't be read",
+                self.server_id,
+                self.uri
+            );
+            return Task::ready(None);
+        };
+        cx.spawn(async move |_| {
+            let client = server.client()?;
+            let uri = Url::parse(&self.uri).log_err()?;
+            // TODO: report load errors instead of just logging
+            let response = client
+                .request::<ResourcesRead>(ResourcesReadParams { uri, meta: None })
+                .await
+                .log_err()?;
+            let context = AgentContext::McpResource(McpResourceContext {
+                text: resource_contents_text(response.contents).into(),
+                handle: self,
+            });
+            Some((context, vec![]))
+        })
+    }
+}
+
+/// The text of a resource's contents, in which binary contents are only described by their MIME
+/// type.
+fn resource_contents_text(contents: Vec<ResourceContentsType>) -> String {
+    let mut text = String::new();
+    for contents in contents {
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        match contents {
+            ResourceContentsType::Text(contents) => text.push_str(&contents.text),
+            ResourceContentsType::Blob(contents) => {
+                let mime_type = contents.mime_type.as_deref().unwrap_or("unknown type");
+                let _ = write!(text, "[binary contents of {} ({mime_type})]", contents.uri);
+            }
+        }
+    }
+    text
+}
+
+impl Display for McpResourceContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let code_block = MarkdownCodeBlock {
+            tag: &self.handle.uri,
+            text: &self.text,
+        };
+        write!(f, "{code_block}")
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ContextLoadResult {
     pub loaded_context: LoadedContext,
@@ -840,6 +952,7 @@ pub fn load_context(
             AgentContextHandle::TextThread(context) => context.load(cx),
             AgentContextHandle::Rules(context) => context.load(prompt_store, cx),
             AgentContextHandle::Image(context) => context.load(cx),
+            AgentContextHandle::McpResource(context) => context.load(project, cx),
         })
         .collect();
 
@@ -865,6 +978,7 @@ pub fn load_context(
         let mut thread_context = Vec::new();
         let mut text_thread_context = Vec::new();
         let mut rules_context = Vec::new();
+        let mut mcp_resource_context = Vec::new();
         let mut images = Vec::new();
         for context in &contexts {
             match context {
@@ -877,6 +991,7 @@ pub fn load_context(
                 AgentContext::TextThread(context) => text_thread_context.push(context),
                 AgentContext::Rules(context) => rules_context.push(context),
                 AgentContext::Image(context) => images.extend(context.image()),
+                AgentContext::McpResource(context) => mcp_resource_context.push(context),
             }
         }
 
@@ -890,6 +1005,7 @@ pub fn load_context(
             && thread_context.is_empty()
             && text_thread_context.is_empty()
             && rules_context.is_empty()
+            && mcp_resource_context.is_empty()
         {
             return ContextLoadResult {
                 loaded_context: LoadedContext {
@@ -970,6 +1086,15 @@ pub fn load_context(
             text.push_str("<text_threads>");
         }
 
+        if !mcp_resource_context.is_empty() {
+            text.push_str("<resources>");
+            for context in mcp_resource_context {
+                text.push('\n');
+                let _ = write!(text, "{context}");
+            }
+            text.push_str("</resources>\n");
+        }
+
         if !rules_context.is_empty() {
             text.push_str(
                 "<user_rules>\n\
@@ -1091,6 +1216,11 @@ impl PartialEq for AgentContextKey {
                     return context.eq_for_key(other_context);
                 }
             }
+            AgentContextHandle::McpResource(context) => {
+                if let AgentContextHandle::McpResource(other_context) = &other.0 {
+                    return context.eq_for_key(other_context);
+                }
+            }
         }
         false
     }
@@ -1108,6 +1238,7 @@ impl Hash for AgentContextKey {
             AgentContextHandle::TextThread(context) => context.hash_for_key(state),
             AgentContextHandle::Rules(context) => context.hash_for_key(state),
             AgentContextHandle::Image(context) => context.hash_for_key(state),
+            AgentContextHandle::McpResource(context) => context.hash_for_key(state),
         }
     }
 }
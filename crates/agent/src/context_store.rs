@@ -1,8 +1,9 @@
 use crate::{
     context::{
         AgentContextHandle, AgentContextKey, ContextId, ContextKind, DirectoryContextHandle,
-        FetchedUrlContext, FileContextHandle, ImageContext, RulesContextHandle,
-        SelectionContextHandle, SymbolContextHandle, TextThreadContextHandle, ThreadContextHandle,
+        FetchedUrlContext, FileContextHandle, ImageContext, McpResourceContextHandle,
+        RulesContextHandle, SelectionContextHandle, SymbolContextHandle, TextThreadContextHandle,
+        ThreadContextHandle,
     },
     thread::{MessageId, Thread, ThreadId},
     thread_store::ThreadStore,
@@ -10,6 +11,7 @@ use crate::{
 use anyhow::{Context as _, Result, anyhow};
 use assistant_context::AssistantContext;
 use collections::{HashSet, IndexSet};
+use context_server::ContextServerId;
 use futures::{self, FutureExt};
 use gpui::{App, Context, Entity, EventEmitter, Image, SharedString, Task, WeakEntity};
 use language::{Buffer, File as _};
@@ -292,6 +294,44 @@ impl ContextStore {
         context
     }
 
+    pub fn add_mcp_resource(
+        &mut self,
+        server_id: ContextServerId,
+        uri: impl Into<SharedString>,
+        name: impl Into<SharedString>,
+        remove_if_exists: bool,
+        cx: &mut Context<ContextStore>,
+    ) -> Option<AgentContextHandle> {
+        let uri = uri.into();
+        let context = AgentContextHandle::McpResource(McpResourceContextHandle {
+            server_id: server_id.clone(),
+            uri: uri.clone(),
+            name: name.into(),
+            context_id: self.next_context_id.post_inc(),
+        });
+
+        if let Some(key) = self.context_set.get(AgentContextKey::ref_cast(&context)) {
+            if remove_if_exists {
+                self.remove_context(&context, cx);
+                None
+            } else {
+                Some(key.as_ref().clone())
+            }
+        } else {
+            // Subscribe to the resource so that threads can reload it when it changes.
+            if let Some(project) = self.project.upgrade() {
+                project
+                    .read(cx)
+                    .context_server_store()
+                    .read(cx)
+                    .subscribe_to_resource(&server_id, &uri, cx)
+                    .detach_and_log_err(cx);
+            }
+            self.insert_context(context.clone(), cx);
+            Some(context)
+        }
+    }
+
     pub fn add_image_from_path(
         &mut self,
         project_path: ProjectPath,
@@ -531,6 +571,18 @@ impl ContextStore {
             .contains(&FetchedUrlContext::lookup_key(url.into()))
     }
 
+    pub fn includes_mcp_resource(
+        &self,
+        server_id: &ContextServerId,
+        uri: impl Into<SharedString>,
+    ) -> bool {
+        self.context_set
+            .contains(&McpResourceContextHandle::lookup_key(
+                server_id.clone(),
+                uri.into(),
+            ))
+    }
+
     pub fn get_url_context(&self, url: SharedString) -> Option<AgentContextHandle> {
         self.context_set
             .get(&FetchedUrlContext::lookup_key(url))
@@ -551,7 +603,8 @@ impl ContextStore {
                 | AgentContextHandle::Thread(_)
                 | AgentContextHandle::TextThread(_)
                 | AgentContextHandle::Rules(_)
-                | AgentContextHandle::Image(_) => None,
+                | AgentContextHandle::Image(_)
+                | AgentContextHandle::McpResource(_) => None,
             })
             .collect()
     }
@@ -1,25 +1,35 @@
 use crate::{
     agent_profile::AgentProfile,
-    context::{AgentContext, AgentContextHandle, ContextLoadResult, LoadedContext},
+    context::{
+        AgentContext, AgentContextHandle, ContextId, ContextLoadResult, LoadedContext,
+        McpResourceContext, McpResourceContextHandle,
+    },
     thread_store::{
-        SerializedCrease, SerializedLanguageModel, SerializedMessage, SerializedMessageSegment,
-        SerializedThread, SerializedToolResult, SerializedToolUse, SharedProjectContext,
-        ThreadStore,
+        SerializedCrease, SerializedLanguageModel, SerializedMcpResourceContext, SerializedMessage,
+        SerializedMessageSegment, SerializedThread, SerializedToolResult, SerializedToolUse,
+        SharedProjectContext, ThreadStore,
+    },
+    tool_permissions::{
+        SessionToolPermissions, ToolPermission, ToolPermissionDecider, ToolPermissionLogEntry,
+        check_tool_permission, session_rule,
     },
-    tool_use::{PendingToolUse, ToolUse, ToolUseMetadata, ToolUseState},
+    tool_use::{Confirmation, PendingToolUse, ToolUse, ToolUseMetadata, ToolUseState},
+};
+use agent_settings::{
+    AgentProfileId, AgentSettings, CompletionMode, ToolPermissionAction, ToolPermissionRule,
 };
-use agent_settings::{AgentProfileId, AgentSettings, CompletionMode};
 use anyhow::{Result, anyhow};
 use assistant_tool::{ActionLog, AnyToolCard, Tool, ToolWorkingSet};
 use chrono::{DateTime, Utc};
 use client::{ModelRequestUsage, RequestUsage};
 use collections::HashMap;
+use context_server::ContextServerId;
 use feature_flags::{self, FeatureFlagAppExt};
 use futures::{FutureExt, StreamExt as _, future::Shared};
 use git::repository::DiffType;
 use gpui::{
-    AnyWindowHandle, App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Task,
-    WeakEntity, Window,
+    AnyWindowHandle, App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString,
+    Subscription, Task, WeakEntity, Window,
 };
 use http_client::StatusCode;
 use language_model::{
@@ -33,6 +43,7 @@ use language_model::{
 use postage::stream::Stream as _;
 use project::{
     Project,
+    context_server_store::{self, ContextServerStore},
     git_store::{GitStore, GitStoreCheckpoint, RepositoryState},
 };
 use prompt_store::{ModelContext, PromptBuilder};
@@ -377,6 +388,8 @@ pub struct Thread {
     prompt_builder: Arc<PromptBuilder>,
     tools: Entity<ToolWorkingSet>,
     tool_use: ToolUseState,
+    tool_permission_log: Vec<ToolPermissionLogEntry>,
+    session_tool_permissions: SessionToolPermissions,
     action_log: Entity<ActionLog>,
     last_restore_checkpoint: Option<LastRestoreCheckpoint>,
     pending_checkpoint: Option<ThreadCheckpoint>,
@@ -397,6 +410,7 @@ pub struct Thread {
     configured_model: Option<ConfiguredModel>,
     profile: AgentProfile,
     last_error_context: Option<(Arc<dyn LanguageModel>, CompletionIntent)>,
+    _context_server_subscription: Subscription,
 }
 
 #[derive(Clone, Debug)]
@@ -452,6 +466,9 @@ impl Thread {
         let (detailed_summary_tx, detailed_summary_rx) = postage::watch::channel();
         let configured_model = LanguageModelRegistry::read_global(cx).default_model();
         let profile_id = AgentSettings::get_global(cx).default_profile.clone();
+        let context_server_store = project.read(cx).context_server_store();
+        let context_server_subscription =
+            cx.subscribe(&context_server_store, Self::handle_context_server_event);
 
         Self {
             id: ThreadId::new(),
@@ -475,6 +492,8 @@ impl Thread {
             last_restore_checkpoint: None,
             pending_checkpoint: None,
             tool_use: ToolUseState::new(tools.clone()),
+            tool_permission_log: Vec::new(),
+            session_tool_permissions: SessionToolPermissions::default(),
             action_log: cx.new(|_| ActionLog::new(project.clone())),
             initial_project_snapshot: {
                 let project_snapshot = Self::project_snapshot(project, cx);
@@ -496,6 +515,7 @@ impl Thread {
             remaining_turns: u32::MAX,
             configured_model: configured_model.clone(),
             profile: AgentProfile::new(profile_id, tools),
+            _context_server_subscription: context_server_subscription,
         }
     }
 
@@ -545,6 +565,10 @@ impl Thread {
         let profile_id = serialized
             .profile
             .unwrap_or_else(|| AgentSettings::get_global(cx).default_profile.clone());
+        let mut next_context_id = ContextId::zero();
+        let context_server_store = project.read(cx).context_server_store();
+        let context_server_subscription =
+            cx.subscribe(&context_server_store, Self::handle_context_server_event);
 
         Self {
             id,
@@ -576,7 +600,21 @@ impl Thread {
                         })
                         .collect(),
                     loaded_context: LoadedContext {
-                        contexts: Vec::new(),
+                        contexts: message
+                            .mcp_resources
+                            .into_iter()
+                            .map(|resource| {
+                                AgentContext::McpResource(McpResourceContext {
+                                    handle: McpResourceContextHandle {
+                                        server_id: ContextServerId(resource.server_id.into()),
+                                        uri: resource.uri.into(),
+                                        name: resource.name.into(),
+                                        context_id: next_context_id.post_inc(),
+                                    },
+                                    text: resource.text.into(),
+                                })
+                            })
+                            .collect(),
                         text: message.context,
                         images: Vec::new(),
                     },
@@ -606,6 +644,8 @@ impl Thread {
             prompt_builder,
             tools: tools.clone(),
             tool_use,
+            tool_permission_log: serialized.tool_permission_log,
+            session_tool_permissions: SessionToolPermissions::default(),
             action_log: cx.new(|_| ActionLog::new(project)),
             initial_project_snapshot: Task::ready(serialized.initial_project_snapshot).shared(),
             request_token_usage: serialized.request_token_usage,
@@ -621,9 +661,84 @@ impl Thread {
             remaining_turns: u32::MAX,
             configured_model,
             profile: AgentProfile::new(profile_id, tools),
+            _context_server_subscription: context_server_subscription,
         }
     }
 
+    fn handle_context_server_event(
+        &mut self,
+        _: Entity<ContextServerStore>,
+        event: &context_server_store::Event,
+        cx: &mut Context<Self>,
+    ) {
+        if let context_server_store::Event::ResourceUpdated { server_id, uri } = event {
+            self.reload_mcp_resource(server_id, uri, cx);
+        }
+    }
+
+    /// Reads a resource attached to the thread again after its server reported that it changed,
+    /// and replaces its contents in the context of every message it's attached to.
+    fn reload_mcp_resource(
+        &mut self,
+        server_id: &ContextServerId,
+        uri: &SharedString,
+        cx: &mut Context<Self>,
+    ) {
+        let handle = self
+            .messages
+            .iter()
+            .flat_map(|message| &message.loaded_context.contexts)
+            .find_map(|context| match context {
+                AgentContext::McpResource(context)
+                    if &context.handle.server_id == server_id && &context.handle.uri == uri =>
+                {
+                    Some(context.handle.clone())
+                }
+                _ => None,
+            });
+        let Some(handle) = handle else {
+            return;
+        };
+        let load = handle.load(&self.project, cx);
+        cx.spawn(async move |this, cx| {
+            let Some((AgentContext::McpResource(reloaded), _)) = load.await else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                let mut edited_message_ids = Vec::new();
+                for message in &mut this.messages {
+                    let mut edited = false;
+                    for context in &mut message.loaded_context.contexts {
+                        let AgentContext::McpResource(context) = context else {
+                            continue;
+                        };
+                        if !context.handle.eq_for_key(&reloaded.handle)
+                            || context.text == reloaded.text
+                        {
+                            continue;
+                        }
+                        let old_text = context.to_string();
+                        context.text = reloaded.text.clone();
+                        message.loaded_context.text = message
+                            .loaded_context
+                            .text
+                            .replace(&old_text, &context.to_string());
+                        edited = true;
+                    }
+                    if edited {
+                        edited_message_ids.push(message.id);
+                    }
+                }
+                for message_id in edited_message_ids {
+                    cx.emit(ThreadEvent::MessageEdited(message_id));
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
     pub fn set_request_callback(
         &mut self,
         callback: impl 'static
@@ -1212,6 +1327,22 @@ impl Thread {
                             })
                             .collect(),
                         context: message.loaded_context.text.clone(),
+                        mcp_resources: message
+                            .loaded_context
+                            .contexts
+                            .iter()
+                            .filter_map(|context| match context {
+                                AgentContext::McpResource(context) => {
+                                    Some(SerializedMcpResourceContext {
+                                        server_id: context.handle.server_id.0.to_string(),
+                                        uri: context.handle.uri.to_string(),
+                                        name: context.handle.name.to_string(),
+                                        text: context.text.to_string(),
+                                    })
+                                }
+                                _ => None,
+                            })
+                            .collect(),
                         creases: message
                             .creases
                             .iter()
@@ -1239,6 +1370,7 @@ impl Thread {
                     }),
                 completion_mode: Some(this.completion_mode),
                 tool_use_limit_reached: this.tool_use_limit_reached,
+                tool_permission_log: this.tool_permission_log.clone(),
                 profile: Some(this.profile.id().clone()),
             })
         })
@@ -1341,6 +1473,7 @@ impl Thread {
     ) -> LanguageModelRequest {
         let mut request = LanguageModelRequest {
             thread_id: Some(self.id.to_string()),
+            profile_id: Some(self.profile.id().to_string()),
             prompt_id: Some(self.last_prompt_id.to_string()),
             intent: Some(intent),
             mode: None,
@@ -1349,6 +1482,7 @@ impl Thread {
             tool_choice: None,
             stop: Vec::new(),
             temperature: AgentSettings::temperature_for_model(&model, cx),
+            max_tokens: None,
             thinking_allowed: true,
         };
 
@@ -1507,6 +1641,7 @@ impl Thread {
     ) -> LanguageModelRequest {
         let mut request = LanguageModelRequest {
             thread_id: None,
+            profile_id: None,
             prompt_id: None,
             intent: Some(intent),
             mode: None,
@@ -1515,6 +1650,7 @@ impl Thread {
             tool_choice: None,
             stop: Vec::new(),
             temperature: AgentSettings::temperature_for_model(model, cx),
+            max_tokens: None,
             thinking_allowed: false,
         };
 
@@ -2544,9 +2680,63 @@ impl Thread {
             return self.handle_hallucinated_tool_use(tool_use.id, tool_use.name, window, cx);
         }
 
-        if tool.needs_confirmation(&tool_use.input, cx)
-            && !AgentSettings::get_global(cx).always_allow_tool_actions
-        {
+        let needs_confirmation = match check_tool_permission(
+            &tool.name(),
+            &tool_use.input,
+            &self.session_tool_permissions,
+            cx,
+        ) {
+            ToolPermission::Rule(rule) => match rule.action {
+                ToolPermissionAction::Deny => {
+                    self.log_tool_permission(
+                        tool_use.id.clone(),
+                        tool_use.name.clone(),
+                        false,
+                        ToolPermissionDecider::Rule {
+                            rule: rule.to_string(),
+                        },
+                    );
+                    let error = anyhow!("Permission to run tool action denied by rule ({rule})");
+                    return self.finish_denied_tool_use(
+                        tool_use.id,
+                        tool_use.name,
+                        error,
+                        false,
+                        window,
+                        cx,
+                    );
+                }
+                ToolPermissionAction::Allow => {
+                    self.log_tool_permission(
+                        tool_use.id.clone(),
+                        tool_use.name.clone(),
+                        true,
+                        ToolPermissionDecider::Rule {
+                            rule: rule.to_string(),
+                        },
+                    );
+                    false
+                }
+                ToolPermissionAction::Ask => true,
+            },
+            ToolPermission::SessionRule(rule) => {
+                self.log_tool_permission(
+                    tool_use.id.clone(),
+                    tool_use.name.clone(),
+                    true,
+                    ToolPermissionDecider::SessionRule {
+                        rule: rule.to_string(),
+                    },
+                );
+                false
+            }
+            ToolPermission::Default => {
+                tool.needs_confirmation(&tool_use.input, cx)
+                    && !AgentSettings::get_global(cx).always_allow_tool_actions
+            }
+        };
+
+        if needs_confirmation {
             self.tool_use.confirm_tool_use(
                 tool_use.id,
                 tool_use.ui_text,
@@ -3262,18 +3452,112 @@ impl Thread {
         window: Option<AnyWindowHandle>,
         cx: &mut Context<Self>,
     ) {
-        let err = Err(anyhow::anyhow!(
-            "Permission to run tool action denied by user"
-        ));
+        self.log_tool_permission(
+            tool_use_id.clone(),
+            tool_name.clone(),
+            false,
+            ToolPermissionDecider::User,
+        );
+        self.finish_denied_tool_use(
+            tool_use_id,
+            tool_name,
+            anyhow!("Permission to run tool action denied by user"),
+            true,
+            window,
+            cx,
+        );
+    }
 
-        self.tool_use.insert_tool_output(
+    fn finish_denied_tool_use(
+        &mut self,
+        tool_use_id: LanguageModelToolUseId,
+        tool_name: Arc<str>,
+        error: anyhow::Error,
+        canceled: bool,
+        window: Option<AnyWindowHandle>,
+        cx: &mut Context<Self>,
+    ) {
+        let pending_tool_use = self.tool_use.insert_tool_output(
             tool_use_id.clone(),
             tool_name,
-            err,
+            Err(error),
             self.configured_model.as_ref(),
             self.completion_mode,
         );
-        self.tool_finished(tool_use_id.clone(), None, true, window, cx);
+        // When the user denies a tool use, the turn ends, but one denied by a rule is reported to
+        // the model like any other failure.
+        let pending_tool_use = if canceled { None } else { pending_tool_use };
+        self.tool_finished(tool_use_id, pending_tool_use, canceled, window, cx);
+    }
+
+    /// Runs a tool use the user allowed when asked for confirmation, allowing uses like it for the
+    /// rest of the session if `for_session` is set.
+    pub fn allow_tool_use(
+        &mut self,
+        confirmation: &Confirmation,
+        for_session: bool,
+        model: Arc<dyn LanguageModel>,
+        window: Option<AnyWindowHandle>,
+        cx: &mut Context<Self>,
+    ) {
+        let tool_name: Arc<str> = confirmation.tool.name().into();
+        let rule_for_session = for_session
+            .then(|| self.session_rule(&tool_name, &confirmation.input, cx))
+            .flatten();
+        let decided_by = if let Some(rule) = rule_for_session {
+            let description = rule.to_string();
+            self.session_tool_permissions.allow(tool_name.clone(), rule);
+            ToolPermissionDecider::UserForSession { rule: description }
+        } else {
+            ToolPermissionDecider::User
+        };
+        self.log_tool_permission(
+            confirmation.tool_use_id.clone(),
+            tool_name,
+            true,
+            decided_by,
+        );
+        self.run_tool(
+            confirmation.tool_use_id.clone(),
+            confirmation.ui_text.clone(),
+            confirmation.input.clone(),
+            confirmation.request.clone(),
+            confirmation.tool.clone(),
+            model,
+            window,
+            cx,
+        );
+    }
+
+    /// The rule that allowing a use of a tool for the rest of the session would add, if uses like
+    /// it can be allowed for the session.
+    pub fn session_rule(
+        &self,
+        tool_name: &str,
+        input: &serde_json::Value,
+        cx: &App,
+    ) -> Option<ToolPermissionRule> {
+        session_rule(tool_name, input, &self.session_tool_permissions, cx)
+    }
+
+    pub fn tool_permission_log(&self) -> &[ToolPermissionLogEntry] {
+        &self.tool_permission_log
+    }
+
+    fn log_tool_permission(
+        &mut self,
+        tool_use_id: LanguageModelToolUseId,
+        tool_name: Arc<str>,
+        allowed: bool,
+        decided_by: ToolPermissionDecider,
+    ) {
+        self.tool_permission_log.push(ToolPermissionLogEntry {
+            tool_use_id,
+            tool_name,
+            allowed,
+            decided_by,
+            timestamp: Utc::now(),
+        });
     }
 }
 
@@ -3352,7 +3636,10 @@ struct PendingCompletion {
 mod tests {
     use super::*;
     use crate::{
-        context::load_context, context_store::ContextStore, thread_store, thread_store::ThreadStore,
+        context::{AgentContextKey, load_context},
+        context_store::ContextStore,
+        thread_store,
+        thread_store::ThreadStore,
     };
 
     // Test-specific constants
@@ -3886,6 +4173,78 @@ fn main() {{
         );
     }
 
+    #[gpui::test]
+    async fn test_serializing_mcp_resource_context(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({})).await;
+        let (_workspace, _thread_store, thread, _context_store, _model) =
+            setup_test_environment(cx, project.clone()).await;
+
+        let loaded_context = ContextLoadResult {
+            loaded_context: LoadedContext {
+                contexts: vec![AgentContext::McpResource(McpResourceContext {
+                    handle: McpResourceContextHandle {
+                        server_id: ContextServerId("docs".into()),
+                        uri: "docs://guide".into(),
+                        name: "Guide".into(),
+                        context_id: ContextId::zero(),
+                    },
+                    text: "Read the guide.".into(),
+                })],
+                text: "\n<context>\n</context>\n".into(),
+                images: Vec::new(),
+            },
+            referenced_buffers: Default::default(),
+        };
+        let message_id = thread.update(cx, |thread, cx| {
+            thread.insert_user_message("Summarize this", loaded_context, None, Vec::new(), cx)
+        });
+
+        let serialized = thread
+            .update(cx, |thread, cx| thread.serialize(cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            serialized.messages[0].mcp_resources,
+            [SerializedMcpResourceContext {
+                server_id: "docs".into(),
+                uri: "docs://guide".into(),
+                name: "Guide".into(),
+                text: "Read the guide.".into(),
+            }]
+        );
+
+        // The resource is shown with the message again, and can be read again from its server.
+        let deserialized = cx.update(|cx| {
+            thread.update(cx, |thread, cx| {
+                Thread::deserialize(
+                    thread.id.clone(),
+                    serialized,
+                    thread.project.clone(),
+                    thread.tools.clone(),
+                    thread.prompt_builder.clone(),
+                    thread.project_context.clone(),
+                    None,
+                    cx,
+                )
+            })
+        });
+        let message = deserialized.message(message_id).unwrap();
+        let [AgentContext::McpResource(context)] = message.loaded_context.contexts.as_slice()
+        else {
+            panic!("expected an MCP resource context");
+        };
+        assert_eq!(context.text.as_ref(), "Read the guide.");
+        assert_eq!(
+            AgentContextKey(context.handle()),
+            McpResourceContextHandle::lookup_key(
+                ContextServerId("docs".into()),
+                "docs://guide".into()
+            )
+        );
+    }
+
     #[gpui::test]
     async fn test_temperature_setting(cx: &mut TestAppContext) {
         init_test_settings(cx);
@@ -3,6 +3,7 @@ use crate::{
     thread::{
         DetailedSummaryState, ExceededWindowError, MessageId, ProjectSnapshot, Thread, ThreadId,
     },
+    tool_permissions::ToolPermissionLogEntry,
 };
 use agent_settings::{AgentProfileId, CompletionMode};
 use anyhow::{Context as _, Result, anyhow};
@@ -544,6 +545,7 @@ impl ThreadStore {
                     }
                 }
             }
+            _ => {}
         }
     }
 
@@ -627,6 +629,8 @@ pub struct SerializedThread {
     pub tool_use_limit_reached: bool,
     #[serde(default)]
     pub profile: Option<AgentProfileId>,
+    #[serde(default)]
+    pub tool_permission_log: Vec<ToolPermissionLogEntry>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -711,11 +715,23 @@ pub struct SerializedMessage {
     #[serde(default)]
     pub context: String,
     #[serde(default)]
+    pub mcp_resources: Vec<SerializedMcpResourceContext>,
+    #[serde(default)]
     pub creases: Vec<SerializedCrease>,
     #[serde(default)]
     pub is_hidden: bool,
 }
 
+/// A context server resource attached to a message, kept so that the message shows it and it can
+/// be read again from its server.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct SerializedMcpResourceContext {
+    pub server_id: String,
+    pub uri: String,
+    pub name: String,
+    pub text: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type")]
 pub enum SerializedMessageSegment {
@@ -774,6 +790,7 @@ impl LegacySerializedThread {
             completion_mode: None,
             tool_use_limit_reached: false,
             profile: None,
+            tool_permission_log: Vec::new(),
         }
     }
 }
@@ -798,6 +815,7 @@ impl LegacySerializedMessage {
             tool_uses: self.tool_uses,
             tool_results: self.tool_results,
             context: String::new(),
+            mcp_resources: Vec::new(),
             creases: Vec::new(),
             is_hidden: false,
         }
@@ -1102,6 +1120,7 @@ mod tests {
                     tool_uses: vec![],
                     tool_results: vec![],
                     context: "".to_string(),
+                    mcp_resources: vec![],
                     creases: vec![],
                     is_hidden: false
                 }],
@@ -1114,7 +1133,8 @@ mod tests {
                 model: None,
                 completion_mode: None,
                 tool_use_limit_reached: false,
-                profile: None
+                profile: None,
+                tool_permission_log: Vec::new(),
             }
         )
     }
@@ -1135,6 +1155,7 @@ mod tests {
                     tool_uses: vec![],
                     tool_results: vec![],
                     context: "".to_string(),
+                    mcp_resources: vec![],
                     creases: vec![],
                     is_hidden: false,
                 },
@@ -1151,6 +1172,7 @@ mod tests {
                     }],
                     tool_results: vec![],
                     context: "".to_string(),
+                    mcp_resources: vec![],
                     creases: vec![],
                     is_hidden: false,
                 },
@@ -1168,6 +1190,7 @@ mod tests {
                         output: Some(serde_json::Value::Null),
                     }],
                     context: "".to_string(),
+                    mcp_resources: vec![],
                     creases: vec![],
                     is_hidden: false,
                 },
@@ -1182,6 +1205,7 @@ mod tests {
             completion_mode: None,
             tool_use_limit_reached: false,
             profile: None,
+            tool_permission_log: Vec::new(),
         });
         let upgraded = thread_v0_1_0.upgrade();
 
@@ -1200,6 +1224,7 @@ mod tests {
                         tool_uses: vec![],
                         tool_results: vec![],
                         context: "".to_string(),
+                        mcp_resources: vec![],
                         creases: vec![],
                         is_hidden: false
                     },
@@ -1221,6 +1246,7 @@ mod tests {
                             output: Some(serde_json::Value::Null),
                         }],
                         context: "".to_string(),
+                        mcp_resources: vec![],
                         creases: vec![],
                         is_hidden: false,
                     },
@@ -1234,7 +1260,8 @@ mod tests {
                 model: None,
                 completion_mode: None,
                 tool_use_limit_reached: false,
-                profile: None
+                profile: None,
+                tool_permission_log: Vec::new(),
             }
         )
     }
@@ -1,4 +1,5 @@
 mod agent_profile;
+mod tool_permissions;
 
 use std::sync::Arc;
 
@@ -12,6 +13,7 @@ use settings::{Settings, SettingsSources};
 use std::borrow::Cow;
 
 pub use crate::agent_profile::*;
+pub use crate::tool_permissions::*;
 
 pub fn init(cx: &mut App) {
     AgentSettings::register(cx);
@@ -60,6 +62,8 @@ pub struct AgentSettings {
     pub default_view: DefaultView,
     pub profiles: IndexMap<AgentProfileId, AgentProfileSettings>,
     pub always_allow_tool_actions: bool,
+    pub tool_permissions: IndexMap<Arc<str>, Vec<ToolPermissionRule>>,
+    pub sandbox_terminal: bool,
     pub notify_when_agent_waiting: NotifyWhenAgentWaiting,
     pub play_sound_when_agent_done: bool,
     pub stream_edits: bool,
@@ -199,6 +203,7 @@ impl AgentSettingsContent {
                 name: profile_settings.name.into(),
                 tools: profile_settings.tools,
                 enable_all_context_servers: Some(profile_settings.enable_all_context_servers),
+                terminal_network: Some(profile_settings.terminal_network),
                 context_servers: profile_settings
                     .context_servers
                     .into_iter()
@@ -265,6 +270,16 @@ pub struct AgentSettingsContent {
     ///
     /// Default: false
     always_allow_tool_actions: Option<bool>,
+    /// Rules deciding whether uses of a tool, by name, are allowed, denied, or confirmed first,
+    /// depending on the tool's input. They take precedence over `always_allow_tool_actions`.
+    ///
+    /// Default: {}
+    tool_permissions: Option<IndexMap<Arc<str>, Vec<ToolPermissionRule>>>,
+    /// Whether to run the agent's terminal commands in a sandbox, in which they can only write to
+    /// the project's worktrees and a temporary directory. Requires Linux and bubblewrap.
+    ///
+    /// Default: false
+    sandbox_terminal: Option<bool>,
     /// Where to show a popup notification when the agent is waiting for user input.
     ///
     /// Default: "primary_screen"
@@ -385,6 +400,10 @@ pub struct AgentProfileContent {
     pub enable_all_context_servers: Option<bool>,
     #[serde(default)]
     pub context_servers: IndexMap<Arc<str>, ContextServerPresetContent>,
+    /// Whether terminal commands can access the network when `sandbox_terminal` is enabled.
+    ///
+    /// Default: true
+    pub terminal_network: Option<bool>,
 }
 
 #[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -443,6 +462,14 @@ impl Settings for AgentSettings {
                 &mut settings.always_allow_tool_actions,
                 value.always_allow_tool_actions,
             );
+            if let Some(tool_permissions) = value.tool_permissions.as_ref() {
+                settings.tool_permissions.extend(
+                    tool_permissions
+                        .iter()
+                        .map(|(tool_name, rules)| (tool_name.clone(), rules.clone())),
+                );
+            }
+            merge(&mut settings.sandbox_terminal, value.sandbox_terminal);
             merge(
                 &mut settings.notify_when_agent_waiting,
                 value.notify_when_agent_waiting,
@@ -498,6 +525,7 @@ impl Settings for AgentSettings {
                                         )
                                     })
                                     .collect(),
+                                terminal_network: profile.terminal_network.unwrap_or(true),
                             },
                         )
                     }));
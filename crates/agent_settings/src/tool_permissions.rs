@@ -0,0 +1,114 @@
+use std::fmt;
+
+use collections::IndexMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolPermissionAction {
+    /// Run the tool without asking for confirmation.
+    Allow,
+    /// Refuse to run the tool, without asking.
+    Deny,
+    /// Ask for confirmation, even when tool actions are always allowed.
+    Ask,
+}
+
+/// A rule deciding whether uses of a tool are allowed, denied, or confirmed first.
+///
+/// The rules of a tool are evaluated in order, and the first one matching the tool's input
+/// decides.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ToolPermissionRule {
+    pub action: ToolPermissionAction,
+    /// Patterns that fields of the tool's input must all match, in which `*` matches any
+    /// sequence of characters and `?` matches any single character.
+    ///
+    /// A rule without patterns matches every use of the tool.
+    #[serde(default)]
+    pub input: IndexMap<String, String>,
+}
+
+impl ToolPermissionRule {
+    pub fn allow(input: IndexMap<String, String>) -> Self {
+        Self {
+            action: ToolPermissionAction::Allow,
+            input,
+        }
+    }
+}
+
+impl fmt::Display for ToolPermissionRule {
+    /// Describes the inputs the rule matches, like `command: cargo *`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.input.is_empty() {
+            return write!(f, "any input");
+        }
+        for (ix, (field, pattern)) in self.input.iter().enumerate() {
+            if ix > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{field}: {pattern}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether `text` matches `pattern`, in which `*` matches any sequence of characters and `?`
+/// matches any single character.
+pub fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut pattern_ix, mut text_ix) = (0, 0);
+    // Where to resume after the last `*`, should the characters it was assumed to match so far
+    // not lead to a match.
+    let mut backtrack = None;
+    while text_ix < text.len() {
+        match pattern.get(pattern_ix) {
+            Some('*') => {
+                pattern_ix += 1;
+                backtrack = Some((pattern_ix, text_ix));
+            }
+            Some(char) if *char == '?' || *char == text[text_ix] => {
+                pattern_ix += 1;
+                text_ix += 1;
+            }
+            _ => {
+                let Some((star_pattern_ix, star_text_ix)) = backtrack else {
+                    return false;
+                };
+                pattern_ix = star_pattern_ix;
+                text_ix = star_text_ix + 1;
+                backtrack = Some((star_pattern_ix, text_ix));
+            }
+        }
+    }
+    pattern[pattern_ix..].iter().all(|char| *char == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("cargo *", "cargo test --workspace"));
+        assert!(matches_pattern("cargo *", "cargo "));
+        assert!(!matches_pattern("cargo *", "cargo"));
+        assert!(!matches_pattern("cargo *", "rm -rf target && cargo build"));
+        assert!(matches_pattern("*/src/*", "project/src/lib.rs"));
+        assert!(!matches_pattern("*/src/*", "project/tests/src.rs"));
+        assert!(matches_pattern("https://docs.rs/*", "https://docs.rs/gpui"));
+        assert!(!matches_pattern(
+            "https://docs.rs/*",
+            "https://docs.rs.example.com/"
+        ));
+        assert!(matches_pattern("file?.txt", "file1.txt"));
+        assert!(!matches_pattern("file?.txt", "file.txt"));
+        assert!(matches_pattern("*a*b", "xaybab"));
+        assert!(matches_pattern("*", ""));
+        assert!(matches_pattern("", ""));
+        assert!(!matches_pattern("", "a"));
+    }
+}
@@ -1429,6 +1429,8 @@ impl AcpThreadView {
                                 show_summary: true,
                                 show_command: true,
                                 show_rerun: false,
+                                test_results: None,
+                                problem_matchers: Vec::new(),
                             };
                             workspace
                                 .spawn_in_terminal(spawn_in_terminal, window, cx)
@@ -749,10 +749,19 @@ fn open_markdown_link(
             }),
             cx,
         ),
+        Some(MentionLink::McpResource(_, uri)) => open_mcp_resource(&uri, cx),
         None => cx.open_url(&text),
     }
 }
 
+/// Opens resources that are web pages. Resources of other schemes can only be read through their
+/// server, so there is nothing to open.
+fn open_mcp_resource(uri: &str, cx: &mut App) {
+    if uri.starts_with("https://") || uri.starts_with("http://") {
+        cx.open_url(uri);
+    }
+}
+
 struct EditingMessageState {
     editor: Entity<Editor>,
     context_strip: Entity<ContextStrip>,
@@ -1498,6 +1507,7 @@ impl ActiveThread {
 
                     let request = language_model::LanguageModelRequest {
                         thread_id: None,
+                        profile_id: None,
                         prompt_id: None,
                         intent: None,
                         mode: None,
@@ -1509,6 +1519,7 @@ impl ActiveThread {
                             &configured_model.model,
                             cx,
                         ),
+                        max_tokens: None,
                         thinking_allowed: true,
                     };
 
@@ -2864,6 +2875,13 @@ impl ActiveThread {
         });
 
         let rendered_tool_use = self.rendered_tool_uses.get(&tool_use.id).cloned();
+        let permission = self
+            .thread
+            .read(cx)
+            .tool_permission_log()
+            .iter()
+            .rfind(|entry| entry.tool_use_id == tool_use.id)
+            .map(|entry| entry.description());
         let results_content_container = || v_flex().p_2().gap_0p5();
 
         let results_content = v_flex()
@@ -2899,6 +2917,20 @@ impl ActiveThread {
                             })),
                     ),
             )
+            .when_some(permission, |container, permission| {
+                container.child(
+                    results_content_container()
+                        .border_t_1()
+                        .border_color(self.tool_card_border_color(cx))
+                        .child(
+                            Label::new("Permission")
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted)
+                                .buffer_font(cx),
+                        )
+                        .child(Label::new(permission).size(LabelSize::Small)),
+                )
+            })
             .map(|container| match tool_use.status {
                 ToolUseStatus::Finished(_) => container.child(
                     results_content_container()
@@ -3243,6 +3275,7 @@ impl ActiveThread {
                                                     }
                                                     this.handle_allow_tool(
                                                         tool_id.clone(),
+                                                        false,
                                                         event,
                                                         window,
                                                         cx,
@@ -3250,6 +3283,45 @@ impl ActiveThread {
                                                 },
                                             ))
                                         })
+                                        .when_some(
+                                            self.thread
+                                                .read(cx)
+                                                .session_rule(&tool_use.name, &tool_use.input, cx),
+                                            |buttons, rule| {
+                                                let tool_id = tool_use.id.clone();
+                                                buttons.child(
+                                                    Button::new(
+                                                        "allow-tool-action-for-session",
+                                                        "Allow for Session",
+                                                    )
+                                                    .label_size(LabelSize::Small)
+                                                    .icon(IconName::Check)
+                                                    .icon_position(IconPosition::Start)
+                                                    .icon_size(IconSize::Small)
+                                                    .icon_color(Color::Success)
+                                                    .tooltip(move |window, cx| {
+                                                        Tooltip::with_meta(
+                                                            "Don't ask again during this session",
+                                                            None,
+                                                            format!("For uses of this tool with {rule}"),
+                                                            window,
+                                                            cx,
+                                                        )
+                                                    })
+                                                    .on_click(cx.listener(
+                                                        move |this, event, window, cx| {
+                                                            this.handle_allow_tool(
+                                                                tool_id.clone(),
+                                                                true,
+                                                                event,
+                                                                window,
+                                                                cx,
+                                                            )
+                                                        },
+                                                    )),
+                                                )
+                                            },
+                                        )
                                         .child({
                                             let tool_id = tool_use.id.clone();
                                             Button::new("allow-tool-action", "Allow")
@@ -3262,6 +3334,7 @@ impl ActiveThread {
                                                     move |this, event, window, cx| {
                                                         this.handle_allow_tool(
                                                             tool_id.clone(),
+                                                            false,
                                                             event,
                                                             window,
                                                             cx,
@@ -3417,6 +3490,7 @@ impl ActiveThread {
     fn handle_allow_tool(
         &mut self,
         tool_use_id: LanguageModelToolUseId,
+        for_session: bool,
         _: &ClickEvent,
         window: &mut Window,
         cx: &mut Context<Self>,
@@ -3429,12 +3503,9 @@ impl ActiveThread {
         {
             self.thread.update(cx, |thread, cx| {
                 if let Some(configured) = thread.get_or_init_configured_model(cx) {
-                    thread.run_tool(
-                        c.tool_use_id.clone(),
-                        c.ui_text.clone(),
-                        c.input.clone(),
-                        c.request.clone(),
-                        c.tool.clone(),
+                    thread.allow_tool_use(
+                        &c,
+                        for_session,
                         configured.model,
                         Some(window.window_handle()),
                         cx,
@@ -3747,6 +3818,10 @@ pub(crate) fn open_context(
             cx,
         ),
 
+        AgentContextHandle::McpResource(mcp_resource_context) => {
+            open_mcp_resource(&mcp_resource_context.uri, cx)
+        }
+
         AgentContextHandle::Image(_) => {}
     }
 }
@@ -323,6 +323,11 @@ impl ConfigureContextServerModal {
                         None => None,
                     }
                 }
+                ContextServerSettings::Http { .. } => {
+                    return Err(anyhow::anyhow!(
+                        "Context servers reached by URL can only be configured in the settings file"
+                    ));
+                }
             };
 
             match target {
@@ -737,6 +742,7 @@ fn wait_for_context_server(
                 _ => {}
             }
         }
+        _ => {}
     });
 
     cx.spawn(async move |_cx| {
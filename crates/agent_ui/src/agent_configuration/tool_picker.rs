@@ -293,6 +293,7 @@ impl PickerDelegate for ToolPickerDelegate {
                                 )
                             })
                             .collect(),
+                        terminal_network: Some(default_profile.terminal_network),
                     });
 
                 if let Some(server_id) = server_id {
@@ -20,6 +20,7 @@ use crate::{
     active_thread::{self, ActiveThread, ActiveThreadEvent},
     agent_configuration::{AgentConfiguration, AssistantConfigurationEvent},
     agent_diff::AgentDiff,
+    context_server_requests,
     message_editor::{MessageEditor, MessageEditorEvent},
     slash_command::SlashCommandCompletionProvider,
     text_thread_editor::{
@@ -55,7 +56,9 @@ use language::LanguageRegistry;
 use language_model::{
     ConfigurationError, ConfiguredModel, LanguageModelProviderTosView, LanguageModelRegistry,
 };
-use project::{Project, ProjectPath, Worktree};
+use project::{
+    Project, ProjectPath, Worktree, context_server_store::Event as ContextServerStoreEvent,
+};
 use prompt_store::{PromptBuilder, PromptStore, UserPromptId};
 use proto::Plan;
 use rules_library::{RulesLibrary, open_rules_library};
@@ -430,6 +433,7 @@ pub struct AgentPanel {
     language_registry: Arc<LanguageRegistry>,
     thread_store: Entity<ThreadStore>,
     _default_model_subscription: Subscription,
+    _context_server_requests_subscription: Subscription,
     context_store: Entity<TextThreadStore>,
     prompt_store: Option<Entity<PromptStore>>,
     inline_assist_context_store: Entity<ContextStore>,
@@ -687,6 +691,29 @@ impl AgentPanel {
             },
         );
 
+        let _context_server_requests_subscription = cx.subscribe_in(
+            &project.read(cx).context_server_store(),
+            window,
+            |this, _, event: &ContextServerStoreEvent, window, cx| match event {
+                ContextServerStoreEvent::SamplingRequested(request) => {
+                    context_server_requests::handle_sampling_request(request.clone(), window, cx);
+                }
+                ContextServerStoreEvent::ElicitationRequested(request) => {
+                    this.workspace
+                        .update(cx, |workspace, cx| {
+                            context_server_requests::handle_elicitation_request(
+                                request.clone(),
+                                workspace,
+                                window,
+                                cx,
+                            );
+                        })
+                        .log_err();
+                }
+                _ => {}
+            },
+        );
+
         let onboarding = cx.new(|cx| {
             AgentPanelOnboarding::new(
                 user_store.clone(),
@@ -707,6 +734,7 @@ impl AgentPanel {
             language_registry,
             thread_store: thread_store.clone(),
             _default_model_subscription,
+            _context_server_requests_subscription,
             context_store,
             prompt_store,
             configuration: None,
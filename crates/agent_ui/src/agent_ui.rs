@@ -8,6 +8,7 @@ mod buffer_codegen;
 mod burn_mode_tooltip;
 mod context_picker;
 mod context_server_configuration;
+mod context_server_requests;
 mod context_strip;
 mod debug;
 mod inline_assistant;
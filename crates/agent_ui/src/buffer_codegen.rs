@@ -467,6 +467,7 @@ impl CodegenAlternative {
 
             LanguageModelRequest {
                 thread_id: None,
+                profile_id: None,
                 prompt_id: None,
                 intent: Some(CompletionIntent::InlineAssist),
                 mode: None,
@@ -475,6 +476,7 @@ impl CodegenAlternative {
                 stop: Vec::new(),
                 temperature,
                 messages: vec![request_message],
+                max_tokens: None,
                 thinking_allowed: false,
             }
         }))
@@ -23,12 +23,13 @@ use workspace::Workspace;
 
 use agent::{
     Thread,
-    context::{AgentContextHandle, AgentContextKey, RULES_ICON},
+    context::{AgentContextHandle, AgentContextKey, ContextKind, RULES_ICON},
     thread_store::{TextThreadStore, ThreadStore},
 };
 
 use super::fetch_context_picker::fetch_url_content;
 use super::file_context_picker::{FileMatch, search_files};
+use super::mcp_resource_context_picker::{McpResourceEntry, search_mcp_resources};
 use super::rules_context_picker::{RulesContextEntry, search_rules};
 use super::symbol_context_picker::SymbolMatch;
 use super::symbol_context_picker::search_symbols;
@@ -45,6 +46,7 @@ pub(crate) enum Match {
     Thread(ThreadMatch),
     Fetch(SharedString),
     Rules(RulesContextEntry),
+    McpResource(McpResourceEntry),
     Entry(EntryMatch),
 }
 
@@ -62,6 +64,7 @@ impl Match {
             Match::Symbol(_) => 1.,
             Match::Fetch(_) => 1.,
             Match::Rules(_) => 1.,
+            Match::McpResource(_) => 1.,
         }
     }
 }
@@ -151,6 +154,21 @@ fn search(
             }
         }
 
+        Some(ContextPickerMode::McpResource) => {
+            let server_store = workspace.read(cx).project().read(cx).context_server_store();
+            let search_resources_task =
+                search_mcp_resources(query.clone(), cancellation_flag.clone(), &server_store, cx);
+            cx.background_spawn(async move {
+                search_resources_task
+                    .await
+                    .into_iter()
+                    // Templates need their variables filled in, which only the picker can do.
+                    .filter(|resource| !resource.is_template)
+                    .map(Match::McpResource)
+                    .collect()
+            })
+        }
+
         None => {
             if query.is_empty() {
                 let mut matches = recent_entries
@@ -523,6 +541,48 @@ impl ContextPickerCompletionProvider {
         }
     }
 
+    fn completion_for_mcp_resource(
+        resource: McpResourceEntry,
+        excerpt_id: ExcerptId,
+        source_range: Range<Anchor>,
+        editor: Entity<Editor>,
+        context_store: Entity<ContextStore>,
+    ) -> Completion {
+        let new_text = format!("{} ", MentionLink::for_mcp_resource(&resource));
+        let new_text_len = new_text.len();
+        let icon_path: SharedString = ContextKind::McpResource.icon().path().into();
+        Completion {
+            replace_range: source_range.clone(),
+            new_text,
+            label: CodeLabel::plain(resource.name.to_string(), None),
+            documentation: None,
+            insert_text_mode: None,
+            source: project::CompletionSource::Custom,
+            icon_path: Some(icon_path.clone()),
+            confirm: Some(confirm_completion_callback(
+                icon_path,
+                resource.name.clone(),
+                excerpt_id,
+                source_range.start,
+                new_text_len - 1,
+                editor.clone(),
+                context_store.clone(),
+                move |_, cx| {
+                    let context = context_store.update(cx, |context_store, cx| {
+                        context_store.add_mcp_resource(
+                            resource.server_id.clone(),
+                            resource.uri.clone(),
+                            resource.name.clone(),
+                            false,
+                            cx,
+                        )
+                    });
+                    Task::ready(context)
+                },
+            )),
+        }
+    }
+
     fn completion_for_fetch(
         source_range: Range<Anchor>,
         url_to_fetch: SharedString,
@@ -884,6 +944,14 @@ impl CompletionProvider for ContextPickerCompletionProvider {
                             context_store.clone(),
                         )),
 
+                        Match::McpResource(resource) => Some(Self::completion_for_mcp_resource(
+                            resource,
+                            excerpt_id,
+                            source_range.clone(),
+                            editor.clone(),
+                            context_store.clone(),
+                        )),
+
                         Match::Fetch(url) => Some(Self::completion_for_fetch(
                             source_range.clone(),
                             url,
@@ -0,0 +1,537 @@
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use collections::HashMap;
+use context_server::ContextServerId;
+use context_server::protocol::ServerCapability;
+use context_server::types::{
+    CompletionArgument, CompletionCompleteParams, CompletionReference, PromptReferenceType,
+    ResourceReference, requests,
+};
+use context_server::uri_template::UriTemplate;
+use fuzzy::StringMatchCandidate;
+use gpui::{App, DismissEvent, Entity, FocusHandle, Focusable, Subscription, Task, WeakEntity};
+use picker::{Picker, PickerDelegate};
+use project::context_server_store::{ContextServerStore, Event as ContextServerStoreEvent};
+use ui::{ListItem, prelude::*};
+use util::ResultExt as _;
+
+use crate::context_picker::ContextPicker;
+use agent::context::ContextKind;
+use agent::context_store::{self, ContextStore};
+
+pub struct McpResourceContextPicker {
+    picker: Entity<Picker<McpResourceContextPickerDelegate>>,
+    server_store: Entity<ContextServerStore>,
+    _list_resources_task: Task<()>,
+    _subscription: Subscription,
+}
+
+impl McpResourceContextPicker {
+    pub fn new(
+        server_store: Entity<ContextServerStore>,
+        context_picker: WeakEntity<ContextPicker>,
+        context_store: WeakEntity<context_store::ContextStore>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = McpResourceContextPickerDelegate::new(
+            server_store.clone(),
+            context_picker,
+            context_store,
+        );
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        let subscription =
+            cx.subscribe_in(
+                &server_store,
+                window,
+                |this, _, event, window, cx| match event {
+                    ContextServerStoreEvent::ServerStatusChanged { .. }
+                    | ContextServerStoreEvent::ResourcesChanged { .. } => {
+                        this.list_resources(window, cx)
+                    }
+                    _ => {}
+                },
+            );
+
+        let mut this = McpResourceContextPicker {
+            picker,
+            server_store,
+            _list_resources_task: Task::ready(()),
+            _subscription: subscription,
+        };
+        this.list_resources(window, cx);
+        this
+    }
+
+    fn list_resources(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let list_task = list_mcp_resources(&self.server_store, cx);
+        self._list_resources_task = cx.spawn_in(window, async move |this, cx| {
+            let entries = list_task.await;
+            this.update_in(cx, |this, window, cx| {
+                this.picker.update(cx, |picker, cx| {
+                    picker.delegate.entries = entries;
+                    picker.refresh(window, cx);
+                })
+            })
+            .ok();
+        });
+    }
+}
+
+impl Focusable for McpResourceContextPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for McpResourceContextPicker {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        self.picker.clone()
+    }
+}
+
+/// A resource, or a resource template, listed by a running context server.
+#[derive(Debug, Clone)]
+pub struct McpResourceEntry {
+    pub server_id: ContextServerId,
+    pub name: SharedString,
+    /// The URI of the resource, or the URI template of the resource template.
+    pub uri: SharedString,
+    pub is_template: bool,
+}
+
+/// A resource template whose variables are given values one at a time, before it is expanded
+/// into the URI of the resource to attach.
+struct TemplateArguments {
+    entry: McpResourceEntry,
+    template: UriTemplate,
+    variables: Vec<String>,
+    values: HashMap<String, String>,
+}
+
+impl TemplateArguments {
+    fn new(entry: McpResourceEntry, template: UriTemplate) -> Self {
+        let mut variables = Vec::<String>::new();
+        for variable in template.variables() {
+            if !variables.iter().any(|existing| existing == variable) {
+                variables.push(variable.to_string());
+            }
+        }
+        Self {
+            entry,
+            template,
+            variables,
+            values: HashMap::default(),
+        }
+    }
+
+    fn current_variable(&self) -> Option<&str> {
+        self.variables
+            .get(self.values.len())
+            .map(|variable| variable.as_str())
+    }
+}
+
+pub struct McpResourceContextPickerDelegate {
+    server_store: Entity<ContextServerStore>,
+    context_picker: WeakEntity<ContextPicker>,
+    context_store: WeakEntity<context_store::ContextStore>,
+    entries: Vec<McpResourceEntry>,
+    matches: Vec<McpResourceEntry>,
+    template_arguments: Option<TemplateArguments>,
+    values: Vec<SharedString>,
+    selected_index: usize,
+}
+
+impl McpResourceContextPickerDelegate {
+    pub fn new(
+        server_store: Entity<ContextServerStore>,
+        context_picker: WeakEntity<ContextPicker>,
+        context_store: WeakEntity<context_store::ContextStore>,
+    ) -> Self {
+        McpResourceContextPickerDelegate {
+            server_store,
+            context_picker,
+            context_store,
+            entries: Vec::new(),
+            matches: Vec::new(),
+            template_arguments: None,
+            values: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    fn add_resource(
+        &self,
+        server_id: ContextServerId,
+        uri: SharedString,
+        name: SharedString,
+        cx: &mut App,
+    ) {
+        self.context_store
+            .update(cx, |context_store, cx| {
+                context_store.add_mcp_resource(server_id, uri, name, true, cx)
+            })
+            .log_err();
+    }
+
+    /// Clears the query for the next variable of a template, or for the listing once the
+    /// template has been expanded.
+    fn reset_query(&self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.defer_in(window, |picker, window, cx| {
+            picker.set_query("", window, cx);
+            picker.refresh_placeholder(window, cx);
+            picker.refresh(window, cx);
+        });
+    }
+}
+
+impl PickerDelegate for McpResourceContextPickerDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        if self.template_arguments.is_some() {
+            self.values.len()
+        } else {
+            self.matches.len()
+        }
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        match self
+            .template_arguments
+            .as_ref()
+            .and_then(|arguments| Some((arguments.current_variable()?, &arguments.entry.name)))
+        {
+            Some((variable, name)) => format!("Enter {variable} for {name}…").into(),
+            None => "Search MCP resources…".into(),
+        }
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        if let Some(arguments) = self.template_arguments.as_ref() {
+            let Some(variable) = arguments.current_variable() else {
+                return Task::ready(());
+            };
+            let completion_task = complete_template_argument(
+                &self.server_store,
+                &arguments.entry,
+                variable.to_string(),
+                query.clone(),
+                cx,
+            );
+            return cx.spawn_in(window, async move |this, cx| {
+                let suggestions = completion_task.await;
+                this.update(cx, |this, cx| {
+                    // What was typed comes first, so that values the server can't suggest can
+                    // still be given.
+                    let typed = Some(query.clone()).filter(|query| !query.is_empty());
+                    this.delegate.values = typed
+                        .into_iter()
+                        .chain(suggestions.into_iter().filter(|value| *value != query))
+                        .map(SharedString::from)
+                        .collect();
+                    this.delegate.selected_index = 0;
+                    cx.notify();
+                })
+                .ok();
+            });
+        }
+
+        let search_task = filter_mcp_resources(
+            query,
+            self.entries.clone(),
+            Arc::new(AtomicBool::default()),
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = search_task.await;
+            this.update(cx, |this, cx| {
+                this.delegate.matches = matches;
+                this.delegate.selected_index = 0;
+                cx.notify();
+            })
+            .ok();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(arguments) = self.template_arguments.as_mut() {
+            let Some(value) = self.values.get(self.selected_index) else {
+                return;
+            };
+            let Some(variable) = arguments.current_variable().map(str::to_string) else {
+                return;
+            };
+            arguments.values.insert(variable, value.to_string());
+
+            if arguments.current_variable().is_none() {
+                let uri: SharedString = arguments.template.expand(&arguments.values).into();
+                let server_id = arguments.entry.server_id.clone();
+                self.template_arguments = None;
+                self.add_resource(server_id, uri.clone(), uri, cx);
+            }
+            self.reset_query(window, cx);
+            return;
+        }
+
+        let Some(entry) = self.matches.get(self.selected_index).cloned() else {
+            return;
+        };
+        if !entry.is_template {
+            self.add_resource(entry.server_id, entry.uri, entry.name, cx);
+            return;
+        }
+
+        let Some(template) = UriTemplate::parse(&entry.uri).log_err() else {
+            return;
+        };
+        let arguments = TemplateArguments::new(entry, template);
+        if arguments.current_variable().is_some() {
+            self.template_arguments = Some(arguments);
+            self.reset_query(window, cx);
+        } else {
+            let uri: SharedString = arguments.template.expand(&arguments.values).into();
+            self.add_resource(arguments.entry.server_id, uri, arguments.entry.name, cx);
+        }
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.context_picker
+            .update(cx, |_, cx| {
+                cx.emit(DismissEvent);
+            })
+            .ok();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let item = ListItem::new(ix).inset(true).toggle_state(selected);
+        if self.template_arguments.is_some() {
+            let value = self.values.get(ix)?;
+            Some(item.child(Label::new(value.clone()).truncate()))
+        } else {
+            let entry = self.matches.get(ix)?;
+            Some(item.child(render_mcp_resource_context_entry(
+                entry,
+                self.context_store.clone(),
+                cx,
+            )))
+        }
+    }
+}
+
+pub fn render_mcp_resource_context_entry(
+    entry: &McpResourceEntry,
+    context_store: WeakEntity<ContextStore>,
+    cx: &mut App,
+) -> Div {
+    let added = !entry.is_template
+        && context_store.upgrade().map_or(false, |context_store| {
+            context_store
+                .read(cx)
+                .includes_mcp_resource(&entry.server_id, entry.uri.clone())
+        });
+
+    h_flex()
+        .gap_1p5()
+        .w_full()
+        .justify_between()
+        .child(
+            h_flex()
+                .gap_1p5()
+                .max_w_72()
+                .child(
+                    Icon::new(ContextKind::McpResource.icon())
+                        .size(IconSize::XSmall)
+                        .color(Color::Muted),
+                )
+                .child(Label::new(entry.name.clone()).truncate())
+                .child(
+                    Label::new(entry.uri.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .truncate(),
+                ),
+        )
+        .when(added, |el| {
+            el.child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Icon::new(IconName::Check)
+                            .size(IconSize::Small)
+                            .color(Color::Success),
+                    )
+                    .child(Label::new("Added").size(LabelSize::Small)),
+            )
+        })
+}
+
+/// Lists the resources and resource templates of every running server that has resources.
+pub(crate) fn list_mcp_resources(
+    server_store: &Entity<ContextServerStore>,
+    cx: &App,
+) -> Task<Vec<McpResourceEntry>> {
+    let servers = server_store.read(cx).running_servers();
+    cx.spawn(async move |_| {
+        let mut entries = Vec::new();
+        for server in servers {
+            let Some(protocol) = server.client() else {
+                continue;
+            };
+            if !protocol.capable(ServerCapability::Resources) {
+                continue;
+            }
+
+            let server_id = server.id();
+            if let Some(response) = protocol
+                .request::<requests::ResourcesList>(())
+                .await
+                .log_err()
+            {
+                entries.extend(
+                    response
+                        .resources
+                        .into_iter()
+                        .map(|resource| McpResourceEntry {
+                            server_id: server_id.clone(),
+                            name: resource.name.into(),
+                            uri: resource.uri.to_string().into(),
+                            is_template: false,
+                        }),
+                );
+            }
+            // Servers implementing earlier versions of the protocol don't list templates.
+            if let Ok(response) = protocol
+                .request::<requests::ListResourceTemplates>(())
+                .await
+            {
+                entries.extend(response.resource_templates.into_iter().map(|template| {
+                    McpResourceEntry {
+                        server_id: server_id.clone(),
+                        name: template.name.into(),
+                        uri: template.uri_template.into(),
+                        is_template: true,
+                    }
+                }));
+            }
+        }
+        entries
+    })
+}
+
+pub(crate) fn search_mcp_resources(
+    query: String,
+    cancellation_flag: Arc<AtomicBool>,
+    server_store: &Entity<ContextServerStore>,
+    cx: &mut App,
+) -> Task<Vec<McpResourceEntry>> {
+    let list_task = list_mcp_resources(server_store, cx);
+    cx.spawn(async move |cx| {
+        let entries = list_task.await;
+        let Ok(filter_task) =
+            cx.update(|cx| filter_mcp_resources(query, entries, cancellation_flag, cx))
+        else {
+            return Vec::new();
+        };
+        filter_task.await
+    })
+}
+
+fn filter_mcp_resources(
+    query: String,
+    entries: Vec<McpResourceEntry>,
+    cancellation_flag: Arc<AtomicBool>,
+    cx: &App,
+) -> Task<Vec<McpResourceEntry>> {
+    let executor = cx.background_executor().clone();
+    cx.background_spawn(async move {
+        if query.is_empty() {
+            return entries;
+        }
+
+        let candidates = entries
+            .iter()
+            .enumerate()
+            .map(|(id, entry)| StringMatchCandidate::new(id, &entry.name))
+            .collect::<Vec<_>>();
+        let matches = fuzzy::match_strings(
+            &candidates,
+            &query,
+            false,
+            true,
+            100,
+            &cancellation_flag,
+            executor,
+        )
+        .await;
+
+        matches
+            .into_iter()
+            .map(|mat| entries[mat.candidate_id].clone())
+            .collect()
+    })
+}
+
+/// Asks the server of a resource template to suggest values for one of its variables, if it
+/// supports completions.
+fn complete_template_argument(
+    server_store: &Entity<ContextServerStore>,
+    entry: &McpResourceEntry,
+    name: String,
+    value: String,
+    cx: &App,
+) -> Task<Vec<String>> {
+    let Some(protocol) = server_store
+        .read(cx)
+        .get_running_server(&entry.server_id)
+        .and_then(|server| server.client())
+    else {
+        return Task::ready(Vec::new());
+    };
+    if protocol.initialize.capabilities.completions.is_none() {
+        return Task::ready(Vec::new());
+    }
+
+    let params = CompletionCompleteParams {
+        reference: CompletionReference::Resource(ResourceReference {
+            ty: PromptReferenceType::Resource,
+            uri: entry.uri.to_string(),
+        }),
+        argument: CompletionArgument { name, value },
+        meta: None,
+    };
+    cx.spawn(async move |_| {
+        protocol
+            .request::<requests::CompletionComplete>(params)
+            .await
+            .log_err()
+            .map(|response| response.completion.values)
+            .unwrap_or_default()
+    })
+}
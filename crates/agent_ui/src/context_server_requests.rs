@@ -0,0 +1,594 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use collections::IndexMap;
+use context_server::types::{
+    self, CreateElicitationResult, CreateMessageRequest, CreateMessageResult, ElicitationAction,
+    ElicitationProperty, ElicitationPropertyKind,
+};
+use editor::Editor;
+use futures::StreamExt as _;
+use gpui::{
+    AsyncApp, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, PromptLevel, prelude::*,
+};
+use language_model::{
+    LanguageModel, LanguageModelRegistry, LanguageModelRequest, LanguageModelRequestMessage, Role,
+};
+use project::context_server_store::{
+    ContextServerElicitationRequest, ContextServerSamplingRequest,
+};
+use serde_json::Value;
+use ui::{Checkbox, KeyBinding, Modal, ModalFooter, ModalHeader, Section, prelude::*};
+use workspace::{DismissDecision, ModalView, Workspace};
+
+/// Asks the user to confirm a context server's request for a completion, which is then made
+/// with their default language model and only sent back to the server once the user approves it.
+pub(crate) fn handle_sampling_request(
+    request: ContextServerSamplingRequest,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let Some(configured_model) = LanguageModelRegistry::read_global(cx).default_model() else {
+        request.respond(Err(anyhow!("no language model is configured")));
+        return;
+    };
+    let model = configured_model.model;
+
+    let message = format!(
+        "{} wants to use {} to generate a response",
+        request.server_id,
+        model.name().0
+    );
+    let detail = request
+        .params
+        .messages
+        .iter()
+        .filter_map(|message| match &message.content {
+            types::MessageContent::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let answer = window.prompt(
+        PromptLevel::Info,
+        &message,
+        Some(&detail),
+        &["Allow", "Deny"],
+        cx,
+    );
+    let window = window.window_handle();
+    cx.spawn(async move |cx| {
+        if answer.await != Ok(0) {
+            request.respond(Err(anyhow!("the user denied the sampling request")));
+            return;
+        }
+        let text = match sample(&request.params, model.clone(), cx).await {
+            Ok(text) => text,
+            Err(error) => {
+                request.respond(Err(error));
+                return;
+            }
+        };
+
+        let message = format!("Send this response to {}?", request.server_id);
+        let answer = window.update(cx, |_, window, cx| {
+            window.prompt(
+                PromptLevel::Info,
+                &message,
+                Some(&text),
+                &["Send", "Discard"],
+                cx,
+            )
+        });
+        let Ok(answer) = answer else {
+            request.respond(Err(anyhow!(
+                "the window of the sampling request was closed"
+            )));
+            return;
+        };
+        if answer.await != Ok(0) {
+            request.respond(Err(anyhow!("the user discarded the sampled response")));
+            return;
+        }
+        request.respond(Ok(CreateMessageResult {
+            role: types::Role::Assistant,
+            content: types::MessageContent::Text {
+                text,
+                annotations: None,
+            },
+            model: model.id().0.to_string(),
+            stop_reason: Some("endTurn".to_string()),
+        }));
+    })
+    .detach();
+}
+
+async fn sample(
+    params: &CreateMessageRequest,
+    model: Arc<dyn LanguageModel>,
+    cx: &AsyncApp,
+) -> Result<String> {
+    let request = language_model_request(params)?;
+    let mut stream = model.stream_completion_text(request, cx).await?.stream;
+    let mut text = String::new();
+    while let Some(chunk) = stream.next().await {
+        text.push_str(&chunk?);
+    }
+    Ok(text)
+}
+
+fn language_model_request(params: &CreateMessageRequest) -> Result<LanguageModelRequest> {
+    let mut messages = Vec::new();
+    if let Some(system_prompt) = &params.system_prompt {
+        messages.push(LanguageModelRequestMessage {
+            role: Role::System,
+            content: vec![system_prompt.clone().into()],
+            cache: false,
+        });
+    }
+    for message in &params.messages {
+        let types::MessageContent::Text { text, .. } = &message.content else {
+            bail!("only text messages can be sampled");
+        };
+        messages.push(LanguageModelRequestMessage {
+            role: match message.role {
+                types::Role::User => Role::User,
+                types::Role::Assistant => Role::Assistant,
+            },
+            content: vec![text.clone().into()],
+            cache: false,
+        });
+    }
+
+    Ok(LanguageModelRequest {
+        thread_id: None,
+        profile_id: None,
+        prompt_id: None,
+        intent: None,
+        mode: None,
+        messages,
+        tools: Vec::new(),
+        tool_choice: None,
+        stop: params.stop_sequences.clone().unwrap_or_default(),
+        temperature: params.temperature.map(|temperature| temperature as f32),
+        max_tokens: Some(params.max_tokens.into()),
+        thinking_allowed: false,
+    })
+}
+
+/// Shows a context server's request for information in a modal, only one of which is shown at a
+/// time.
+pub(crate) fn handle_elicitation_request(
+    request: ContextServerElicitationRequest,
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    if workspace.active_modal::<ElicitationModal>(cx).is_some() {
+        request.respond(CreateElicitationResult {
+            action: ElicitationAction::Cancel,
+            content: None,
+        });
+        return;
+    }
+    workspace.toggle_modal(window, cx, |window, cx| {
+        ElicitationModal::new(request, window, cx)
+    });
+}
+
+enum FieldInput {
+    Text(Entity<Editor>),
+    Boolean(bool),
+    Choice(Option<usize>),
+}
+
+struct ElicitationField {
+    name: String,
+    property: ElicitationProperty,
+    required: bool,
+    input: FieldInput,
+}
+
+pub(crate) struct ElicitationModal {
+    request: ContextServerElicitationRequest,
+    fields: Vec<ElicitationField>,
+    error: Option<SharedString>,
+    focus_handle: FocusHandle,
+}
+
+impl ElicitationModal {
+    fn new(
+        request: ContextServerElicitationRequest,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let schema = &request.params.requested_schema;
+        let fields = schema
+            .properties
+            .iter()
+            .map(|(name, property)| {
+                let input = match (&property.kind, &property.enum_values) {
+                    (ElicitationPropertyKind::Boolean, _) => FieldInput::Boolean(
+                        property
+                            .default
+                            .as_ref()
+                            .and_then(Value::as_bool)
+                            .unwrap_or(false),
+                    ),
+                    (ElicitationPropertyKind::String, Some(values)) => {
+                        let default = property.default.as_ref().and_then(Value::as_str);
+                        FieldInput::Choice(
+                            values
+                                .iter()
+                                .position(|value| Some(value.as_str()) == default),
+                        )
+                    }
+                    _ => {
+                        let editor = cx.new(|cx| {
+                            let mut editor = Editor::single_line(window, cx);
+                            if let Some(description) = &property.description {
+                                editor.set_placeholder_text(description.clone(), cx);
+                            }
+                            if let Some(default) = &property.default {
+                                let default = match default {
+                                    Value::String(default) => default.clone(),
+                                    default => default.to_string(),
+                                };
+                                editor.set_text(default, window, cx);
+                            }
+                            editor
+                        });
+                        FieldInput::Text(editor)
+                    }
+                };
+                ElicitationField {
+                    name: name.clone(),
+                    property: property.clone(),
+                    required: schema.required.contains(name),
+                    input,
+                }
+            })
+            .collect();
+
+        Self {
+            request,
+            fields,
+            error: None,
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    fn content(&self, cx: &App) -> Result<IndexMap<String, Value>> {
+        let mut content = IndexMap::default();
+        for field in &self.fields {
+            let value = match &field.input {
+                FieldInput::Text(editor) => {
+                    parse_field_value(&field.property, &editor.read(cx).text(cx))
+                        .with_context(|| format!("invalid {}", field.label()))?
+                }
+                FieldInput::Boolean(value) => Some(Value::Bool(*value)),
+                FieldInput::Choice(choice) => choice.and_then(|ix| {
+                    let values = field.property.enum_values.as_ref()?;
+                    Some(Value::String(values.get(ix)?.clone()))
+                }),
+            };
+            match value {
+                Some(value) => {
+                    content.insert(field.name.clone(), value);
+                }
+                None if field.required => bail!("{} is required", field.label()),
+                None => {}
+            }
+        }
+        Ok(content)
+    }
+
+    fn accept(&mut self, _: &menu::Confirm, cx: &mut Context<Self>) {
+        match self.content(cx) {
+            Ok(content) => {
+                self.request.respond(CreateElicitationResult {
+                    action: ElicitationAction::Accept,
+                    content: Some(content),
+                });
+                cx.emit(DismissEvent);
+            }
+            Err(error) => {
+                self.error = Some(format!("{error:#}").into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn decline(&mut self, cx: &mut Context<Self>) {
+        self.request.respond(CreateElicitationResult {
+            action: ElicitationAction::Decline,
+            content: None,
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_field(&self, ix: usize, field: &ElicitationField, cx: &Context<Self>) -> AnyElement {
+        let label = Label::new(if field.required {
+            format!("{} *", field.label())
+        } else {
+            field.label().to_string()
+        })
+        .size(LabelSize::Small);
+
+        let input = match &field.input {
+            FieldInput::Text(editor) => div()
+                .p_1()
+                .rounded_md()
+                .border_1()
+                .border_color(cx.theme().colors().border_variant)
+                .bg(cx.theme().colors().editor_background)
+                .child(editor.clone())
+                .into_any_element(),
+            FieldInput::Boolean(value) => Checkbox::new(("field", ix), (*value).into())
+                .when_some(
+                    field.property.description.clone(),
+                    |checkbox, description| checkbox.label(description),
+                )
+                .on_click(cx.listener(move |this, state: &ToggleState, _, cx| {
+                    if let Some(FieldInput::Boolean(value)) =
+                        this.fields.get_mut(ix).map(|field| &mut field.input)
+                    {
+                        *value = state.selected();
+                        cx.notify();
+                    }
+                }))
+                .into_any_element(),
+            FieldInput::Choice(choice) => {
+                let values = field.property.enum_values.iter().flatten();
+                let names = field.property.enum_names.as_ref();
+                h_flex()
+                    .flex_wrap()
+                    .gap_1()
+                    .children(values.enumerate().map(|(value_ix, value)| {
+                        let name = names.and_then(|names| names.get(value_ix)).unwrap_or(value);
+                        Button::new(("choice", ix * 1000 + value_ix), name.clone())
+                            .style(ButtonStyle::Filled)
+                            .toggle_state(*choice == Some(value_ix))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                if let Some(FieldInput::Choice(choice)) =
+                                    this.fields.get_mut(ix).map(|field| &mut field.input)
+                                {
+                                    *choice = Some(value_ix);
+                                    cx.notify();
+                                }
+                            }))
+                    }))
+                    .into_any_element()
+            }
+        };
+
+        v_flex()
+            .gap_1()
+            .child(label)
+            .child(input)
+            .into_any_element()
+    }
+}
+
+impl ElicitationField {
+    fn label(&self) -> &str {
+        self.property.title.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// Parses the text of a field, returning `None` when it's empty.
+fn parse_field_value(property: &ElicitationProperty, text: &str) -> Result<Option<Value>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let value = match property.kind {
+        ElicitationPropertyKind::String => Value::String(text.to_string()),
+        ElicitationPropertyKind::Integer => Value::from(text.parse::<i64>()?),
+        ElicitationPropertyKind::Number => {
+            let number = text.parse::<f64>()?;
+            serde_json::Number::from_f64(number)
+                .map(Value::Number)
+                .context("not a finite number")?
+        }
+        ElicitationPropertyKind::Boolean => Value::Bool(text.parse::<bool>()?),
+    };
+    Ok(Some(value))
+}
+
+impl ModalView for ElicitationModal {
+    fn on_before_dismiss(&mut self, _: &mut Window, _: &mut Context<Self>) -> DismissDecision {
+        // Requests that were already answered ignore this response.
+        self.request.respond(CreateElicitationResult {
+            action: ElicitationAction::Cancel,
+            content: None,
+        });
+        DismissDecision::Dismiss(true)
+    }
+}
+
+impl Focusable for ElicitationModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.fields
+            .iter()
+            .find_map(|field| match &field.input {
+                FieldInput::Text(editor) => Some(editor.focus_handle(cx)),
+                _ => None,
+            })
+            .unwrap_or_else(|| self.focus_handle.clone())
+    }
+}
+
+impl EventEmitter<DismissEvent> for ElicitationModal {}
+
+impl Render for ElicitationModal {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle(cx);
+        div()
+            .elevation_3(cx)
+            .w(rems(34.))
+            .key_context("ElicitationModal")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::accept))
+            .child(
+                Modal::new("context-server-elicitation", None)
+                    .header(
+                        ModalHeader::new()
+                            .headline(format!("{} needs more information", self.request.server_id)),
+                    )
+                    .section(
+                        Section::new()
+                            .child(
+                                div()
+                                    .pb_2()
+                                    .child(Label::new(self.request.params.message.clone())),
+                            )
+                            .child(
+                                v_flex().gap_2().children(
+                                    self.fields
+                                        .iter()
+                                        .enumerate()
+                                        .map(|(ix, field)| self.render_field(ix, field, cx)),
+                                ),
+                            )
+                            .children(self.error.clone().map(|error| {
+                                h_flex()
+                                    .pt_2()
+                                    .gap_2()
+                                    .child(
+                                        Icon::new(IconName::Warning)
+                                            .size(IconSize::XSmall)
+                                            .color(Color::Warning),
+                                    )
+                                    .child(
+                                        Label::new(error)
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    )
+                            })),
+                    )
+                    .footer(
+                        ModalFooter::new().end_slot(
+                            h_flex()
+                                .gap_2()
+                                .child(
+                                    Button::new("decline", "Decline")
+                                        .on_click(cx.listener(|this, _, _, cx| this.decline(cx))),
+                                )
+                                .child(
+                                    Button::new("accept", "Submit")
+                                        .key_binding(
+                                            KeyBinding::for_action_in(
+                                                &menu::Confirm,
+                                                &focus_handle,
+                                                window,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.))),
+                                        )
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.accept(&menu::Confirm, cx)
+                                        })),
+                                ),
+                        ),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context_server::types::SamplingMessage;
+
+    fn property(kind: ElicitationPropertyKind) -> ElicitationProperty {
+        ElicitationProperty {
+            kind,
+            title: None,
+            description: None,
+            enum_values: None,
+            enum_names: None,
+            default: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_field_value() {
+        let string = property(ElicitationPropertyKind::String);
+        let integer = property(ElicitationPropertyKind::Integer);
+        let number = property(ElicitationPropertyKind::Number);
+
+        assert_eq!(parse_field_value(&string, "  ").unwrap(), None);
+        assert_eq!(
+            parse_field_value(&string, " name ").unwrap(),
+            Some(Value::from("name"))
+        );
+        assert_eq!(
+            parse_field_value(&integer, "42").unwrap(),
+            Some(Value::from(42))
+        );
+        assert!(parse_field_value(&integer, "4.2").is_err());
+        assert_eq!(
+            parse_field_value(&number, "4.5").unwrap(),
+            Some(Value::from(4.5))
+        );
+        assert!(parse_field_value(&number, "four").is_err());
+    }
+
+    #[test]
+    fn test_language_model_request() {
+        let text = |text: &str| types::MessageContent::Text {
+            text: text.to_string(),
+            annotations: None,
+        };
+        let mut params = CreateMessageRequest {
+            messages: vec![
+                SamplingMessage {
+                    role: types::Role::User,
+                    content: text("What's the capital of France?"),
+                },
+                SamplingMessage {
+                    role: types::Role::Assistant,
+                    content: text("Paris."),
+                },
+            ],
+            model_preferences: None,
+            system_prompt: Some("Be brief.".to_string()),
+            include_context: None,
+            temperature: Some(0.5),
+            max_tokens: 100,
+            stop_sequences: None,
+            metadata: None,
+        };
+        let request = language_model_request(&params).unwrap();
+        assert_eq!(
+            request
+                .messages
+                .iter()
+                .map(|message| (message.role, message.string_contents()))
+                .collect::<Vec<_>>(),
+            [
+                (Role::System, "Be brief.".to_string()),
+                (Role::User, "What's the capital of France?".to_string()),
+                (Role::Assistant, "Paris.".to_string()),
+            ]
+        );
+        assert_eq!(request.temperature, Some(0.5));
+        assert_eq!(request.max_tokens, Some(100));
+
+        params.messages.push(SamplingMessage {
+            role: types::Role::User,
+            content: types::MessageContent::Image {
+                data: String::new(),
+                mime_type: "image/png".to_string(),
+                annotations: None,
+            },
+        });
+        assert!(language_model_request(&params).is_err());
+    }
+}
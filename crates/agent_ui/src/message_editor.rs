@@ -1456,6 +1456,7 @@ impl MessageEditor {
 
                     let request = language_model::LanguageModelRequest {
                         thread_id: None,
+                        profile_id: None,
                         prompt_id: None,
                         intent: None,
                         mode: None,
@@ -1464,6 +1465,7 @@ impl MessageEditor {
                         tool_choice: None,
                         stop: vec![],
                         temperature: AgentSettings::temperature_for_model(&model.model, cx),
+                        max_tokens: None,
                         thinking_allowed: true,
                     };
 
@@ -289,6 +289,7 @@ impl TerminalInlineAssistant {
 
             LanguageModelRequest {
                 thread_id: None,
+                profile_id: None,
                 prompt_id: None,
                 mode: None,
                 intent: Some(CompletionIntent::TerminalInlineAssist),
@@ -297,6 +298,7 @@ impl TerminalInlineAssistant {
                 tool_choice: None,
                 stop: Vec::new(),
                 temperature,
+                max_tokens: None,
                 thinking_allowed: false,
             }
         }))
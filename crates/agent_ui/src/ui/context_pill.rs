@@ -15,9 +15,9 @@ use ui::{IconButtonShape, Tooltip, prelude::*, tooltip_container};
 use agent::context::{
     AgentContext, AgentContextHandle, ContextId, ContextKind, DirectoryContext,
     DirectoryContextHandle, FetchedUrlContext, FileContext, FileContextHandle, ImageContext,
-    ImageStatus, RulesContext, RulesContextHandle, SelectionContext, SelectionContextHandle,
-    SymbolContext, SymbolContextHandle, TextThreadContext, TextThreadContextHandle, ThreadContext,
-    ThreadContextHandle,
+    ImageStatus, McpResourceContext, McpResourceContextHandle, RulesContext, RulesContextHandle,
+    SelectionContext, SelectionContextHandle, SymbolContext, SymbolContextHandle,
+    TextThreadContext, TextThreadContextHandle, ThreadContext, ThreadContextHandle,
 };
 
 #[derive(IntoElement)]
@@ -313,6 +313,7 @@ impl AddedContext {
             AgentContextHandle::Thread(handle) => Some(Self::pending_thread(handle, cx)),
             AgentContextHandle::TextThread(handle) => Some(Self::pending_text_thread(handle, cx)),
             AgentContextHandle::Rules(handle) => Self::pending_rules(handle, prompt_store, cx),
+            AgentContextHandle::McpResource(handle) => Some(Self::pending_mcp_resource(handle)),
             AgentContextHandle::Image(handle) => Some(Self::image(handle, model, cx)),
         }
     }
@@ -331,6 +332,7 @@ impl AddedContext {
             AgentContext::Thread(context) => Self::attached_thread(context),
             AgentContext::TextThread(context) => Self::attached_text_thread(context),
             AgentContext::Rules(context) => Self::attached_rules(context),
+            AgentContext::McpResource(context) => Self::attached_mcp_resource(context),
             AgentContext::Image(context) => Self::image(context.clone(), model, cx),
         }
     }
@@ -606,6 +608,37 @@ impl AddedContext {
         }
     }
 
+    fn pending_mcp_resource(handle: McpResourceContextHandle) -> AddedContext {
+        AddedContext {
+            kind: ContextKind::McpResource,
+            name: handle.name.clone(),
+            parent: None,
+            tooltip: Some(handle.uri.clone()),
+            icon_path: None,
+            status: ContextStatus::Ready,
+            render_hover: None,
+            handle: AgentContextHandle::McpResource(handle),
+        }
+    }
+
+    fn attached_mcp_resource(context: &McpResourceContext) -> AddedContext {
+        AddedContext {
+            kind: ContextKind::McpResource,
+            name: context.handle.name.clone(),
+            parent: None,
+            tooltip: None,
+            icon_path: None,
+            status: ContextStatus::Ready,
+            render_hover: {
+                let text = context.text.clone();
+                Some(Rc::new(move |_, cx| {
+                    ContextPillHover::new_text(text.clone(), cx).into()
+                }))
+            },
+            handle: AgentContextHandle::McpResource(context.handle.clone()),
+        }
+    }
+
     fn image(
         context: ImageContext,
         model: Option<&Arc<dyn language_model::LanguageModel>>,
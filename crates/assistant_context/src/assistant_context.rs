@@ -2285,6 +2285,7 @@ impl AssistantContext {
 
         let mut completion_request = LanguageModelRequest {
             thread_id: None,
+            profile_id: None,
             prompt_id: None,
             intent: Some(CompletionIntent::UserPrompt),
             mode: None,
@@ -2293,6 +2294,7 @@ impl AssistantContext {
             tool_choice: None,
             stop: Vec::new(),
             temperature: model.and_then(|model| AgentSettings::temperature_for_model(model, cx)),
+            max_tokens: None,
             thinking_allowed: true,
         };
         for message in self.messages(cx) {
@@ -850,6 +850,7 @@ impl ContextStore {
                     _ => {}
                 }
             }
+            _ => {}
         }
     }
 
@@ -711,6 +711,7 @@ impl EditAgent {
 
         let request = LanguageModelRequest {
             thread_id: conversation.thread_id,
+            profile_id: conversation.profile_id,
             prompt_id: conversation.prompt_id,
             intent: Some(intent),
             mode: conversation.mode,
@@ -719,6 +720,7 @@ impl EditAgent {
             tools,
             stop: Vec::new(),
             temperature: None,
+            max_tokens: None,
             thinking_allowed: true,
         };
 
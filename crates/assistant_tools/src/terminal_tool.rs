@@ -1,8 +1,10 @@
+mod sandbox;
+
 use crate::{
     schema::json_schema_for,
     ui::{COLLAPSED_LINES, ToolOutputPreview},
 };
-use agent_settings;
+use agent_settings::{AgentProfileId, AgentSettings};
 use anyhow::{Context as _, Result, anyhow};
 use assistant_tool::{ActionLog, Tool, ToolCard, ToolResult, ToolUseStatus};
 use futures::{FutureExt as _, future::Shared};
@@ -15,6 +17,7 @@ use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchem
 use markdown::{Markdown, MarkdownElement, MarkdownStyle};
 use portable_pty::{CommandBuilder, PtySize, native_pty_system};
 use project::{Project, terminals::TerminalKind};
+use sandbox::Sandbox;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
@@ -121,7 +124,7 @@ impl Tool for TerminalTool {
     fn run(
         self: Arc<Self>,
         input: serde_json::Value,
-        _request: Arc<LanguageModelRequest>,
+        request: Arc<LanguageModelRequest>,
         project: Entity<Project>,
         _action_log: Entity<ActionLog>,
         _model: Arc<dyn LanguageModel>,
@@ -137,6 +140,10 @@ impl Tool for TerminalTool {
             Ok(dir) => dir,
             Err(err) => return Task::ready(Err(err)).into(),
         };
+        let sandbox = match sandbox_for_project(&project, request.profile_id.as_deref(), cx) {
+            Ok(sandbox) => sandbox,
+            Err(err) => return Task::ready(Err(err)).into(),
+        };
         let program = self.determine_shell.clone();
         let command = if cfg!(windows) {
             format!("$null | & {{{}}}", input.command.replace("\"", "'"))
@@ -174,6 +181,10 @@ impl Tool for TerminalTool {
                 let env = env.await;
                 let pty_system = native_pty_system();
                 let program = program.await;
+                let (program, args) = match &sandbox {
+                    Some(sandbox) => sandbox.wrap(program, args),
+                    None => (program, args),
+                };
                 let mut cmd = CommandBuilder::new(program);
                 cmd.args(args);
                 for (k, v) in env {
@@ -200,8 +211,13 @@ impl Tool for TerminalTool {
                     .collect();
                 let content = content.trim_start().trim_start_matches("^D");
                 let exit_status = child.wait()?;
+                if let Some(sandbox) = &sandbox {
+                    sandbox.remove_mount_points();
+                }
+                let success = exit_status.success();
                 let (processed_content, _) =
                     process_content(content, &input.command, Some(exit_status));
+                check_sandbox_violation(sandbox.as_ref(), content, success, &processed_content)?;
                 Ok(processed_content.into())
             });
             return ToolResult {
@@ -212,8 +228,13 @@ impl Tool for TerminalTool {
 
         let terminal = cx.spawn({
             let project = project.downgrade();
+            let sandbox = sandbox.clone();
             async move |cx| {
                 let program = program.await;
+                let (program, args) = match &sandbox {
+                    Some(sandbox) => sandbox.wrap(program, args),
+                    None => (program, args),
+                };
                 let env = env.await;
                 let terminal = project
                     .update(cx, |project, cx| {
@@ -285,6 +306,9 @@ impl Tool for TerminalTool {
                 let exit_status = terminal
                     .update(cx, |terminal, cx| terminal.wait_for_completed_task(cx))?
                     .await;
+                if let Some(sandbox) = &sandbox {
+                    sandbox.remove_mount_points();
+                }
                 let (content, content_line_count) = terminal.read_with(cx, |terminal, _| {
                     (terminal.get_content(), terminal.total_lines())
                 })?;
@@ -307,6 +331,12 @@ impl Tool for TerminalTool {
                 })
                 .log_err();
 
+                check_sandbox_violation(
+                    sandbox.as_ref(),
+                    &content,
+                    exit_status.is_some_and(|status| status.success()),
+                    &processed_content,
+                )?;
                 Ok(processed_content.into())
             }
         });
@@ -318,6 +348,84 @@ impl Tool for TerminalTool {
     }
 }
 
+/// The sandbox to run commands in, if `agent.sandbox_terminal` is enabled, in which they can write
+/// to the project's worktrees and access the network if the profile of the thread running them
+/// allows it.
+fn sandbox_for_project(
+    project: &Entity<Project>,
+    profile_id: Option<&str>,
+    cx: &App,
+) -> Result<Option<Sandbox>> {
+    let settings = AgentSettings::get_global(cx);
+    if !settings.sandbox_terminal {
+        return Ok(None);
+    }
+
+    let project = project.read(cx);
+    anyhow::ensure!(
+        project.is_local(),
+        "Sandboxing terminal commands is only supported in local projects."
+    );
+    let mut writable_paths = Vec::new();
+    let mut protected_paths = Vec::new();
+    for worktree in project.worktrees(cx) {
+        let worktree = worktree.read(cx);
+        writable_paths.push(worktree.abs_path().to_path_buf());
+        protected_paths.extend(Sandbox::protected_paths_in_worktree(&worktree.abs_path()));
+        // Nested projects have settings and tasks of their own.
+        protected_paths.extend(
+            worktree
+                .entries(true, 0)
+                .filter(|entry| {
+                    entry.is_dir()
+                        && entry.path.file_name().is_some_and(|name| {
+                            Sandbox::is_protected_directory_name(&name.to_string_lossy())
+                        })
+                })
+                .map(|entry| worktree.absolutize(&entry.path))
+                .filter_map(Result::ok),
+        );
+    }
+    // As do nested repositories, whose `.git` directories aren't scanned.
+    for repository in project.git_store().read(cx).repositories().values() {
+        let work_directory = &repository.read(cx).work_directory_abs_path;
+        if writable_paths
+            .iter()
+            .any(|path| work_directory.starts_with(path))
+        {
+            protected_paths.push(work_directory.join(".git"));
+        }
+    }
+    protected_paths.sort();
+    protected_paths.dedup();
+    let profile_id = profile_id.map_or_else(
+        || settings.default_profile.clone(),
+        |profile_id| AgentProfileId(profile_id.into()),
+    );
+    let allow_network = settings
+        .profiles
+        .get(&profile_id)
+        .is_none_or(|profile| profile.terminal_network);
+    Sandbox::new(writable_paths, protected_paths, allow_network).map(Some)
+}
+
+/// Reports failed commands that the sandbox got in the way of as errors, so that the model
+/// doesn't mistake them for problems with the commands themselves.
+fn check_sandbox_violation(
+    sandbox: Option<&Sandbox>,
+    content: &str,
+    success: bool,
+    processed_content: &str,
+) -> Result<()> {
+    if success {
+        return Ok(());
+    }
+    if let Some(violation) = sandbox.and_then(|sandbox| sandbox.violation(content)) {
+        anyhow::bail!("{violation}\n\n{processed_content}");
+    }
+    Ok(())
+}
+
 fn process_content(
     content: &str,
     command: &str,
@@ -742,6 +850,7 @@ mod tests {
             ThemeSettings::register(cx);
             TerminalSettings::register(cx);
             EditorSettings::register(cx);
+            AgentSettings::register(cx);
         });
     }
 
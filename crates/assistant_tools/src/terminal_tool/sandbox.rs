@@ -0,0 +1,271 @@
+//! Runs terminal commands in a sandbox made with [bubblewrap](https://github.com/containers/bubblewrap),
+//! in which the whole filesystem is read-only except for the project's worktrees and a private
+//! temporary directory, and the network can be cut off.
+
+use anyhow::{Context as _, Result};
+use std::path::{Path, PathBuf};
+
+/// Directories hidden behind empty ones in the sandbox, as they hold the sockets of services
+/// outside of it, like the D-Bus session bus or Docker.
+const RUNTIME_DIRS: &[&str] = &["/run", "/var/run"];
+/// Directories under `/run` that are shown again when the network is allowed, as name resolution
+/// goes through them.
+const NAME_RESOLUTION_DIRS: &[&str] = &["/run/systemd/resolve"];
+
+/// Output of commands the sandbox stopped from writing to the filesystem.
+const FILESYSTEM_VIOLATIONS: &[&str] = &["Read-only file system"];
+/// Output of commands the sandbox stopped from accessing the network.
+const NETWORK_VIOLATIONS: &[&str] = &[
+    "Could not resolve host",
+    "Temporary failure in name resolution",
+    "Network is unreachable",
+    "network is unreachable",
+    "Name or service not known",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sandbox {
+    bwrap: PathBuf,
+    writable_paths: Vec<PathBuf>,
+    /// Paths in the writable ones that stay read-only, as the editor runs git hooks and the
+    /// project's tasks and settings from them outside of the sandbox.
+    protected_paths: Vec<PathBuf>,
+    /// Protected paths that don't exist yet, which are kept from being created by mounting empty
+    /// read-only directories over them.
+    missing_protected_paths: Vec<PathBuf>,
+    allow_network: bool,
+}
+
+impl Sandbox {
+    /// Finds bubblewrap, failing when it isn't installed, or the platform isn't Linux, so that
+    /// commands are never run unsandboxed when a sandbox was asked for.
+    pub fn new(
+        writable_paths: Vec<PathBuf>,
+        protected_paths: Vec<PathBuf>,
+        allow_network: bool,
+    ) -> Result<Self> {
+        anyhow::ensure!(
+            cfg!(target_os = "linux"),
+            "Sandboxing terminal commands is only supported on Linux."
+        );
+        let bwrap = which::which("bwrap").context(
+            "Sandboxing terminal commands requires bubblewrap (`bwrap`) to be installed",
+        )?;
+        let (protected_paths, missing_protected_paths) = protected_paths
+            .into_iter()
+            .partition(|path| path.symlink_metadata().is_ok());
+        Ok(Self {
+            bwrap,
+            writable_paths,
+            protected_paths,
+            missing_protected_paths,
+            allow_network,
+        })
+    }
+
+    /// The paths in a worktree that stay read-only even when they don't exist yet.
+    pub fn protected_paths_in_worktree(worktree_path: &Path) -> Vec<PathBuf> {
+        [
+            Path::new(".git"),
+            paths::local_settings_folder_relative_path(),
+            paths::local_vscode_folder_relative_path(),
+        ]
+        .into_iter()
+        .map(|path| worktree_path.join(path))
+        .collect()
+    }
+
+    /// Whether a directory found in a worktree has to stay read-only.
+    pub fn is_protected_directory_name(name: &str) -> bool {
+        name == ".git"
+            || Path::new(name) == paths::local_settings_folder_relative_path()
+            || Path::new(name) == paths::local_vscode_folder_relative_path()
+    }
+
+    /// Removes the directories that were created on the host to mount over missing protected
+    /// paths, once the command is done.
+    pub fn remove_mount_points(&self) {
+        for path in &self.missing_protected_paths {
+            std::fs::remove_dir(path).ok();
+        }
+    }
+
+    /// The program and arguments that run the given ones in the sandbox.
+    pub fn wrap(&self, program: String, args: Vec<String>) -> (String, Vec<String>) {
+        let mut bwrap_args = [
+            "--ro-bind",
+            "/",
+            "/",
+            "--dev",
+            "/dev",
+            "--proc",
+            "/proc",
+            "--tmpfs",
+            "/tmp",
+            "--setenv",
+            "TMPDIR",
+            "/tmp",
+            "--unshare-user",
+            "--unshare-pid",
+            "--unshare-ipc",
+            "--unshare-uts",
+            "--unshare-cgroup-try",
+            "--die-with-parent",
+            "--new-session",
+        ]
+        .map(String::from)
+        .to_vec();
+        for dir in RUNTIME_DIRS {
+            bwrap_args.extend(["--tmpfs".into(), dir.to_string()]);
+        }
+        if self.allow_network {
+            for dir in NAME_RESOLUTION_DIRS {
+                bwrap_args.extend(["--ro-bind-try".into(), dir.to_string(), dir.to_string()]);
+            }
+        } else {
+            bwrap_args.push("--unshare-net".into());
+        }
+        for path in &self.writable_paths {
+            let path = path.to_string_lossy().to_string();
+            bwrap_args.extend(["--bind".into(), path.clone(), path]);
+        }
+        for path in &self.protected_paths {
+            let path = path.to_string_lossy().to_string();
+            bwrap_args.extend(["--ro-bind".into(), path.clone(), path]);
+        }
+        for path in &self.missing_protected_paths {
+            let path = path.to_string_lossy().to_string();
+            bwrap_args.extend([
+                "--dir".into(),
+                path.clone(),
+                "--ro-bind".into(),
+                path.clone(),
+                path,
+            ]);
+        }
+        bwrap_args.push("--".into());
+        bwrap_args.push(program);
+        bwrap_args.extend(args);
+        (self.bwrap.to_string_lossy().to_string(), bwrap_args)
+    }
+
+    /// Describes how the sandbox got in the way of a failed command, judging by its output.
+    pub fn violation(&self, output: &str) -> Option<String> {
+        if FILESYSTEM_VIOLATIONS
+            .iter()
+            .any(|message| output.contains(message))
+        {
+            let writable_paths = self
+                .writable_paths
+                .iter()
+                .map(|path| format!("`{}`", path.display()))
+                .chain(["`/tmp`".to_string()])
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Some(format!(
+                "The command tried to write outside of the directories it's allowed to write to in the sandbox: {writable_paths}."
+            ));
+        }
+        if !self.allow_network
+            && NETWORK_VIOLATIONS
+                .iter()
+                .any(|message| output.contains(message))
+        {
+            return Some(
+                "The command tried to access the network, which is disabled in the sandbox."
+                    .to_string(),
+            );
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sandbox_args() {
+        let sandbox = Sandbox {
+            bwrap: "/usr/bin/bwrap".into(),
+            writable_paths: vec!["/home/user/project".into()],
+            protected_paths: vec![
+                "/home/user/project/.git".into(),
+                "/home/user/project/.vscode".into(),
+                "/home/user/project/crates/cli/.zed".into(),
+            ],
+            // The project has no `.zed` directory of its own.
+            missing_protected_paths: vec!["/home/user/project/.zed".into()],
+            allow_network: false,
+        };
+        let (program, args) = sandbox.wrap("bash".into(), vec!["-c".into(), "ls".into()]);
+        assert_eq!(program, "/usr/bin/bwrap");
+        assert!(args.windows(3).any(|args| args == ["--ro-bind", "/", "/"]));
+        assert!(
+            args.windows(3)
+                .any(|args| args == ["--bind", "/home/user/project", "/home/user/project"])
+        );
+        assert!(args.iter().any(|arg| arg == "--unshare-net"));
+        assert!(args.windows(2).any(|args| args == ["--tmpfs", "/run"]));
+        assert!(args.windows(2).any(|args| args == ["--tmpfs", "/var/run"]));
+        let bind_ix = args.iter().position(|arg| arg == "--bind").unwrap();
+        for protected_path in [
+            "/home/user/project/.git",
+            "/home/user/project/.vscode",
+            "/home/user/project/crates/cli/.zed",
+        ] {
+            let protected_ix = args
+                .windows(3)
+                .position(|args| args == ["--ro-bind", protected_path, protected_path])
+                .unwrap();
+            assert!(
+                protected_ix > bind_ix,
+                "{protected_path} is bound before the worktree"
+            );
+        }
+        let missing_ix = args
+            .windows(5)
+            .position(|args| {
+                args == [
+                    "--dir",
+                    "/home/user/project/.zed",
+                    "--ro-bind",
+                    "/home/user/project/.zed",
+                    "/home/user/project/.zed",
+                ]
+            })
+            .unwrap();
+        assert!(missing_ix > bind_ix);
+        assert!(args.ends_with(&["--".into(), "bash".into(), "-c".into(), "ls".into()]));
+
+        assert_eq!(sandbox.violation("total 0"), None);
+        assert!(
+            sandbox
+                .violation("touch: cannot touch '/etc/foo': Read-only file system")
+                .is_some()
+        );
+        assert!(
+            sandbox
+                .violation("curl: (6) Could not resolve host: example.com")
+                .is_some()
+        );
+
+        let sandbox = Sandbox {
+            allow_network: true,
+            ..sandbox
+        };
+        let (_, args) = sandbox.wrap("bash".into(), Vec::new());
+        assert!(!args.iter().any(|arg| arg == "--unshare-net"));
+        assert!(args.windows(2).any(|args| args == ["--tmpfs", "/run"]));
+        assert!(args.windows(3).any(|args| args
+            == [
+                "--ro-bind-try",
+                "/run/systemd/resolve",
+                "/run/systemd/resolve"
+            ]));
+        assert_eq!(
+            sandbox.violation("curl: (6) Could not resolve host: example.com"),
+            None
+        );
+    }
+}
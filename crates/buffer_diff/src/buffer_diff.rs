@@ -1,4 +1,5 @@
 use futures::channel::oneshot;
+use git::line_diff::{LineChange, line_changes};
 use git2::{DiffLineType as GitDiffLineType, DiffOptions as GitOptions, Patch as GitPatch};
 use gpui::{App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Task, TaskLabel};
 use language::{Language, LanguageRegistry};
@@ -850,6 +851,79 @@ fn process_patch_hunk(
     }
 }
 
+/// Maps rows that are unchanged between two texts from one side to the other,
+/// dropping any rows that fall inside a change.
+fn map_unchanged_rows(changes: &[LineChange], rows: &[u32], old_to_new: bool) -> Vec<u32> {
+    rows.iter()
+        .filter_map(|&row| {
+            let mut delta = 0i64;
+            for change in changes {
+                let (from, to) = if old_to_new {
+                    (&change.old_rows, &change.new_rows)
+                } else {
+                    (&change.new_rows, &change.old_rows)
+                };
+                if from.contains(&row) {
+                    return None;
+                }
+                if row < from.end {
+                    break;
+                }
+                delta = to.end as i64 - from.end as i64;
+            }
+            u32::try_from(row as i64 + delta).ok()
+        })
+        .collect()
+}
+
+/// Produces the text obtained by applying to `old_text` only those changes
+/// from `new_text` that touch the selected rows. Removed lines are dropped
+/// when their old row is selected, and added lines are kept when their new
+/// row is selected.
+fn apply_selected_lines(
+    old_text: &str,
+    new_text: &str,
+    selected_old_rows: &[u32],
+    selected_new_rows: &[u32],
+) -> String {
+    let old_lines = old_text.split_inclusive('\n').collect::<Vec<_>>();
+    let new_lines = new_text.split_inclusive('\n').collect::<Vec<_>>();
+    let mut result = String::with_capacity(old_text.len());
+    fn push_line(result: &mut String, line: &str) {
+        if !result.is_empty() && !result.ends_with('\n') {
+            result.push('\n');
+        }
+        result.push_str(line);
+    }
+
+    let mut old_row = 0;
+    for change in line_changes(old_text, new_text) {
+        let unchanged = old_lines.get(old_row..change.old_rows.start as usize);
+        for line in unchanged.into_iter().flatten() {
+            push_line(&mut result, line);
+        }
+        for row in change.old_rows.clone() {
+            if !selected_old_rows.contains(&row) {
+                if let Some(line) = old_lines.get(row as usize) {
+                    push_line(&mut result, line);
+                }
+            }
+        }
+        for row in change.new_rows.clone() {
+            if selected_new_rows.contains(&row) {
+                if let Some(line) = new_lines.get(row as usize) {
+                    push_line(&mut result, line);
+                }
+            }
+        }
+        old_row = change.old_rows.end as usize;
+    }
+    for line in old_lines.iter().skip(old_row) {
+        push_line(&mut result, line);
+    }
+    result
+}
+
 impl std::fmt::Debug for BufferDiff {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("BufferChangeSet")
@@ -947,6 +1021,55 @@ impl BufferDiff {
         new_index_text
     }
 
+    /// Stages or unstages individual lines rather than whole hunks.
+    ///
+    /// `buffer_rows` are rows of the buffer whose additions should be staged
+    /// (or unstaged), and `base_rows` are rows of the committed text whose
+    /// deletions should be staged (or unstaged). Rows that aren't part of a
+    /// change are ignored. Returns the new index text, or `None` if the
+    /// selection didn't change the index or the file was removed from it.
+    pub fn stage_or_unstage_lines(
+        &mut self,
+        stage: bool,
+        buffer_rows: &[u32],
+        base_rows: &[u32],
+        buffer: &text::BufferSnapshot,
+        cx: &mut Context<Self>,
+    ) -> Option<Rope> {
+        let secondary = &self.secondary_diff.as_ref()?.read(cx).inner;
+        let head_exists = self.inner.base_text_exists;
+        let index_exists = secondary.base_text_exists;
+        let head_text = self.inner.base_text.text();
+        let index_text = secondary.base_text.text();
+
+        let new_index_text = if stage {
+            let changes = line_changes(&head_text, &index_text);
+            let index_rows = map_unchanged_rows(&changes, base_rows, true);
+            apply_selected_lines(&index_text, &buffer.text(), &index_rows, buffer_rows)
+        } else {
+            let changes = line_changes(&index_text, &buffer.text());
+            let index_rows = map_unchanged_rows(&changes, buffer_rows, false);
+            apply_selected_lines(&index_text, &head_text, &index_rows, base_rows)
+        };
+
+        if new_index_text == index_text && (index_exists || new_index_text.is_empty()) {
+            return None;
+        }
+        let new_index_text = if !stage && !head_exists && new_index_text.is_empty() {
+            None
+        } else {
+            Some(Rope::from(new_index_text.as_str()))
+        };
+
+        cx.emit(BufferDiffEvent::HunksStagedOrUnstaged(
+            new_index_text.clone(),
+        ));
+        cx.emit(BufferDiffEvent::DiffChanged {
+            changed_range: Some(Anchor::MIN..Anchor::MAX),
+        });
+        new_index_text
+    }
+
     pub fn range_to_hunk_range(
         &self,
         range: Range<Anchor>,
@@ -1893,6 +2016,96 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_stage_and_unstage_lines(cx: &mut TestAppContext) {
+        let head_text = "
+            one
+            two
+            three
+            four
+        "
+        .unindent();
+        let buffer_text = "
+            one
+            TWO
+            three
+            four
+            five
+            six
+        "
+        .unindent();
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), buffer_text.clone());
+
+        let diff_with_index = |index_text: &str, cx: &mut TestAppContext| {
+            let unstaged = BufferDiffSnapshot::new_sync(buffer.clone(), index_text.to_string(), cx);
+            let uncommitted = BufferDiffSnapshot::new_sync(buffer.clone(), head_text.clone(), cx);
+            let unstaged_diff = cx.new(|cx| {
+                let mut diff = BufferDiff::new(&buffer, cx);
+                diff.set_snapshot(unstaged, &buffer, cx);
+                diff
+            });
+            cx.new(|cx| {
+                let mut diff = BufferDiff::new(&buffer, cx);
+                diff.set_snapshot(uncommitted, &buffer, cx);
+                diff.set_secondary_diff(unstaged_diff);
+                diff
+            })
+        };
+
+        let cases: &[(bool, &str, &[u32], &[u32], Option<&str>)] = &[
+            // Stage a single added line.
+            (
+                true,
+                &head_text,
+                &[5],
+                &[],
+                Some("one\ntwo\nthree\nfour\nsix\n"),
+            ),
+            // Stage the removed half of a modification without the added half.
+            (true, &head_text, &[], &[1], Some("one\nthree\nfour\n")),
+            // Stage both halves of a modification.
+            (
+                true,
+                &head_text,
+                &[1],
+                &[1],
+                Some("one\nTWO\nthree\nfour\n"),
+            ),
+            // Lines outside of any change are ignored.
+            (true, &head_text, &[0, 2], &[3], None),
+            // Unstage a staged addition.
+            (
+                false,
+                "one\nthree\nfour\nsix\n",
+                &[5],
+                &[],
+                Some("one\nthree\nfour\n"),
+            ),
+            // Unstage a staged removal.
+            (
+                false,
+                "one\nthree\nfour\nsix\n",
+                &[],
+                &[1],
+                Some("one\ntwo\nthree\nfour\nsix\n"),
+            ),
+            // Unstaging an addition that isn't staged does nothing.
+            (false, "one\nthree\nfour\nsix\n", &[4], &[], None),
+        ];
+
+        for (stage, index_text, buffer_rows, base_rows, expected) in cases {
+            let diff = diff_with_index(index_text, cx);
+            let new_index_text = diff.update(cx, |diff, cx| {
+                diff.stage_or_unstage_lines(*stage, buffer_rows, base_rows, &buffer, cx)
+            });
+            assert_eq!(
+                new_index_text.map(|text| text.to_string()).as_deref(),
+                *expected,
+                "stage: {stage}, index: {index_text:?}, buffer rows: {buffer_rows:?}, base rows: {base_rows:?}"
+            );
+        }
+    }
+
     #[gpui::test]
     async fn test_buffer_diff_compare(cx: &mut TestAppContext) {
         let base_text = "
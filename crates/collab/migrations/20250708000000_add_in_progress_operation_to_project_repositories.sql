@@ -0,0 +1,2 @@
+alter table project_repositories
+    add column in_progress_operation varchar;
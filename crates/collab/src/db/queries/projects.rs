@@ -354,6 +354,7 @@ impl Database {
                                 abs_path: ActiveValue::set(String::new()),
                                 entry_ids: ActiveValue::set("[]".into()),
                                 head_commit_details: ActiveValue::set(None),
+                                in_progress_operation: ActiveValue::set(None),
                             }
                         }),
                     )
@@ -499,6 +500,12 @@ impl Database {
                         .as_ref()
                         .map(|details| serde_json::to_string(details).unwrap()),
                 ),
+                in_progress_operation: ActiveValue::Set(
+                    update
+                        .in_progress_operation
+                        .as_ref()
+                        .map(|operation| serde_json::to_string(operation).unwrap()),
+                ),
                 current_merge_conflicts: ActiveValue::Set(Some(
                     serde_json::to_string(&update.current_merge_conflicts).unwrap(),
                 )),
@@ -515,6 +522,7 @@ impl Database {
                     project_repository::Column::AbsPath,
                     project_repository::Column::CurrentMergeConflicts,
                     project_repository::Column::HeadCommitDetails,
+                    project_repository::Column::InProgressOperation,
                 ])
                 .to_owned(),
             )
@@ -957,6 +965,12 @@ impl Database {
                     .transpose()?
                     .unwrap_or_default();
 
+                let in_progress_operation = db_repository_entry
+                    .in_progress_operation
+                    .as_ref()
+                    .map(|operation| serde_json::from_str(&operation))
+                    .transpose()?;
+
                 let entry_ids = serde_json::from_str(&db_repository_entry.entry_ids)
                     .context("failed to deserialize repository's entry ids")?;
 
@@ -984,6 +998,7 @@ impl Database {
                         current_merge_conflicts,
                         branch_summary,
                         head_commit_details,
+                        in_progress_operation,
                         scan_id: db_repository_entry.scan_id as u64,
                         is_last_update: true,
                     });
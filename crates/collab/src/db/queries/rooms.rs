@@ -764,6 +764,12 @@ impl Database {
                         .transpose()?
                         .unwrap_or_default();
 
+                    let in_progress_operation = db_repository
+                        .in_progress_operation
+                        .as_ref()
+                        .map(|operation| serde_json::from_str(&operation))
+                        .transpose()?;
+
                     let entry_ids = serde_json::from_str(&db_repository.entry_ids)
                         .context("failed to deserialize repository's entry ids")?;
 
@@ -788,6 +794,7 @@ impl Database {
                             current_merge_conflicts,
                             branch_summary,
                             head_commit_details,
+                            in_progress_operation,
                             project_id: project_id.to_proto(),
                             id: db_repository.id as u64,
                             abs_path: db_repository.abs_path,
@@ -20,6 +20,8 @@ pub struct Model {
     pub branch_summary: Option<String>,
     // A JSON object representing the current Head commit values
     pub head_commit_details: Option<String>,
+    // A JSON object representing the merge, rebase, cherry-pick or revert in progress
+    pub in_progress_operation: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
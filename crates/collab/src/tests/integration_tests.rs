@@ -6968,3 +6968,70 @@ async fn test_remote_git_branches(
 
     assert_eq!(host_branch.name(), "totally-new-branch");
 }
+
+#[gpui::test]
+async fn test_remote_git_worktrees(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/code/project", serde_json::json!({ ".git": {} }))
+        .await;
+    client_a
+        .fs()
+        .insert_branches(Path::new("/code/project/.git"), &["main", "dev"]);
+
+    let (project_a, _) = client_a.build_local_project("/code/project", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    executor.run_until_parked();
+
+    let repo_b = cx_b.update(|cx| project_b.read(cx).active_repository(cx).unwrap());
+
+    // Guests can't create working trees anywhere on the host's disk.
+    let error = cx_b
+        .update(|cx| {
+            repo_b.update(cx, |repository, _| {
+                repository.create_worktree("/etc/foo".into(), "dev".to_string(), false)
+            })
+        })
+        .await
+        .unwrap()
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("/code/project-dev"),
+        "unexpected error: {error}"
+    );
+
+    // Only where the host would create them.
+    let path = cx_b.update(|cx| repo_b.read(cx).worktree_path_for_branch("dev").unwrap());
+    assert_eq!(path, Path::new("/code/project-dev"));
+    cx_b.update(|cx| {
+        repo_b.update(cx, |repository, _| {
+            repository.create_worktree(path.clone(), "dev".to_string(), false)
+        })
+    })
+    .await
+    .unwrap()
+    .unwrap();
+
+    let worktrees_b = cx_b
+        .update(|cx| repo_b.update(cx, |repository, _| repository.worktrees()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(worktrees_b.iter().any(|worktree| worktree.path == path));
+}
@@ -5,20 +5,28 @@ pub mod protocol;
 pub mod test;
 pub mod transport;
 pub mod types;
+pub mod uri_template;
 
 use std::fmt::Display;
 use std::path::Path;
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use client::Client;
 use collections::HashMap;
-use gpui::AsyncApp;
+use gpui::{AsyncApp, Task};
+use http_client::{HttpClient, Url};
 use parking_lot::RwLock;
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
 use util::redact::should_redact;
 
+use crate::types::{
+    Request as _,
+    requests::{CreateElicitation, CreateMessage, ListRoots},
+};
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ContextServerId(pub Arc<str>);
 
@@ -52,15 +60,61 @@ impl std::fmt::Debug for ContextServerCommand {
     }
 }
 
+/// How to reach a context server that runs as an HTTP service, such as a local daemon or a
+/// sidecar container.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, JsonSchema)]
+pub struct ContextServerUrl {
+    /// The URL of the server's MCP endpoint, such as `http://localhost:8080/mcp`.
+    pub url: String,
+    /// Headers sent with every request, such as an `Authorization` header.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// The HTTP transport spoken by the server.
+    #[serde(default)]
+    pub transport: HttpTransportKind,
+}
+
+impl std::fmt::Debug for ContextServerUrl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Headers often hold credentials, so only their names are shown.
+        let header_names = self.headers.keys().collect::<Vec<_>>();
+
+        f.debug_struct("ContextServerUrl")
+            .field("url", &self.url)
+            .field("headers", &header_names)
+            .field("transport", &self.transport)
+            .finish()
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HttpTransportKind {
+    /// The Streamable HTTP transport.
+    #[default]
+    StreamableHttp,
+    /// The HTTP with Server-Sent Events transport of earlier MCP versions.
+    Sse,
+}
+
 enum ContextServerTransport {
     Stdio(ContextServerCommand),
+    Http {
+        url: ContextServerUrl,
+        http_client: Arc<dyn HttpClient>,
+    },
     Custom(Arc<dyn crate::transport::Transport>),
 }
 
+type RequestHandler = Arc<dyn Send + Sync + Fn(Value, AsyncApp) -> Task<Result<Value>>>;
+type NotificationHandler = Arc<dyn Send + Sync + Fn(Value, AsyncApp)>;
+
 pub struct ContextServer {
     id: ContextServerId,
     client: RwLock<Option<Arc<crate::protocol::InitializedContextServerProtocol>>>,
     configuration: ContextServerTransport,
+    request_handlers: RwLock<HashMap<&'static str, RequestHandler>>,
+    notification_handlers: RwLock<HashMap<&'static str, NotificationHandler>>,
 }
 
 impl ContextServer {
@@ -69,6 +123,22 @@ impl ContextServer {
             id,
             client: RwLock::new(None),
             configuration: ContextServerTransport::Stdio(command),
+            request_handlers: RwLock::default(),
+            notification_handlers: RwLock::default(),
+        }
+    }
+
+    pub fn http(
+        id: ContextServerId,
+        url: ContextServerUrl,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Self {
+        Self {
+            id,
+            client: RwLock::new(None),
+            configuration: ContextServerTransport::Http { url, http_client },
+            request_handlers: RwLock::default(),
+            notification_handlers: RwLock::default(),
         }
     }
 
@@ -77,6 +147,8 @@ impl ContextServer {
             id,
             client: RwLock::new(None),
             configuration: ContextServerTransport::Custom(transport),
+            request_handlers: RwLock::default(),
+            notification_handlers: RwLock::default(),
         }
     }
 
@@ -88,6 +160,43 @@ impl ContextServer {
         self.client.read().clone()
     }
 
+    /// Registers a handler for the requests of the given type that the server makes of the
+    /// client. Handlers must be registered before the server is started, as the client
+    /// capabilities they provide, such as roots or sampling, are advertised when it's initialized.
+    pub fn on_request<T, F>(&self, handler: F)
+    where
+        T: types::Request,
+        F: 'static + Send + Sync + Fn(T::Params, AsyncApp) -> Task<Result<T::Response>>,
+    {
+        let handler = move |params: Value, cx: AsyncApp| {
+            let params: T::Params = match deserialize_params(params) {
+                Ok(params) => params,
+                Err(error) => return Task::ready(Err(error.into())),
+            };
+            let response = handler(params, cx.clone());
+            cx.spawn(async move |_| Ok(serde_json::to_value(response.await?)?))
+        };
+        self.request_handlers
+            .write()
+            .insert(T::METHOD, Arc::new(handler));
+    }
+
+    /// Registers a handler for the notifications of the given type that the server sends. Like
+    /// request handlers, these must be registered before the server is started.
+    pub fn on_notification<T, F>(&self, handler: F)
+    where
+        T: types::Notification,
+        F: 'static + Send + Sync + Fn(T::Params, AsyncApp),
+    {
+        let handler = move |params: Value, cx: AsyncApp| match deserialize_params(params) {
+            Ok(params) => handler(params, cx),
+            Err(error) => log::error!("invalid {} notification: {error}", T::METHOD),
+        };
+        self.notification_handlers
+            .write()
+            .insert(T::METHOD, Arc::new(handler));
+    }
+
     pub async fn start(self: Arc<Self>, cx: &AsyncApp) -> Result<()> {
         let client = match &self.configuration {
             ContextServerTransport::Stdio(command) => Client::stdio(
@@ -99,6 +208,36 @@ impl ContextServer {
                 },
                 cx.clone(),
             )?,
+            ContextServerTransport::Http { url, http_client } => {
+                log::info!("connecting to context server (url={:?})", url.url);
+                let endpoint = Url::parse(&url.url)
+                    .with_context(|| format!("invalid context server URL {:?}", url.url))?;
+                let transport: Arc<dyn crate::transport::Transport> = match url.transport {
+                    HttpTransportKind::StreamableHttp => {
+                        Arc::new(crate::transport::StreamableHttpTransport::new(
+                            endpoint,
+                            url.headers.clone(),
+                            http_client.clone(),
+                            cx,
+                        ))
+                    }
+                    HttpTransportKind::Sse => Arc::new(
+                        crate::transport::SseTransport::connect(
+                            endpoint,
+                            url.headers.clone(),
+                            http_client.clone(),
+                            cx,
+                        )
+                        .await?,
+                    ),
+                };
+                Client::new(
+                    client::ContextServerId(self.id.0.clone()),
+                    url.url.as_str().into(),
+                    transport,
+                    cx.clone(),
+                )?
+            }
             ContextServerTransport::Custom(transport) => Client::new(
                 client::ContextServerId(self.id.0.clone()),
                 self.id().0,
@@ -111,12 +250,32 @@ impl ContextServer {
 
     async fn initialize(&self, client: Client) -> Result<()> {
         log::info!("starting context server {}", self.id);
+        let capabilities = {
+            let request_handlers = self.request_handlers.read();
+            for (method, handler) in request_handlers.iter() {
+                let handler = handler.clone();
+                client.on_request(*method, move |params, cx| handler(params, cx));
+            }
+            for (method, handler) in self.notification_handlers.read().iter() {
+                let handler = handler.clone();
+                client.on_notification(*method, move |params, cx| handler(params, cx));
+            }
+            let handles = |method: &str| request_handlers.contains_key(method);
+            types::ClientCapabilities {
+                experimental: None,
+                sampling: handles(CreateMessage::METHOD).then(|| serde_json::json!({})),
+                roots: handles(ListRoots::METHOD).then_some(types::RootsCapabilities {
+                    list_changed: Some(true),
+                }),
+                elicitation: handles(CreateElicitation::METHOD).then(|| serde_json::json!({})),
+            }
+        };
         let protocol = crate::protocol::ModelContextProtocol::new(client);
         let client_info = types::Implementation {
             name: "Zed".to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
         };
-        let initialized_protocol = protocol.initialize(client_info).await?;
+        let initialized_protocol = protocol.initialize(client_info, capabilities).await?;
 
         log::debug!(
             "context server {} initialized: {:?}",
@@ -136,3 +295,95 @@ impl ContextServer {
         Ok(())
     }
 }
+
+/// Deserializes the parameters of a message from the server. Messages without parameters may
+/// still have an object holding their metadata.
+fn deserialize_params<T: DeserializeOwned>(params: Value) -> serde_json::Result<T> {
+    match params {
+        Value::Object(object) if object.keys().all(|key| key == "_meta") => {
+            serde_json::from_value(Value::Object(object))
+                .or_else(|_| serde_json::from_value(Value::Null))
+        }
+        params => serde_json::from_value(params),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use parking_lot::Mutex;
+
+    use crate::{
+        test::{FakeTransport, create_initialize_response},
+        types::{
+            CreateMessageRequest, ListRootsResponse, ResourcesUpdatedParams, Root,
+            notifications::ResourcesUpdated, requests::Initialize,
+        },
+    };
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_requests_from_server(cx: &mut TestAppContext) {
+        let capabilities = Arc::new(Mutex::new(None));
+        let transport = Arc::new(FakeTransport::new(cx.executor()).on_request::<Initialize>({
+            let capabilities = capabilities.clone();
+            move |params| {
+                *capabilities.lock() = serde_json::to_value(&params.capabilities).ok();
+                create_initialize_response("test".to_string())
+            }
+        }));
+        let server = Arc::new(ContextServer::new(
+            ContextServerId("test".into()),
+            transport.clone(),
+        ));
+        server.on_request::<ListRoots, _>(|_, _| {
+            Task::ready(Ok(ListRootsResponse {
+                roots: vec![Root {
+                    uri: "file:///project".parse().unwrap(),
+                    name: Some("project".to_string()),
+                }],
+                meta: None,
+            }))
+        });
+        let (updated_tx, mut updated_rx) = futures::channel::mpsc::unbounded();
+        server.on_notification::<ResourcesUpdated, _>(move |params, _| {
+            updated_tx.unbounded_send(params.uri).ok();
+        });
+        server.clone().start(&cx.to_async()).await.unwrap();
+
+        // Only the capabilities of the registered handlers are advertised.
+        assert_eq!(
+            capabilities.lock().take(),
+            Some(serde_json::json!({ "roots": { "listChanged": true } }))
+        );
+
+        let response = transport.request::<ListRoots>(()).await.unwrap();
+        assert_eq!(response.roots.len(), 1);
+        assert_eq!(response.roots[0].name.as_deref(), Some("project"));
+
+        let error = transport
+            .request::<CreateMessage>(CreateMessageRequest {
+                messages: Vec::new(),
+                model_preferences: None,
+                system_prompt: None,
+                include_context: None,
+                temperature: None,
+                max_tokens: 100,
+                stop_sequences: None,
+                metadata: None,
+            })
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("unhandled method"));
+
+        transport.notify::<ResourcesUpdated>(ResourcesUpdatedParams {
+            uri: "file:///project/a.txt".into(),
+        });
+        assert_eq!(
+            updated_rx.next().await.as_deref(),
+            Some("file:///project/a.txt")
+        );
+    }
+}
@@ -1,12 +1,31 @@
 use anyhow::Context as _;
 use collections::HashMap;
-use futures::{Stream, StreamExt as _, lock::Mutex};
+use futures::{
+    AsyncReadExt as _, Stream, StreamExt as _, TryStreamExt as _,
+    channel::{mpsc, oneshot},
+    lock::Mutex,
+};
 use gpui::BackgroundExecutor;
-use std::{pin::Pin, sync::Arc};
+use http_client::{AsyncBody, FakeHttpClient, HttpClientWithUrl, Method, Request, Response};
+use serde_json::{Value, json};
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering::SeqCst},
+    },
+};
 
 use crate::{
+    HttpTransportKind,
+    client::METHOD_NOT_FOUND,
     transport::Transport,
-    types::{Implementation, InitializeResponse, ProtocolVersion, ServerCapabilities},
+    types::{
+        Implementation, InitializeResponse, ProtocolVersion, Request as _, ServerCapabilities,
+        requests::Initialize,
+    },
 };
 
 pub fn create_fake_transport(
@@ -19,7 +38,7 @@ pub fn create_fake_transport(
     })
 }
 
-fn create_initialize_response(server_name: String) -> InitializeResponse {
+pub(crate) fn create_initialize_response(server_name: String) -> InitializeResponse {
     InitializeResponse {
         protocol_version: ProtocolVersion(crate::types::LATEST_PROTOCOL_VERSION.to_string()),
         server_info: Implementation {
@@ -37,6 +56,9 @@ pub struct FakeTransport {
     tx: futures::channel::mpsc::UnboundedSender<String>,
     rx: Arc<Mutex<futures::channel::mpsc::UnboundedReceiver<String>>>,
     executor: BackgroundExecutor,
+    next_client_request_id: AtomicUsize,
+    client_requests: parking_lot::Mutex<HashMap<String, oneshot::Sender<Value>>>,
+    notifications: parking_lot::Mutex<Vec<String>>,
 }
 
 impl FakeTransport {
@@ -47,9 +69,43 @@ impl FakeTransport {
             tx,
             rx: Arc::new(Mutex::new(rx)),
             executor,
+            next_client_request_id: AtomicUsize::new(0),
+            client_requests: Default::default(),
+            notifications: Default::default(),
+        }
+    }
+
+    /// Makes a request of the client, as servers do for its roots or for sampling.
+    pub fn request<T: crate::types::Request>(
+        &self,
+        params: T::Params,
+    ) -> impl Future<Output = anyhow::Result<T::Response>> + use<T> {
+        let id = format!("fake-{}", self.next_client_request_id.fetch_add(1, SeqCst));
+        let (response_tx, response_rx) = oneshot::channel();
+        self.client_requests.lock().insert(id.clone(), response_tx);
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": T::METHOD, "params": params });
+        self.tx.unbounded_send(request.to_string()).ok();
+        async move {
+            let response = response_rx.await?;
+            if let Some(error) = response.get("error") {
+                anyhow::bail!("client responded with an error: {error}");
+            }
+            let result = response.get("result").cloned().unwrap_or(Value::Null);
+            Ok(serde_json::from_value(result)?)
         }
     }
 
+    /// Sends a notification to the client, as servers do when their tools or resources change.
+    pub fn notify<T: crate::types::Notification>(&self, params: T::Params) {
+        let notification = json!({ "jsonrpc": "2.0", "method": T::METHOD, "params": params });
+        self.tx.unbounded_send(notification.to_string()).ok();
+    }
+
+    /// The methods of the notifications sent by the client.
+    pub fn notifications(&self) -> Vec<String> {
+        self.notifications.lock().clone()
+    }
+
     pub fn on_request<T: crate::types::Request>(
         mut self,
         handler: impl Fn(T::Params) -> T::Response + Send + Sync + 'static,
@@ -72,7 +128,18 @@ impl FakeTransport {
 impl Transport for FakeTransport {
     async fn send(&self, message: String) -> anyhow::Result<()> {
         if let Ok(msg) = serde_json::from_str::<serde_json::Value>(&message) {
-            let id = msg.get("id").and_then(|id| id.as_u64()).unwrap_or(0);
+            if let Some(client_request_id) = msg.get("id").and_then(Value::as_str) {
+                if let Some(response_tx) = self.client_requests.lock().remove(client_request_id) {
+                    response_tx.send(msg).ok();
+                    return Ok(());
+                }
+            }
+            let Some(id) = msg.get("id").and_then(|id| id.as_u64()) else {
+                if let Some(method) = msg.get("method").and_then(Value::as_str) {
+                    self.notifications.lock().push(method.to_string());
+                }
+                return Ok(());
+            };
 
             if let Some(method) = msg.get("method") {
                 let method = method.as_str().expect("Invalid method received");
@@ -116,3 +183,282 @@ impl Transport for FakeTransport {
         Box::pin(futures::stream::empty())
     }
 }
+
+/// Creates a [`FakeHttpServer`] that answers the `initialize` request as the named server.
+pub fn create_fake_http_server(
+    name: impl Into<String>,
+    transport: HttpTransportKind,
+) -> FakeHttpServer {
+    let name = name.into();
+    FakeHttpServer::new(transport)
+        .on_request::<Initialize>(move |_params| create_initialize_response(name.clone()))
+}
+
+/// A request received by a [`FakeHttpServer`].
+#[derive(Clone, Debug)]
+pub struct FakeHttpRequest {
+    pub method: Method,
+    pub path: String,
+    /// The headers of the request, by lowercase name.
+    pub headers: HashMap<String, String>,
+}
+
+type FakeRequestHandler = Arc<dyn Fn(Value) -> Value + Send + Sync>;
+
+/// An in-process stand-in for an MCP server running as an HTTP service, answering the requests
+/// made with [`FakeHttpServer::http_client`] over the given transport.
+///
+/// With Streamable HTTP, the response to `initialize` is a JSON body assigning a session, and the
+/// responses to other requests are event streams that can be resumed from their events.
+#[derive(Clone)]
+pub struct FakeHttpServer {
+    state: Arc<parking_lot::Mutex<FakeHttpServerState>>,
+}
+
+struct FakeHttpServerState {
+    transport: HttpTransportKind,
+    request_handlers: HashMap<&'static str, FakeRequestHandler>,
+    requests: Vec<FakeHttpRequest>,
+    session_id: Option<String>,
+    session_count: usize,
+    event_count: usize,
+    /// The events sent on streams, replayed on the streams resumed after one of them.
+    events: Vec<(usize, String)>,
+    streams: Vec<mpsc::UnboundedSender<io::Result<Vec<u8>>>>,
+    interrupt_responses: bool,
+    /// The endpoint sent at the start of SSE event streams.
+    sse_endpoint: String,
+}
+
+impl FakeHttpServer {
+    pub const URL: &str = "http://localhost:8080/mcp";
+
+    pub fn new(transport: HttpTransportKind) -> Self {
+        Self {
+            state: Arc::new(parking_lot::Mutex::new(FakeHttpServerState {
+                transport,
+                request_handlers: HashMap::default(),
+                requests: Vec::new(),
+                session_id: None,
+                session_count: 0,
+                event_count: 0,
+                events: Vec::new(),
+                streams: Vec::new(),
+                interrupt_responses: false,
+                sse_endpoint: "/messages?session=1".into(),
+            })),
+        }
+    }
+
+    pub fn on_request<T: crate::types::Request>(
+        self,
+        handler: impl Fn(T::Params) -> T::Response + Send + Sync + 'static,
+    ) -> Self {
+        self.state.lock().request_handlers.insert(
+            T::METHOD,
+            Arc::new(move |params| {
+                let params: T::Params =
+                    serde_json::from_value(params).expect("Invalid parameters received");
+                serde_json::to_value(handler(params)).unwrap()
+            }),
+        );
+        self
+    }
+
+    /// An HTTP client whose requests are answered by this server.
+    pub fn http_client(&self) -> Arc<HttpClientWithUrl> {
+        let state = self.state.clone();
+        FakeHttpClient::create(move |request| {
+            let state = state.clone();
+            async move { FakeHttpServerState::handle(state, request).await }
+        })
+    }
+
+    pub fn requests(&self) -> Vec<FakeHttpRequest> {
+        self.state.lock().requests.clone()
+    }
+
+    pub fn session_id(&self) -> Option<String> {
+        self.state.lock().session_id.clone()
+    }
+
+    /// Sends a message on every open event stream, as servers do for the notifications and
+    /// requests they send on their own.
+    pub fn send_message(&self, message: Value) {
+        let mut state = self.state.lock();
+        let id = state.record_event(message.to_string());
+        let event = event_bytes(id, &message.to_string());
+        state
+            .streams
+            .retain(|stream| stream.unbounded_send(Ok(event.clone())).is_ok());
+    }
+
+    /// Sets the endpoint the following SSE event streams tell clients to send their messages to.
+    pub fn set_sse_endpoint(&self, endpoint: impl Into<String>) {
+        self.state.lock().sse_endpoint = endpoint.into();
+    }
+
+    /// Breaks the event streams of the following responses before the response is sent, as when
+    /// the connection drops.
+    pub fn set_interrupt_responses(&self, interrupt: bool) {
+        self.state.lock().interrupt_responses = interrupt;
+    }
+
+    /// Forgets the current session, as when the server restarts.
+    pub fn expire_session(&self) {
+        self.state.lock().session_id = None;
+    }
+}
+
+impl FakeHttpServerState {
+    async fn handle(
+        state: Arc<parking_lot::Mutex<Self>>,
+        request: Request<AsyncBody>,
+    ) -> anyhow::Result<Response<AsyncBody>> {
+        let (parts, mut body) = request.into_parts();
+        let mut text = String::new();
+        body.read_to_string(&mut text).await?;
+        let headers = parts
+            .headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect::<HashMap<_, _>>();
+
+        let mut state = state.lock();
+        state.requests.push(FakeHttpRequest {
+            method: parts.method.clone(),
+            path: parts.uri.path().to_string(),
+            headers: headers.clone(),
+        });
+        match (state.transport, parts.method.as_str()) {
+            (_, "GET") => Ok(state.open_stream(headers.get("last-event-id"))),
+            (HttpTransportKind::StreamableHttp, "POST") => {
+                state.handle_streamable_http_post(&headers, &text)
+            }
+            (HttpTransportKind::Sse, "POST") => state.handle_sse_post(&text),
+            (HttpTransportKind::StreamableHttp, "DELETE") => {
+                state.session_id = None;
+                Ok(status(200))
+            }
+            _ => Ok(status(405)),
+        }
+    }
+
+    fn handle_streamable_http_post(
+        &mut self,
+        headers: &HashMap<String, String>,
+        text: &str,
+    ) -> anyhow::Result<Response<AsyncBody>> {
+        let message = serde_json::from_str::<Value>(text)?;
+        let is_initialize =
+            message.get("method").and_then(Value::as_str) == Some(Initialize::METHOD);
+        if is_initialize {
+            self.session_count += 1;
+            self.session_id = Some(format!("session-{}", self.session_count));
+        } else {
+            match headers.get("mcp-session-id") {
+                Some(session_id) if Some(session_id) == self.session_id.as_ref() => {}
+                Some(_) => return Ok(status(404)),
+                None => return Ok(status(400)),
+            }
+        }
+
+        let Some(response) = self.respond(&message) else {
+            return Ok(status(202));
+        };
+        if is_initialize {
+            return Ok(Response::builder()
+                .status(200)
+                .header("Content-Type", "application/json")
+                .header(
+                    "Mcp-Session-Id",
+                    self.session_id.clone().unwrap_or_default(),
+                )
+                .body(response.into())?);
+        }
+
+        // The response is preceded by an empty event, that the stream can be resumed after.
+        let (tx, rx) = mpsc::unbounded();
+        self.event_count += 1;
+        tx.unbounded_send(Ok(event_bytes(self.event_count, "")))
+            .ok();
+        let id = self.record_event(response.clone());
+        if self.interrupt_responses {
+            tx.unbounded_send(Err(io::ErrorKind::ConnectionReset.into()))
+                .ok();
+        } else {
+            tx.unbounded_send(Ok(event_bytes(id, &response))).ok();
+        }
+        stream_response(rx)
+    }
+
+    fn handle_sse_post(&mut self, text: &str) -> anyhow::Result<Response<AsyncBody>> {
+        let message = serde_json::from_str::<Value>(text)?;
+        if let Some(response) = self.respond(&message) {
+            let id = self.record_event(response.clone());
+            let event = event_bytes(id, &response);
+            self.streams
+                .retain(|stream| stream.unbounded_send(Ok(event.clone())).is_ok());
+        }
+        Ok(status(202))
+    }
+
+    /// Answers a request with the result of its handler, or returns `None` for other messages.
+    fn respond(&self, message: &Value) -> Option<String> {
+        let id = message.get("id")?;
+        let method = message.get("method")?.as_str()?;
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let response = match self.request_handlers.get(method) {
+            Some(handler) => json!({ "jsonrpc": "2.0", "id": id, "result": handler(params) }),
+            None => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": METHOD_NOT_FOUND, "message": format!("unknown method {method}") },
+            }),
+        };
+        Some(response.to_string())
+    }
+
+    fn open_stream(&mut self, last_event_id: Option<&String>) -> Response<AsyncBody> {
+        let (tx, rx) = mpsc::unbounded();
+        if self.transport == HttpTransportKind::Sse {
+            let event = format!("event: endpoint\ndata: {}\n\n", self.sse_endpoint);
+            tx.unbounded_send(Ok(event.into_bytes())).ok();
+        }
+        if let Some(last_event_id) = last_event_id.and_then(|id| id.parse::<usize>().ok()) {
+            for (id, data) in &self.events {
+                if *id > last_event_id {
+                    tx.unbounded_send(Ok(event_bytes(*id, data))).ok();
+                }
+            }
+        }
+        self.streams.push(tx);
+        stream_response(rx).unwrap()
+    }
+
+    fn record_event(&mut self, data: String) -> usize {
+        self.event_count += 1;
+        self.events.push((self.event_count, data));
+        self.event_count
+    }
+}
+
+fn event_bytes(id: usize, data: &str) -> Vec<u8> {
+    format!("id: {id}\ndata: {data}\n\n").into_bytes()
+}
+
+fn status(status: u16) -> Response<AsyncBody> {
+    Response::builder()
+        .status(status)
+        .body(AsyncBody::empty())
+        .unwrap()
+}
+
+fn stream_response(
+    rx: mpsc::UnboundedReceiver<io::Result<Vec<u8>>>,
+) -> anyhow::Result<Response<AsyncBody>> {
+    Ok(Response::builder()
+        .status(200)
+        .header("Content-Type", "text/event-stream")
+        .body(AsyncBody::from_reader(rx.into_async_read()))?)
+}
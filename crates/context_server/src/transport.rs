@@ -1,4 +1,7 @@
+mod event_stream;
+mod sse_transport;
 mod stdio_transport;
+mod streamable_http_transport;
 
 use std::pin::Pin;
 
@@ -6,7 +9,9 @@ use anyhow::Result;
 use async_trait::async_trait;
 use futures::Stream;
 
+pub use sse_transport::*;
 pub use stdio_transport::*;
+pub use streamable_http_transport::*;
 
 #[async_trait]
 pub trait Transport: Send + Sync {
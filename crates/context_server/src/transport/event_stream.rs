@@ -0,0 +1,134 @@
+use std::{mem, time::Duration};
+
+use anyhow::Result;
+use futures::{AsyncBufReadExt as _, AsyncRead, io::BufReader};
+
+/// An event of a `text/event-stream` response body.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct ServerSentEvent {
+    pub event: Option<String>,
+    pub data: String,
+    pub id: Option<String>,
+    pub retry: Option<Duration>,
+}
+
+impl ServerSentEvent {
+    /// Whether this event carries a JSON-RPC message, which MCP servers send as events of the
+    /// default type.
+    pub fn is_message(&self) -> bool {
+        !self.data.is_empty() && self.event.as_deref().is_none_or(|event| event == "message")
+    }
+}
+
+/// Assembles the events of an event stream from its lines, following
+/// <https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation>.
+#[derive(Default)]
+pub(crate) struct EventStreamParser {
+    event: ServerSentEvent,
+    has_data: bool,
+    has_fields: bool,
+}
+
+impl EventStreamParser {
+    /// Feeds a line without its line ending, returning the event completed by a blank line.
+    pub fn feed_line(&mut self, line: &str) -> Option<ServerSentEvent> {
+        if line.is_empty() {
+            self.has_data = false;
+            return mem::take(&mut self.has_fields).then(|| mem::take(&mut self.event));
+        }
+        // Lines starting with a colon are comments, often sent to keep the connection alive.
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event.event = Some(value.to_string()),
+            "data" => {
+                if mem::replace(&mut self.has_data, true) {
+                    self.event.data.push('\n');
+                }
+                self.event.data.push_str(value);
+            }
+            "id" => self.event.id = Some(value.to_string()),
+            "retry" => self.event.retry = value.parse().ok().map(Duration::from_millis),
+            _ => return None,
+        }
+        self.has_fields = true;
+        None
+    }
+}
+
+/// Reads the events of a response body until it ends, passing each one to `on_event`.
+pub(crate) async fn read_events(
+    body: impl AsyncRead + Unpin,
+    mut on_event: impl FnMut(ServerSentEvent),
+) -> Result<()> {
+    let mut reader = BufReader::new(body);
+    let mut parser = EventStreamParser::default();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(());
+        }
+        if let Some(event) = parser.feed_line(line.trim_end_matches(['\r', '\n'])) {
+            on_event(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_read_events() {
+        let body = concat!(
+            ": keep-alive\n",
+            "event: endpoint\r\n",
+            "data: /messages\r\n",
+            "\r\n",
+            "id: 1\n",
+            "retry: 500\n",
+            "data: {\"a\":\n",
+            "data: 1}\n",
+            "\n",
+            "id: 2\n",
+            "data\n",
+            "\n",
+            "\n",
+            "data: unterminated\n",
+        );
+        let mut events = Vec::new();
+        read_events(body.as_bytes(), |event| events.push(event))
+            .await
+            .unwrap();
+        assert_eq!(
+            events,
+            [
+                ServerSentEvent {
+                    event: Some("endpoint".into()),
+                    data: "/messages".into(),
+                    ..Default::default()
+                },
+                ServerSentEvent {
+                    data: "{\"a\":\n1}".into(),
+                    id: Some("1".into()),
+                    retry: Some(Duration::from_millis(500)),
+                    ..Default::default()
+                },
+                ServerSentEvent {
+                    id: Some("2".into()),
+                    ..Default::default()
+                },
+            ]
+        );
+        assert!(!events[0].is_message());
+        assert!(events[1].is_message());
+        assert!(!events[2].is_message());
+    }
+}
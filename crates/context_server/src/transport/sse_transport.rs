@@ -0,0 +1,239 @@
+use std::{pin::Pin, sync::Arc};
+
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::{AsyncReadExt as _, Stream, channel::oneshot};
+use gpui::{AsyncApp, Task};
+use http_client::{AsyncBody, HttpClient, Method, Request, Url, http::header};
+use smol::channel;
+
+use crate::transport::Transport;
+use crate::transport::event_stream::read_events;
+
+/// The HTTP with Server-Sent Events transport of earlier MCP versions, which some servers still
+/// speak instead of Streamable HTTP.
+///
+/// The server sends its messages on an event stream opened with a GET request, starting with an
+/// `endpoint` event holding the URL that messages are POSTed to.
+pub struct SseTransport {
+    endpoint: Url,
+    headers: HashMap<String, String>,
+    http_client: Arc<dyn HttpClient>,
+    message_rx: channel::Receiver<String>,
+    error_rx: channel::Receiver<String>,
+    _stream_task: Task<()>,
+}
+
+impl SseTransport {
+    /// Opens the event stream and waits for the server to send the endpoint of its messages.
+    pub async fn connect(
+        url: Url,
+        headers: HashMap<String, String>,
+        http_client: Arc<dyn HttpClient>,
+        cx: &AsyncApp,
+    ) -> Result<Self> {
+        let mut request = Request::builder()
+            .method(Method::GET)
+            .uri(url.as_str())
+            .header(header::ACCEPT, "text/event-stream");
+        for (name, value) in &headers {
+            request = request.header(name, value);
+        }
+        let response = http_client.send(request.body(AsyncBody::empty())?).await?;
+        let status = response.status();
+        if !status.is_success() {
+            let mut body = String::new();
+            response.into_body().read_to_string(&mut body).await.ok();
+            return Err(anyhow!("context server responded with {status}: {body}"));
+        }
+
+        let (message_tx, message_rx) = channel::unbounded();
+        let (error_tx, error_rx) = channel::unbounded();
+        let (endpoint_tx, endpoint_rx) = oneshot::channel();
+        let body = response.into_body();
+        let stream_task = cx.background_executor().spawn(async move {
+            let mut endpoint_tx = Some(endpoint_tx);
+            let result = read_events(body, |event| {
+                if event.event.as_deref() == Some("endpoint") {
+                    if let Some(endpoint_tx) = endpoint_tx.take() {
+                        endpoint_tx.send(event.data).ok();
+                    }
+                } else if event.is_message() {
+                    message_tx.try_send(event.data).ok();
+                }
+            })
+            .await;
+            if let Err(error) = result {
+                error_tx
+                    .try_send(format!("event stream failed: {error}"))
+                    .ok();
+            }
+        });
+
+        let endpoint = endpoint_rx
+            .await
+            .context("context server closed its event stream before sending an endpoint")?;
+        let endpoint = url
+            .join(&endpoint)
+            .with_context(|| format!("invalid context server endpoint {endpoint:?}"))?;
+        // Messages carry the configured headers, which often hold credentials, so they must
+        // not be sent anywhere but the server that was configured.
+        if endpoint.origin() != url.origin() {
+            return Err(anyhow!(
+                "context server endpoint {endpoint} is not on the same origin as {url}"
+            ));
+        }
+
+        Ok(Self {
+            endpoint,
+            headers,
+            http_client,
+            message_rx,
+            error_rx,
+            _stream_task: stream_task,
+        })
+    }
+}
+
+#[async_trait]
+impl Transport for SseTransport {
+    async fn send(&self, message: String) -> Result<()> {
+        let mut request = Request::builder()
+            .method(Method::POST)
+            .uri(self.endpoint.as_str())
+            .header(header::CONTENT_TYPE, "application/json");
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        let response = self
+            .http_client
+            .send(request.body(AsyncBody::from(message))?)
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let mut body = String::new();
+            response.into_body().read_to_string(&mut body).await.ok();
+            return Err(anyhow!("context server responded with {status}: {body}"));
+        }
+        Ok(())
+    }
+
+    fn receive(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(self.message_rx.clone())
+    }
+
+    fn receive_err(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(self.error_rx.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    use crate::{
+        ContextServer, ContextServerId, ContextServerUrl, HttpTransportKind,
+        test::{FakeHttpServer, create_fake_http_server},
+        types::{ListToolsResponse, requests::ListTools},
+    };
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_sse_transport(cx: &mut TestAppContext) {
+        let server = create_fake_http_server("test", HttpTransportKind::Sse)
+            .on_request::<ListTools>(|_| ListToolsResponse {
+                tools: Vec::new(),
+                next_cursor: Some("next".into()),
+                meta: None,
+            });
+        let context_server = Arc::new(ContextServer::http(
+            ContextServerId("test".into()),
+            ContextServerUrl {
+                url: FakeHttpServer::URL.into(),
+                headers: [("Authorization".into(), "Bearer secret".into())]
+                    .into_iter()
+                    .collect(),
+                transport: HttpTransportKind::Sse,
+            },
+            server.http_client(),
+        ));
+        context_server.clone().start(&cx.to_async()).await.unwrap();
+        let client = context_server.client().unwrap();
+        let response = client.request::<ListTools>(()).await.unwrap();
+        assert_eq!(response.next_cursor.as_deref(), Some("next"));
+
+        // Messages are posted to the endpoint sent by the server, with the configured headers.
+        let requests = server.requests();
+        assert_eq!(requests[0].method, Method::GET);
+        assert!(
+            requests[1..]
+                .iter()
+                .all(|request| { request.method == Method::POST && request.path == "/messages" })
+        );
+        assert!(requests.iter().all(|request| {
+            request.headers.get("authorization").map(String::as_str) == Some("Bearer secret")
+        }));
+
+        // Messages the server sends on its own arrive on the event stream.
+        let transport = SseTransport::connect(
+            Url::parse(FakeHttpServer::URL).unwrap(),
+            HashMap::default(),
+            server.http_client(),
+            &cx.to_async(),
+        )
+        .await
+        .unwrap();
+        let mut messages = transport.receive();
+        let notification =
+            json!({ "jsonrpc": "2.0", "method": "notifications/tools/list_changed" });
+        server.send_message(notification.clone());
+        let message = messages.next().await.unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&message).unwrap(),
+            notification
+        );
+    }
+
+    #[gpui::test]
+    async fn test_sse_endpoint_on_another_origin(cx: &mut TestAppContext) {
+        let server = create_fake_http_server("test", HttpTransportKind::Sse);
+        for endpoint in [
+            "https://localhost:8080/messages",
+            "http://example.com/messages",
+            "http://localhost:9090/messages",
+            "//example.com/messages",
+        ] {
+            server.set_sse_endpoint(endpoint);
+            let result = SseTransport::connect(
+                Url::parse(FakeHttpServer::URL).unwrap(),
+                HashMap::default(),
+                server.http_client(),
+                &cx.to_async(),
+            )
+            .await;
+            assert!(
+                result.is_err_and(|error| error.to_string().contains("same origin")),
+                "endpoint {endpoint} was accepted"
+            );
+        }
+
+        // Absolute endpoints on the configured origin are fine.
+        server.set_sse_endpoint("http://localhost:8080/messages?session=2");
+        let transport = SseTransport::connect(
+            Url::parse(FakeHttpServer::URL).unwrap(),
+            HashMap::default(),
+            server.http_client(),
+            &cx.to_async(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            transport.endpoint.as_str(),
+            "http://localhost:8080/messages?session=2"
+        );
+    }
+}
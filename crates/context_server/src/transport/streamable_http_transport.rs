@@ -0,0 +1,447 @@
+use std::{pin::Pin, sync::Arc, time::Duration};
+
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::{AsyncReadExt as _, Stream};
+use gpui::{AsyncApp, BackgroundExecutor, Task};
+use http_client::{
+    AsyncBody, HttpClient, Method, Request, Response, StatusCode, Url, http::header,
+};
+use parking_lot::Mutex;
+use serde_json::Value;
+use smol::channel;
+
+use crate::transport::Transport;
+use crate::transport::event_stream::{ServerSentEvent, read_events};
+
+const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_ATTEMPTS: usize = 5;
+
+/// The Streamable HTTP transport of MCP, for servers running as HTTP services.
+///
+/// Every message is POSTed to the server's endpoint, which answers with a JSON body or with an
+/// event stream of messages. Once initialized, a GET request opens a stream for the messages the
+/// server sends on its own. Streams that break are resumed from their last event, and the
+/// session the server assigns is sent with every request and ended when the transport is dropped.
+pub struct StreamableHttpTransport {
+    connection: Arc<Connection>,
+    message_rx: channel::Receiver<String>,
+    error_rx: channel::Receiver<String>,
+    executor: BackgroundExecutor,
+    listen_task: Mutex<Option<Task<()>>>,
+}
+
+struct Connection {
+    url: Url,
+    headers: HashMap<String, String>,
+    http_client: Arc<dyn HttpClient>,
+    session_id: Mutex<Option<String>>,
+    message_tx: channel::Sender<String>,
+    error_tx: channel::Sender<String>,
+}
+
+impl StreamableHttpTransport {
+    pub fn new(
+        url: Url,
+        headers: HashMap<String, String>,
+        http_client: Arc<dyn HttpClient>,
+        cx: &AsyncApp,
+    ) -> Self {
+        let (message_tx, message_rx) = channel::unbounded();
+        let (error_tx, error_rx) = channel::unbounded();
+        Self {
+            connection: Arc::new(Connection {
+                url,
+                headers,
+                http_client,
+                session_id: Mutex::new(None),
+                message_tx,
+                error_tx,
+            }),
+            message_rx,
+            error_rx,
+            executor: cx.background_executor().clone(),
+            listen_task: Mutex::new(None),
+        }
+    }
+
+    /// The id of the session assigned by the server, if any.
+    pub fn session_id(&self) -> Option<String> {
+        self.connection.session_id.lock().clone()
+    }
+
+    /// Opens the stream of the messages the server sends on its own, keeping it open until the
+    /// transport is dropped.
+    fn listen(&self) {
+        let connection = self.connection.clone();
+        let executor = self.executor.clone();
+        let task = self.executor.spawn(async move {
+            match connection.open_stream(None).await {
+                Ok(Some(body)) => connection.read_stream(body, true, executor).await,
+                // The server doesn't offer a stream of its own.
+                Ok(None) => {}
+                Err(error) => connection.report(format!("failed to open event stream: {error}")),
+            }
+        });
+        *self.listen_task.lock() = Some(task);
+    }
+}
+
+impl Connection {
+    fn request(&self, method: Method) -> http_client::http::request::Builder {
+        let mut request = Request::builder().method(method).uri(self.url.as_str());
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        if let Some(session_id) = self.session_id.lock().as_ref() {
+            request = request.header(SESSION_ID_HEADER, session_id);
+        }
+        request
+    }
+
+    /// Opens an event stream with a GET request, resuming after the given event when there's
+    /// one. Returns `None` when the server doesn't support such streams.
+    async fn open_stream(&self, last_event_id: Option<&str>) -> Result<Option<AsyncBody>> {
+        let mut request = self
+            .request(Method::GET)
+            .header(header::ACCEPT, "text/event-stream");
+        if let Some(last_event_id) = last_event_id {
+            request = request.header(LAST_EVENT_ID_HEADER, last_event_id);
+        }
+        let response = self
+            .http_client
+            .send(request.body(AsyncBody::empty())?)
+            .await?;
+        if response.status() == StatusCode::METHOD_NOT_ALLOWED {
+            return Ok(None);
+        }
+        Ok(Some(check_status(response).await?.into_body()))
+    }
+
+    /// Forwards the messages of an event stream. Listening streams are reopened whenever they
+    /// end, and other streams only when they break after an event that they can be resumed from.
+    async fn read_stream(
+        &self,
+        mut body: AsyncBody,
+        is_listening: bool,
+        executor: BackgroundExecutor,
+    ) {
+        let mut last_event_id = None;
+        let mut delay = RECONNECT_DELAY;
+        loop {
+            let result = read_events(&mut body, |event| {
+                if let Some(retry) = event.retry {
+                    delay = retry;
+                }
+                if let Some(id) = &event.id {
+                    last_event_id = Some(id.clone());
+                }
+                self.forward_event(event);
+            })
+            .await;
+            match result {
+                Ok(()) if !is_listening => return,
+                Ok(()) => {}
+                Err(error) => {
+                    if !is_listening && last_event_id.is_none() {
+                        self.report(format!("event stream failed: {error}"));
+                        return;
+                    }
+                    self.report(format!("event stream interrupted, resuming: {error}"));
+                }
+            }
+
+            let mut attempts = 0;
+            body = loop {
+                if self.message_tx.is_closed() {
+                    return;
+                }
+                if attempts == MAX_RECONNECT_ATTEMPTS {
+                    self.report(format!(
+                        "gave up resuming event stream after {attempts} attempts"
+                    ));
+                    return;
+                }
+                attempts += 1;
+                executor.timer(delay).await;
+                match self.open_stream(last_event_id.as_deref()).await {
+                    Ok(Some(body)) => break body,
+                    Ok(None) => return,
+                    Err(error) => self.report(format!("failed to resume event stream: {error}")),
+                }
+            };
+        }
+    }
+
+    fn forward_event(&self, event: ServerSentEvent) {
+        if event.is_message() {
+            self.message_tx.try_send(event.data).ok();
+        }
+    }
+
+    /// Forwards the message or batch of messages of a JSON response.
+    fn forward_json(&self, body: &str) -> Result<()> {
+        let body = body.trim();
+        if body.is_empty() {
+            return Ok(());
+        }
+        match serde_json::from_str::<Value>(body)? {
+            Value::Array(messages) => {
+                for message in messages {
+                    self.message_tx.try_send(message.to_string()).ok();
+                }
+            }
+            _ => {
+                self.message_tx.try_send(body.to_string()).ok();
+            }
+        }
+        Ok(())
+    }
+
+    fn report(&self, error: String) {
+        self.error_tx.try_send(error).ok();
+    }
+}
+
+async fn check_status(response: Response<AsyncBody>) -> Result<Response<AsyncBody>> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let mut body = String::new();
+    response.into_body().read_to_string(&mut body).await.ok();
+    Err(anyhow!("context server responded with {status}: {body}"))
+}
+
+fn is_initialized_notification(message: &str) -> bool {
+    serde_json::from_str::<Value>(message).is_ok_and(|message| {
+        message.get("method").and_then(Value::as_str) == Some("notifications/initialized")
+    })
+}
+
+#[async_trait]
+impl Transport for StreamableHttpTransport {
+    async fn send(&self, message: String) -> Result<()> {
+        let is_initialized_notification = is_initialized_notification(&message);
+        let request = self
+            .connection
+            .request(Method::POST)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::ACCEPT, "application/json, text/event-stream")
+            .body(AsyncBody::from(message))?;
+        let response = self.connection.http_client.send(request).await?;
+
+        // Servers forget sessions, such as when they restart, after which they must be
+        // initialized again.
+        if response.status() == StatusCode::NOT_FOUND
+            && self.connection.session_id.lock().take().is_some()
+        {
+            anyhow::bail!("context server session expired");
+        }
+        let response = check_status(response).await?;
+
+        if let Some(session_id) = response
+            .headers()
+            .get(SESSION_ID_HEADER)
+            .and_then(|session_id| session_id.to_str().ok())
+        {
+            *self.connection.session_id.lock() = Some(session_id.to_string());
+        }
+
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        if content_type.starts_with("text/event-stream") {
+            let connection = self.connection.clone();
+            let executor = self.executor.clone();
+            let body = response.into_body();
+            self.executor
+                .spawn(async move { connection.read_stream(body, false, executor).await })
+                .detach();
+        } else if content_type.starts_with("application/json") {
+            let mut body = String::new();
+            response
+                .into_body()
+                .read_to_string(&mut body)
+                .await
+                .context("reading context server response")?;
+            self.connection.forward_json(&body)?;
+        }
+
+        if is_initialized_notification {
+            self.listen();
+        }
+        Ok(())
+    }
+
+    fn receive(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(self.message_rx.clone())
+    }
+
+    fn receive_err(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(self.error_rx.clone())
+    }
+}
+
+impl Drop for StreamableHttpTransport {
+    fn drop(&mut self) {
+        // Let the server release the session rather than waiting for it to expire.
+        if self.connection.session_id.lock().is_none() {
+            return;
+        }
+        if let Ok(request) = self
+            .connection
+            .request(Method::DELETE)
+            .body(AsyncBody::empty())
+        {
+            self.executor
+                .spawn(self.connection.http_client.send(request))
+                .detach();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    use crate::{
+        ContextServer, ContextServerId, ContextServerUrl, HttpTransportKind,
+        test::{FakeHttpServer, create_fake_http_server},
+        types::{ListToolsResponse, requests::ListTools},
+    };
+
+    use super::*;
+
+    fn list_tools_response(_: ()) -> ListToolsResponse {
+        ListToolsResponse {
+            tools: Vec::new(),
+            next_cursor: Some("next".into()),
+            meta: None,
+        }
+    }
+
+    fn header<'a>(request: &'a crate::test::FakeHttpRequest, name: &str) -> Option<&'a str> {
+        request.headers.get(name).map(String::as_str)
+    }
+
+    #[gpui::test]
+    async fn test_session_and_headers(cx: &mut TestAppContext) {
+        let server = create_fake_http_server("test", HttpTransportKind::StreamableHttp)
+            .on_request::<ListTools>(list_tools_response);
+        let context_server = Arc::new(ContextServer::http(
+            ContextServerId("test".into()),
+            ContextServerUrl {
+                url: FakeHttpServer::URL.into(),
+                headers: [("Authorization".into(), "Bearer secret".into())]
+                    .into_iter()
+                    .collect(),
+                transport: HttpTransportKind::StreamableHttp,
+            },
+            server.http_client(),
+        ));
+        context_server.clone().start(&cx.to_async()).await.unwrap();
+        let client = context_server.client().unwrap();
+        let response = client.request::<ListTools>(()).await.unwrap();
+        assert_eq!(response.next_cursor.as_deref(), Some("next"));
+
+        // Every request has the configured headers, and the ones after `initialize` its session.
+        let requests = server.requests();
+        assert_eq!(server.session_id().as_deref(), Some("session-1"));
+        assert!(
+            requests
+                .iter()
+                .all(|request| header(request, "authorization") == Some("Bearer secret"))
+        );
+        assert_eq!(header(&requests[0], "mcp-session-id"), None);
+        assert!(
+            requests[1..]
+                .iter()
+                .all(|request| header(request, "mcp-session-id") == Some("session-1"))
+        );
+
+        // Once initialized, a stream is opened for the messages the server sends on its own.
+        cx.run_until_parked();
+        assert!(
+            server
+                .requests()
+                .iter()
+                .any(|request| request.method == Method::GET)
+        );
+
+        // Dropping the transport ends the session.
+        drop(client);
+        context_server.stop().unwrap();
+        cx.run_until_parked();
+        assert_eq!(server.session_id(), None);
+        assert_eq!(server.requests().last().unwrap().method, Method::DELETE);
+    }
+
+    #[gpui::test]
+    async fn test_resuming_interrupted_response(cx: &mut TestAppContext) {
+        let server = create_fake_http_server("test", HttpTransportKind::StreamableHttp)
+            .on_request::<ListTools>(list_tools_response);
+        let context_server = Arc::new(ContextServer::http(
+            ContextServerId("test".into()),
+            ContextServerUrl {
+                url: FakeHttpServer::URL.into(),
+                headers: HashMap::default(),
+                transport: HttpTransportKind::StreamableHttp,
+            },
+            server.http_client(),
+        ));
+        context_server.clone().start(&cx.to_async()).await.unwrap();
+        let client = context_server.client().unwrap();
+
+        server.set_interrupt_responses(true);
+        let response = cx.spawn(|_| async move { client.request::<ListTools>(()).await });
+        cx.run_until_parked();
+        cx.executor().advance_clock(RECONNECT_DELAY);
+        let response = response.await.unwrap();
+        assert_eq!(response.next_cursor.as_deref(), Some("next"));
+
+        // The response was replayed on a stream resumed after the event preceding it.
+        let resumed = server
+            .requests()
+            .into_iter()
+            .filter(|request| header(request, "last-event-id").is_some())
+            .count();
+        assert_eq!(resumed, 1);
+    }
+
+    #[gpui::test]
+    async fn test_expired_session(cx: &mut TestAppContext) {
+        let server = create_fake_http_server("test", HttpTransportKind::StreamableHttp);
+        let transport = StreamableHttpTransport::new(
+            Url::parse(FakeHttpServer::URL).unwrap(),
+            HashMap::default(),
+            server.http_client(),
+            &cx.to_async(),
+        );
+        let initialize = json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": { "name": "test", "version": "1" },
+            },
+        });
+        transport.send(initialize.to_string()).await.unwrap();
+        assert_eq!(transport.session_id().as_deref(), Some("session-1"));
+
+        server.expire_session();
+        let ping = json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" });
+        let error = transport.send(ping.to_string()).await.unwrap_err();
+        assert_eq!(error.to_string(), "context server session expired");
+        assert_eq!(transport.session_id(), None);
+    }
+}
@@ -0,0 +1,196 @@
+//! Expansion of the URI templates that resource templates are described with, following
+//! <https://datatracker.ietf.org/doc/html/rfc6570>.
+
+use anyhow::{Result, anyhow, bail};
+use collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UriTemplate {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Expression {
+        operator: Option<char>,
+        variables: Vec<Variable>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Variable {
+    name: String,
+    prefix: Option<usize>,
+}
+
+impl UriTemplate {
+    pub fn parse(template: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                parts.push(Part::Literal(rest[..start].to_string()));
+            }
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| anyhow!("unterminated expression in URI template {template:?}"))?
+                + start;
+            parts.push(Self::parse_expression(&rest[start + 1..end])?);
+            rest = &rest[end + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Literal(rest.to_string()));
+        }
+        Ok(Self { parts })
+    }
+
+    fn parse_expression(expression: &str) -> Result<Part> {
+        let operator = expression
+            .chars()
+            .next()
+            .filter(|char| "+#./;?&".contains(*char));
+        let variables = expression[operator.map_or(0, char::len_utf8)..]
+            .split(',')
+            .map(|variable| {
+                // Lists and maps can't be given as values, so exploding them is the same as
+                // expanding the variable.
+                let variable = variable.trim_end_matches('*');
+                let (name, prefix) = match variable.split_once(':') {
+                    Some((name, prefix)) => (name, Some(prefix.parse()?)),
+                    None => (variable, None),
+                };
+                if name.is_empty() {
+                    bail!("empty variable name in URI template expression {expression:?}");
+                }
+                Ok(Variable {
+                    name: name.to_string(),
+                    prefix,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Part::Expression {
+            operator,
+            variables,
+        })
+    }
+
+    /// The names of the template's variables, in the order they appear.
+    pub fn variables(&self) -> impl Iterator<Item = &str> {
+        self.parts
+            .iter()
+            .flat_map(|part| match part {
+                Part::Literal(_) => &[][..],
+                Part::Expression { variables, .. } => variables.as_slice(),
+            })
+            .map(|variable| variable.name.as_str())
+    }
+
+    /// Expands the template with the given values. Variables without a value are left out.
+    pub fn expand(&self, values: &HashMap<String, String>) -> String {
+        let mut uri = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(literal) => uri.push_str(literal),
+                Part::Expression {
+                    operator,
+                    variables,
+                } => {
+                    let (first, separator, named, if_empty, allow_reserved) = match operator {
+                        None => ("", ",", false, "", false),
+                        Some('+') => ("", ",", false, "", true),
+                        Some('#') => ("#", ",", false, "", true),
+                        Some('.') => (".", ".", false, "", false),
+                        Some('/') => ("/", "/", false, "", false),
+                        Some(';') => (";", ";", true, "", false),
+                        Some('?') => ("?", "&", true, "=", false),
+                        Some(_) => ("&", "&", true, "=", false),
+                    };
+                    let mut is_first = true;
+                    for variable in variables {
+                        let Some(value) = values.get(&variable.name) else {
+                            continue;
+                        };
+                        uri.push_str(if is_first { first } else { separator });
+                        is_first = false;
+                        if named {
+                            uri.push_str(&variable.name);
+                            if value.is_empty() {
+                                uri.push_str(if_empty);
+                                continue;
+                            }
+                            uri.push('=');
+                        }
+                        let value = match variable.prefix {
+                            Some(prefix) => value.chars().take(prefix).collect(),
+                            None => value.clone(),
+                        };
+                        encode(&value, allow_reserved, &mut uri);
+                    }
+                }
+            }
+        }
+        uri
+    }
+}
+
+fn encode(value: &str, allow_reserved: bool, uri: &mut String) {
+    for char in value.chars() {
+        let is_unreserved = char.is_ascii_alphanumeric() || "-._~".contains(char);
+        let is_reserved = ":/?#[]@!$&'()*+,;=%".contains(char);
+        if is_unreserved || (allow_reserved && is_reserved) {
+            uri.push(char);
+        } else {
+            let mut buffer = [0; 4];
+            for byte in char.encode_utf8(&mut buffer).bytes() {
+                uri.push_str(&format!("%{byte:02X}"));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_uri_template() {
+        let values: HashMap<String, String> = [
+            ("var", "value"),
+            ("hello", "Hello World!"),
+            ("path", "/foo/bar"),
+            ("x", "1024"),
+            ("y", "768"),
+            ("empty", ""),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+        let expand = |template: &str| UriTemplate::parse(template).unwrap().expand(&values);
+
+        assert_eq!(expand("file:///{var}"), "file:///value");
+        assert_eq!(expand("{hello}"), "Hello%20World%21");
+        assert_eq!(expand("{+path}/here"), "/foo/bar/here");
+        assert_eq!(expand("{path}"), "%2Ffoo%2Fbar");
+        assert_eq!(expand("X{#path}"), "X#/foo/bar");
+        assert_eq!(expand("map{?x,y,undefined}"), "map?x=1024&y=768");
+        assert_eq!(expand("?fixed=yes{&x}"), "?fixed=yes&x=1024");
+        assert_eq!(expand("{/var,x}/here"), "/value/1024/here");
+        assert_eq!(expand("X{.var}"), "X.value");
+        assert_eq!(expand("{;x,empty}"), ";x=1024;empty");
+        assert_eq!(expand("{var:3}"), "val");
+        assert_eq!(expand("db://{undefined}tables"), "db://tables");
+    }
+
+    #[test]
+    fn test_uri_template_variables() {
+        let template =
+            UriTemplate::parse("repo://{owner}/{repo}/blob{/path*}{?ref,depth:2}").unwrap();
+        assert_eq!(
+            template.variables().collect::<Vec<_>>(),
+            ["owner", "repo", "path", "ref", "depth"]
+        );
+        assert!(UriTemplate::parse("file:///{path").is_err());
+        assert!(UriTemplate::parse("file:///{}").is_err());
+    }
+}
@@ -1076,6 +1076,8 @@ impl SearchableItem for DapLogView {
             case: true,
             word: true,
             regex: true,
+            structural: false,
+            syntax: false,
             find_in_results: true,
             // DAP log is read-only.
             replacement: false,
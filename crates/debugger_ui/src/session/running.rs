@@ -1184,6 +1184,8 @@ impl RunningState {
             show_summary: false,
             show_command: false,
             show_rerun: false,
+            test_results: None,
+            problem_matchers: Vec::new(),
         });
 
         let workspace = self.workspace.clone();
@@ -404,6 +404,9 @@ actions!(
         DuplicateLineUp,
         /// Duplicates the current selection.
         DuplicateSelection,
+        /// Enables syntax highlighting, language servers and git diffs for buffers that were
+        /// opened in large-file mode.
+        ExitLargeFileMode,
         /// Expands all diff hunks in the editor.
         #[action(deprecated_aliases = ["editor::ExpandAllHunkDiffs"])]
         ExpandAllDiffHunks,
@@ -596,6 +599,14 @@ actions!(
         SelectAll,
         /// Selects all matches of the current selection.
         SelectAllMatches,
+        /// Extends the rectangular block selection down by one line.
+        SelectBlockDown,
+        /// Extends the rectangular block selection left by one column.
+        SelectBlockLeft,
+        /// Extends the rectangular block selection right by one column, past the end of the line.
+        SelectBlockRight,
+        /// Extends the rectangular block selection up by one line.
+        SelectBlockUp,
         /// Selects to the start of the current excerpt.
         SelectToStartOfExcerpt,
         /// Selects to the start of the next excerpt.
@@ -723,6 +734,10 @@ actions!(
         Undo,
         /// Undoes the last selection change.
         UndoSelection,
+        /// Moves to the text state that was created right after the current one, across undo branches.
+        UndoTreeNewer,
+        /// Moves to the text state that was created right before the current one, across undo branches.
+        UndoTreeOlder,
         /// Unfolds all folded regions.
         UnfoldAll,
         /// Unfolds lines at cursor.
@@ -537,6 +537,30 @@ impl DisplayMap {
             .update(cx, |map, cx| map.set_wrap_width(width, cx))
     }
 
+    /// Sets whether lines are only soft wrapped once they're passed to [`Self::wrap_rows`].
+    pub fn set_wrap_lazily(&self, wrap_lazily: bool, cx: &mut Context<Self>) -> bool {
+        self.wrap_map
+            .update(cx, |map, cx| map.set_wrap_lazily(wrap_lazily, cx))
+    }
+
+    /// Soft wraps the lines shown in the given rows of `snapshot` that haven't been wrapped yet,
+    /// when wrapping lazily.
+    pub fn wrap_rows(
+        &self,
+        rows: Range<DisplayRow>,
+        snapshot: &DisplaySnapshot,
+        cx: &mut Context<Self>,
+    ) {
+        let tab_row = |row: DisplayRow| {
+            let wrap_point = snapshot
+                .block_snapshot
+                .to_wrap_point(BlockPoint::new(row.0, 0), Bias::Left);
+            snapshot.wrap_snapshot.to_tab_point(wrap_point).row()
+        };
+        let rows = tab_row(rows.start)..tab_row(rows.end) + 1;
+        self.wrap_map.update(cx, |map, cx| map.wrap_rows(rows, cx));
+    }
+
     pub fn update_fold_widths(
         &mut self,
         widths: impl IntoIterator<Item = (ChunkRendererId, Pixels)>,
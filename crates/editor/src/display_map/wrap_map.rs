@@ -24,6 +24,10 @@ pub struct WrapMap {
     interpolated_edits: Patch<u32>,
     edits_since_sync: Patch<u32>,
     wrap_width: Option<Pixels>,
+    /// Whether only the rows passed to [`WrapMap::wrap_rows`] are wrapped, rather than all of them.
+    wrap_lazily: bool,
+    /// The tab rows wrapped since the text or the wrap width last changed, when wrapping lazily.
+    wrapped_rows: Vec<Range<u32>>,
     background_task: Option<Task<()>>,
     font_with_size: (Font, Pixels),
 }
@@ -96,6 +100,8 @@ impl WrapMap {
             let mut this = Self {
                 font_with_size: (font, font_size),
                 wrap_width: None,
+                wrap_lazily: false,
+                wrapped_rows: Vec::new(),
                 pending_edits: Default::default(),
                 interpolated_edits: Default::default(),
                 edits_since_sync: Default::default(),
@@ -121,10 +127,15 @@ impl WrapMap {
         edits: Vec<TabEdit>,
         cx: &mut Context<Self>,
     ) -> (WrapSnapshot, Patch<u32>) {
-        if self.wrap_width.is_some() {
+        if self.wrap_width.is_some() && !self.wrap_lazily {
             self.pending_edits.push_back((tab_snapshot, edits));
             self.flush_edits(cx);
         } else {
+            if !edits.is_empty() {
+                // Edited rows are left unwrapped until they're requested again.
+                self.background_task.take();
+                self.wrapped_rows.clear();
+            }
             self.edits_since_sync = self
                 .edits_since_sync
                 .compose(self.snapshot.interpolate(tab_snapshot, &edits));
@@ -161,12 +172,112 @@ impl WrapMap {
         true
     }
 
+    /// Sets whether only the rows passed to [`WrapMap::wrap_rows`] are wrapped, which avoids
+    /// wrapping every row of large buffers.
+    pub fn set_wrap_lazily(&mut self, wrap_lazily: bool, cx: &mut Context<Self>) -> bool {
+        if wrap_lazily == self.wrap_lazily {
+            return false;
+        }
+
+        self.wrap_lazily = wrap_lazily;
+        self.rewrap(cx);
+        true
+    }
+
+    /// Wraps the given tab rows that haven't been wrapped yet, when wrapping lazily.
+    pub fn wrap_rows(&mut self, rows: Range<u32>, cx: &mut Context<Self>) {
+        let Some(wrap_width) = self.wrap_width.filter(|_| self.wrap_lazily) else {
+            return;
+        };
+        if self.background_task.is_some() {
+            return;
+        }
+
+        let end_row = rows
+            .end
+            .min(self.snapshot.tab_snapshot.max_point().row() + 1);
+        let mut unwrapped_rows = Vec::new();
+        let mut row = rows.start;
+        for wrapped_rows in &self.wrapped_rows {
+            if row >= end_row {
+                break;
+            }
+            if wrapped_rows.start > row {
+                unwrapped_rows.push(row..wrapped_rows.start.min(end_row));
+            }
+            row = row.max(wrapped_rows.end);
+        }
+        if row < end_row {
+            unwrapped_rows.push(row..end_row);
+        }
+        if unwrapped_rows.is_empty() {
+            return;
+        }
+
+        let tab_edits = unwrapped_rows
+            .iter()
+            .map(|rows| {
+                let range = TabPoint::new(rows.start, 0)..TabPoint::new(rows.end - 1, 0);
+                TabEdit {
+                    old: range.clone(),
+                    new: range,
+                }
+            })
+            .collect::<Vec<_>>();
+        self.wrapped_rows.extend(unwrapped_rows);
+        self.wrapped_rows.sort_unstable_by_key(|rows| rows.start);
+        self.wrapped_rows.dedup_by(|next, previous| {
+            if next.start <= previous.end {
+                previous.end = previous.end.max(next.end);
+                true
+            } else {
+                false
+            }
+        });
+
+        let mut new_snapshot = self.snapshot.clone();
+        let text_system = cx.text_system().clone();
+        let (font, font_size) = self.font_with_size.clone();
+        let task = cx.background_spawn(async move {
+            let mut line_wrapper = text_system.line_wrapper(font, font_size);
+            let tab_snapshot = new_snapshot.tab_snapshot.clone();
+            let edits = new_snapshot
+                .update(tab_snapshot, &tab_edits, wrap_width, &mut line_wrapper)
+                .await;
+            (new_snapshot, edits)
+        });
+
+        match cx
+            .background_executor()
+            .block_with_timeout(Duration::from_millis(1), task)
+        {
+            Ok((snapshot, edits)) => {
+                self.snapshot = snapshot;
+                self.edits_since_sync = self.edits_since_sync.compose(&edits);
+                cx.notify();
+            }
+            Err(wrap_task) => {
+                self.background_task = Some(cx.spawn(async move |this, cx| {
+                    let (snapshot, edits) = wrap_task.await;
+                    this.update(cx, |this, cx| {
+                        this.snapshot = snapshot;
+                        this.edits_since_sync = this.edits_since_sync.compose(&edits);
+                        this.background_task = None;
+                        cx.notify();
+                    })
+                    .ok();
+                }));
+            }
+        }
+    }
+
     fn rewrap(&mut self, cx: &mut Context<Self>) {
         self.background_task.take();
         self.interpolated_edits.clear();
         self.pending_edits.clear();
+        self.wrapped_rows.clear();
 
-        if let Some(wrap_width) = self.wrap_width {
+        if let Some(wrap_width) = self.wrap_width.filter(|_| !self.wrap_lazily) {
             let mut new_snapshot = self.snapshot.clone();
 
             let text_system = cx.text_system().clone();
@@ -1430,6 +1541,66 @@ mod tests {
         wrap_map.read_with(cx, |map, _| assert!(map.pending_edits.is_empty()));
     }
 
+    #[gpui::test]
+    async fn test_lazy_wraps(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let text_system = cx.read(|cx| cx.text_system().clone());
+        let font = test_font();
+        let _font_id = text_system.font_id(&font);
+        let font_size = px(14.0);
+        let wrap_width = Some(px(200.0));
+
+        let line = "lorem ipsum ".repeat(20);
+        let text = [line.as_str(); 4].join("\n");
+        let buffer = cx.update(|cx| MultiBuffer::build_simple(&text, cx));
+        let buffer_snapshot = buffer.read_with(cx, |buffer, cx| buffer.snapshot(cx));
+        let (_, inlay_snapshot) = InlayMap::new(buffer_snapshot);
+        let (_, fold_snapshot) = FoldMap::new(inlay_snapshot);
+        let (_, tab_snapshot) = TabMap::new(fold_snapshot, NonZeroU32::new(4).unwrap());
+        let mut line_wrapper = text_system.line_wrapper(font.clone(), font_size);
+        let wrapped_text = wrap_text(&tab_snapshot, wrap_width, &mut line_wrapper);
+
+        let (wrap_map, _) =
+            cx.update(|cx| WrapMap::new(tab_snapshot.clone(), font, font_size, None, cx));
+        let mut notifications = observe(&wrap_map, cx);
+        let mut wrap_rows = async |rows: Range<u32>, cx: &mut gpui::TestAppContext| {
+            wrap_map.update(cx, |map, cx| map.wrap_rows(rows, cx));
+            while wrap_map.read_with(cx, |map, _| map.is_rewrapping()) {
+                notifications.next().await.unwrap();
+            }
+            wrap_map
+                .update(cx, |map, cx| map.sync(tab_snapshot.clone(), Vec::new(), cx))
+                .0
+                .text()
+        };
+
+        // Lines aren't wrapped until they're requested.
+        wrap_map.update(cx, |map, cx| {
+            map.set_wrap_lazily(true, cx);
+            map.set_wrap_width(wrap_width, cx);
+        });
+        assert_eq!(wrap_rows(0..0, cx).await, text);
+
+        let partially_wrapped_text = wrap_rows(1..3, cx).await;
+        assert_ne!(partially_wrapped_text, text);
+        assert_ne!(partially_wrapped_text, wrapped_text);
+        assert!(partially_wrapped_text.starts_with(&format!("{line}\n")));
+        assert!(partially_wrapped_text.ends_with(&format!("\n{line}")));
+        assert_eq!(wrap_rows(1..3, cx).await, partially_wrapped_text);
+        assert_eq!(wrap_rows(0..10, cx).await, wrapped_text);
+
+        // Changing the wrap width unwraps every line until they're requested again.
+        wrap_map.update(cx, |map, cx| map.set_wrap_width(Some(px(300.0)), cx));
+        assert_eq!(wrap_rows(0..0, cx).await, text);
+
+        wrap_map.update(cx, |map, cx| {
+            map.set_wrap_width(wrap_width, cx);
+            map.set_wrap_lazily(false, cx);
+        });
+        assert_eq!(wrap_rows(0..0, cx).await, wrapped_text);
+    }
+
     fn init_test(cx: &mut gpui::TestAppContext) {
         cx.update(|cx| {
             let settings = SettingsStore::test(cx);
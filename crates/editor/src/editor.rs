@@ -28,12 +28,14 @@ mod indent_guides;
 mod inlay_hint_cache;
 pub mod items;
 mod jsx_tag_auto_close;
+mod large_file_indicator;
 mod linked_editing_ranges;
 mod lsp_colors;
 mod lsp_ext;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
+mod persistent_undo;
 mod proposed_changes_editor;
 mod rust_analyzer_ext;
 pub mod scroll;
@@ -120,6 +122,7 @@ use language::{
     point_from_lsp, text_diff_with_options,
 };
 use language::{BufferRow, CharClassifier, Runnable, RunnableRange, point_to_lsp};
+pub use large_file_indicator::LargeFileIndicator;
 use linked_editing_ranges::refresh_linked_ranges;
 use markdown::Markdown;
 use mouse_context_menu::MouseContextMenu;
@@ -142,7 +145,7 @@ pub use proposed_changes_editor::{
     ProposedChangeLocation, ProposedChangesEditor, ProposedChangesEditorToolbar,
 };
 use std::{cell::OnceCell, iter::Peekable, ops::Not};
-use task::{ResolvedTask, RunnableTag, TaskTemplate, TaskVariables};
+use task::{ResolvedTask, RunnableTag, TaskTemplate, TaskVariables, TestStatus};
 
 pub use lsp::CompletionContext;
 use lsp::{
@@ -173,6 +176,7 @@ use project::{
 use rand::prelude::*;
 use rpc::{ErrorExt, proto::*};
 use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, ScrollbarAutoHide};
+pub use selections_collection::BlockSelection;
 use selections_collection::{
     MutableSelectionsCollection, SelectionsCollection, resolve_selections,
 };
@@ -1163,6 +1167,7 @@ pub struct Editor {
     _scroll_cursor_center_top_bottom_task: Task<()>,
     serialize_selections: Task<()>,
     serialize_folds: Task<()>,
+    undo_history_cache: persistent_undo::UndoHistoryCache,
     mouse_cursor_hidden: bool,
     minimap: Option<Entity<Self>>,
     hide_mouse_mode: HideMouseMode,
@@ -1543,6 +1548,9 @@ pub struct ClipboardSelection {
     pub is_entire_line: bool,
     /// The indentation of the first line when this content was originally copied.
     pub first_line_indent: u32,
+    /// Whether this is a row of a rectangular block selection, padded to the block's width.
+    #[serde(default)]
+    pub is_block: bool,
 }
 
 // selections, scroll behavior, was newest selection reversed
@@ -1834,6 +1842,11 @@ impl Editor {
                 )
             })
         });
+        let wrap_lazily = buffer
+            .read(cx)
+            .as_singleton()
+            .is_some_and(|buffer| buffer.read(cx).is_in_large_file_mode());
+        display_map.update(cx, |map, cx| map.set_wrap_lazily(wrap_lazily, cx));
 
         let selections = SelectionsCollection::new(display_map.clone(), buffer.clone());
 
@@ -2187,6 +2200,7 @@ impl Editor {
             toggle_fold_multiple_buffers: Task::ready(()),
             serialize_selections: Task::ready(()),
             serialize_folds: Task::ready(()),
+            undo_history_cache: Default::default(),
             text_style_refinement: None,
             load_diff_task: load_uncommitted_diff,
             temporary_diff_override: false,
@@ -3717,53 +3731,11 @@ impl Editor {
             }
         };
 
-        let start_row = cmp::min(tail.row(), head.row());
-        let end_row = cmp::max(tail.row(), head.row());
-        let start_column = cmp::min(tail.column(), goal_column);
-        let end_column = cmp::max(tail.column(), goal_column);
-        let reversed = start_column < tail.column();
-
-        let selection_ranges = (start_row.0..=end_row.0)
-            .map(DisplayRow)
-            .filter_map(|row| {
-                if (matches!(columnar_state, ColumnarSelectionState::FromMouse { .. })
-                    || start_column <= display_map.line_len(row))
-                    && !display_map.is_block_line(row)
-                {
-                    let start = display_map
-                        .clip_point(DisplayPoint::new(row, start_column), Bias::Left)
-                        .to_point(display_map);
-                    let end = display_map
-                        .clip_point(DisplayPoint::new(row, end_column), Bias::Right)
-                        .to_point(display_map);
-                    if reversed {
-                        Some(end..start)
-                    } else {
-                        Some(start..end)
-                    }
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
-
-        let ranges = match columnar_state {
-            ColumnarSelectionState::FromMouse { .. } => {
-                let mut non_empty_ranges = selection_ranges
-                    .iter()
-                    .filter(|selection_range| selection_range.start != selection_range.end)
-                    .peekable();
-                if non_empty_ranges.peek().is_some() {
-                    non_empty_ranges.cloned().collect()
-                } else {
-                    selection_ranges
-                }
-            }
-            _ => selection_ranges,
-        };
-
+        // The goal column isn't clipped, so that lines ending before it get a cursor in virtual
+        // space rather than being skipped.
+        let block = BlockSelection::new(tail, DisplayPoint::new(head.row(), goal_column));
         self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
-            s.select_ranges(ranges);
+            s.select_block(block);
         });
         cx.notify();
     }
@@ -3917,6 +3889,7 @@ impl Editor {
         }
 
         self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
+        self.pad_block_selection(window, cx);
 
         let selections = self.selections.all_adjusted(cx);
         let mut bracket_inserted = false;
@@ -8287,15 +8260,43 @@ impl Editor {
         None
     }
 
+    /// Returns the status of the test a runnable runs in the latest test run, if any.
+    fn runnable_test_status(
+        &self,
+        tasks: &RunnableTasks,
+        snapshot: &MultiBufferSnapshot,
+        cx: &App,
+    ) -> Option<TestStatus> {
+        let inventory = self
+            .project
+            .as_ref()?
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()?
+            .read(cx);
+        let file = snapshot
+            .buffer_for_excerpt(tasks.offset.excerpt_id)?
+            .file()?;
+        let (name_range, _) = snapshot.surrounding_word(tasks.offset, false);
+        let name = snapshot.text_for_range(name_range).collect::<String>();
+        inventory.test_status(Some(file.worktree_id(cx)), file.path(), &name)
+    }
+
     fn render_run_indicator(
         &self,
         _style: &EditorStyle,
         is_active: bool,
         row: DisplayRow,
         breakpoint: Option<(Anchor, Breakpoint, Option<BreakpointSessionState>)>,
+        test_status: Option<TestStatus>,
         cx: &mut Context<Self>,
     ) -> IconButton {
-        let color = Color::Muted;
+        let color = match test_status {
+            Some(TestStatus::Passed) => Color::Success,
+            Some(TestStatus::Failed) => Color::Error,
+            Some(TestStatus::Ignored) | None => Color::Muted,
+        };
         let position = breakpoint.as_ref().map(|(anchor, _, _)| *anchor);
 
         IconButton::new(("run_indicator", row.0 as usize), ui::IconName::Play)
@@ -11852,6 +11853,11 @@ impl Editor {
     }
 
     pub fn cut_common(&mut self, window: &mut Window, cx: &mut Context<Self>) -> ClipboardItem {
+        if let Some(item) = self.block_clipboard_item(cx) {
+            self.insert("", window, cx);
+            return item;
+        }
+
         let mut text = String::new();
         let buffer = self.buffer.read(cx).snapshot(cx);
         let mut selections = self.selections.all::<Point>(cx);
@@ -11886,6 +11892,7 @@ impl Editor {
                     first_line_indent: buffer
                         .indent_size_for_line(MultiBufferRow(selection.start.row))
                         .len,
+                    is_block: false,
                 });
             }
         }
@@ -11946,6 +11953,11 @@ impl Editor {
     }
 
     fn do_copy(&self, strip_leading_indents: bool, cx: &mut Context<Self>) {
+        if let Some(item) = self.block_clipboard_item(cx) {
+            cx.write_to_clipboard(item);
+            return;
+        }
+
         let selections = self.selections.all::<Point>(cx);
         let buffer = self.buffer.read(cx).read(cx);
         let mut text = String::new();
@@ -12017,6 +12029,7 @@ impl Editor {
                         first_line_indent: buffer
                             .indent_size_for_line(MultiBufferRow(trimmed_range.start.row))
                             .len,
+                        is_block: false,
                     });
                 }
             }
@@ -12028,6 +12041,144 @@ impl Editor {
         ));
     }
 
+    /// The text of the block selection, one row per line, with each row padded with spaces to
+    /// the width of the block so that pasting it restores the rectangle.
+    fn block_clipboard_item(&self, cx: &mut Context<Self>) -> Option<ClipboardItem> {
+        let block = self
+            .selections
+            .block
+            .filter(|_| !self.selections.line_mode)?;
+        let (display_map, selections) = self.selections.all_display(cx);
+        let width = block.columns().end - block.columns().start;
+        let mut text = String::new();
+        let mut clipboard_selections = Vec::with_capacity(selections.len());
+        for (ix, selection) in selections.iter().enumerate() {
+            if ix > 0 {
+                text.push('\n');
+            }
+            let range =
+                selection.start.to_point(&display_map)..selection.end.to_point(&display_map);
+            let row_start = text.len();
+            text.extend(display_map.buffer_snapshot.text_for_range(range.clone()));
+            if !block.to_line_end {
+                let selected_columns = selection.end.column() - selection.start.column();
+                text.push_str(&" ".repeat(width.saturating_sub(selected_columns) as usize));
+            }
+            clipboard_selections.push(ClipboardSelection {
+                len: text.len() - row_start,
+                is_entire_line: false,
+                first_line_indent: display_map
+                    .buffer_snapshot
+                    .indent_size_for_line(MultiBufferRow(range.start.row))
+                    .len,
+                is_block: true,
+            });
+        }
+        Some(ClipboardItem::new_string_with_json_metadata(
+            text,
+            clipboard_selections,
+        ))
+    }
+
+    /// Inserts spaces after the lines ending before the start of the block selection, so that
+    /// its cursors in virtual space are on real columns before text is inserted at them.
+    fn pad_block_selection(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(block) = self.selections.block.filter(|block| !block.to_line_end) else {
+            return;
+        };
+        let (display_map, selections) = self.selections.all_display(cx);
+        let edits = selections
+            .iter()
+            .filter(|selection| selection.is_empty())
+            .filter_map(|selection| {
+                let padding = block
+                    .columns()
+                    .start
+                    .checked_sub(display_map.line_len(selection.head().row()))
+                    .filter(|padding| *padding > 0)?;
+                let position = selection.head().to_point(&display_map);
+                Some((position..position, " ".repeat(padding as usize)))
+            })
+            .collect::<Vec<_>>();
+        if edits.is_empty() {
+            return;
+        }
+        self.buffer
+            .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+        self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_block(block)
+        });
+    }
+
+    /// Pastes the rows of a block selection as a rectangle, one row per line starting at the
+    /// newest cursor's column. Lines ending before that column are padded with spaces, and lines
+    /// are added at the end of the buffer when the block doesn't fit.
+    fn paste_block(&mut self, text: &str, window: &mut Window, cx: &mut Context<Self>) {
+        if self
+            .selections
+            .all::<usize>(cx)
+            .iter()
+            .any(|selection| !selection.is_empty())
+        {
+            self.insert("", window, cx);
+        }
+        let position = self.selections.newest::<Point>(cx).head();
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let column = buffer
+            .text_for_range(Point::new(position.row, 0)..position)
+            .flat_map(str::chars)
+            .count();
+        let max_point = buffer.max_point();
+        let mut edits = Vec::new();
+        let mut appended_lines = String::new();
+        for (ix, line) in text.split('\n').enumerate() {
+            let row = position.row + ix as u32;
+            if row > max_point.row {
+                appended_lines.push('\n');
+                appended_lines.push_str(&" ".repeat(column));
+                appended_lines.push_str(line);
+                continue;
+            }
+            let line_start = Point::new(row, 0);
+            let line_end = Point::new(row, buffer.line_len(MultiBufferRow(row)));
+            let mut line_columns = 0;
+            let mut insertion_column = None;
+            for (offset, _) in buffer
+                .text_for_range(line_start..line_end)
+                .collect::<String>()
+                .char_indices()
+            {
+                if line_columns == column {
+                    insertion_column = Some(offset as u32);
+                    break;
+                }
+                line_columns += 1;
+            }
+            let (insertion, to_insert) = match insertion_column {
+                Some(insertion_column) => (Point::new(row, insertion_column), line.to_string()),
+                None => {
+                    let padding = " ".repeat(column - line_columns);
+                    (line_end, padding + line)
+                }
+            };
+            edits.push((insertion..insertion, to_insert));
+        }
+        if !appended_lines.is_empty() {
+            match edits.last_mut() {
+                Some((range, to_insert)) if range.start == max_point => {
+                    to_insert.push_str(&appended_lines)
+                }
+                _ => edits.push((max_point..max_point, appended_lines)),
+            }
+        }
+
+        self.buffer
+            .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+        self.change_selections(Default::default(), window, cx, |s| {
+            s.select_ranges([position..position])
+        });
+    }
+
     pub fn do_paste(
         &mut self,
         text: &String,
@@ -12043,8 +12194,16 @@ impl Editor {
         let clipboard_text = Cow::Borrowed(text);
 
         self.transact(window, cx, |this, window, cx| {
+            this.pad_block_selection(window, cx);
             if let Some(mut clipboard_selections) = clipboard_selections {
                 let old_selections = this.selections.all::<usize>(cx);
+                if clipboard_selections.iter().all(|s| s.is_block)
+                    && clipboard_selections.len() > 1
+                    && clipboard_selections.len() != old_selections.len()
+                {
+                    this.paste_block(&clipboard_text, window, cx);
+                    return;
+                }
                 let all_selections_were_entire_line =
                     clipboard_selections.iter().all(|s| s.is_entire_line);
                 let first_selection_indent_column =
@@ -12198,6 +12357,135 @@ impl Editor {
         }
     }
 
+    pub fn undo_tree_older(
+        &mut self,
+        _: &UndoTreeOlder,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_in_undo_tree(|buffer| buffer.undo_tree_older(), window, cx);
+    }
+
+    pub fn undo_tree_newer(
+        &mut self,
+        _: &UndoTreeNewer,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_in_undo_tree(|buffer| buffer.undo_tree_newer().map(Some), window, cx);
+    }
+
+    /// Moves to the latest text state of the undo tree that was reached `duration` before the
+    /// current one, as with Vim's `:earlier`.
+    pub fn undo_tree_earlier(
+        &mut self,
+        duration: Duration,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_in_undo_tree(
+            |buffer| {
+                let current = buffer.undo_tree_position()?;
+                let edited_at = buffer
+                    .undo_tree()
+                    .into_iter()
+                    .find(|node| node.transaction_id == current)?
+                    .edited_at;
+                Some(
+                    edited_at
+                        .checked_sub(duration)
+                        .and_then(|time| buffer.undo_tree_state_at(time)),
+                )
+            },
+            window,
+            cx,
+        );
+    }
+
+    /// Moves to the latest text state of the undo tree that was reached `duration` after the
+    /// current one, as with Vim's `:later`.
+    pub fn undo_tree_later(
+        &mut self,
+        duration: Duration,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_in_undo_tree(
+            |buffer| {
+                let tree = buffer.undo_tree();
+                let current = buffer.undo_tree_position();
+                let edited_at = match current {
+                    Some(current) => {
+                        tree.iter()
+                            .find(|node| node.transaction_id == current)?
+                            .edited_at
+                    }
+                    None => tree.first()?.edited_at,
+                };
+                let target = buffer.undo_tree_state_at(edited_at.checked_add(duration)?)?;
+                (current < Some(target)).then_some(Some(target))
+            },
+            window,
+            cx,
+        );
+    }
+
+    fn move_in_undo_tree(
+        &mut self,
+        target: impl FnOnce(&Buffer) -> Option<Option<TransactionId>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+        if let Some(target) = target(buffer.read(cx)) {
+            self.undo_tree_goto(target, window, cx);
+        }
+    }
+
+    /// Moves the singleton buffer of this editor to the given state of its undo tree, or to its
+    /// initial state for `None`, selecting the start of the first change.
+    pub fn undo_tree_goto(
+        &mut self,
+        target: Option<TransactionId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.read_only(cx) {
+            return false;
+        }
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return false;
+        };
+
+        self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
+
+        let old_version = buffer.read(cx).version();
+        let Some(transaction_id) = target.or(buffer.read(cx).undo_tree_position()) else {
+            return false;
+        };
+        if !buffer.update(cx, |buffer, cx| buffer.undo_tree_goto(target, cx)) {
+            return false;
+        }
+
+        let first_edit = buffer
+            .read(cx)
+            .edits_since::<usize>(&old_version)
+            .next()
+            .map(|edit| edit.new.start);
+        if let Some(offset) = first_edit {
+            self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                s.select_ranges([offset..offset]);
+            });
+        }
+        self.request_autoscroll(Autoscroll::fit(), cx);
+        self.unmark_text(window, cx);
+        self.refresh_inline_completion(true, false, window, cx);
+        cx.emit(EditorEvent::Edited { transaction_id });
+        true
+    }
+
     pub fn finalize_last_transaction(&mut self, cx: &mut Context<Self>) {
         self.buffer
             .update(cx, |buffer, cx| buffer.finalize_last_transaction(cx));
@@ -13638,6 +13926,73 @@ impl Editor {
         }
     }
 
+    pub fn select_block_up(
+        &mut self,
+        _: &SelectBlockUp,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.extend_block_selection(-1, 0, window, cx);
+    }
+
+    pub fn select_block_down(
+        &mut self,
+        _: &SelectBlockDown,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.extend_block_selection(1, 0, window, cx);
+    }
+
+    pub fn select_block_left(
+        &mut self,
+        _: &SelectBlockLeft,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.extend_block_selection(0, -1, window, cx);
+    }
+
+    pub fn select_block_right(
+        &mut self,
+        _: &SelectBlockRight,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.extend_block_selection(0, 1, window, cx);
+    }
+
+    /// Moves the head of the block selection, starting a block at the newest cursor when there's
+    /// none. The head can move past the end of shorter lines, into virtual space.
+    fn extend_block_selection(
+        &mut self,
+        rows: i32,
+        columns: i32,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.hide_mouse_cursor(HideMouseCursorOrigin::MovementAction, cx);
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let block = self.selections.block.unwrap_or_else(|| {
+            let head = self.selections.newest_display(cx).head();
+            BlockSelection::new(head, head)
+        });
+        let row = block
+            .head
+            .row()
+            .0
+            .saturating_add_signed(rows)
+            .min(display_map.max_point().row().0);
+        let column = block.head.column().saturating_add_signed(columns);
+        let block = BlockSelection {
+            head: DisplayPoint::new(DisplayRow(row), column),
+            ..block
+        };
+        self.change_selections(Default::default(), window, cx, |s| {
+            s.select_block(block);
+        });
+    }
+
     fn select_match_ranges(
         &mut self,
         range: Range<usize>,
@@ -17669,6 +18024,103 @@ impl Editor {
         .detach_and_log_err(cx);
     }
 
+    pub fn stage_selected_lines(
+        &mut self,
+        _: &::git::StageSelectedLines,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ranges = self.selections.disjoint_anchor_ranges().collect::<Vec<_>>();
+        self.stage_or_unstage_lines(true, ranges, cx);
+    }
+
+    pub fn unstage_selected_lines(
+        &mut self,
+        _: &::git::UnstageSelectedLines,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ranges = self.selections.disjoint_anchor_ranges().collect::<Vec<_>>();
+        self.stage_or_unstage_lines(false, ranges, cx);
+    }
+
+    /// Stages or unstages only the changed lines touched by the given ranges,
+    /// including lines of expanded deleted hunks. An empty range selects the
+    /// line it's on.
+    pub fn stage_or_unstage_lines(
+        &mut self,
+        stage: bool,
+        ranges: Vec<Range<Anchor>>,
+        cx: &mut Context<Self>,
+    ) {
+        let task = self.save_buffers_for_ranges_if_needed(&ranges, cx);
+        cx.spawn(async move |this, cx| {
+            task.await?;
+            this.update(cx, |this, cx| {
+                let snapshot = this.buffer.read(cx).snapshot(cx);
+                // Selected rows of each buffer, and of the base text of its diff.
+                let mut rows_by_buffer: HashMap<BufferId, (Vec<u32>, Vec<u32>)> =
+                    HashMap::default();
+                for range in &ranges {
+                    let range = range.to_offset(&snapshot);
+                    let is_empty = range.is_empty();
+                    for (buffer, buffer_range, _, deleted_hunk_anchor) in
+                        snapshot.range_to_buffer_ranges_with_deleted_hunks(range)
+                    {
+                        if !is_empty && buffer_range.is_empty() {
+                            continue;
+                        }
+                        let start = buffer.offset_to_point(buffer_range.start);
+                        let end = buffer.offset_to_point(buffer_range.end);
+                        let end_row = if !is_empty && end.column == 0 && end.row > start.row {
+                            end.row
+                        } else {
+                            end.row + 1
+                        };
+                        let rows = match deleted_hunk_anchor {
+                            Some(anchor) => match anchor.buffer_id {
+                                Some(buffer_id) => {
+                                    &mut rows_by_buffer.entry(buffer_id).or_default().1
+                                }
+                                None => continue,
+                            },
+                            None => &mut rows_by_buffer.entry(buffer.remote_id()).or_default().0,
+                        };
+                        rows.extend(start.row..end_row);
+                    }
+                }
+
+                for (buffer_id, (buffer_rows, base_rows)) in rows_by_buffer {
+                    this.do_stage_or_unstage_lines(stage, buffer_id, &buffer_rows, &base_rows, cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn do_stage_or_unstage_lines(
+        &self,
+        stage: bool,
+        buffer_id: BufferId,
+        buffer_rows: &[u32],
+        base_rows: &[u32],
+        cx: &mut App,
+    ) {
+        let Some(project) = self.project.as_ref() else {
+            return;
+        };
+        let Some(buffer) = project.read(cx).buffer_for_id(buffer_id, cx) else {
+            return;
+        };
+        let Some(diff) = self.buffer.read(cx).diff_for(buffer_id) else {
+            return;
+        };
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        diff.update(cx, |diff, cx| {
+            diff.stage_or_unstage_lines(stage, buffer_rows, base_rows, &buffer_snapshot, cx)
+        });
+    }
+
     fn save_buffers_for_ranges_if_needed(
         &mut self,
         ranges: &[Range<Anchor>],
@@ -18134,6 +18586,26 @@ impl Editor {
         }
     }
 
+    /// Whether the editor's buffer was opened in large-file mode, in which syntax highlighting,
+    /// language servers and git diffs are skipped, and lines are only soft wrapped once shown.
+    pub fn is_in_large_file_mode(&self, cx: &App) -> bool {
+        self.buffer
+            .read(cx)
+            .as_singleton()
+            .is_some_and(|buffer| buffer.read(cx).is_in_large_file_mode())
+    }
+
+    pub fn exit_large_file_mode(
+        &mut self,
+        _: &ExitLargeFileMode,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for buffer in self.buffer.read(cx).all_buffers() {
+            buffer.update(cx, |buffer, cx| buffer.set_large_file_mode(false, cx));
+        }
+    }
+
     pub fn set_soft_wrap_mode(
         &mut self,
         mode: language_settings::SoftWrap,
@@ -18187,6 +18659,17 @@ impl Editor {
             .update(cx, |map, cx| map.set_wrap_width(width, cx))
     }
 
+    // Called by the element, like `set_wrap_width`, with the rows it's about to lay out.
+    pub(crate) fn wrap_visible_rows(
+        &self,
+        rows: Range<DisplayRow>,
+        snapshot: &EditorSnapshot,
+        cx: &mut App,
+    ) {
+        self.display_map
+            .update(cx, |map, cx| map.wrap_rows(rows, snapshot, cx))
+    }
+
     pub fn set_soft_wrap(&mut self) {
         self.soft_wrap_mode_override = Some(language_settings::SoftWrap::EditorWidth)
     }
@@ -19890,6 +20373,27 @@ impl Editor {
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
             }
+            multi_buffer::Event::LargeFileModeChanged(buffer_id) => {
+                if let Some(buffer) = multibuffer.read(cx).buffer(*buffer_id) {
+                    if self.buffer.read(cx).diff_for(*buffer_id).is_none() {
+                        if let Some(project) = &self.project {
+                            update_uncommitted_diff_for_buffer(
+                                cx.entity(),
+                                project,
+                                [buffer],
+                                self.buffer.clone(),
+                                cx,
+                            )
+                            .detach();
+                        }
+                    }
+                }
+                let wrap_lazily = self.is_in_large_file_mode(cx);
+                self.display_map
+                    .update(cx, |map, cx| map.set_wrap_lazily(wrap_lazily, cx));
+                self.tasks_update_task = Some(self.refresh_runnables(window, cx));
+                cx.notify();
+            }
             multi_buffer::Event::DirtyChanged => cx.emit(EditorEvent::DirtyChanged),
             multi_buffer::Event::Saved => cx.emit(EditorEvent::Saved),
             multi_buffer::Event::FileHandleChanged
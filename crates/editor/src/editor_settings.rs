@@ -54,6 +54,7 @@ pub struct EditorSettings {
     pub inline_code_actions: bool,
     pub drag_and_drop_selection: DragAndDropSelection,
     pub lsp_document_colors: DocumentColorsRenderMode,
+    pub persistent_undo: PersistentUndo,
 }
 
 /// How to render LSP `textDocument/documentColor` colors in the editor.
@@ -295,6 +296,47 @@ fn default_drag_and_drop_selection_delay_ms() -> u64 {
     300
 }
 
+/// Whether to keep the undo history of files across restarts.
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct PersistentUndo {
+    /// When true, the undo history of a file is saved to the database when the file is saved,
+    /// and restored when the file is reopened with the same contents.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// The maximum number of undo steps to save for each file. Older steps are dropped first.
+    ///
+    /// Default: 100
+    #[serde(default = "default_persistent_undo_max_entries")]
+    pub max_entries: usize,
+
+    /// The maximum size, in kilobytes, of the undo history saved for each file. Older steps are dropped first.
+    ///
+    /// Default: 1024
+    #[serde(default = "default_persistent_undo_max_size_kb")]
+    pub max_size_kb: usize,
+
+    /// The maximum number of files to keep undo history for. The least recently saved histories are evicted first.
+    ///
+    /// Default: 500
+    #[serde(default = "default_persistent_undo_max_files")]
+    pub max_files: usize,
+}
+
+fn default_persistent_undo_max_entries() -> usize {
+    100
+}
+
+fn default_persistent_undo_max_size_kb() -> usize {
+    1024
+}
+
+fn default_persistent_undo_max_files() -> usize {
+    500
+}
+
 /// Which diagnostic indicators to show in the scrollbar.
 ///
 /// Default: all
@@ -565,6 +607,9 @@ pub struct EditorSettingsContent {
     ///
     /// Default: [`DocumentColorsRenderMode::Inlay`]
     pub lsp_document_colors: Option<DocumentColorsRenderMode>,
+
+    /// Undo history persistence related settings
+    pub persistent_undo: Option<PersistentUndo>,
 }
 
 // Toolbar related settings
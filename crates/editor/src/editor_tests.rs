@@ -7004,6 +7004,70 @@ async fn test_add_selection_above_below_multi_cursor_existing_state(cx: &mut Tes
     ));
 }
 
+#[gpui::test]
+async fn test_block_selection_in_virtual_space(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        one ˇtwo
+        x
+        abcdefgh"});
+    cx.update_editor(|editor, window, cx| {
+        editor.select_block_down(&SelectBlockDown, window, cx);
+        editor.select_block_down(&SelectBlockDown, window, cx);
+    });
+    // The line ending before the block gets a cursor at its end rather than being skipped.
+    cx.assert_editor_state(indoc! {"
+        one ˇtwo
+        xˇ
+        abcdˇefgh"});
+
+    // Typing pads the short line up to the block's column.
+    cx.update_editor(|editor, window, cx| editor.handle_input("|", window, cx));
+    cx.assert_editor_state(indoc! {"
+        one |ˇtwo
+        x   |ˇ
+        abcd|ˇefgh"});
+}
+
+#[gpui::test]
+async fn test_block_selection_copy_and_paste(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        ˇab
+        c
+        defg"});
+    cx.update_editor(|editor, window, cx| {
+        editor.select_block_down(&SelectBlockDown, window, cx);
+        editor.select_block_down(&SelectBlockDown, window, cx);
+        editor.select_block_right(&SelectBlockRight, window, cx);
+        editor.select_block_right(&SelectBlockRight, window, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        «abˇ»
+        «cˇ»
+        «deˇ»fg"});
+
+    // Copied rows are padded to the width of the block.
+    cx.update_editor(|editor, window, cx| editor.copy(&Copy, window, cx));
+    assert_eq!(
+        cx.read_from_clipboard().and_then(|item| item.text()),
+        Some("ab\nc \nde".to_string())
+    );
+
+    // Pasting with a single cursor restores the rectangle, adding lines past the end of the buffer.
+    cx.update_editor(|editor, window, cx| {
+        editor.change_selections(Default::default(), window, cx, |s| {
+            s.select_ranges([Point::new(2, 4)..Point::new(2, 4)])
+        });
+        editor.paste(&Paste, window, cx);
+    });
+    cx.assert_editor_state("ab\nc\ndefgˇab\n    c \n    de");
+}
+
 #[gpui::test]
 async fn test_select_next(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
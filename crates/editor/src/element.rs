@@ -264,6 +264,8 @@ impl EditorElement {
         register_action(editor, window, Editor::paste);
         register_action(editor, window, Editor::undo);
         register_action(editor, window, Editor::redo);
+        register_action(editor, window, Editor::undo_tree_older);
+        register_action(editor, window, Editor::undo_tree_newer);
         register_action(editor, window, Editor::move_page_up);
         register_action(editor, window, Editor::move_page_down);
         register_action(editor, window, Editor::next_screen);
@@ -339,6 +341,10 @@ impl EditorElement {
         register_action(editor, window, Editor::split_selection_into_lines);
         register_action(editor, window, Editor::add_selection_above);
         register_action(editor, window, Editor::add_selection_below);
+        register_action(editor, window, Editor::select_block_up);
+        register_action(editor, window, Editor::select_block_down);
+        register_action(editor, window, Editor::select_block_left);
+        register_action(editor, window, Editor::select_block_right);
         register_action(editor, window, |editor, action, window, cx| {
             editor.select_next(action, window, cx).log_err();
         });
@@ -462,7 +468,10 @@ impl EditorElement {
         register_action(editor, window, Editor::toggle_staged_selected_diff_hunks);
         register_action(editor, window, Editor::stage_and_next);
         register_action(editor, window, Editor::unstage_and_next);
+        register_action(editor, window, Editor::stage_selected_lines);
+        register_action(editor, window, Editor::unstage_selected_lines);
         register_action(editor, window, Editor::expand_all_diff_hunks);
+        register_action(editor, window, Editor::exit_large_file_mode);
         register_action(editor, window, Editor::go_to_previous_change);
         register_action(editor, window, Editor::go_to_next_change);
 
@@ -1564,9 +1573,18 @@ impl EditorElement {
                         [cursor_position.row().minus(visible_display_row_range.start) as usize];
                     let cursor_column = cursor_position.column() as usize;
 
-                    let cursor_character_x = cursor_row_layout.x_for_index(cursor_column);
+                    // Empty selections of a block can lie past the end of their line.
+                    let virtual_columns = editor
+                        .selections
+                        .block
+                        .filter(|_| selection.is_local && selection.range.is_empty())
+                        .map_or(0, |block| {
+                            block.virtual_columns(cursor_position, &snapshot.display_snapshot)
+                        });
+                    let mut cursor_character_x = cursor_row_layout.x_for_index(cursor_column);
                     let mut block_width =
                         cursor_row_layout.x_for_index(cursor_column + 1) - cursor_character_x;
+                    cursor_character_x += em_advance * virtual_columns as f32;
                     if block_width == Pixels::ZERO {
                         block_width = em_advance;
                     }
@@ -2907,11 +2925,14 @@ impl EditorElement {
                         return None;
                     }
 
+                    let test_status =
+                        editor.runnable_test_status(tasks, &snapshot.buffer_snapshot, cx);
                     let button = editor.render_run_indicator(
                         &self.style,
                         Some(display_row) == active_task_indicator_row,
                         display_row,
                         breakpoints.remove(&display_row),
+                        test_status,
                         cx,
                     );
 
@@ -8050,6 +8071,12 @@ impl Element for EditorElement {
                     );
                     let end_row = DisplayRow(end_row);
 
+                    // Large files are only soft wrapped as their lines are shown, which
+                    // takes effect from the next frame.
+                    self.editor.update(cx, |editor, cx| {
+                        editor.wrap_visible_rows(start_row..end_row, &snapshot, cx)
+                    });
+
                     let row_infos = snapshot
                         .row_infos(start_row)
                         .take((start_row..end_row).len())
@@ -5,6 +5,7 @@ use crate::{
     display_map::HighlightKey,
     editor_settings::SeedQuerySetting,
     persistence::{DB, SerializedEditor},
+    persistent_undo,
     scroll::ScrollAnchor,
 };
 use anyhow::{Context as _, Result, anyhow};
@@ -1190,6 +1191,7 @@ impl SerializableItem for Editor {
                             }
 
                             cx.update(|window, cx| {
+                                persistent_undo::restore_undo_history(&buffer, &project, cx);
                                 cx.new(|cx| {
                                     let mut editor =
                                         Editor::for_buffer(buffer, Some(project), window, cx);
@@ -1286,10 +1288,26 @@ impl SerializableItem for Editor {
 
         let is_dirty = buffer.read(cx).is_dirty();
         let mtime = buffer.read(cx).saved_mtime();
+        let save_undo_history = abs_path.clone().and_then(|abs_path| {
+            persistent_undo::save_undo_history(
+                &buffer,
+                abs_path,
+                &project,
+                &self.undo_history_cache,
+                cx,
+            )
+        });
 
         let snapshot = buffer.read(cx).snapshot();
 
         Some(cx.spawn_in(window, async move |_this, cx| {
+            if let Some(save_undo_history) = save_undo_history {
+                save_undo_history
+                    .await
+                    .context("failed to save undo history")
+                    .log_err();
+            }
+
             cx.background_spawn(async move {
                 let (contents, language) = if serialize_dirty_buffers && is_dirty {
                     let contents = snapshot.text();
@@ -1351,6 +1369,7 @@ impl ProjectItem for Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
+        persistent_undo::restore_undo_history(&buffer, &project, cx);
         let mut editor = Self::for_buffer(buffer.clone(), Some(project), window, cx);
         if let Some((excerpt_id, buffer_id, snapshot)) =
             editor.buffer().read(cx).snapshot(cx).as_singleton()
@@ -1521,6 +1540,8 @@ impl SearchableItem for Editor {
                 case: true,
                 word: true,
                 regex: true,
+                structural: true,
+                syntax: true,
                 replacement: false,
                 selection: false,
                 find_in_results: true,
@@ -1530,6 +1551,8 @@ impl SearchableItem for Editor {
                 case: true,
                 word: true,
                 regex: true,
+                structural: true,
+                syntax: true,
                 replacement: true,
                 selection: true,
                 find_in_results: false,
@@ -1600,19 +1623,10 @@ impl SearchableItem for Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let text = self.buffer.read(cx);
-        let text = text.snapshot(cx);
-        let text = text.text_for_range(identifier.clone()).collect::<Vec<_>>();
-        let text: Cow<_> = if text.len() == 1 {
-            text.first().cloned().unwrap().into()
-        } else {
-            let joined_chunks = text.join("");
-            joined_chunks.into()
-        };
-
-        if let Some(replacement) = query.replacement_for(&text) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        if let Some(replacement) = replacement_for_match(&snapshot, identifier, query) {
             self.transact(window, cx, |this, _, cx| {
-                this.edit([(identifier.clone(), Arc::from(&*replacement))], cx);
+                this.edit([(identifier.clone(), replacement)], cx);
             });
         }
     }
@@ -1623,24 +1637,10 @@ impl SearchableItem for Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let text = self.buffer.read(cx);
-        let text = text.snapshot(cx);
-        let mut edits = vec![];
-
-        for m in matches {
-            let text = text.text_for_range(m.clone()).collect::<Vec<_>>();
-
-            let text: Cow<_> = if text.len() == 1 {
-                text.first().cloned().unwrap().into()
-            } else {
-                let joined_chunks = text.join("");
-                joined_chunks.into()
-            };
-
-            if let Some(replacement) = query.replacement_for(&text) {
-                edits.push((m.clone(), Arc::from(&*replacement)));
-            }
-        }
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let edits = matches
+            .filter_map(|m| Some((m.clone(), replacement_for_match(&snapshot, m, query)?)))
+            .collect::<Vec<_>>();
 
         if !edits.is_empty() {
             self.transact(window, cx, |this, _, cx| {
@@ -1783,6 +1783,39 @@ impl SearchableItem for Editor {
     }
 }
 
+/// Computes the text that replaces a search match, given a query with a replacement.
+pub fn replacement_for_match(
+    snapshot: &MultiBufferSnapshot,
+    range: &Range<Anchor>,
+    query: &SearchQuery,
+) -> Option<Arc<str>> {
+    if query.is_structural() {
+        return structural_replacement(snapshot, range, query);
+    }
+    let text = snapshot.text_for_range(range.clone()).collect::<Vec<_>>();
+    let text: Cow<_> = if text.len() == 1 {
+        text.first().cloned().unwrap().into()
+    } else {
+        text.join("").into()
+    };
+    let replacement = query.replacement_for(&text)?;
+    Some(Arc::from(&*replacement))
+}
+
+/// Computes the replacement for a structural search match, which may reuse the text captured by its metavariables.
+fn structural_replacement(
+    snapshot: &MultiBufferSnapshot,
+    range: &Range<Anchor>,
+    query: &SearchQuery,
+) -> Option<Arc<str>> {
+    let buffer_ranges = snapshot.range_to_buffer_ranges(range.clone());
+    let [(buffer, range, _)] = buffer_ranges.as_slice() else {
+        return None;
+    };
+    let replacement = query.structural_replacement_for(buffer, range.clone())?;
+    Some(Arc::from(replacement))
+}
+
 pub fn active_match_index(
     direction: Direction,
     ranges: &[Range<Anchor>],
@@ -0,0 +1,69 @@
+use gpui::{Context, Entity, IntoElement, ParentElement, Render, Subscription, Window, div};
+use ui::{Button, ButtonCommon, Clickable, Color, FluentBuilder, LabelSize, Tooltip};
+use workspace::{StatusItemView, item::ItemHandle};
+
+use crate::{Editor, actions::ExitLargeFileMode};
+
+/// A status bar item shown when the active editor's buffer is in large-file mode, which enables
+/// full features for the buffer when clicked.
+pub struct LargeFileIndicator {
+    active_editor: Option<Entity<Editor>>,
+    _observe_active_editor: Option<Subscription>,
+}
+
+impl LargeFileIndicator {
+    pub fn new() -> Self {
+        Self {
+            active_editor: None,
+            _observe_active_editor: None,
+        }
+    }
+}
+
+impl Render for LargeFileIndicator {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let editor = self
+            .active_editor
+            .clone()
+            .filter(|editor| editor.read(cx).is_in_large_file_mode(cx));
+        div().when_some(editor, |el, editor| {
+            el.child(
+                Button::new("large-file-mode", "Large File")
+                    .label_size(LabelSize::Small)
+                    .color(Color::Warning)
+                    .on_click(move |_, window, cx| {
+                        editor.update(cx, |editor, cx| {
+                            editor.exit_large_file_mode(&ExitLargeFileMode, window, cx)
+                        });
+                    })
+                    .tooltip(|window, cx| {
+                        Tooltip::with_meta(
+                            "Enable Full Features",
+                            Some(&ExitLargeFileMode),
+                            "Syntax highlighting, language servers and git diffs are disabled for this file",
+                            window,
+                            cx,
+                        )
+                    }),
+            )
+        })
+    }
+}
+
+impl StatusItemView for LargeFileIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.downcast::<Editor>()) {
+            self._observe_active_editor = Some(cx.observe(&editor, |_, _, cx| cx.notify()));
+            self.active_editor = Some(editor);
+        } else {
+            self.active_editor = None;
+            self._observe_active_editor = None;
+        }
+        cx.notify();
+    }
+}
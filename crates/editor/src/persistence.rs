@@ -113,6 +113,12 @@ define_connection!(
     //   start: usize,
     //   end: usize,
     // )
+    //
+    // undo_histories(
+    //   path: PathBuf,
+    //   fingerprint: String,
+    //   history: String,
+    // )
     pub static ref DB: EditorDb<WorkspaceDb> = &[
         sql! (
             CREATE TABLE editors(
@@ -188,6 +194,14 @@ define_connection!(
                 ON DELETE CASCADE
             ) STRICT;
         ),
+        sql! (
+            CREATE TABLE undo_histories (
+                path BLOB NOT NULL,
+                fingerprint TEXT NOT NULL,
+                history TEXT NOT NULL,
+                PRIMARY KEY(path)
+            ) STRICT;
+        ),
     ];
 );
 
@@ -270,6 +284,31 @@ impl EditorDb {
         }
     }
 
+    query! {
+        pub fn get_undo_history(path: PathBuf) -> Result<Option<(String, String)>> {
+            SELECT fingerprint, history
+            FROM undo_histories
+            WHERE path = ?
+        }
+    }
+
+    // Replacing a row gives it a new rowid, so the rowids order the histories by how recently they were saved.
+    query! {
+        pub async fn save_undo_history(
+            path: PathBuf,
+            fingerprint: String,
+            history: String,
+            max_files: usize
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO undo_histories (path, fingerprint, history)
+            VALUES (?1, ?2, ?3);
+            DELETE FROM undo_histories
+            WHERE rowid NOT IN (
+                SELECT rowid FROM undo_histories ORDER BY rowid DESC LIMIT ?4
+            )
+        }
+    }
+
     pub async fn save_editor_selections(
         &self,
         editor_id: ItemId,
@@ -454,4 +493,28 @@ mod tests {
             .unwrap();
         assert_eq!(have, serialized_editor);
     }
+
+    #[gpui::test]
+    async fn test_save_and_evict_undo_histories() {
+        for (path, history) in [
+            ("a.txt", "a"),
+            ("b.txt", "b"),
+            ("a.txt", "a2"),
+            ("c.txt", "c"),
+        ] {
+            DB.save_undo_history(PathBuf::from(path), "fingerprint".into(), history.into(), 2)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            DB.get_undo_history(PathBuf::from("a.txt")).unwrap(),
+            Some(("fingerprint".to_string(), "a2".to_string()))
+        );
+        assert_eq!(DB.get_undo_history(PathBuf::from("b.txt")).unwrap(), None);
+        assert_eq!(
+            DB.get_undo_history(PathBuf::from("c.txt")).unwrap(),
+            Some(("fingerprint".to_string(), "c".to_string()))
+        );
+    }
 }
@@ -0,0 +1,324 @@
+use crate::{EditorSettings, editor_settings::PersistentUndo, persistence::DB};
+use anyhow::Result;
+use collections::HashMap;
+use gpui::{App, AppContext as _, Context, Entity, Task};
+use language::{Buffer, text_diff};
+use parking_lot::Mutex;
+use project::Project;
+use serde::{Deserialize, Serialize};
+use settings::Settings as _;
+use sha2::{Digest, Sha256};
+use std::{ops::Range, path::PathBuf, sync::Arc};
+use text::Rope;
+
+/// The undo history of a buffer, as text edits that don't depend on the buffer's replica or
+/// operations, so that it can be persisted and reapplied to a buffer with the same text.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SerializedUndoHistory {
+    /// The undo steps, oldest first. Each step applies to the text left by the previous one,
+    /// and the last one leaves the text the history was captured from.
+    steps: Vec<Vec<SerializedEdit>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct SerializedEdit {
+    /// The range replaced by the edit, in the text before the step.
+    range: Range<usize>,
+    old_text: String,
+    new_text: String,
+}
+
+/// The steps captured for a buffer's undo history, by the versions they go from and to, so that
+/// each save only needs to diff the versions of the transactions made since the previous one.
+#[derive(Clone, Default)]
+pub(crate) struct UndoHistoryCache(
+    Arc<Mutex<HashMap<(clock::Global, clock::Global), Arc<[SerializedEdit]>>>>,
+);
+
+impl SerializedUndoHistory {
+    /// Captures the most recent steps of the undo stack, given the versions at which its transactions
+    /// started, oldest first. Steps are dropped, oldest first, to stay within the settings' limits.
+    /// Steps found in the `cache` are reused, and the cache is left with the steps of this history.
+    pub(crate) fn capture(
+        snapshot: &text::BufferSnapshot,
+        start_versions: &[clock::Global],
+        settings: &PersistentUndo,
+        cache: &UndoHistoryCache,
+    ) -> Self {
+        let max_size = settings.max_size_kb * 1024;
+        let mut size = 0;
+        let mut steps = Vec::new();
+        let mut cached_steps = cache.0.lock();
+        let mut captured_steps = HashMap::default();
+        let mut end_version = snapshot.version().clone();
+        // The text at `end_version`, only materialized when a step isn't cached.
+        let mut new_text = None;
+        for version in start_versions.iter().rev().take(settings.max_entries) {
+            let key = (version.clone(), end_version);
+            let step = match cached_steps.get(&key) {
+                Some(step) => {
+                    new_text = None;
+                    step.clone()
+                }
+                None => {
+                    let text = new_text
+                        .get_or_insert_with(|| snapshot.rope_for_version(&key.1).to_string());
+                    let old_text = snapshot.rope_for_version(version).to_string();
+                    let step = text_diff(&old_text, text)
+                        .into_iter()
+                        .map(|(range, new_text)| SerializedEdit {
+                            old_text: old_text[range.clone()].to_string(),
+                            new_text: new_text.to_string(),
+                            range,
+                        })
+                        .collect::<Arc<[_]>>();
+                    *text = old_text;
+                    step
+                }
+            };
+            captured_steps.insert(key, step.clone());
+            end_version = version.clone();
+            size += step
+                .iter()
+                .map(|edit| edit.old_text.len() + edit.new_text.len())
+                .sum::<usize>();
+            if size > max_size {
+                break;
+            }
+            if !step.is_empty() {
+                steps.push(step.to_vec());
+            }
+        }
+        *cached_steps = captured_steps;
+        steps.reverse();
+        Self { steps }
+    }
+
+    /// Reapplies the history to a buffer containing the text it was captured from, so that each
+    /// step can be undone separately.
+    pub(crate) fn restore(self, buffer: &mut Buffer, cx: &mut Context<Buffer>) {
+        if self.steps.is_empty() {
+            return;
+        }
+        let was_dirty = buffer.is_dirty();
+
+        // Rewind the buffer to the text before the first step, and replay each step in its own
+        // transaction. The rewind itself is not part of the history.
+        buffer.start_transaction();
+        for step in self.steps.iter().rev() {
+            let mut delta = 0_isize;
+            let edits = step
+                .iter()
+                .map(|edit| {
+                    let start = (edit.range.start as isize + delta) as usize;
+                    delta += edit.new_text.len() as isize - edit.range.len() as isize;
+                    (start..start + edit.new_text.len(), edit.old_text.as_str())
+                })
+                .collect::<Vec<_>>();
+            buffer.edit(edits, None, cx);
+        }
+        let rewind = buffer.end_transaction(cx);
+        buffer.finalize_last_transaction();
+
+        for step in &self.steps {
+            buffer.start_transaction();
+            buffer.edit(
+                step.iter()
+                    .map(|edit| (edit.range.clone(), edit.new_text.as_str())),
+                None,
+                cx,
+            );
+            buffer.end_transaction(cx);
+            buffer.finalize_last_transaction();
+        }
+        if let Some(rewind) = rewind {
+            buffer.forget_transaction(rewind);
+        }
+
+        if !was_dirty {
+            buffer.set_saved_version(buffer.version(), cx);
+        }
+    }
+}
+
+fn content_fingerprint(text: &Rope) -> String {
+    let mut hasher = Sha256::new();
+    for chunk in text.chunks() {
+        hasher.update(chunk.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Saves the undo history of a saved buffer to the database, if enabled in the settings.
+pub(crate) fn save_undo_history(
+    buffer: &Entity<Buffer>,
+    abs_path: PathBuf,
+    project: &Entity<Project>,
+    cache: &UndoHistoryCache,
+    cx: &App,
+) -> Option<Task<Result<()>>> {
+    let settings = EditorSettings::get_global(cx).persistent_undo;
+    let buffer = buffer.read(cx);
+    if !settings.enabled || !project.read(cx).is_local() || buffer.is_dirty() {
+        return None;
+    }
+    let start_versions = buffer
+        .undo_stack()
+        .iter()
+        .map(|entry| entry.transaction().start.clone())
+        .collect::<Vec<_>>();
+    if start_versions.is_empty() {
+        return None;
+    }
+    let snapshot = buffer.text_snapshot();
+    let cache = cache.clone();
+    Some(cx.background_spawn(async move {
+        let history = SerializedUndoHistory::capture(&snapshot, &start_versions, &settings, &cache);
+        let fingerprint = content_fingerprint(snapshot.as_rope());
+        DB.save_undo_history(
+            abs_path,
+            fingerprint,
+            serde_json::to_string(&history)?,
+            settings.max_files,
+        )
+        .await
+    }))
+}
+
+/// Restores the undo history saved for a freshly opened buffer, if enabled in the settings and
+/// the buffer's text still matches the text the history was saved for.
+pub(crate) fn restore_undo_history(
+    buffer: &Entity<Buffer>,
+    project: &Entity<Project>,
+    cx: &mut App,
+) {
+    let settings = EditorSettings::get_global(cx).persistent_undo;
+    if !settings.enabled || !project.read(cx).is_local() {
+        return;
+    }
+    let buffer_ref = buffer.read(cx);
+    if buffer_ref.is_dirty() || !buffer_ref.undo_stack().is_empty() {
+        return;
+    }
+    let Some(file) = project::File::from_dyn(buffer_ref.file()) else {
+        return;
+    };
+    let abs_path = file.abs_path(cx);
+    let snapshot = buffer_ref.text_snapshot();
+    let version = snapshot.version().clone();
+    let buffer = buffer.downgrade();
+    cx.spawn(async move |cx| {
+        let history = cx
+            .background_spawn(async move {
+                let Some((fingerprint, history)) = DB.get_undo_history(abs_path)? else {
+                    return Ok(None);
+                };
+                if fingerprint != content_fingerprint(snapshot.as_rope()) {
+                    return Ok(None);
+                }
+                anyhow::Ok(Some(serde_json::from_str::<SerializedUndoHistory>(
+                    &history,
+                )?))
+            })
+            .await?;
+        if let Some(history) = history {
+            buffer.update(cx, |buffer, cx| {
+                if buffer.version() == version
+                    && buffer.undo_stack().is_empty()
+                    && !buffer.is_dirty()
+                {
+                    history.restore(buffer, cx);
+                }
+            })?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use std::{cell::Cell, rc::Rc};
+
+    #[gpui::test]
+    fn test_capture_and_restore_undo_history(cx: &mut TestAppContext) {
+        let settings = PersistentUndo {
+            enabled: true,
+            max_entries: 2,
+            max_size_kb: 1,
+            max_files: 1,
+        };
+
+        let buffer = cx.new(|cx| Buffer::local("one two three", cx));
+        let history = buffer.update(cx, |buffer, cx| {
+            buffer.edit([(0..3, "ONE")], None, cx);
+            buffer.finalize_last_transaction();
+            buffer.edit([(4..7, "")], None, cx);
+            buffer.finalize_last_transaction();
+            buffer.edit([(0..0, "> "), (5..10, "3")], None, cx);
+            buffer.finalize_last_transaction();
+            assert_eq!(buffer.text(), "> ONE  3");
+
+            let start_versions = buffer
+                .undo_stack()
+                .iter()
+                .map(|entry| entry.transaction().start.clone())
+                .collect::<Vec<_>>();
+            let cache = UndoHistoryCache::default();
+            let history = SerializedUndoHistory::capture(
+                &buffer.text_snapshot(),
+                &start_versions,
+                &settings,
+                &cache,
+            );
+            // Capturing again reuses the cached steps, and gives the same history.
+            assert_eq!(cache.0.lock().len(), 2);
+            assert_eq!(
+                SerializedUndoHistory::capture(
+                    &buffer.text_snapshot(),
+                    &start_versions,
+                    &settings,
+                    &cache,
+                ),
+                history
+            );
+            history
+        });
+        assert_eq!(history.steps.len(), 2);
+
+        let history: SerializedUndoHistory =
+            serde_json::from_str(&serde_json::to_string(&history).unwrap()).unwrap();
+        let restored = cx.new(|cx| Buffer::local("> ONE  3", cx));
+        let saved = Rc::new(Cell::new(false));
+        cx.update(|cx| {
+            let saved = saved.clone();
+            cx.subscribe(&restored, move |_, event, _| {
+                if let language::BufferEvent::Saved = event {
+                    saved.set(true);
+                }
+            })
+            .detach();
+        });
+        restored.update(cx, |buffer, cx| {
+            history.restore(buffer, cx);
+            assert_eq!(buffer.text(), "> ONE  3");
+            assert!(!buffer.is_dirty());
+            assert_eq!(buffer.undo_stack().len(), 2);
+
+            buffer.undo(cx);
+            assert_eq!(buffer.text(), "ONE  three");
+            buffer.undo(cx);
+            assert_eq!(buffer.text(), "ONE two three");
+            buffer.undo(cx);
+            assert_eq!(buffer.text(), "ONE two three");
+            buffer.redo(cx);
+            buffer.redo(cx);
+            assert_eq!(buffer.text(), "> ONE  3");
+        });
+        // Restoring the history doesn't save the buffer.
+        cx.run_until_parked();
+        assert!(!saved.get());
+    }
+}
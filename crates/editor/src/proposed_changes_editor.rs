@@ -135,6 +135,10 @@ impl ProposedChangesEditor {
         })
     }
 
+    pub fn editor(&self) -> &Entity<Editor> {
+        &self.editor
+    }
+
     pub fn set_title(&mut self, title: SharedString, cx: &mut Context<Self>) {
         self.title = title;
         cx.notify();
@@ -1,7 +1,7 @@
 use std::{
     cell::Ref,
     cmp, iter, mem,
-    ops::{Deref, DerefMut, Range, Sub},
+    ops::{Deref, DerefMut, Range, RangeInclusive, Sub},
     sync::Arc,
 };
 
@@ -24,6 +24,53 @@ pub struct PendingSelection {
     pub mode: SelectMode,
 }
 
+/// A rectangular selection spanning the same display columns on consecutive display rows. Its
+/// columns can lie past the end of shorter lines, in virtual space, where the selection of such a
+/// line is a cursor at its end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockSelection {
+    pub tail: DisplayPoint,
+    pub head: DisplayPoint,
+    /// Whether the selection of each row extends to the end of its line, as after `$` in Vim.
+    pub to_line_end: bool,
+}
+
+impl BlockSelection {
+    pub fn new(tail: DisplayPoint, head: DisplayPoint) -> Self {
+        Self {
+            tail,
+            head,
+            to_line_end: false,
+        }
+    }
+
+    pub fn rows(&self) -> RangeInclusive<DisplayRow> {
+        cmp::min(self.tail.row(), self.head.row())..=cmp::max(self.tail.row(), self.head.row())
+    }
+
+    pub fn columns(&self) -> Range<u32> {
+        cmp::min(self.tail.column(), self.head.column())
+            ..cmp::max(self.tail.column(), self.head.column())
+    }
+
+    pub fn is_reversed(&self) -> bool {
+        self.head.column() < self.tail.column()
+    }
+
+    /// The number of columns past the end of its line at which a cursor of the block is shown,
+    /// for cursors at the end of lines that end before the block's head column.
+    pub fn virtual_columns(&self, cursor: DisplayPoint, map: &DisplaySnapshot) -> u32 {
+        if self.to_line_end || !self.rows().contains(&cursor.row()) {
+            return 0;
+        }
+        let line_len = map.line_len(cursor.row());
+        if cursor.column() < line_len {
+            return 0;
+        }
+        self.head.column().saturating_sub(line_len)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SelectionsCollection {
     display_map: Entity<DisplayMap>,
@@ -35,6 +82,8 @@ pub struct SelectionsCollection {
     pub disjoint: Arc<[Selection<Anchor>]>,
     /// A pending selection, such as when the mouse is being dragged
     pub pending: Option<PendingSelection>,
+    /// The rectangle the selections were made from, until they're changed in any other way.
+    pub block: Option<BlockSelection>,
 }
 
 impl SelectionsCollection {
@@ -55,6 +104,7 @@ impl SelectionsCollection {
                 },
                 mode: SelectMode::Character,
             }),
+            block: None,
         }
     }
 
@@ -71,6 +121,7 @@ impl SelectionsCollection {
         self.line_mode = other.line_mode;
         self.disjoint = other.disjoint.clone();
         self.pending.clone_from(&other.pending);
+        self.block = other.block;
     }
 
     pub fn count(&self) -> usize {
@@ -399,6 +450,8 @@ impl SelectionsCollection {
         cx: &mut App,
         change: impl FnOnce(&mut MutableSelectionsCollection) -> R,
     ) -> (bool, R) {
+        // Changing the selections in any other way than selecting a block ends the block.
+        let block = self.block.take();
         let mut mutable_collection = MutableSelectionsCollection {
             collection: self,
             selections_changed: false,
@@ -410,7 +463,11 @@ impl SelectionsCollection {
             !mutable_collection.disjoint.is_empty() || mutable_collection.pending.is_some(),
             "There must be at least one selection"
         );
-        (mutable_collection.selections_changed, result)
+        let selections_changed = mutable_collection.selections_changed;
+        if !selections_changed && self.block.is_none() {
+            self.block = block;
+        }
+        (selections_changed, result)
     }
 }
 
@@ -632,6 +689,51 @@ impl<'a> MutableSelectionsCollection<'a> {
         self.select_anchors(selections)
     }
 
+    /// Selects the given block, with one selection for every line it spans. The selection of a
+    /// line that ends before the block's start is a cursor at its end.
+    pub fn select_block(&mut self, block: BlockSelection) {
+        let map = self.display_map();
+        let columns = block.columns();
+        let reversed = block.is_reversed();
+        let selections = (block.rows().start().0..=block.rows().end().0)
+            .map(DisplayRow)
+            .filter(|row| !map.is_block_line(*row))
+            .map(|row| {
+                let line_len = map.line_len(row);
+                let start = map.clip_point(
+                    DisplayPoint::new(row, cmp::min(columns.start, line_len)),
+                    Bias::Left,
+                );
+                let end = if block.to_line_end {
+                    DisplayPoint::new(row, line_len)
+                } else {
+                    map.clip_point(
+                        DisplayPoint::new(row, cmp::min(columns.end, line_len)),
+                        Bias::Right,
+                    )
+                };
+                Selection {
+                    id: post_inc(&mut self.collection.next_selection_id),
+                    start: start.to_point(&map),
+                    end: end.to_point(&map),
+                    reversed,
+                    goal: SelectionGoal::None,
+                }
+            })
+            .collect::<Vec<_>>();
+        if selections.is_empty() {
+            return;
+        }
+        self.select(selections);
+        self.collection.block = Some(block);
+    }
+
+    /// Records the block that the current selections were made from, for callers that build
+    /// the selections of a block themselves.
+    pub fn set_block(&mut self, block: BlockSelection) {
+        self.collection.block = Some(block);
+    }
+
     pub fn new_selection_id(&mut self) -> usize {
         post_inc(&mut self.next_selection_id)
     }
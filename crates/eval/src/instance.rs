@@ -582,6 +582,7 @@ impl ExampleInstance {
         let assertions = assertions.into_iter().map(|assertion| {
             let request = LanguageModelRequest {
                 thread_id: None,
+                profile_id: None,
                 prompt_id: None,
                 mode: None,
                 intent: None,
@@ -594,6 +595,7 @@ impl ExampleInstance {
                 tools: Vec::new(),
                 tool_choice: None,
                 stop: Vec::new(),
+                max_tokens: None,
                 thinking_allowed: true,
             };
 
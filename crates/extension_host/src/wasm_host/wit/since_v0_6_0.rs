@@ -971,6 +971,14 @@ impl ExtensionImports for WasmState {
                                 command: None,
                                 settings: Some(settings),
                             })?),
+                            // Extensions can't provide servers reached by URL, so there is
+                            // neither a command nor settings of theirs.
+                            project::project_settings::ContextServerSettings::Http { .. } => {
+                                Ok(serde_json::to_string(&settings::ContextServerSettings {
+                                    command: None,
+                                    settings: None,
+                                })?)
+                            }
                         }
                     }
                     _ => {
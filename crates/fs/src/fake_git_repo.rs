@@ -1,14 +1,17 @@
-use crate::{FakeFs, Fs};
+use crate::{FakeFs, Fs, RemoveOptions};
 use anyhow::{Context as _, Result};
 use collections::{HashMap, HashSet};
 use futures::future::{self, BoxFuture, join_all};
 use git::{
     blame::Blame,
     repository::{
-        AskPassDelegate, Branch, CommitDetails, CommitOptions, FetchOptions, GitRepository,
-        GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode,
+        AskPassDelegate, Branch, CommitDetails, CommitOptions, ConflictStages, FetchOptions,
+        GitRepository, GitRepositoryCheckpoint, GitWorktree, InProgressOperation, LogEntry,
+        LogOptions, PushOptions, RebaseTodoEntry, Remote, RepoPath, ResetMode, StashEntry, Tag,
+    },
+    status::{
+        FileStatus, GitStatus, StatusCode, TrackedStatus, UnmergedStatus, UnmergedStatusCode,
     },
-    status::{FileStatus, GitStatus, StatusCode, TrackedStatus, UnmergedStatus},
 };
 use gpui::{AsyncApp, BackgroundExecutor};
 use ignore::gitignore::GitignoreBuilder;
@@ -29,6 +32,8 @@ pub struct FakeGitRepository {
 pub struct FakeGitRepositoryState {
     pub event_emitter: smol::channel::Sender<PathBuf>,
     pub unmerged_paths: HashMap<RepoPath, UnmergedStatus>,
+    /// The index stages of conflicted paths.
+    pub conflict_stages: HashMap<RepoPath, ConflictStages>,
     pub head_contents: HashMap<RepoPath, String>,
     pub index_contents: HashMap<RepoPath, String>,
     pub blames: HashMap<RepoPath, Blame>,
@@ -36,6 +41,29 @@ pub struct FakeGitRepositoryState {
     pub branches: HashSet<String>,
     pub simulated_index_write_error_message: Option<String>,
     pub refs: HashMap<String, String>,
+    /// Stash entries, most recent first.
+    pub stash_entries: Vec<FakeStashEntry>,
+    /// The commit history returned by `log`, most recent first, along with the paths each
+    /// commit touched.
+    pub log: Vec<(LogEntry, Vec<RepoPath>)>,
+    pub in_progress_operation: Option<InProgressOperation>,
+    /// The cherry-picks, reverts and rebases that were run, as the arguments to git.
+    pub sequencer_commands: Vec<Vec<String>>,
+    /// When set, the next cherry-pick, revert or rebase stops with a conflict at this path.
+    pub simulated_sequencer_conflict: Option<RepoPath>,
+    /// Tags, most recently created first.
+    pub tags: Vec<Tag>,
+    /// Linked working trees. The main working tree isn't included.
+    pub worktrees: Vec<GitWorktree>,
+}
+
+/// A stash entry recorded by [`FakeGitRepository`]. A `None` content means the path
+/// didn't exist in the index or the working tree when it was stashed.
+#[derive(Debug, Clone)]
+pub struct FakeStashEntry {
+    pub message: String,
+    pub index_contents: HashMap<RepoPath, Option<String>>,
+    pub worktree_contents: HashMap<RepoPath, Option<String>>,
 }
 
 impl FakeGitRepositoryState {
@@ -45,11 +73,19 @@ impl FakeGitRepositoryState {
             head_contents: Default::default(),
             index_contents: Default::default(),
             unmerged_paths: Default::default(),
+            conflict_stages: Default::default(),
             blames: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
             simulated_index_write_error_message: Default::default(),
             refs: HashMap::from_iter([("HEAD".into(), "abc".into())]),
+            stash_entries: Default::default(),
+            log: Default::default(),
+            in_progress_operation: Default::default(),
+            sequencer_commands: Default::default(),
+            simulated_sequencer_conflict: Default::default(),
+            tags: Default::default(),
+            worktrees: Default::default(),
         }
     }
 }
@@ -69,6 +105,96 @@ impl FakeGitRepository {
         }
         .boxed()
     }
+
+    async fn write_worktree_contents(
+        &self,
+        contents: impl IntoIterator<Item = (RepoPath, Option<String>)>,
+    ) -> Result<()> {
+        let workdir_path = self.dot_git_path.parent().unwrap();
+        for (path, content) in contents {
+            let abs_path = workdir_path.join(&path);
+            match content {
+                Some(content) => self.fs.write(&abs_path, content.as_bytes()).await?,
+                None => {
+                    self.fs
+                        .remove_file(
+                            &abs_path,
+                            RemoveOptions {
+                                ignore_if_not_exists: true,
+                                ..Default::default()
+                            },
+                        )
+                        .await?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn finish_operation(&self, operation: InProgressOperation) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            anyhow::ensure!(
+                state.in_progress_operation == Some(operation),
+                "no {} in progress",
+                operation.command()
+            );
+            state.in_progress_operation = None;
+            state.unmerged_paths.clear();
+            Ok(())
+        })
+    }
+
+    fn run_sequencer_operation(
+        &self,
+        operation: InProgressOperation,
+        args: Vec<String>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if let Some(in_progress_operation) = state.in_progress_operation {
+                anyhow::bail!("a {} is in progress", in_progress_operation.command());
+            }
+            state.sequencer_commands.push(args);
+            if let Some(path) = state.simulated_sequencer_conflict.take() {
+                state.unmerged_paths.insert(
+                    path,
+                    UnmergedStatus {
+                        first_head: UnmergedStatusCode::Updated,
+                        second_head: UnmergedStatusCode::Updated,
+                    },
+                );
+                state.in_progress_operation = Some(operation);
+            }
+            Ok(())
+        })
+    }
+
+    fn apply_stash_entry(&self, index: usize, pop: bool) -> BoxFuture<'_, Result<()>> {
+        async move {
+            let entry = self
+                .with_state_async(true, move |state| {
+                    let entry = state
+                        .stash_entries
+                        .get(index)
+                        .cloned()
+                        .with_context(|| format!("no stash entry at index {index}"))?;
+                    for (path, content) in &entry.index_contents {
+                        match content {
+                            Some(content) => {
+                                state.index_contents.insert(path.clone(), content.clone())
+                            }
+                            None => state.index_contents.remove(path),
+                        };
+                    }
+                    if pop {
+                        state.stash_entries.remove(index);
+                    }
+                    Ok(entry)
+                })
+                .await?;
+            self.write_worktree_contents(entry.worktree_contents).await
+        }
+        .boxed()
+    }
 }
 
 impl GitRepository for FakeGitRepository {
@@ -89,6 +215,16 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
+    fn load_conflict_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<ConflictStages>> {
+        self.with_state_async(false, move |state| {
+            state
+                .conflict_stages
+                .get(&path)
+                .cloned()
+                .with_context(|| format!("{} is not conflicted", path.display()))
+        })
+    }
+
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
         async {
             self.with_state_async(false, move |state| {
@@ -153,6 +289,215 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let args = ["cherry-pick".to_string()]
+            .into_iter()
+            .chain(commits)
+            .collect();
+        self.run_sequencer_operation(InProgressOperation::CherryPick, args)
+    }
+
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let args = ["revert".to_string()].into_iter().chain(commits).collect();
+        self.run_sequencer_operation(InProgressOperation::Revert, args)
+    }
+
+    fn rebase(
+        &self,
+        upstream: String,
+        todo: Option<Vec<RebaseTodoEntry>>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let args = match todo {
+            Some(todo) => ["rebase".to_string(), "--interactive".to_string(), upstream]
+                .into_iter()
+                .chain(todo.into_iter().map(|entry| entry.sha.to_string()))
+                .collect(),
+            None => vec!["rebase".to_string(), upstream],
+        };
+        self.run_sequencer_operation(InProgressOperation::Rebase, args)
+    }
+
+    fn in_progress_operation(&self) -> BoxFuture<'_, Option<InProgressOperation>> {
+        async {
+            self.with_state_async(false, |state| Ok(state.in_progress_operation))
+                .await
+                .ok()
+                .flatten()
+        }
+        .boxed()
+    }
+
+    fn continue_operation(
+        &self,
+        operation: InProgressOperation,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.finish_operation(operation)
+    }
+
+    fn skip_operation(
+        &self,
+        operation: InProgressOperation,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.finish_operation(operation)
+    }
+
+    fn abort_operation(
+        &self,
+        operation: InProgressOperation,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.finish_operation(operation)
+    }
+
+    fn log(&self, options: LogOptions) -> BoxFuture<'_, Result<Vec<LogEntry>>> {
+        self.with_state_async(false, move |state| {
+            Ok(state
+                .log
+                .iter()
+                .filter(|(entry, paths)| {
+                    options
+                        .author
+                        .as_ref()
+                        .is_none_or(|author| entry.author_name.contains(author.as_str()))
+                        && options
+                            .path
+                            .as_ref()
+                            .is_none_or(|path| paths.contains(path))
+                })
+                .skip(options.skip)
+                .take(options.limit.unwrap_or(usize::MAX))
+                .map(|(entry, _)| entry.clone())
+                .collect())
+        })
+    }
+
+    fn stash_paths(
+        &self,
+        paths: Vec<RepoPath>,
+        message: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        async move {
+            let paths = if paths.is_empty() {
+                self.with_state_async(false, |state| {
+                    Ok(state
+                        .head_contents
+                        .keys()
+                        .chain(state.index_contents.keys())
+                        .cloned()
+                        .collect::<HashSet<_>>())
+                })
+                .await?
+                .into_iter()
+                .collect()
+            } else {
+                paths
+            };
+
+            let workdir_path = self.dot_git_path.parent().unwrap();
+            let mut worktree_contents = HashMap::default();
+            for path in paths {
+                let content = self.fs.load(&workdir_path.join(&path)).await.ok();
+                worktree_contents.insert(path, content);
+            }
+
+            let restored_contents = self
+                .with_state_async(true, move |state| {
+                    let mut entry = FakeStashEntry {
+                        message: message.unwrap_or_else(|| {
+                            let branch = state.current_branch_name.as_deref().unwrap_or("HEAD");
+                            format!("WIP on {branch}")
+                        }),
+                        index_contents: HashMap::default(),
+                        worktree_contents: HashMap::default(),
+                    };
+                    let mut restored_contents = Vec::new();
+                    for (path, worktree_content) in worktree_contents {
+                        let head_content = state.head_contents.get(&path).cloned();
+                        let index_content = state.index_contents.get(&path).cloned();
+                        if index_content == head_content && worktree_content == head_content {
+                            continue;
+                        }
+                        match &head_content {
+                            Some(content) => {
+                                state.index_contents.insert(path.clone(), content.clone())
+                            }
+                            None => state.index_contents.remove(&path),
+                        };
+                        entry.index_contents.insert(path.clone(), index_content);
+                        entry
+                            .worktree_contents
+                            .insert(path.clone(), worktree_content);
+                        restored_contents.push((path, head_content));
+                    }
+                    anyhow::ensure!(!restored_contents.is_empty(), "No local changes to save");
+                    state.stash_entries.insert(0, entry);
+                    Ok(restored_contents)
+                })
+                .await?;
+            self.write_worktree_contents(restored_contents).await
+        }
+        .boxed()
+    }
+
+    fn stash_entries(&self) -> BoxFuture<'_, Result<Vec<StashEntry>>> {
+        self.with_state_async(false, |state| {
+            Ok(state
+                .stash_entries
+                .iter()
+                .enumerate()
+                .map(|(index, entry)| StashEntry {
+                    index,
+                    sha: format!("stash-{index}").into(),
+                    message: entry.message.clone().into(),
+                    commit_timestamp: 0,
+                })
+                .collect())
+        })
+    }
+
+    fn stash_apply(
+        &self,
+        index: usize,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.apply_stash_entry(index, false)
+    }
+
+    fn stash_pop(
+        &self,
+        index: usize,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.apply_stash_entry(index, true)
+    }
+
+    fn stash_drop(
+        &self,
+        index: usize,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            anyhow::ensure!(
+                index < state.stash_entries.len(),
+                "no stash entry at index {index}"
+            );
+            state.stash_entries.remove(index);
+            Ok(())
+        })
+    }
+
     fn reset(
         &self,
         _commit: String,
@@ -344,6 +689,175 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
+    fn delete_branch(
+        &self,
+        name: String,
+        _force: bool,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            anyhow::ensure!(
+                state.current_branch_name.as_ref() != Some(&name),
+                "cannot delete the current branch {name}"
+            );
+            anyhow::ensure!(
+                !state
+                    .worktrees
+                    .iter()
+                    .any(|worktree| worktree.branch_name() == Some(name.as_str())),
+                "branch {name} is checked out in another worktree"
+            );
+            anyhow::ensure!(state.branches.remove(&name), "no such branch {name}");
+            Ok(())
+        })
+    }
+
+    fn rename_branch(
+        &self,
+        old_name: String,
+        new_name: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            anyhow::ensure!(
+                !state.branches.contains(&new_name),
+                "branch {new_name} already exists"
+            );
+            anyhow::ensure!(
+                state.branches.remove(&old_name),
+                "no such branch {old_name}"
+            );
+            if state.current_branch_name.as_ref() == Some(&old_name) {
+                state.current_branch_name = Some(new_name.clone());
+            }
+            state.branches.insert(new_name);
+            Ok(())
+        })
+    }
+
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>> {
+        self.with_state_async(false, |state| Ok(state.tags.clone()))
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            anyhow::ensure!(
+                !state.tags.iter().any(|tag| tag.name.as_ref() == name),
+                "tag {name} already exists"
+            );
+            let sha = match target {
+                Some(target) => state.refs.get(&target).cloned().unwrap_or(target),
+                None => state.refs.get("HEAD").cloned().context("no HEAD")?,
+            };
+            state.tags.insert(
+                0,
+                Tag {
+                    name: name.into(),
+                    sha: sha.into(),
+                    subject: message.clone().unwrap_or_default().into(),
+                    is_annotated: message.is_some(),
+                    timestamp: None,
+                },
+            );
+            Ok(())
+        })
+    }
+
+    fn delete_tag(
+        &self,
+        name: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let ix = state
+                .tags
+                .iter()
+                .position(|tag| tag.name.as_ref() == name)
+                .with_context(|| format!("no such tag {name}"))?;
+            state.tags.remove(ix);
+            Ok(())
+        })
+    }
+
+    fn worktrees(&self) -> BoxFuture<'_, Result<Vec<GitWorktree>>> {
+        let main_worktree_path = self.dot_git_path.parent().unwrap().to_path_buf();
+        self.with_state_async(false, move |state| {
+            let main_worktree = GitWorktree {
+                path: main_worktree_path,
+                sha: state.refs.get("HEAD").map(|sha| sha.clone().into()),
+                branch: state
+                    .current_branch_name
+                    .as_ref()
+                    .map(|name| format!("refs/heads/{name}").into()),
+                is_main: true,
+                is_locked: false,
+                is_prunable: false,
+            };
+            Ok(std::iter::once(main_worktree)
+                .chain(state.worktrees.iter().cloned())
+                .collect())
+        })
+    }
+
+    fn create_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if create_branch {
+                anyhow::ensure!(
+                    state.branches.insert(branch.clone()),
+                    "branch {branch} already exists"
+                );
+            } else {
+                anyhow::ensure!(state.branches.contains(&branch), "no such branch {branch}");
+                anyhow::ensure!(
+                    state.current_branch_name.as_ref() != Some(&branch)
+                        && !state
+                            .worktrees
+                            .iter()
+                            .any(|worktree| worktree.branch_name() == Some(branch.as_str())),
+                    "branch {branch} is already checked out"
+                );
+            }
+            state.worktrees.push(GitWorktree {
+                path,
+                sha: state.refs.get("HEAD").map(|sha| sha.clone().into()),
+                branch: Some(format!("refs/heads/{branch}").into()),
+                is_main: false,
+                is_locked: false,
+                is_prunable: false,
+            });
+            Ok(())
+        })
+    }
+
+    fn remove_worktree(
+        &self,
+        path: PathBuf,
+        _force: bool,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let ix = state
+                .worktrees
+                .iter()
+                .position(|worktree| worktree.path == path)
+                .with_context(|| format!("no worktree at {path:?}"))?;
+            state.worktrees.remove(ix);
+            Ok(())
+        })
+    }
+
     fn blame(&self, path: RepoPath, _content: Rope) -> BoxFuture<'_, Result<git::blame::Blame>> {
         self.with_state_async(false, move |state| {
             state
@@ -420,6 +934,17 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn push_tag(
+        &self,
+        _tag_name: String,
+        _remote: String,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<git::repository::RemoteCommandOutput>> {
+        unimplemented!()
+    }
+
     fn pull(
         &self,
         _branch: String,
@@ -27,7 +27,7 @@ use rope::Rope;
 use serde::{Deserialize, Serialize};
 use smol::io::AsyncWriteExt;
 use std::{
-    io::{self, Write},
+    io::{self, Read, Seek, Write},
     path::{Component, Path, PathBuf},
     pin::Pin,
     sync::Arc,
@@ -109,6 +109,8 @@ pub trait Fs: Send + Sync {
         Ok(String::from_utf8(self.load_bytes(path).await?)?)
     }
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
+    /// Loads the bytes of the file starting at the given offset, e.g. to read what was appended to it.
+    async fn load_bytes_from(&self, path: &Path, offset: u64) -> Result<Vec<u8>>;
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
     async fn write(&self, path: &Path, content: &[u8]) -> Result<()>;
@@ -546,6 +548,19 @@ impl Fs for RealFs {
         Ok(bytes)
     }
 
+    async fn load_bytes_from(&self, path: &Path, offset: u64) -> Result<Vec<u8>> {
+        let path = path.to_path_buf();
+        let bytes = smol::unblock(move || {
+            let mut file = std::fs::File::open(path)?;
+            file.seek(io::SeekFrom::Start(offset))?;
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes)?;
+            anyhow::Ok(bytes)
+        })
+        .await?;
+        Ok(bytes)
+    }
+
     #[cfg(not(target_os = "windows"))]
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         smol::unblock(move || {
@@ -1545,6 +1560,17 @@ impl FakeFs {
         .unwrap();
     }
 
+    pub fn set_log_for_repo(
+        &self,
+        dot_git: &Path,
+        log: Vec<(git::repository::LogEntry, Vec<RepoPath>)>,
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state.log = log;
+        })
+        .unwrap();
+    }
+
     /// Put the given git repository into a state with the given status,
     /// by mutating the head, index, and unmerged state.
     pub fn set_status_for_repo(&self, dot_git: &Path, statuses: &[(&Path, FileStatus)]) {
@@ -2151,6 +2177,12 @@ impl Fs for FakeFs {
         self.load_internal(path).await
     }
 
+    async fn load_bytes_from(&self, path: &Path, offset: u64) -> Result<Vec<u8>> {
+        let mut bytes = self.load_internal(path).await?;
+        bytes.drain(..(offset as usize).min(bytes.len()));
+        Ok(bytes)
+    }
+
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path.as_path());
@@ -1,6 +1,8 @@
 pub mod blame;
 pub mod commit;
 mod hosting_provider;
+pub mod line_diff;
+pub mod merge;
 mod remote;
 pub mod repository;
 pub mod status;
@@ -39,6 +41,10 @@ actions!(
         StageAndNext,
         /// Unstages the current hunk and moves to the next one.
         UnstageAndNext,
+        /// Stages only the selected lines of the hunks under the selection.
+        StageSelectedLines,
+        /// Unstages only the selected lines of the hunks under the selection.
+        UnstageSelectedLines,
         /// Restores the selected hunks to their original state.
         #[action(deprecated_aliases = ["editor::RevertSelectedHunks"])]
         Restore,
@@ -50,6 +56,12 @@ actions!(
         StageFile,
         /// Unstages the current file.
         UnstageFile,
+        /// Stashes the changes to the current file.
+        StashFile,
+        /// Shows the commit history of the current file.
+        FileHistory,
+        /// Opens a three-way merge editor for the current conflicted file.
+        OpenMergeEditor,
         // repo-wide
         /// Stages all changes in the repository.
         StageAll,
@@ -61,6 +73,24 @@ actions!(
         TrashUntrackedFiles,
         /// Undoes the last commit, keeping changes in the working directory.
         Uncommit,
+        /// Stashes all local changes, including untracked files.
+        StashAll,
+        /// Applies the most recent stash entry and removes it from the stash.
+        StashPop,
+        /// Opens the list of stash entries.
+        ViewStash,
+        /// Shows the commit history of the repository.
+        ViewHistory,
+        /// Opens the list of tags.
+        ViewTags,
+        /// Opens the todo list for an interactive rebase onto the current branch's upstream.
+        InteractiveRebase,
+        /// Continues the stopped merge, rebase, cherry-pick or revert.
+        ContinueOperation,
+        /// Skips the commit the stopped rebase, cherry-pick or revert is applying.
+        SkipOperation,
+        /// Aborts the stopped merge, rebase, cherry-pick or revert.
+        AbortOperation,
         /// Pushes commits to the remote repository.
         Push,
         /// Pushes commits to a specific remote branch.
@@ -0,0 +1,74 @@
+//! Row-level diffs between two texts.
+
+use git2::{DiffOptions, Patch};
+use std::ops::Range;
+use util::ResultExt as _;
+
+/// A single changed region between two texts, expressed in rows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineChange {
+    pub old_rows: Range<u32>,
+    pub new_rows: Range<u32>,
+}
+
+/// Diffs `old_text` against `new_text` without context, returning the changed rows in order.
+pub fn line_changes(old_text: &str, new_text: &str) -> Vec<LineChange> {
+    let mut options = DiffOptions::new();
+    options.context_lines(0);
+    let Some(patch) = Patch::from_buffers(
+        old_text.as_bytes(),
+        None,
+        new_text.as_bytes(),
+        None,
+        Some(&mut options),
+    )
+    .log_err() else {
+        return Vec::new();
+    };
+
+    // With zero context lines, git reports empty sides of a hunk as starting
+    // at the row *before* the insertion or deletion point.
+    fn hunk_rows(start: u32, count: u32) -> Range<u32> {
+        let start = if count == 0 { start } else { start - 1 };
+        start..start + count
+    }
+
+    (0..patch.num_hunks())
+        .filter_map(|hunk_index| {
+            let (hunk, _) = patch.hunk(hunk_index).log_err()?;
+            Some(LineChange {
+                old_rows: hunk_rows(hunk.old_start(), hunk.old_lines()),
+                new_rows: hunk_rows(hunk.new_start(), hunk.new_lines()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_changes() {
+        let old = "one\ntwo\nthree\nfour\n";
+        let new = "zero\none\nthree\nFOUR\n";
+        assert_eq!(
+            line_changes(old, new),
+            [
+                LineChange {
+                    old_rows: 0..0,
+                    new_rows: 0..1,
+                },
+                LineChange {
+                    old_rows: 1..2,
+                    new_rows: 2..2,
+                },
+                LineChange {
+                    old_rows: 3..4,
+                    new_rows: 3..4,
+                },
+            ]
+        );
+        assert_eq!(line_changes(old, old), []);
+    }
+}
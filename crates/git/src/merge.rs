@@ -0,0 +1,366 @@
+//! Line-based three-way merging, used to resolve conflicted files.
+
+use crate::line_diff::{LineChange, line_changes};
+use std::ops::Range;
+
+/// A run of lines in a three-way merge, with the rows it covers in each version.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergeChunk {
+    pub base: Range<u32>,
+    pub ours: Range<u32>,
+    pub theirs: Range<u32>,
+    pub kind: MergeChunkKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeChunkKind {
+    /// Neither side changed these lines.
+    Unchanged,
+    /// Only our side changed these lines.
+    Ours,
+    /// Only their side changed these lines.
+    Theirs,
+    /// Both sides made the same change.
+    Both,
+    /// The sides made different changes to overlapping or adjacent lines.
+    Conflict,
+}
+
+impl MergeChunk {
+    pub fn is_conflict(&self) -> bool {
+        self.kind == MergeChunkKind::Conflict
+    }
+}
+
+/// Maps a range of base rows covering `changes` (or none) to the corresponding side rows.
+fn side_range(base: &Range<u32>, changes: &[LineChange], offset: i64) -> Range<u32> {
+    match (changes.first(), changes.last()) {
+        (Some(first), Some(last)) => {
+            let start = first.new_rows.start - (first.old_rows.start - base.start);
+            let end = last.new_rows.end + (base.end - last.old_rows.end);
+            start..end
+        }
+        _ => (base.start as i64 + offset) as u32..(base.end as i64 + offset) as u32,
+    }
+}
+
+/// Splits a three-way merge of `base`, `ours` and `theirs` into chunks. Changes made by only one
+/// side, or identically by both, are resolved automatically. Changes from both sides that
+/// overlap or touch are reported as conflicts.
+pub fn merge_chunks(base: &str, ours: &str, theirs: &str) -> Vec<MergeChunk> {
+    let ours_changes = line_changes(base, ours);
+    let theirs_changes = line_changes(base, theirs);
+    let base_row_count = lines(base).len() as u32;
+    let ours_lines = lines(ours);
+    let theirs_lines = lines(theirs);
+
+    let mut chunks = Vec::new();
+    let (mut ours_ix, mut theirs_ix) = (0, 0);
+    // The difference between side rows and base rows after the changes processed so far.
+    let (mut ours_offset, mut theirs_offset) = (0i64, 0i64);
+    let mut base_row = 0;
+
+    fn push_unchanged(
+        chunks: &mut Vec<MergeChunk>,
+        base: Range<u32>,
+        ours_offset: i64,
+        theirs_offset: i64,
+    ) {
+        if !base.is_empty() {
+            chunks.push(MergeChunk {
+                ours: side_range(&base, &[], ours_offset),
+                theirs: side_range(&base, &[], theirs_offset),
+                base,
+                kind: MergeChunkKind::Unchanged,
+            });
+        }
+    }
+
+    loop {
+        let next_ours = ours_changes.get(ours_ix);
+        let next_theirs = theirs_changes.get(theirs_ix);
+        let mut region = match (next_ours, next_theirs) {
+            (Some(a), Some(b)) if b.old_rows.start < a.old_rows.start => b.old_rows.clone(),
+            (Some(a), _) => a.old_rows.clone(),
+            (None, Some(b)) => b.old_rows.clone(),
+            (None, None) => break,
+        };
+
+        // Grow the region until it includes every change from either side that touches it.
+        let (ours_start, theirs_start) = (ours_ix, theirs_ix);
+        loop {
+            let mut grew = false;
+            while let Some(change) = ours_changes.get(ours_ix) {
+                if change.old_rows.start > region.end {
+                    break;
+                }
+                region.start = region.start.min(change.old_rows.start);
+                region.end = region.end.max(change.old_rows.end);
+                ours_ix += 1;
+                grew = true;
+            }
+            while let Some(change) = theirs_changes.get(theirs_ix) {
+                if change.old_rows.start > region.end {
+                    break;
+                }
+                region.start = region.start.min(change.old_rows.start);
+                region.end = region.end.max(change.old_rows.end);
+                theirs_ix += 1;
+                grew = true;
+            }
+            if !grew {
+                break;
+            }
+        }
+
+        push_unchanged(
+            &mut chunks,
+            base_row..region.start,
+            ours_offset,
+            theirs_offset,
+        );
+
+        let ours_group = &ours_changes[ours_start..ours_ix];
+        let theirs_group = &theirs_changes[theirs_start..theirs_ix];
+        let ours_range = side_range(&region, ours_group, ours_offset);
+        let theirs_range = side_range(&region, theirs_group, theirs_offset);
+        let kind = if theirs_group.is_empty() {
+            MergeChunkKind::Ours
+        } else if ours_group.is_empty() {
+            MergeChunkKind::Theirs
+        } else if ours_lines[ours_range.start as usize..ours_range.end as usize]
+            == theirs_lines[theirs_range.start as usize..theirs_range.end as usize]
+        {
+            MergeChunkKind::Both
+        } else {
+            MergeChunkKind::Conflict
+        };
+
+        ours_offset = ours_range.end as i64 - region.end as i64;
+        theirs_offset = theirs_range.end as i64 - region.end as i64;
+        base_row = region.end;
+        chunks.push(MergeChunk {
+            base: region,
+            ours: ours_range,
+            theirs: theirs_range,
+            kind,
+        });
+    }
+
+    push_unchanged(
+        &mut chunks,
+        base_row..base_row_count,
+        ours_offset,
+        theirs_offset,
+    );
+    chunks
+}
+
+/// Builds the merged text for `chunks`, writing conflicts with diff3-style conflict markers.
+pub fn merged_text(base: &str, ours: &str, theirs: &str, chunks: &[MergeChunk]) -> String {
+    let base_lines = lines(base);
+    let ours_lines = lines(ours);
+    let theirs_lines = lines(theirs);
+    let mut text = String::with_capacity(ours.len().max(theirs.len()));
+
+    fn push_lines(text: &mut String, lines: &[&str]) {
+        for line in lines {
+            text.push_str(line);
+        }
+    }
+    fn push_marker(text: &mut String, marker: &str) {
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(marker);
+        text.push('\n');
+    }
+
+    for chunk in chunks {
+        let base = &base_lines[chunk.base.start as usize..chunk.base.end as usize];
+        let ours = &ours_lines[chunk.ours.start as usize..chunk.ours.end as usize];
+        let theirs = &theirs_lines[chunk.theirs.start as usize..chunk.theirs.end as usize];
+        match chunk.kind {
+            MergeChunkKind::Unchanged => push_lines(&mut text, base),
+            MergeChunkKind::Ours | MergeChunkKind::Both => push_lines(&mut text, ours),
+            MergeChunkKind::Theirs => push_lines(&mut text, theirs),
+            MergeChunkKind::Conflict => {
+                push_marker(&mut text, "<<<<<<< ours");
+                push_lines(&mut text, ours);
+                push_marker(&mut text, "||||||| base");
+                push_lines(&mut text, base);
+                push_marker(&mut text, "=======");
+                push_lines(&mut text, theirs);
+                push_marker(&mut text, ">>>>>>> theirs");
+            }
+        }
+    }
+    text
+}
+
+/// Returns the text of the given rows of `text`, including their trailing newlines.
+pub fn text_for_rows(text: &str, rows: &Range<u32>) -> String {
+    lines(text)[rows.start as usize..rows.end as usize].concat()
+}
+
+fn lines(text: &str) -> Vec<&str> {
+    text.split_inclusive('\n').collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use unindent::Unindent as _;
+
+    #[test]
+    fn test_non_overlapping_changes_are_merged() {
+        let base = "
+            one
+            two
+            three
+            four
+            five
+        "
+        .unindent();
+        let ours = "
+            ONE
+            two
+            three
+            four
+            five
+        "
+        .unindent();
+        let theirs = "
+            one
+            two
+            three
+            four
+            FIVE
+            six
+        "
+        .unindent();
+
+        let chunks = merge_chunks(&base, &ours, &theirs);
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|chunk| (chunk.base.clone(), chunk.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                (0..1, MergeChunkKind::Ours),
+                (1..4, MergeChunkKind::Unchanged),
+                (4..5, MergeChunkKind::Theirs),
+            ]
+        );
+        assert_eq!(
+            merged_text(&base, &ours, &theirs, &chunks),
+            "ONE\ntwo\nthree\nfour\nFIVE\nsix\n"
+        );
+    }
+
+    #[test]
+    fn test_identical_changes_are_merged() {
+        let base = "one\ntwo\nthree\n";
+        let ours = "one\nTWO\nthree\n";
+        let theirs = "one\nTWO\nthree\n";
+
+        let chunks = merge_chunks(base, ours, theirs);
+        assert_eq!(chunks[1].kind, MergeChunkKind::Both);
+        assert_eq!(merged_text(base, ours, theirs, &chunks), ours);
+    }
+
+    #[test]
+    fn test_overlapping_changes_conflict() {
+        let base = "
+            one
+            two
+            three
+            four
+        "
+        .unindent();
+        let ours = "
+            one
+            TWO
+            three
+            four
+        "
+        .unindent();
+        let theirs = "
+            one
+            2
+            three
+            four
+            five
+        "
+        .unindent();
+
+        let chunks = merge_chunks(&base, &ours, &theirs);
+        assert_eq!(
+            chunks,
+            vec![
+                MergeChunk {
+                    base: 0..1,
+                    ours: 0..1,
+                    theirs: 0..1,
+                    kind: MergeChunkKind::Unchanged,
+                },
+                MergeChunk {
+                    base: 1..2,
+                    ours: 1..2,
+                    theirs: 1..2,
+                    kind: MergeChunkKind::Conflict,
+                },
+                MergeChunk {
+                    base: 2..4,
+                    ours: 2..4,
+                    theirs: 2..4,
+                    kind: MergeChunkKind::Unchanged,
+                },
+                MergeChunk {
+                    base: 4..4,
+                    ours: 4..4,
+                    theirs: 4..5,
+                    kind: MergeChunkKind::Theirs,
+                },
+            ]
+        );
+        assert_eq!(
+            merged_text(&base, &ours, &theirs, &chunks),
+            "
+            one
+            <<<<<<< ours
+            TWO
+            ||||||| base
+            two
+            =======
+            2
+            >>>>>>> theirs
+            three
+            four
+            five
+            "
+            .unindent()
+        );
+        assert_eq!(text_for_rows(&theirs, &chunks[1].theirs), "2\n");
+    }
+
+    #[test]
+    fn test_adjacent_changes_conflict() {
+        let base = "one\ntwo\nthree\n";
+        let ours = "ONE\ntwo\nthree\n";
+        let theirs = "one\nTWO\nthree\n";
+
+        let chunks = merge_chunks(base, ours, theirs);
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|chunk| (chunk.base.clone(), chunk.ours.clone(), chunk.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                (0..2, 0..2, MergeChunkKind::Conflict),
+                (2..3, 2..3, MergeChunkKind::Unchanged),
+            ]
+        );
+    }
+}
@@ -71,6 +71,56 @@ impl Branch {
     }
 }
 
+/// The contents of the index stages of a conflicted path. Each side is `None` if it doesn't
+/// have the path, e.g. when it was deleted on one side of the merge.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConflictStages {
+    /// Stage 1, the common ancestor.
+    pub base: Option<String>,
+    /// Stage 2, the side being merged into.
+    pub ours: Option<String>,
+    /// Stage 3, the side being merged in.
+    pub theirs: Option<String>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Tag {
+    pub name: SharedString,
+    /// The commit the tag points to.
+    pub sha: SharedString,
+    /// The tag message for annotated tags, or the subject of the tagged commit otherwise.
+    pub subject: SharedString,
+    pub is_annotated: bool,
+    /// This is a unix timestamp. For lightweight tags, it's the time of the tagged commit.
+    pub timestamp: Option<i64>,
+}
+
+impl Tag {
+    pub fn ref_name(&self) -> String {
+        format!("refs/tags/{}", self.name)
+    }
+}
+
+/// A working tree attached to the repository, as listed by `git worktree list`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct GitWorktree {
+    pub path: PathBuf,
+    pub sha: Option<SharedString>,
+    /// The ref name of the checked out branch, or `None` if `HEAD` is detached.
+    pub branch: Option<SharedString>,
+    pub is_main: bool,
+    pub is_locked: bool,
+    /// Whether the working tree's directory no longer exists.
+    pub is_prunable: bool,
+}
+
+impl GitWorktree {
+    pub fn branch_name(&self) -> Option<&str> {
+        let branch = self.branch.as_ref()?;
+        Some(branch.strip_prefix("refs/heads/").unwrap_or(branch))
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Upstream {
     pub ref_name: SharedString,
@@ -185,6 +235,153 @@ pub struct Remote {
     pub name: SharedString,
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct StashEntry {
+    /// The position of this entry in the stash, as in `stash@{index}`.
+    pub index: usize,
+    pub sha: SharedString,
+    /// The stash's reflog subject, e.g. `On main: message` or `WIP on main: abc1234 subject`.
+    pub message: SharedString,
+    /// This is a unix timestamp
+    pub commit_timestamp: i64,
+}
+
+impl StashEntry {
+    pub fn ref_name(&self) -> String {
+        format!("stash@{{{}}}", self.index)
+    }
+
+    /// The branch the stash was created on, if it was recorded in the stash message.
+    pub fn branch(&self) -> Option<&str> {
+        let (prefix, _) = self.message.split_once(": ")?;
+        prefix
+            .strip_prefix("WIP on ")
+            .or_else(|| prefix.strip_prefix("On "))
+    }
+
+    /// The stash message without the `On <branch>: ` prefix.
+    pub fn description(&self) -> &str {
+        self.message
+            .split_once(": ")
+            .map_or(self.message.as_ref(), |(_, description)| description)
+    }
+}
+
+/// Which commits [`GitRepository::log`] should return.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct LogOptions {
+    /// The number of commits to skip, for paginating through the history.
+    pub skip: usize,
+    /// The maximum number of commits to return.
+    pub limit: Option<usize>,
+    /// A revision or revision range, like `main` or `v1.0..HEAD`. Defaults to `HEAD`.
+    pub revision_range: Option<String>,
+    /// Only include commits whose author matches this pattern.
+    pub author: Option<String>,
+    /// Only include commits touching this path, following renames.
+    pub path: Option<RepoPath>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct LogEntry {
+    pub sha: SharedString,
+    pub parent_shas: Vec<SharedString>,
+    pub subject: SharedString,
+    pub author_name: SharedString,
+    pub author_email: SharedString,
+    /// This is a unix timestamp
+    pub commit_timestamp: i64,
+    /// Branch and tag names pointing at this commit, as printed by `git log --decorate`.
+    pub refs: Vec<SharedString>,
+}
+
+impl LogEntry {
+    pub fn short_sha(&self) -> SharedString {
+        self.sha[..SHORT_SHA_LENGTH.min(self.sha.len())]
+            .to_string()
+            .into()
+    }
+
+    pub fn to_commit_summary(&self) -> CommitSummary {
+        CommitSummary {
+            sha: self.sha.clone(),
+            subject: self.subject.clone(),
+            commit_timestamp: self.commit_timestamp,
+            has_parent: !self.parent_shas.is_empty(),
+        }
+    }
+}
+
+/// A multi-step operation that stopped part way through, usually because of conflicts, and
+/// must be continued, skipped or aborted.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum InProgressOperation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+}
+
+impl InProgressOperation {
+    /// The git subcommand that drives this operation.
+    pub fn command(&self) -> &'static str {
+        match self {
+            InProgressOperation::Merge => "merge",
+            InProgressOperation::Rebase => "rebase",
+            InProgressOperation::CherryPick => "cherry-pick",
+            InProgressOperation::Revert => "revert",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            InProgressOperation::Merge => "Merge",
+            InProgressOperation::Rebase => "Rebase",
+            InProgressOperation::CherryPick => "Cherry-pick",
+            InProgressOperation::Revert => "Revert",
+        }
+    }
+
+    /// Merges consist of a single step, so there is nothing to skip.
+    pub fn can_skip(&self) -> bool {
+        *self != InProgressOperation::Merge
+    }
+
+    fn detect(git_dir: &Path) -> Option<Self> {
+        if git_dir.join("rebase-merge").is_dir() || git_dir.join("rebase-apply").is_dir() {
+            Some(InProgressOperation::Rebase)
+        } else if git_dir.join("CHERRY_PICK_HEAD").exists() {
+            Some(InProgressOperation::CherryPick)
+        } else if git_dir.join("REVERT_HEAD").exists() {
+            Some(InProgressOperation::Revert)
+        } else if git_dir.join("MERGE_HEAD").exists() {
+            Some(InProgressOperation::Merge)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum RebaseAction {
+    Pick,
+    /// Pick the commit, replacing its message.
+    Reword(String),
+    /// Meld the commit into the previous one, keeping both messages.
+    Squash,
+    /// Meld the commit into the previous one, discarding its message.
+    Fixup,
+    Drop,
+}
+
+/// A line of an interactive rebase's todo list.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RebaseTodoEntry {
+    pub action: RebaseAction,
+    pub sha: SharedString,
+    pub subject: SharedString,
+}
+
 pub enum ResetMode {
     /// Reset the branch pointer, leave index and worktree unchanged (this will make it look like things that were
     /// committed are now staged).
@@ -311,6 +508,9 @@ pub trait GitRepository: Send + Sync {
     /// Also returns `None` for symlinks.
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>>;
 
+    /// Returns the base, ours and theirs versions of a conflicted path from index stages 1, 2 and 3.
+    fn load_conflict_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<ConflictStages>>;
+
     fn set_index_text(
         &self,
         path: RepoPath,
@@ -345,6 +545,62 @@ pub trait GitRepository: Send + Sync {
     fn change_branch(&self, name: String) -> BoxFuture<'_, Result<()>>;
     fn create_branch(&self, name: String) -> BoxFuture<'_, Result<()>>;
 
+    /// Deletes a local branch. Unless `force` is set, branches that haven't been merged into
+    /// their upstream or `HEAD` are kept.
+    fn delete_branch(
+        &self,
+        name: String,
+        force: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    fn rename_branch(
+        &self,
+        old_name: String,
+        new_name: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Lists the tags in the repository, most recently created first.
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>>;
+
+    /// Tags `target`, or `HEAD` if no target is given. Tags with a message are annotated.
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    fn delete_tag(
+        &self,
+        name: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Lists the working trees of the repository, starting with the main one.
+    fn worktrees(&self) -> BoxFuture<'_, Result<Vec<GitWorktree>>>;
+
+    /// Checks out `branch` in a new working tree at `path`, creating the branch from `HEAD` first
+    /// if `create_branch` is set.
+    fn create_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Removes the working tree at `path`. Unless `force` is set, working trees with local
+    /// changes are kept.
+    fn remove_worktree(
+        &self,
+        path: PathBuf,
+        force: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
     fn reset(
         &self,
         commit: String,
@@ -359,8 +615,94 @@ pub trait GitRepository: Send + Sync {
         env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<'_, Result<()>>;
 
+    /// Stashes local changes at the given paths, or all local changes (including untracked files)
+    /// if no paths are given.
+    fn stash_paths(
+        &self,
+        paths: Vec<RepoPath>,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Lists the stash entries, most recent first.
+    fn stash_entries(&self) -> BoxFuture<'_, Result<Vec<StashEntry>>>;
+
+    /// Applies the stash entry at `index` to the working tree, keeping it in the stash.
+    fn stash_apply(
+        &self,
+        index: usize,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Applies the stash entry at `index` and removes it from the stash.
+    fn stash_pop(
+        &self,
+        index: usize,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Removes the stash entry at `index` without applying it.
+    fn stash_drop(
+        &self,
+        index: usize,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Applies the changes introduced by the given commits on top of `HEAD`, oldest first.
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Creates commits reverting the changes introduced by the given commits, in order.
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Rebases the current branch onto `upstream`. When a todo list is given, the rebase is run
+    /// interactively with those entries, oldest commit first.
+    fn rebase(
+        &self,
+        upstream: String,
+        todo: Option<Vec<RebaseTodoEntry>>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Returns the merge, rebase, cherry-pick or revert that is currently stopped, if any.
+    fn in_progress_operation(&self) -> BoxFuture<'_, Option<InProgressOperation>>;
+
+    /// Resumes a stopped operation once its conflicts have been resolved and staged.
+    fn continue_operation(
+        &self,
+        operation: InProgressOperation,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Skips the commit a stopped operation is currently applying.
+    fn skip_operation(
+        &self,
+        operation: InProgressOperation,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Abandons a stopped operation, restoring the state from before it started.
+    fn abort_operation(
+        &self,
+        operation: InProgressOperation,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
     fn show(&self, commit: String) -> BoxFuture<'_, Result<CommitDetails>>;
 
+    /// Lists commits in reverse chronological order, with parents always listed after their
+    /// children.
+    fn log(&self, options: LogOptions) -> BoxFuture<'_, Result<Vec<LogEntry>>>;
+
+    /// Loads the files changed by a commit. For merge commits (including stash entries), the
+    /// changes are relative to the first parent.
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>>;
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<'_, Result<crate::blame::Blame>>;
 
@@ -407,6 +749,17 @@ pub trait GitRepository: Send + Sync {
         cx: AsyncApp,
     ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
 
+    fn push_tag(
+        &self,
+        tag_name: String,
+        remote_name: String,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
+
     fn pull(
         &self,
         branch_name: String,
@@ -502,6 +855,97 @@ impl RealGitRepository {
             .context("failed to read git work directory")
             .map(Path::to_path_buf)
     }
+
+    /// Runs a git command that only reports failure, without any output of interest.
+    fn run_plain_command(
+        &self,
+        args: Vec<OsString>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .args(&args)
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to run git {}:\n{}",
+                    args.first()
+                        .map(|arg| arg.to_string_lossy())
+                        .unwrap_or_default(),
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn run_stash_command(
+        &self,
+        subcommand: &'static str,
+        index: usize,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .args(["stash", subcommand, "--quiet"])
+                    .arg(format!("stash@{{{index}}}"))
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to {subcommand} stash:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    /// Runs a command that may stop part way through because of conflicts. Stopping is not
+    /// treated as an error, since the conflicts are picked up by the next status scan and the
+    /// operation can then be continued, skipped or aborted.
+    fn run_sequencer_command(
+        &self,
+        args: Vec<String>,
+        extra_env: Vec<(&'static str, String)>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    // Accept the default message whenever git would open an editor.
+                    .env("GIT_EDITOR", "true")
+                    .envs(extra_env)
+                    .args(&args)
+                    .output()
+                    .await?;
+                if !output.status.success() && InProgressOperation::detect(&git_dir).is_none() {
+                    bail!(
+                        "Failed to run git {}:\n{}",
+                        args.first().map(String::as_str).unwrap_or_default(),
+                        String::from_utf8_lossy(&output.stderr)
+                    );
+                }
+                Ok(())
+            })
+            .boxed()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -606,39 +1050,272 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
-    fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>> {
-        let Some(working_directory) = self.repository.lock().workdir().map(ToOwned::to_owned)
-        else {
-            return future::ready(Err(anyhow!("no working directory"))).boxed();
-        };
-        cx.background_spawn(async move {
-            let show_output = util::command::new_std_command("git")
-                .current_dir(&working_directory)
-                .args([
-                    "--no-optional-locks",
-                    "show",
-                    "--format=%P",
-                    "-z",
-                    "--no-renames",
-                    "--name-status",
-                ])
-                .arg(&commit)
-                .stdin(Stdio::null())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .output()
-                .context("starting git show process")?;
-
-            let show_stdout = String::from_utf8_lossy(&show_output.stdout);
-            let mut lines = show_stdout.split('\n');
-            let parent_sha = lines.next().unwrap().trim().trim_end_matches('\0');
-            let changes = parse_git_diff_name_status(lines.next().unwrap_or(""));
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let args = ["cherry-pick".to_string(), "--end-of-options".to_string()]
+            .into_iter()
+            .chain(commits)
+            .collect();
+        self.run_sequencer_command(args, Vec::new(), env)
+    }
 
-            let mut cat_file_process = util::command::new_std_command("git")
-                .current_dir(&working_directory)
-                .args(["--no-optional-locks", "cat-file", "--batch=%(objectsize)"])
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let args = [
+            "revert".to_string(),
+            "--no-edit".to_string(),
+            "--end-of-options".to_string(),
+        ]
+        .into_iter()
+        .chain(commits)
+        .collect();
+        self.run_sequencer_command(args, Vec::new(), env)
+    }
+
+    fn rebase(
+        &self,
+        upstream: String,
+        todo: Option<Vec<RebaseTodoEntry>>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let Some(todo) = todo else {
+            return self.run_sequencer_command(
+                vec!["rebase".into(), "--end-of-options".into(), upstream],
+                Vec::new(),
+                env,
+            );
+        };
+        let script = match rebase_todo_script(&todo) {
+            Ok(script) => script,
+            Err(error) => return future::ready(Err(error)).boxed(),
+        };
+        // Git opens the sequence editor with the path of its todo list appended, so copying our
+        // script over it replaces the list without any user interaction.
+        let todo_path = self.path().join("ZED_REBASE_TODO");
+        let sequence_editor = format!("cp {}", shell_quote(&todo_path.to_string_lossy()));
+        async move {
+            smol::fs::write(&todo_path, script).await?;
+            let result = self
+                .run_sequencer_command(
+                    vec![
+                        "rebase".into(),
+                        "--interactive".into(),
+                        "--end-of-options".into(),
+                        upstream,
+                    ],
+                    vec![("GIT_SEQUENCE_EDITOR", sequence_editor)],
+                    env,
+                )
+                .await;
+            smol::fs::remove_file(&todo_path).await.log_err();
+            result
+        }
+        .boxed()
+    }
+
+    fn in_progress_operation(&self) -> BoxFuture<'_, Option<InProgressOperation>> {
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move { InProgressOperation::detect(&git_dir) })
+            .boxed()
+    }
+
+    fn continue_operation(
+        &self,
+        operation: InProgressOperation,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.run_sequencer_command(
+            vec![operation.command().into(), "--continue".into()],
+            Vec::new(),
+            env,
+        )
+    }
+
+    fn skip_operation(
+        &self,
+        operation: InProgressOperation,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        if !operation.can_skip() {
+            return future::ready(Err(anyhow!(
+                "{} cannot be skipped",
+                operation.display_name()
+            )))
+            .boxed();
+        }
+        self.run_sequencer_command(
+            vec![operation.command().into(), "--skip".into()],
+            Vec::new(),
+            env,
+        )
+    }
+
+    fn abort_operation(
+        &self,
+        operation: InProgressOperation,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.run_sequencer_command(
+            vec![operation.command().into(), "--abort".into()],
+            Vec::new(),
+            env,
+        )
+    }
+
+    fn log(&self, options: LogOptions) -> BoxFuture<'_, Result<Vec<LogEntry>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let mut command = new_smol_command(&git_binary_path);
+                command.current_dir(&working_directory?).args([
+                    "--no-optional-locks",
+                    "log",
+                    "--topo-order",
+                    "--decorate=short",
+                    "-z",
+                    "--format=%H%x00%P%x00%s%x00%an%x00%ae%x00%ct%x00%D",
+                ]);
+                let revision_range = options.revision_range.as_deref().unwrap_or("HEAD");
+                anyhow::ensure!(
+                    !revision_range.starts_with('-'),
+                    "invalid revision range {revision_range:?}"
+                );
+                command.arg(format!("--skip={}", options.skip));
+                // When following a path, `%P` gives the commits' parents in the full history,
+                // so they are rebuilt from the commits that touch the path instead, looking one
+                // commit past the page for the parent of its last one.
+                let limit = match options.path {
+                    Some(_) => options.limit.map(|limit| limit + 1),
+                    None => options.limit,
+                };
+                if let Some(limit) = limit {
+                    command.arg(format!("--max-count={limit}"));
+                }
+                if let Some(author) = &options.author {
+                    anyhow::ensure!(
+                        !author.chars().any(char::is_control),
+                        "invalid author pattern {author:?}"
+                    );
+                    command.arg(format!("--author={author}"));
+                }
+                if options.path.is_some() {
+                    command.arg("--follow");
+                }
+                command
+                    .arg("--end-of-options")
+                    .arg(revision_range)
+                    .arg("--");
+                if let Some(path) = &options.path {
+                    command.arg(path.to_unix_style());
+                }
+                let output = command.output().await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to load git log:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                let mut entries = parse_log_output(&String::from_utf8_lossy(&output.stdout))?;
+                if options.path.is_some() {
+                    let parent_shas = entries
+                        .iter()
+                        .skip(1)
+                        .map(|entry| vec![entry.sha.clone()])
+                        .chain([Vec::new()])
+                        .collect::<Vec<_>>();
+                    for (entry, parent_shas) in entries.iter_mut().zip(parent_shas) {
+                        entry.parent_shas = parent_shas;
+                    }
+                    if let Some(limit) = options.limit {
+                        entries.truncate(limit);
+                    }
+                }
+                Ok(entries)
+            })
+            .boxed()
+    }
+
+    fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>> {
+        let Some(working_directory) = self.repository.lock().workdir().map(ToOwned::to_owned)
+        else {
+            return future::ready(Err(anyhow!("no working directory"))).boxed();
+        };
+        cx.background_spawn(async move {
+            let show_output = util::command::new_std_command("git")
+                .current_dir(&working_directory)
+                .args([
+                    "--no-optional-locks",
+                    "show",
+                    "--format=%P",
+                    "-z",
+                    "--no-renames",
+                    "--name-status",
+                    "--diff-merges=first-parent",
+                ])
+                .arg(&commit)
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .output()
+                .context("starting git show process")?;
+
+            let show_stdout = String::from_utf8_lossy(&show_output.stdout);
+            let mut lines = show_stdout.split('\n');
+            let parent_shas = lines
+                .next()
+                .unwrap()
+                .trim()
+                .trim_end_matches('\0')
+                .split(' ')
+                .collect::<Vec<_>>();
+            let parent_sha = parent_shas.first().copied().unwrap_or_default();
+            let changes = parse_git_diff_name_status(lines.next().unwrap_or(""));
+
+            // Stash entries made with `--include-untracked` keep the untracked files in a third
+            // parent, which they are shown as adding.
+            let untracked_files_sha = parent_shas
+                .get(2)
+                .copied()
+                .filter(|sha| is_stash_untracked_files_commit(&working_directory, sha));
+            let untracked_changes = match untracked_files_sha {
+                Some(sha) => {
+                    let output = util::command::new_std_command("git")
+                        .current_dir(&working_directory)
+                        .args([
+                            "--no-optional-locks",
+                            "show",
+                            "--format=",
+                            "-z",
+                            "--no-renames",
+                            "--name-status",
+                            sha,
+                        ])
+                        .stdin(Stdio::null())
+                        .output()
+                        .context("starting git show process")?;
+                    let stdout = String::from_utf8_lossy(&output.stdout);
+                    parse_git_diff_name_status(stdout.trim_start_matches('\n'))
+                        .map(|(path, _)| (path.to_path_buf(), StatusCode::Added, sha))
+                        .collect::<Vec<_>>()
+                }
+                None => Vec::new(),
+            };
+            let changes = changes
+                .map(|(path, status_code)| (path.to_path_buf(), status_code, commit.as_str()))
+                .chain(untracked_changes);
+
+            let mut cat_file_process = util::command::new_std_command("git")
+                .current_dir(&working_directory)
+                .args(["--no-optional-locks", "cat-file", "--batch=%(objectsize)"])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
                 .stderr(Stdio::piped())
                 .spawn()
                 .context("starting git cat-file process")?;
@@ -649,7 +1326,7 @@ impl GitRepository for RealGitRepository {
             let mut stdout = BufReader::new(cat_file_process.stdout.take().unwrap());
             let mut info_line = String::new();
             let mut newline = [b'\0'];
-            for (path, status_code) in changes {
+            for (path, status_code, commit) in changes {
                 match status_code {
                     StatusCode::Modified => {
                         writeln!(&mut stdin, "{commit}:{}", path.display())?;
@@ -817,6 +1494,41 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn load_conflict_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<ConflictStages>> {
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                check_path_to_repo_path_errors(&path)?;
+                let repo = repo.lock();
+                let mut index = repo.index()?;
+                index.read(false)?;
+
+                let load_stage = |stage: i32| -> Result<Option<String>> {
+                    let Some(entry) = index.get_path(&path, stage) else {
+                        return Ok(None);
+                    };
+                    let content = repo.find_blob(entry.id)?.content().to_owned();
+                    Ok(Some(String::from_utf8(content)?))
+                };
+
+                const STAGE_BASE: i32 = 1;
+                const STAGE_OURS: i32 = 2;
+                const STAGE_THEIRS: i32 = 3;
+                let stages = ConflictStages {
+                    base: load_stage(STAGE_BASE)?,
+                    ours: load_stage(STAGE_OURS)?,
+                    theirs: load_stage(STAGE_THEIRS)?,
+                };
+                anyhow::ensure!(
+                    stages.ours.is_some() || stages.theirs.is_some(),
+                    "{} is not conflicted",
+                    path.display()
+                );
+                Ok(stages)
+            })
+            .boxed()
+    }
+
     fn set_index_text(
         &self,
         path: RepoPath,
@@ -880,6 +1592,90 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn stash_paths(
+        &self,
+        paths: Vec<RepoPath>,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let mut command = new_smol_command(&git_binary_path);
+                command
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .args(["stash", "push", "--quiet", "--include-untracked"]);
+                if let Some(message) = message {
+                    command.arg("--message").arg(message);
+                }
+                if !paths.is_empty() {
+                    command
+                        .arg("--")
+                        .args(paths.iter().map(|path| path.to_unix_style()));
+                }
+
+                let output = command.output().await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to stash:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn stash_entries(&self) -> BoxFuture<'_, Result<Vec<StashEntry>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .args([
+                        "--no-optional-locks",
+                        "stash",
+                        "list",
+                        "--format=%H%x00%ct%x00%gs",
+                    ])
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to list stash entries:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                parse_stash_list(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn stash_apply(
+        &self,
+        index: usize,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.run_stash_command("apply", index, env)
+    }
+
+    fn stash_pop(
+        &self,
+        index: usize,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.run_stash_command("pop", index, env)
+    }
+
+    fn stash_drop(
+        &self,
+        index: usize,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.run_stash_command("drop", index, env)
+    }
+
     fn remote_url(&self, name: &str) -> Option<String> {
         let repo = self.repository.lock();
         let remote = repo.find_remote(name).ok()?;
@@ -1082,6 +1878,171 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn delete_branch(
+        &self,
+        name: String,
+        force: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let flag = if force { "-D" } else { "-d" };
+        self.run_plain_command(
+            vec![
+                "branch".into(),
+                flag.into(),
+                "--end-of-options".into(),
+                name.into(),
+            ],
+            env,
+        )
+    }
+
+    fn rename_branch(
+        &self,
+        old_name: String,
+        new_name: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.run_plain_command(
+            vec![
+                "branch".into(),
+                "-m".into(),
+                "--end-of-options".into(),
+                old_name.into(),
+                new_name.into(),
+            ],
+            env,
+        )
+    }
+
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let fields = [
+                    "%(refname:strip=2)",
+                    "%(objecttype)",
+                    "%(objectname)",
+                    "%(*objectname)",
+                    "%(contents:subject)",
+                    "%(creatordate:unix)",
+                ]
+                .join("%00");
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .args([
+                        "for-each-ref",
+                        "refs/tags",
+                        "--sort=-creatordate",
+                        "--format",
+                    ])
+                    .arg(&fields)
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to list tags:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                parse_tag_list(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let mut args: Vec<OsString> = vec!["tag".into()];
+        if let Some(message) = message {
+            args.extend(["--annotate".into(), "--message".into(), message.into()]);
+        }
+        args.extend(["--end-of-options".into(), name.into()]);
+        args.extend(target.map(Into::into));
+        self.run_plain_command(args, env)
+    }
+
+    fn delete_tag(
+        &self,
+        name: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.run_plain_command(
+            vec![
+                "tag".into(),
+                "--delete".into(),
+                "--end-of-options".into(),
+                name.into(),
+            ],
+            env,
+        )
+    }
+
+    fn worktrees(&self) -> BoxFuture<'_, Result<Vec<GitWorktree>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .args(["worktree", "list", "--porcelain", "-z"])
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to list worktrees:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                parse_worktree_list(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn create_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let args = if create_branch {
+            vec![
+                "worktree".into(),
+                "add".into(),
+                "-b".into(),
+                branch.into(),
+                "--".into(),
+                path.into(),
+            ]
+        } else {
+            vec![
+                "worktree".into(),
+                "add".into(),
+                "--".into(),
+                path.into(),
+                branch.into(),
+            ]
+        };
+        self.run_plain_command(args, env)
+    }
+
+    fn remove_worktree(
+        &self,
+        path: PathBuf,
+        force: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let mut args: Vec<OsString> = vec!["worktree".into(), "remove".into()];
+        if force {
+            args.push("--force".into());
+        }
+        args.extend(["--".into(), path.into()]);
+        self.run_plain_command(args, env)
+    }
+
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<'_, Result<crate::blame::Blame>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.git_binary_path.clone();
@@ -1263,9 +2224,9 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
-    fn pull(
+    fn push_tag(
         &self,
-        branch_name: String,
+        tag_name: String,
         remote_name: String,
         ask_pass: AskPassDelegate,
         env: Arc<HashMap<String, String>>,
@@ -1274,10 +2235,38 @@ impl GitRepository for RealGitRepository {
         let working_directory = self.working_directory();
         let executor = cx.background_executor().clone();
         async move {
+            let working_directory = working_directory?;
             let mut command = new_smol_command("git");
             command
                 .envs(env.iter())
-                .current_dir(&working_directory?)
+                .current_dir(&working_directory)
+                .args(["push", "--end-of-options"])
+                .arg(remote_name)
+                .arg(format!("refs/tags/{tag_name}"))
+                .stdin(smol::process::Stdio::null())
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
+    }
+
+    fn pull(
+        &self,
+        branch_name: String,
+        remote_name: String,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let executor = cx.background_executor().clone();
+        async move {
+            let mut command = new_smol_command("git");
+            command
+                .envs(env.iter())
+                .current_dir(&working_directory?)
                 .args(["pull"])
                 .arg(remote_name)
                 .arg(branch_name)
@@ -1961,6 +2950,210 @@ fn parse_branch_input(input: &str) -> Result<Vec<Branch>> {
     Ok(branches)
 }
 
+/// Whether the commit is the one in which `git stash --include-untracked` records untracked files,
+/// which is a root commit with a subject like `untracked files on main: 1234567 Subject`.
+fn is_stash_untracked_files_commit(working_directory: &Path, sha: &str) -> bool {
+    new_std_command("git")
+        .current_dir(working_directory)
+        .args(["--no-optional-locks", "log", "-1", "--format=%P%x00%s", sha])
+        .stdin(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .is_some_and(|output| {
+            let output = String::from_utf8_lossy(&output.stdout);
+            output
+                .trim_end()
+                .split_once('\0')
+                .is_some_and(|(parents, subject)| {
+                    parents.is_empty() && subject.starts_with("untracked files on ")
+                })
+        })
+}
+
+fn parse_stash_list(input: &str) -> Result<Vec<StashEntry>> {
+    input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .enumerate()
+        .map(|(index, line)| {
+            let mut fields = line.split('\x00');
+            let sha = fields.next().context("no stash sha")?.to_string().into();
+            let commit_timestamp = fields.next().context("no stash timestamp")?.parse()?;
+            let message = fields
+                .next()
+                .context("no stash subject")?
+                .to_string()
+                .into();
+            Ok(StashEntry {
+                index,
+                sha,
+                message,
+                commit_timestamp,
+            })
+        })
+        .collect()
+}
+
+/// Parses `git for-each-ref refs/tags` output with NUL-separated fields: name, object type,
+/// object, peeled object, subject and creation date.
+fn parse_tag_list(input: &str) -> Result<Vec<Tag>> {
+    input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let mut fields = line.split('\x00');
+            let name = fields.next().context("no tag name")?;
+            let object_type = fields.next().context("no tag object type")?;
+            let object = fields.next().context("no tag object")?;
+            let peeled_object = fields.next().context("no peeled tag object")?;
+            let subject = fields.next().context("no tag subject")?;
+            let timestamp = fields.next().context("no tag date")?;
+            let is_annotated = object_type == "tag";
+            let sha = if is_annotated && !peeled_object.is_empty() {
+                peeled_object
+            } else {
+                object
+            };
+            Ok(Tag {
+                name: name.to_string().into(),
+                sha: sha.to_string().into(),
+                subject: subject.to_string().into(),
+                is_annotated,
+                timestamp: timestamp.parse().ok(),
+            })
+        })
+        .collect()
+}
+
+/// Parses `git worktree list --porcelain -z` output, in which every attribute is terminated by
+/// a NUL and every working tree by an additional NUL.
+fn parse_worktree_list(input: &str) -> Result<Vec<GitWorktree>> {
+    let mut worktrees = Vec::new();
+    let mut current: Option<GitWorktree> = None;
+    for attribute in input.split('\0') {
+        if attribute.is_empty() {
+            worktrees.extend(current.take());
+            continue;
+        }
+        let (key, value) = attribute.split_once(' ').unwrap_or((attribute, ""));
+        if key == "worktree" {
+            worktrees.extend(current.take());
+            current = Some(GitWorktree {
+                path: PathBuf::from(value),
+                sha: None,
+                branch: None,
+                is_main: worktrees.is_empty(),
+                is_locked: false,
+                is_prunable: false,
+            });
+            continue;
+        }
+        let worktree = current
+            .as_mut()
+            .with_context(|| format!("unexpected git-worktree output: {attribute:?}"))?;
+        match key {
+            "HEAD" => worktree.sha = Some(value.to_string().into()),
+            "branch" => worktree.branch = Some(value.to_string().into()),
+            "locked" => worktree.is_locked = true,
+            "prunable" => worktree.is_prunable = true,
+            _ => {}
+        }
+    }
+    worktrees.extend(current);
+    Ok(worktrees)
+}
+
+/// Builds the todo list for an interactive rebase. Rewording is done with an `exec` line
+/// amending the picked commit, so that no editor has to be opened while the rebase runs.
+fn rebase_todo_script(entries: &[RebaseTodoEntry]) -> Result<String> {
+    let mut script = String::new();
+    let mut has_previous_commit = false;
+    for entry in entries {
+        let command = match &entry.action {
+            RebaseAction::Pick | RebaseAction::Reword(_) => "pick",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup => "fixup",
+            RebaseAction::Drop => "drop",
+        };
+        if matches!(entry.action, RebaseAction::Squash | RebaseAction::Fixup)
+            && !has_previous_commit
+        {
+            bail!("cannot {command} {} without a previous commit", entry.sha);
+        }
+        has_previous_commit |= entry.action != RebaseAction::Drop;
+
+        // The todo list is line-based, so anything but an object ID in place of the commit, or a
+        // line break in its subject, could add commands to it.
+        anyhow::ensure!(
+            is_object_id(&entry.sha),
+            "invalid commit {:?} in rebase todo list",
+            entry.sha
+        );
+        let subject = entry.subject.replace(['\n', '\r'], " ");
+        script.push_str(&format!("{command} {} {subject}\n", entry.sha));
+        if let RebaseAction::Reword(message) = &entry.action {
+            if message.trim().is_empty() {
+                bail!("cannot reword {} with an empty message", entry.sha);
+            }
+            script.push_str("exec printf '%s\\n'");
+            for line in message.lines() {
+                script.push(' ');
+                script.push_str(&shell_quote(line));
+            }
+            script.push_str(" | git commit --amend --only --no-verify --allow-empty --file=-\n");
+        }
+    }
+    Ok(script)
+}
+
+/// Whether the text is an abbreviated or full hexadecimal object ID.
+fn is_object_id(text: &str) -> bool {
+    (4..=64).contains(&text.len()) && text.chars().all(|char| char.is_ascii_hexdigit())
+}
+
+/// Quotes a string for use as a single argument in a POSIX shell command.
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+const LOG_FIELD_COUNT: usize = 7;
+
+fn parse_log_output(input: &str) -> Result<Vec<LogEntry>> {
+    // With `-z`, both the fields within a commit and the commits themselves are separated by
+    // NULs, so every commit spans exactly `LOG_FIELD_COUNT` fields.
+    let fields = input.split('\0').collect::<Vec<_>>();
+    let mut entries = Vec::new();
+    for commit_fields in fields.chunks(LOG_FIELD_COUNT) {
+        if commit_fields.len() < LOG_FIELD_COUNT {
+            if commit_fields.iter().all(|field| field.trim().is_empty()) {
+                break;
+            }
+            bail!("unexpected git-log output: {commit_fields:?}");
+        }
+        let decorations = commit_fields[6];
+        entries.push(LogEntry {
+            sha: commit_fields[0].to_string().into(),
+            parent_shas: commit_fields[1]
+                .split_whitespace()
+                .map(|sha| sha.to_string().into())
+                .collect(),
+            subject: commit_fields[2].to_string().into(),
+            author_name: commit_fields[3].to_string().into(),
+            author_email: commit_fields[4].to_string().into(),
+            commit_timestamp: commit_fields[5].parse()?,
+            refs: decorations
+                .split(", ")
+                .filter(|name| !name.is_empty())
+                .map(|name| name.strip_prefix("HEAD -> ").unwrap_or(name))
+                .map(|name| name.strip_prefix("tag: ").unwrap_or(name))
+                .map(|name| name.to_string().into())
+                .collect(),
+        });
+    }
+    Ok(entries)
+}
+
 fn parse_upstream_track(upstream_track: &str) -> Result<UpstreamTracking> {
     if upstream_track == "" {
         return Ok(UpstreamTracking::Tracked(UpstreamTrackingStatus {
@@ -2278,6 +3471,586 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_parse_stash_list() {
+        let input = "1111111111111111111111111111111111111111\x001733187470\x00On main: tidy up\n\
+                     2222222222222222222222222222222222222222\x001733187400\x00WIP on feature/x: abc1234 Add thing\n";
+        let entries = parse_stash_list(input).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].index, 0);
+        assert_eq!(entries[0].ref_name(), "stash@{0}");
+        assert_eq!(entries[0].branch(), Some("main"));
+        assert_eq!(entries[0].description(), "tidy up");
+        assert_eq!(entries[0].commit_timestamp, 1733187470);
+        assert_eq!(entries[1].index, 1);
+        assert_eq!(entries[1].branch(), Some("feature/x"));
+        assert_eq!(entries[1].description(), "abc1234 Add thing");
+    }
+
+    #[test]
+    fn test_parse_log_output() {
+        let input = "1111111111111111111111111111111111111111\x00\
+            2222222222222222222222222222222222222222 3333333333333333333333333333333333333333\x00\
+            Merge branch 'feature'\x00Ada\x00ada@example.com\x001700000000\x00\
+            HEAD -> main, tag: v1.0, origin/main\x00\
+            2222222222222222222222222222222222222222\x00\x00Initial commit\x00Grace\x00\
+            grace@example.com\x001600000000\x00\x00";
+        let entries = parse_log_output(input).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].parent_shas.len(), 2);
+        assert_eq!(entries[0].subject.as_ref(), "Merge branch 'feature'");
+        assert_eq!(entries[0].author_name.as_ref(), "Ada");
+        assert_eq!(entries[0].commit_timestamp, 1700000000);
+        assert_eq!(
+            entries[0].refs,
+            vec![
+                SharedString::from("main"),
+                SharedString::from("v1.0"),
+                SharedString::from("origin/main")
+            ]
+        );
+        assert!(entries[1].parent_shas.is_empty());
+        assert!(entries[1].refs.is_empty());
+        assert_eq!(entries[1].author_email.as_ref(), "grace@example.com");
+        assert!(!entries[1].to_commit_summary().has_parent);
+    }
+
+    #[gpui::test]
+    async fn test_stash_push_and_pop(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let file_a = repo_dir.path().join("a");
+        let file_b = repo_dir.path().join("b");
+        smol::fs::write(&file_a, "a").await.unwrap();
+        smol::fs::write(&file_b, "b").await.unwrap();
+
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        repo.stage_paths(
+            vec![RepoPath::from_str("a"), RepoPath::from_str("b")],
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+
+        smol::fs::write(&file_a, "a modified").await.unwrap();
+        smol::fs::write(&file_b, "b modified").await.unwrap();
+        repo.stash_paths(
+            vec![RepoPath::from_str("a")],
+            Some("only a".into()),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(smol::fs::read_to_string(&file_a).await.unwrap(), "a");
+        assert_eq!(
+            smol::fs::read_to_string(&file_b).await.unwrap(),
+            "b modified"
+        );
+
+        let entries = repo.stash_entries().await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].description(), "only a");
+
+        let diff = repo
+            .load_commit(entries[0].sha.to_string(), cx.to_async())
+            .await
+            .unwrap();
+        assert_eq!(diff.files.len(), 1);
+        assert_eq!(diff.files[0].path, RepoPath::from_str("a"));
+        assert_eq!(diff.files[0].old_text.as_deref(), Some("a"));
+        assert_eq!(diff.files[0].new_text.as_deref(), Some("a modified"));
+
+        repo.stash_pop(0, env.clone()).await.unwrap();
+        assert_eq!(
+            smol::fs::read_to_string(&file_a).await.unwrap(),
+            "a modified"
+        );
+        assert!(repo.stash_entries().await.unwrap().is_empty());
+
+        // Untracked files are stashed too, and shown as added.
+        let file_c = repo_dir.path().join("c");
+        smol::fs::write(&file_c, "c").await.unwrap();
+        repo.stash_paths(Vec::new(), None, env.clone())
+            .await
+            .unwrap();
+        assert!(!file_c.exists());
+        let entries = repo.stash_entries().await.unwrap();
+        let diff = repo
+            .load_commit(entries[0].sha.to_string(), cx.to_async())
+            .await
+            .unwrap();
+        let mut paths = diff
+            .files
+            .iter()
+            .map(|file| file.path.clone())
+            .collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(
+            paths,
+            [
+                RepoPath::from_str("a"),
+                RepoPath::from_str("b"),
+                RepoPath::from_str("c")
+            ]
+        );
+        let file = diff
+            .files
+            .iter()
+            .find(|file| file.path == RepoPath::from_str("c"))
+            .unwrap();
+        assert_eq!(file.old_text, None);
+        assert_eq!(file.new_text.as_deref(), Some("c"));
+
+        repo.stash_drop(0, env.clone()).await.unwrap();
+        assert!(repo.stash_entries().await.unwrap().is_empty());
+        assert_eq!(smol::fs::read_to_string(&file_b).await.unwrap(), "b");
+    }
+
+    #[gpui::test]
+    async fn test_log(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        for (path, message) in [("a", "Add a"), ("b", "Add b"), ("a", "Change a")] {
+            smol::fs::write(repo_dir.path().join(path), message)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![RepoPath::from_str(path)], env.clone())
+                .await
+                .unwrap();
+            repo.commit(message.into(), None, CommitOptions::default(), env.clone())
+                .await
+                .unwrap();
+        }
+
+        let entries = repo.log(LogOptions::default()).await.unwrap();
+        let subjects = entries
+            .iter()
+            .map(|entry| entry.subject.as_ref())
+            .collect::<Vec<_>>();
+        assert_eq!(subjects, ["Change a", "Add b", "Add a"]);
+        assert_eq!(entries[0].parent_shas, vec![entries[1].sha.clone()]);
+        assert!(!entries[0].refs.is_empty());
+        assert!(entries[1].refs.is_empty());
+
+        let page = repo
+            .log(LogOptions {
+                skip: 1,
+                limit: Some(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].subject.as_ref(), "Add b");
+
+        let file_history = repo
+            .log(LogOptions {
+                path: Some(RepoPath::from_str("a")),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let subjects = file_history
+            .iter()
+            .map(|entry| entry.subject.as_ref())
+            .collect::<Vec<_>>();
+        assert_eq!(subjects, ["Change a", "Add a"]);
+        // Parents skip the commits that don't touch the path.
+        assert_eq!(
+            file_history[0].parent_shas,
+            vec![file_history[1].sha.clone()]
+        );
+        assert!(file_history[1].parent_shas.is_empty());
+
+        let file_history_page = repo
+            .log(LogOptions {
+                limit: Some(1),
+                path: Some(RepoPath::from_str("a")),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(file_history_page.len(), 1);
+        assert_eq!(
+            file_history_page[0].parent_shas,
+            vec![file_history[1].sha.clone()]
+        );
+
+        // Revisions and authors can't smuggle in options.
+        let output_path = repo_dir.path().join("output");
+        assert!(
+            repo.log(LogOptions {
+                revision_range: Some(format!("--output={}", output_path.display())),
+                ..Default::default()
+            })
+            .await
+            .is_err()
+        );
+        assert!(!output_path.exists());
+        assert!(
+            repo.log(LogOptions {
+                author: Some("Ada\n--output=/tmp/log".into()),
+                ..Default::default()
+            })
+            .await
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_worktree_list() {
+        let input = "worktree /home/user/project\0HEAD 1111111111111111111111111111111111111111\0branch refs/heads/main\0\0\
+            worktree /home/user/project-feature\0HEAD 2222222222222222222222222222222222222222\0branch refs/heads/feature\0locked\0\0\
+            worktree /tmp/gone\0HEAD 3333333333333333333333333333333333333333\0detached\0prunable gitdir file points to non-existent location\0\0";
+        let worktrees = parse_worktree_list(input).unwrap();
+        assert_eq!(
+            worktrees
+                .iter()
+                .map(|worktree| (
+                    worktree.path.to_str().unwrap(),
+                    worktree.branch_name(),
+                    worktree.is_main,
+                    worktree.is_locked,
+                    worktree.is_prunable
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("/home/user/project", Some("main"), true, false, false),
+                (
+                    "/home/user/project-feature",
+                    Some("feature"),
+                    false,
+                    true,
+                    false
+                ),
+                ("/tmp/gone", None, false, false, true),
+            ]
+        );
+        assert_eq!(
+            worktrees[1].sha.as_deref(),
+            Some("2222222222222222222222222222222222222222")
+        );
+    }
+
+    #[gpui::test]
+    async fn test_tags_and_worktrees(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        smol::fs::write(repo_dir.path().join("a"), "a")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![RepoPath::from_str("a")], env.clone())
+            .await
+            .unwrap();
+        repo.commit("Add a".into(), None, CommitOptions::default(), env.clone())
+            .await
+            .unwrap();
+        let head = repo.head_sha().await.unwrap();
+
+        repo.create_tag("v1".into(), None, None, env.clone())
+            .await
+            .unwrap();
+        repo.create_tag("v2".into(), None, Some("Release 2".into()), env.clone())
+            .await
+            .unwrap();
+        let mut tags = repo.tags().await.unwrap();
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(
+            tags.iter()
+                .map(|tag| (
+                    tag.name.as_ref(),
+                    tag.sha.as_ref(),
+                    tag.subject.as_ref(),
+                    tag.is_annotated
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("v1", head.as_str(), "Add a", false),
+                ("v2", head.as_str(), "Release 2", true),
+            ]
+        );
+        repo.delete_tag("v1".into(), env.clone()).await.unwrap();
+        assert_eq!(repo.tags().await.unwrap().len(), 1);
+
+        // Names that look like options are passed to git as names.
+        assert!(repo.delete_tag("--help".into(), env.clone()).await.is_err());
+        assert!(
+            repo.create_tag("-f".into(), None, None, env.clone())
+                .await
+                .is_err()
+        );
+        assert_eq!(repo.tags().await.unwrap().len(), 1);
+
+        repo.create_branch("feature".into()).await.unwrap();
+        repo.rename_branch("feature".into(), "topic".into(), env.clone())
+            .await
+            .unwrap();
+        let worktree_dir = tempfile::tempdir().unwrap();
+        let worktree_path = worktree_dir.path().join("topic");
+        repo.create_worktree(worktree_path.clone(), "topic".into(), false, env.clone())
+            .await
+            .unwrap();
+        let worktrees = repo.worktrees().await.unwrap();
+        assert_eq!(worktrees.len(), 2);
+        assert!(worktrees[0].is_main);
+        assert_eq!(worktrees[1].branch_name(), Some("topic"));
+
+        // A branch that is checked out in a working tree can't be deleted.
+        assert!(
+            repo.delete_branch("topic".into(), true, env.clone())
+                .await
+                .is_err()
+        );
+        repo.remove_worktree(worktrees[1].path.clone(), false, env.clone())
+            .await
+            .unwrap();
+        assert_eq!(repo.worktrees().await.unwrap().len(), 1);
+        repo.delete_branch("topic".into(), false, env.clone())
+            .await
+            .unwrap();
+        assert!(
+            repo.branches()
+                .await
+                .unwrap()
+                .iter()
+                .all(|branch| branch.name() != "topic")
+        );
+    }
+
+    #[gpui::test]
+    async fn test_load_conflict_stages(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(repo_dir.path()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let commit = |content: &str, parents: &[&git2::Commit], update_ref: Option<&str>| {
+            let blob = git.blob(content.as_bytes()).unwrap();
+            let mut tree = git.treebuilder(None).unwrap();
+            tree.insert("a", blob, 0o100644).unwrap();
+            let tree = git.find_tree(tree.write().unwrap()).unwrap();
+            let oid = git
+                .commit(update_ref, &signature, &signature, content, &tree, parents)
+                .unwrap();
+            git.find_commit(oid).unwrap()
+        };
+        let base = commit("one\ntwo\n", &[], Some("HEAD"));
+        let ours = commit("one\nTWO\n", &[&base], Some("HEAD"));
+        let theirs = commit("one\n2\n", &[&base], None);
+
+        // Write the conflicted entries of merging the two commits into the index.
+        let mut merged = git.merge_commits(&ours, &theirs, None).unwrap();
+        let mut index = git.index().unwrap();
+        for conflict in merged.conflicts().unwrap() {
+            let conflict = conflict.unwrap();
+            for entry in [conflict.ancestor, conflict.our, conflict.their]
+                .into_iter()
+                .flatten()
+            {
+                index.add(&entry).unwrap();
+            }
+        }
+        index.write().unwrap();
+
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        assert_eq!(
+            repo.load_conflict_stages(RepoPath::from_str("a"))
+                .await
+                .unwrap(),
+            ConflictStages {
+                base: Some("one\ntwo\n".into()),
+                ours: Some("one\nTWO\n".into()),
+                theirs: Some("one\n2\n".into()),
+            }
+        );
+        assert!(
+            repo.load_conflict_stages(RepoPath::from_str("b"))
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_rebase_todo_script() {
+        let entry = |action, sha: &str, subject: &str| RebaseTodoEntry {
+            action,
+            sha: sha.to_string().into(),
+            subject: subject.to_string().into(),
+        };
+        let script = rebase_todo_script(&[
+            entry(
+                RebaseAction::Reword("New\n\nIt's better".into()),
+                "aaaa",
+                "Old",
+            ),
+            entry(RebaseAction::Squash, "bbbb", "Second"),
+            entry(RebaseAction::Drop, "cccc", "Third"),
+            entry(RebaseAction::Fixup, "dddd", "Fourth"),
+        ])
+        .unwrap();
+        assert_eq!(
+            script,
+            "pick aaaa Old\n\
+             exec printf '%s\\n' 'New' '' 'It'\\''s better' \
+             | git commit --amend --only --no-verify --allow-empty --file=-\n\
+             squash bbbb Second\n\
+             drop cccc Third\n\
+             fixup dddd Fourth\n"
+        );
+
+        assert!(
+            rebase_todo_script(&[
+                entry(RebaseAction::Drop, "aaaa", "First"),
+                entry(RebaseAction::Fixup, "bbbb", "Second"),
+            ])
+            .is_err()
+        );
+        assert!(
+            rebase_todo_script(&[entry(RebaseAction::Reword(" ".into()), "aaaa", "")]).is_err()
+        );
+
+        // Commits and subjects can't add commands to the todo list.
+        assert!(
+            rebase_todo_script(&[entry(RebaseAction::Pick, "aaaa\nexec touch pwned", "First")])
+                .is_err()
+        );
+        assert_eq!(
+            rebase_todo_script(&[entry(RebaseAction::Pick, "aaaa", "First\nexec touch pwned")])
+                .unwrap(),
+            "pick aaaa First exec touch pwned\n"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_cherry_pick_conflict_and_abort(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        let file = repo_dir.path().join("file");
+
+        for (content, message) in [("base", "Base"), ("one", "One"), ("two", "Two")] {
+            smol::fs::write(&file, content).await.unwrap();
+            repo.stage_paths(vec![RepoPath::from_str("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(message.into(), None, CommitOptions::default(), env.clone())
+                .await
+                .unwrap();
+        }
+        let log = repo.log(LogOptions::default()).await.unwrap();
+        let (two, one) = (log[0].sha.to_string(), log[1].sha.to_string());
+
+        repo.reset(one.clone(), ResetMode::Mixed, env.clone())
+            .await
+            .unwrap();
+        smol::fs::write(&file, "one").await.unwrap();
+        assert_eq!(repo.in_progress_operation().await, None);
+
+        repo.revert(vec![one], env.clone()).await.unwrap();
+        assert_eq!(smol::fs::read_to_string(&file).await.unwrap(), "base");
+        assert_eq!(repo.in_progress_operation().await, None);
+
+        // Picking `two` on top of the revert conflicts, which stops the cherry-pick.
+        repo.cherry_pick(vec![two], env.clone()).await.unwrap();
+        assert_eq!(
+            repo.in_progress_operation().await,
+            Some(InProgressOperation::CherryPick)
+        );
+
+        repo.abort_operation(InProgressOperation::CherryPick, env.clone())
+            .await
+            .unwrap();
+        assert_eq!(repo.in_progress_operation().await, None);
+        assert_eq!(smol::fs::read_to_string(&file).await.unwrap(), "base");
+    }
+
+    #[gpui::test]
+    async fn test_interactive_rebase(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        for name in ["base", "a", "b", "c", "d"] {
+            smol::fs::write(repo_dir.path().join(name), name)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![RepoPath::from_str(name)], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                format!("Add {name}").into(),
+                None,
+                CommitOptions::default(),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let mut log = repo.log(LogOptions::default()).await.unwrap();
+        let base = log.pop().unwrap().sha.to_string();
+        let todo = log
+            .iter()
+            .rev()
+            .map(|entry| RebaseTodoEntry {
+                action: match entry.subject.as_ref() {
+                    "Add a" => RebaseAction::Reword("Add the letter a".into()),
+                    "Add c" => RebaseAction::Drop,
+                    "Add d" => RebaseAction::Fixup,
+                    _ => RebaseAction::Pick,
+                },
+                sha: entry.sha.clone(),
+                subject: entry.subject.clone(),
+            })
+            .collect();
+        repo.rebase(base, Some(todo), env.clone()).await.unwrap();
+        assert_eq!(repo.in_progress_operation().await, None);
+
+        let subjects = repo
+            .log(LogOptions::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.subject.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(subjects, ["Add b", "Add the letter a", "Add base"]);
+        assert!(repo_dir.path().join("d").exists());
+        assert!(!repo_dir.path().join("c").exists());
+    }
+
     impl RealGitRepository {
         /// Force a Git garbage collection on the repository.
         fn gc(&self) -> BoxFuture<'_, Result<()>> {
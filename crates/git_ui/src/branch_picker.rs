@@ -2,21 +2,22 @@ use anyhow::Context as _;
 use fuzzy::StringMatchCandidate;
 
 use collections::HashSet;
-use git::repository::Branch;
+use git::repository::{Branch, GitWorktree};
 use gpui::{
     App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement,
-    IntoElement, Modifiers, ModifiersChangedEvent, ParentElement, Render, SharedString, Styled,
-    Subscription, Task, Window, rems,
+    IntoElement, Modifiers, ModifiersChangedEvent, ParentElement, PromptLevel, Render,
+    SharedString, Styled, Subscription, Task, Window, rems,
 };
 use picker::{Picker, PickerDelegate, PickerEditorPosition};
 use project::git_store::Repository;
+use std::path::PathBuf;
 use std::sync::Arc;
 use time::OffsetDateTime;
 use time_format::format_local_timestamp;
-use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
 use util::ResultExt;
 use workspace::notifications::DetachAndPromptErr;
-use workspace::{ModalView, Workspace};
+use workspace::{AppState, ModalView, OpenOptions, Workspace};
 
 pub fn register(workspace: &mut Workspace) {
     workspace.register_action(open);
@@ -90,6 +91,9 @@ impl BranchList {
         let all_branches_request = repository
             .clone()
             .map(|repository| repository.update(cx, |repository, _| repository.branches()));
+        let worktrees_request = repository
+            .clone()
+            .map(|repository| repository.update(cx, |repository, _| repository.worktrees()));
 
         cx.spawn_in(window, async move |this, cx| {
             let mut all_branches = all_branches_request
@@ -122,9 +126,19 @@ impl BranchList {
                 })
                 .await;
 
+            let worktrees = match worktrees_request {
+                Some(request) => request
+                    .await
+                    .ok()
+                    .and_then(|worktrees| worktrees.log_err())
+                    .unwrap_or_default(),
+                None => Vec::new(),
+            };
+
             this.update_in(cx, |this, window, cx| {
                 this.picker.update(cx, |picker, cx| {
                     picker.delegate.all_branches = Some(all_branches);
+                    picker.delegate.worktrees = worktrees;
                     picker.refresh(window, cx);
                 })
             })?;
@@ -192,6 +206,10 @@ struct BranchEntry {
 pub struct BranchListDelegate {
     matches: Vec<BranchEntry>,
     all_branches: Option<Vec<Branch>>,
+    /// The working trees of the repository, used to show where branches are checked out.
+    worktrees: Vec<GitWorktree>,
+    /// The branch being renamed. While set, the query is used as its new name.
+    renaming: Option<Branch>,
     repo: Option<Entity<Repository>>,
     style: BranchListStyle,
     selected_index: usize,
@@ -206,12 +224,176 @@ impl BranchListDelegate {
             repo,
             style,
             all_branches: None,
+            worktrees: Vec::new(),
+            renaming: None,
             selected_index: 0,
             last_query: Default::default(),
             modifiers: Default::default(),
         }
     }
 
+    /// Returns the working tree other than the current one that has `branch` checked out.
+    fn worktree_for_branch(&self, branch: &Branch) -> Option<&GitWorktree> {
+        if branch.is_head {
+            return None;
+        }
+        self.worktrees
+            .iter()
+            .find(|worktree| worktree.branch.as_ref() == Some(&branch.ref_name))
+    }
+
+    fn is_local(&self, cx: &App) -> bool {
+        self.repo.as_ref().is_some_and(|repo| {
+            repo.read(cx)
+                .git_store()
+                .is_some_and(|git_store| git_store.read(cx).is_local())
+        })
+    }
+
+    fn start_rename(&mut self, ix: usize) -> Option<String> {
+        let branch = self.matches.get(ix)?.branch.clone();
+        let name = branch.name().to_string();
+        self.renaming = Some(branch);
+        Some(name)
+    }
+
+    fn rename_branch(
+        &mut self,
+        new_name: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some((repo, branch)) = self.repo.clone().zip(self.renaming.take()) else {
+            return;
+        };
+        let old_name = branch.name().to_string();
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, _| repo.rename_branch(old_name, new_name))?
+                .await??;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to rename branch", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn delete_branch(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let Some(branch) = self.matches.get(ix).map(|entry| entry.branch.clone()) else {
+            return;
+        };
+        cx.spawn_in(window, async move |picker, cx| {
+            let name = branch.name().to_string();
+            let result = repo
+                .update(cx, |repo, _| repo.delete_branch(name.clone(), false))?
+                .await?;
+            if let Err(error) = result {
+                let answer = cx.update(|window, cx| {
+                    window.prompt(
+                        PromptLevel::Warning,
+                        &format!("Branch {name} could not be deleted"),
+                        Some(&error.to_string()),
+                        &["Force Delete", "Cancel"],
+                        cx,
+                    )
+                })?;
+                if answer.await != Ok(0) {
+                    return Ok(());
+                }
+                repo.update(cx, |repo, _| repo.delete_branch(name, true))?
+                    .await??;
+            }
+            picker.update_in(cx, |picker, window, cx| {
+                if let Some(all_branches) = picker.delegate.all_branches.as_mut() {
+                    all_branches.retain(|candidate| candidate.ref_name != branch.ref_name);
+                }
+                picker.refresh(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to delete branch", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn create_worktree(
+        &self,
+        branch_name: String,
+        create_branch: bool,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let Some(path) = repo.read(cx).worktree_path_for_branch(&branch_name) else {
+            return;
+        };
+        let open_after_creation = self.is_local(cx);
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, _| {
+                repo.create_worktree(path.clone(), branch_name, create_branch)
+            })?
+            .await??;
+            if open_after_creation {
+                cx.update(|cx| open_worktree(path, cx))?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to create worktree", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn remove_worktree(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let Some(worktree) = self
+            .matches
+            .get(ix)
+            .and_then(|entry| self.worktree_for_branch(&entry.branch))
+            .cloned()
+        else {
+            return;
+        };
+        cx.spawn_in(window, async move |picker, cx| {
+            let path = worktree.path;
+            let result = repo
+                .update(cx, |repo, _| repo.remove_worktree(path.clone(), false))?
+                .await?;
+            if let Err(error) = result {
+                let answer = cx.update(|window, cx| {
+                    window.prompt(
+                        PromptLevel::Warning,
+                        &format!("Worktree at {} could not be removed", path.display()),
+                        Some(&error.to_string()),
+                        &["Force Remove", "Cancel"],
+                        cx,
+                    )
+                })?;
+                if answer.await != Ok(0) {
+                    return Ok(());
+                }
+                repo.update(cx, |repo, _| repo.remove_worktree(path, true))?
+                    .await??;
+            }
+            let worktrees = repo.update(cx, |repo, _| repo.worktrees())?.await??;
+            picker.update(cx, |picker, cx| {
+                picker.delegate.worktrees = worktrees;
+                cx.notify();
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to remove worktree", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
     fn create_branch(
         &self,
         new_branch_name: SharedString,
@@ -241,6 +423,98 @@ impl BranchListDelegate {
     }
 }
 
+impl BranchListDelegate {
+    fn render_branch_actions(
+        &self,
+        ix: usize,
+        worktree: Option<&GitWorktree>,
+        cx: &mut Context<Picker<Self>>,
+    ) -> impl IntoElement {
+        let entry = &self.matches[ix];
+        let actions = h_flex().gap_1();
+        if let Some(worktree) = worktree {
+            let path = worktree.path.clone();
+            return actions
+                .when(self.is_local(cx), |this| {
+                    this.child(
+                        IconButton::new(("open-worktree", ix), IconName::ArrowUpRight)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Open Worktree"))
+                            .on_click(cx.listener(move |_, _, _, cx| {
+                                cx.stop_propagation();
+                                open_worktree(path.clone(), cx);
+                                cx.emit(DismissEvent);
+                            })),
+                    )
+                })
+                .child(
+                    IconButton::new(("remove-worktree", ix), IconName::Trash)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Remove Worktree"))
+                        .on_click(cx.listener(move |picker, _, window, cx| {
+                            cx.stop_propagation();
+                            picker.delegate.remove_worktree(ix, window, cx);
+                        })),
+                );
+        }
+
+        let is_head = entry.branch.is_head;
+        let branch_name = entry.branch.name().to_string();
+        actions
+            .when(!is_head, |this| {
+                this.child(
+                    IconButton::new(("new-worktree", ix), IconName::FileTree)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Check Out in New Worktree"))
+                        .on_click(cx.listener(move |picker, _, window, cx| {
+                            cx.stop_propagation();
+                            picker
+                                .delegate
+                                .create_worktree(branch_name.clone(), false, window, cx);
+                        })),
+                )
+            })
+            .child(
+                IconButton::new(("rename-branch", ix), IconName::Pencil)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text("Rename Branch"))
+                    .on_click(cx.listener(move |picker, _, window, cx| {
+                        cx.stop_propagation();
+                        if let Some(name) = picker.delegate.start_rename(ix) {
+                            picker.set_query(name, window, cx);
+                        }
+                    })),
+            )
+            .when(!is_head, |this| {
+                this.child(
+                    IconButton::new(("delete-branch", ix), IconName::Trash)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Delete Branch"))
+                        .on_click(cx.listener(move |picker, _, window, cx| {
+                            cx.stop_propagation();
+                            picker.delegate.delete_branch(ix, window, cx);
+                        })),
+                )
+            })
+    }
+}
+
+fn open_worktree(path: PathBuf, cx: &mut App) {
+    let Some(app_state) = AppState::global(cx).upgrade() else {
+        return;
+    };
+    workspace::open_paths(
+        &[path],
+        app_state,
+        OpenOptions {
+            open_new_workspace: Some(true),
+            ..Default::default()
+        },
+        cx,
+    )
+    .detach_and_log_err(cx);
+}
+
 impl PickerDelegate for BranchListDelegate {
     type ListItem = ListItem;
 
@@ -282,6 +556,26 @@ impl PickerDelegate for BranchListDelegate {
             return Task::ready(());
         };
 
+        if let Some(renaming) = self.renaming.as_ref() {
+            let new_name = query.trim().replace(' ', "-");
+            self.matches.clear();
+            if !new_name.is_empty() && new_name != renaming.name() {
+                self.matches.push(BranchEntry {
+                    branch: Branch {
+                        ref_name: format!("refs/heads/{new_name}").into(),
+                        is_head: false,
+                        upstream: None,
+                        most_recent_commit: None,
+                    },
+                    positions: Vec::new(),
+                    is_new: true,
+                });
+            }
+            self.selected_index = 0;
+            self.last_query = query;
+            return Task::ready(());
+        }
+
         const RECENT_BRANCHES_COUNT: usize = 10;
         cx.spawn_in(window, async move |picker, cx| {
             let mut matches: Vec<BranchEntry> = if query.is_empty() {
@@ -353,12 +647,19 @@ impl PickerDelegate for BranchListDelegate {
         })
     }
 
-    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
         let Some(entry) = self.matches.get(self.selected_index()) else {
             return;
         };
         if entry.is_new {
-            self.create_branch(entry.branch.name().to_owned().into(), window, cx);
+            let name = entry.branch.name().to_owned();
+            if self.renaming.is_some() {
+                self.rename_branch(name, window, cx);
+            } else if secondary {
+                self.create_worktree(name, true, window, cx);
+            } else {
+                self.create_branch(name.into(), window, cx);
+            }
             return;
         }
 
@@ -439,19 +740,26 @@ impl PickerDelegate for BranchListDelegate {
             })
             .unwrap_or_else(|| (None, None));
 
+        let worktree = self.worktree_for_branch(&entry.branch);
         let branch_name = if entry.is_new {
+            let (icon, label) = match &self.renaming {
+                Some(renaming) => (
+                    IconName::Pencil,
+                    format!(
+                        "Rename branch \"{}\" to \"{}\"…",
+                        renaming.name(),
+                        entry.branch.name()
+                    ),
+                ),
+                None => (
+                    IconName::Plus,
+                    format!("Create branch \"{}\"…", entry.branch.name()),
+                ),
+            };
             h_flex()
                 .gap_1()
-                .child(
-                    Icon::new(IconName::Plus)
-                        .size(IconSize::Small)
-                        .color(Color::Muted),
-                )
-                .child(
-                    Label::new(format!("Create branch \"{}\"…", entry.branch.name()))
-                        .single_line()
-                        .truncate(),
-                )
+                .child(Icon::new(icon).size(IconSize::Small).color(Color::Muted))
+                .child(Label::new(label).single_line().truncate())
                 .into_any_element()
         } else {
             HighlightedLabel::new(entry.branch.name().to_owned(), entry.positions.clone())
@@ -485,7 +793,11 @@ impl PickerDelegate for BranchListDelegate {
                         )
                         .when(self.style == BranchListStyle::Modal, |el| {
                             el.child(div().max_w_96().child({
-                                let message = if entry.is_new {
+                                let message = if let Some(worktree) = worktree {
+                                    format!("checked out at {}", worktree.path.display())
+                                } else if self.renaming.is_some() {
+                                    "press enter to rename".to_string()
+                                } else if entry.is_new {
                                     if let Some(current_branch) =
                                         self.repo.as_ref().and_then(|repo| {
                                             repo.read(cx).branch.as_ref().map(|b| b.name())
@@ -504,6 +816,12 @@ impl PickerDelegate for BranchListDelegate {
                                     .color(Color::Muted)
                             }))
                         }),
+                )
+                .when(
+                    self.style == BranchListStyle::Modal
+                        && !entry.is_new
+                        && !entry.branch.is_remote(),
+                    |item| item.end_slot(self.render_branch_actions(ix, worktree, cx)),
                 ),
         )
     }
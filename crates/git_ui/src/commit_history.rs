@@ -0,0 +1,809 @@
+//! CommitHistory lists the commits of a repository, or of a single file within it, along with a
+//! graph of the branches they belong to.
+
+use std::ops::Range;
+
+use editor::Editor;
+use git::repository::{LogEntry, LogOptions, RepoPath};
+use gpui::{
+    AnyElement, App, ClipboardItem, Context, Corner, DismissEvent, Entity, EventEmitter,
+    FocusHandle, Focusable, Hsla, IntoElement, ListSizingBehavior, MouseButton, MouseDownEvent,
+    PathBuilder, Pixels, Point, Render, ScrollStrategy, SharedString, Subscription, Task,
+    UniformListScrollHandle, WeakEntity, Window, anchored, canvas, deferred, point, px,
+    uniform_list,
+};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrevious};
+use project::git_store::Repository;
+use time::OffsetDateTime;
+use time_format::format_local_timestamp;
+use ui::{Chip, ContextMenu, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::{
+    Item, Workspace,
+    item::{ItemEvent, TabContentParams},
+    notifications::DetachAndPromptErr,
+};
+
+use crate::{commit_view::CommitView, rebase_editor::RebaseEditor};
+
+/// The number of commits requested from the repository at a time.
+const PAGE_SIZE: usize = 200;
+/// Start loading the next page once the visible range gets this close to the end of the list.
+const LOAD_MORE_THRESHOLD: usize = 50;
+const LANE_WIDTH: Pixels = px(14.);
+const COMMIT_DOT_RADIUS: Pixels = px(3.5);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::ViewHistory, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        CommitHistory::deploy(workspace, repository, None, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::FileHistory, window, cx| {
+        let Some(project_path) = workspace
+            .active_item_as::<Editor>(cx)
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+            .and_then(|buffer| buffer.read(cx).project_path(cx))
+        else {
+            return;
+        };
+        let Some((repository, repo_path)) = workspace
+            .project()
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_project_path(&project_path, cx)
+        else {
+            return;
+        };
+        CommitHistory::deploy(workspace, repository, Some(repo_path), window, cx);
+    });
+}
+
+pub struct CommitHistory {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    /// When set, only commits touching this path are listed.
+    path: Option<RepoPath>,
+    entries: Vec<LogEntry>,
+    graph: GraphBuilder,
+    rows: Vec<GraphRow>,
+    selected_index: Option<usize>,
+    has_more: bool,
+    load_task: Option<Task<()>>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
+}
+
+impl CommitHistory {
+    fn deploy(
+        workspace: &mut Workspace,
+        repository: Entity<Repository>,
+        path: Option<RepoPath>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let existing = workspace
+            .active_pane()
+            .read(cx)
+            .items()
+            .filter_map(|item| item.downcast::<CommitHistory>())
+            .find(|history| {
+                let history = history.read(cx);
+                history.repository == repository && history.path == path
+            });
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+
+        let weak_workspace = workspace.weak_handle();
+        let history = cx.new(|cx| Self::new(repository, path, weak_workspace, cx));
+        workspace.add_item_to_active_pane(Box::new(history), None, true, window, cx);
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        path: Option<RepoPath>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut this = Self {
+            repository,
+            workspace,
+            path,
+            entries: Vec::new(),
+            graph: GraphBuilder::default(),
+            rows: Vec::new(),
+            selected_index: None,
+            has_more: true,
+            load_task: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            context_menu: None,
+        };
+        this.load_more(cx);
+        this
+    }
+
+    fn load_more(&mut self, cx: &mut Context<Self>) {
+        if !self.has_more || self.load_task.is_some() {
+            return;
+        }
+        let options = LogOptions {
+            skip: self.entries.len(),
+            limit: Some(PAGE_SIZE),
+            path: self.path.clone(),
+            ..Default::default()
+        };
+        let request = self
+            .repository
+            .update(cx, |repository, _| repository.log(options));
+        self.load_task = Some(cx.spawn(async move |this, cx| {
+            let entries = request.await.ok().and_then(|entries| entries.log_err());
+            this.update(cx, |this, cx| {
+                this.load_task = None;
+                let Some(entries) = entries else {
+                    this.has_more = false;
+                    return;
+                };
+                this.has_more = entries.len() == PAGE_SIZE;
+                for entry in &entries {
+                    this.rows.push(this.graph.push(entry));
+                }
+                this.entries.extend(entries);
+                if this.selected_index.is_none() && !this.entries.is_empty() {
+                    this.selected_index = Some(0);
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn open_entry(&self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        CommitView::open(
+            entry.to_commit_summary(),
+            self.repository.downgrade(),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    fn deploy_context_menu(
+        &mut self,
+        position: Point<Pixels>,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(sha) = self.entries.get(ix).map(|entry| entry.sha.clone()) else {
+            return;
+        };
+        self.selected_index = Some(ix);
+
+        let this = cx.entity().downgrade();
+        let context_menu = ContextMenu::build(window, cx, move |context_menu, _, _| {
+            context_menu
+                .entry("View Commit", None, {
+                    let this = this.clone();
+                    move |window, cx| {
+                        this.update(cx, |this, cx| this.open_entry(ix, window, cx))
+                            .ok();
+                    }
+                })
+                .entry("Copy SHA", None, {
+                    let sha = sha.clone();
+                    move |_, cx| cx.write_to_clipboard(ClipboardItem::new_string(sha.to_string()))
+                })
+                .separator()
+                .entry("Cherry-Pick", None, {
+                    let this = this.clone();
+                    let sha = sha.clone();
+                    move |window, cx| {
+                        this.update(cx, |this, cx| this.cherry_pick(sha.clone(), window, cx))
+                            .ok();
+                    }
+                })
+                .entry("Revert", None, {
+                    let this = this.clone();
+                    let sha = sha.clone();
+                    move |window, cx| {
+                        this.update(cx, |this, cx| this.revert(sha.clone(), window, cx))
+                            .ok();
+                    }
+                })
+                .entry("Interactive Rebase onto This Commit", None, {
+                    let this = this.clone();
+                    move |window, cx| {
+                        this.update(cx, |this, cx| {
+                            this.interactive_rebase(sha.clone(), window, cx)
+                        })
+                        .ok();
+                    }
+                })
+        });
+
+        let subscription = cx.subscribe_in(
+            &context_menu,
+            window,
+            |this, _, _: &DismissEvent, window, cx| {
+                if this.context_menu.as_ref().is_some_and(|context_menu| {
+                    context_menu.0.focus_handle(cx).contains_focused(window, cx)
+                }) {
+                    cx.focus_self(window);
+                }
+                this.context_menu.take();
+                cx.notify();
+            },
+        );
+        self.context_menu = Some((context_menu, position, subscription));
+        cx.notify();
+    }
+
+    fn cherry_pick(&self, sha: SharedString, window: &mut Window, cx: &mut Context<Self>) {
+        let task = self.repository.update(cx, |repository, _| {
+            repository.cherry_pick(vec![sha.to_string()])
+        });
+        cx.spawn(async move |_, _| task.await?)
+            .detach_and_prompt_err("Failed to cherry-pick", window, cx, |e, _, _| {
+                Some(e.to_string())
+            });
+    }
+
+    fn revert(&self, sha: SharedString, window: &mut Window, cx: &mut Context<Self>) {
+        let task = self
+            .repository
+            .update(cx, |repository, _| repository.revert(vec![sha.to_string()]));
+        cx.spawn(async move |_, _| task.await?)
+            .detach_and_prompt_err("Failed to revert", window, cx, |e, _, _| {
+                Some(e.to_string())
+            });
+    }
+
+    fn interactive_rebase(&self, sha: SharedString, window: &mut Window, cx: &mut Context<Self>) {
+        let repository = self.repository.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                RebaseEditor::toggle(workspace, repository, sha.to_string(), window, cx)
+            })
+            .ok();
+    }
+
+    fn select_index(&mut self, ix: usize, cx: &mut Context<Self>) {
+        self.selected_index = Some(ix);
+        self.scroll_handle
+            .scroll_to_item(ix, ScrollStrategy::Center);
+        if ix + LOAD_MORE_THRESHOLD >= self.entries.len() {
+            self.load_more(cx);
+        }
+        cx.notify();
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.entries.is_empty() {
+            self.select_index(0, cx);
+        }
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(last) = self.entries.len().checked_sub(1) {
+            self.select_index(last, cx);
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let next = self.selected_index.map_or(0, |ix| ix + 1);
+        if next < self.entries.len() {
+            self.select_index(next, cx);
+        }
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(previous) = self.selected_index.and_then(|ix| ix.checked_sub(1)) {
+            self.select_index(previous, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_index {
+            self.open_entry(ix, window, cx);
+        }
+    }
+
+    fn render_entries(
+        &mut self,
+        range: Range<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        if range.end + LOAD_MORE_THRESHOLD >= self.entries.len() {
+            self.load_more(cx);
+        }
+
+        let lane_count = self.rows[range.clone()]
+            .iter()
+            .map(|row| row.lane_count)
+            .max()
+            .unwrap_or(1);
+        let now = OffsetDateTime::now_utc();
+        let selected_background = cx.theme().colors().element_selected;
+        let hover_background = cx.theme().colors().element_hover;
+
+        range
+            .map(|ix| {
+                let entry = &self.entries[ix];
+                let row = self.rows[ix].clone();
+                let timestamp = OffsetDateTime::from_unix_timestamp(entry.commit_timestamp)
+                    .map(|timestamp| {
+                        format_local_timestamp(
+                            timestamp,
+                            now,
+                            time_format::TimestampFormat::Relative,
+                        )
+                    })
+                    .unwrap_or_default();
+
+                let selected = self.selected_index == Some(ix);
+                h_flex()
+                    .id(("commit", ix))
+                    .h_6()
+                    .w_full()
+                    .px_2()
+                    .gap_2()
+                    .cursor_pointer()
+                    .when(selected, |this| this.bg(selected_background))
+                    .hover(move |this| this.bg(hover_background))
+                    .tooltip(Tooltip::text(entry.sha.clone()))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.selected_index = Some(ix);
+                        this.open_entry(ix, window, cx);
+                        cx.notify();
+                    }))
+                    .on_mouse_down(
+                        MouseButton::Right,
+                        cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                            this.deploy_context_menu(event.position, ix, window, cx);
+                            cx.stop_propagation();
+                        }),
+                    )
+                    .child(render_graph_row(row, lane_count, cx))
+                    .children(
+                        entry
+                            .refs
+                            .iter()
+                            .map(|name| Chip::new(name.clone()).label_color(Color::Accent)),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .child(Label::new(entry.subject.clone()).single_line().truncate()),
+                    )
+                    .child(
+                        Label::new(entry.author_name.clone())
+                            .color(Color::Muted)
+                            .single_line(),
+                    )
+                    .child(Label::new(timestamp).color(Color::Muted).single_line())
+                    .child(
+                        Label::new(entry.short_sha())
+                            .color(Color::Muted)
+                            .buffer_font(cx),
+                    )
+                    .into_any_element()
+            })
+            .collect()
+    }
+}
+
+fn render_graph_row(row: GraphRow, lane_count: usize, cx: &App) -> impl IntoElement {
+    let accents = cx.theme().accents().clone();
+    let lane_color = move |lane: usize| -> Hsla { accents.color_for_index(lane as u32) };
+
+    canvas(
+        |_, _, _| {},
+        move |bounds, _, window, _| {
+            let lane_x = |lane: usize| bounds.left() + LANE_WIDTH * (lane as f32 + 0.5);
+            let top = bounds.top();
+            let middle = bounds.center().y;
+            let bottom = bounds.bottom();
+
+            for edge in &row.edges {
+                let (start_y, end_y) = match edge.half {
+                    GraphHalf::Upper => (top, middle),
+                    GraphHalf::Lower => (middle, bottom),
+                };
+                let start = point(lane_x(edge.from_lane), start_y);
+                let end = point(lane_x(edge.to_lane), end_y);
+                let mut path = PathBuilder::stroke(px(1.5));
+                path.move_to(start);
+                if start.x == end.x {
+                    path.line_to(end);
+                } else {
+                    let control: Point<Pixels> = match edge.half {
+                        GraphHalf::Upper => point(start.x, end.y),
+                        GraphHalf::Lower => point(end.x, start.y),
+                    };
+                    path.curve_to(end, control);
+                }
+                if let Ok(path) = path.build() {
+                    let color_lane = match edge.half {
+                        GraphHalf::Upper => edge.from_lane,
+                        GraphHalf::Lower => edge.to_lane,
+                    };
+                    window.paint_path(path, lane_color(color_lane));
+                }
+            }
+
+            let center = point(lane_x(row.lane), middle);
+            let mut dot = PathBuilder::fill();
+            dot.move_to(point(center.x + COMMIT_DOT_RADIUS, center.y));
+            dot.arc_to(
+                point(COMMIT_DOT_RADIUS, COMMIT_DOT_RADIUS),
+                px(0.),
+                false,
+                false,
+                point(center.x - COMMIT_DOT_RADIUS, center.y),
+            );
+            dot.arc_to(
+                point(COMMIT_DOT_RADIUS, COMMIT_DOT_RADIUS),
+                px(0.),
+                false,
+                false,
+                point(center.x + COMMIT_DOT_RADIUS, center.y),
+            );
+            if let Ok(dot) = dot.build() {
+                window.paint_path(dot, lane_color(row.lane));
+            }
+        },
+    )
+    .flex_none()
+    .h_full()
+    .w(LANE_WIDTH * lane_count as f32)
+}
+
+impl EventEmitter<ItemEvent> for CommitHistory {}
+
+impl Focusable for CommitHistory {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for CommitHistory {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &ItemEvent, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        match &self.path {
+            Some(path) => {
+                let file_name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.to_string_lossy().to_string());
+                format!("History: {file_name}").into()
+            }
+            None => "History".into(),
+        }
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let repository = self.repository.read(cx);
+        let repository_name = repository
+            .work_directory_abs_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Some(match &self.path {
+            Some(path) => format!("History of {}", path.to_string_lossy()).into(),
+            None => format!("History of {repository_name}").into(),
+        })
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Commit History Opened")
+    }
+}
+
+impl Render for CommitHistory {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entry_count = self.entries.len();
+        v_flex()
+            .key_context("CommitHistory")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .map(|this| {
+                if entry_count == 0 {
+                    let message = if self.load_task.is_some() {
+                        "Loading history…"
+                    } else {
+                        "No commits"
+                    };
+                    this.items_center()
+                        .justify_center()
+                        .child(Label::new(message).color(Color::Muted))
+                } else {
+                    this.child(
+                        uniform_list(
+                            "commit-history",
+                            entry_count,
+                            cx.processor(Self::render_entries),
+                        )
+                        .size_full()
+                        .with_sizing_behavior(ListSizingBehavior::Infer)
+                        .track_scroll(self.scroll_handle.clone()),
+                    )
+                }
+            })
+            .children(self.context_menu.as_ref().map(|(menu, position, _)| {
+                deferred(
+                    anchored()
+                        .position(*position)
+                        .anchor(Corner::TopLeft)
+                        .child(menu.clone()),
+                )
+                .with_priority(1)
+            }))
+    }
+}
+
+/// Which half of a row an edge of the graph is drawn in. Edges in the upper half connect the
+/// lanes of the previous row to this row's commit, edges in the lower half connect this row's
+/// commit to the lanes of the next row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum GraphHalf {
+    Upper,
+    Lower,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct GraphEdge {
+    from_lane: usize,
+    to_lane: usize,
+    half: GraphHalf,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct GraphRow {
+    /// The lane containing this row's commit.
+    lane: usize,
+    /// The number of lanes needed to draw this row.
+    lane_count: usize,
+    edges: Vec<GraphEdge>,
+}
+
+/// Assigns commits to lanes as they are loaded. Each lane holds the sha of the commit it is
+/// waiting for, which is always further down the list since parents are listed after their
+/// children.
+#[derive(Default)]
+struct GraphBuilder {
+    lanes: Vec<Option<SharedString>>,
+}
+
+impl GraphBuilder {
+    fn push(&mut self, entry: &LogEntry) -> GraphRow {
+        let lane = self
+            .lanes
+            .iter()
+            .position(|sha| sha.as_ref() == Some(&entry.sha))
+            .or_else(|| self.lanes.iter().position(Option::is_none))
+            .unwrap_or(self.lanes.len());
+        if lane == self.lanes.len() {
+            self.lanes.push(None);
+        }
+
+        let mut edges = Vec::new();
+        for (ix, sha) in self.lanes.iter_mut().enumerate() {
+            let Some(expected_sha) = sha.as_ref() else {
+                continue;
+            };
+            if *expected_sha == entry.sha {
+                edges.push(GraphEdge {
+                    from_lane: ix,
+                    to_lane: lane,
+                    half: GraphHalf::Upper,
+                });
+                *sha = None;
+            } else {
+                edges.push(GraphEdge {
+                    from_lane: ix,
+                    to_lane: ix,
+                    half: GraphHalf::Upper,
+                });
+            }
+        }
+
+        let mut parents = entry.parent_shas.iter();
+        self.lanes[lane] = parents.next().cloned();
+        let mut merged_lanes = Vec::new();
+        for parent in parents {
+            let parent_lane = match self
+                .lanes
+                .iter()
+                .position(|sha| sha.as_ref() == Some(parent))
+            {
+                Some(parent_lane) => parent_lane,
+                None => {
+                    let parent_lane = self
+                        .lanes
+                        .iter()
+                        .position(Option::is_none)
+                        .unwrap_or(self.lanes.len());
+                    if parent_lane == self.lanes.len() {
+                        self.lanes.push(None);
+                    }
+                    self.lanes[parent_lane] = Some(parent.clone());
+                    parent_lane
+                }
+            };
+            merged_lanes.push(parent_lane);
+            edges.push(GraphEdge {
+                from_lane: lane,
+                to_lane: parent_lane,
+                half: GraphHalf::Lower,
+            });
+        }
+
+        for (ix, sha) in self.lanes.iter().enumerate() {
+            if sha.is_none() || merged_lanes.contains(&ix) {
+                continue;
+            }
+            edges.push(GraphEdge {
+                from_lane: ix,
+                to_lane: ix,
+                half: GraphHalf::Lower,
+            });
+        }
+
+        while self.lanes.last().is_some_and(Option::is_none) {
+            self.lanes.pop();
+        }
+
+        let lane_count = edges
+            .iter()
+            .flat_map(|edge| [edge.from_lane, edge.to_lane])
+            .chain([lane])
+            .max()
+            .map_or(1, |max_lane| max_lane + 1);
+        GraphRow {
+            lane,
+            lane_count,
+            edges,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn entry(sha: &str, parents: &[&str]) -> LogEntry {
+        LogEntry {
+            sha: sha.to_string().into(),
+            parent_shas: parents.iter().map(|sha| sha.to_string().into()).collect(),
+            subject: SharedString::default(),
+            author_name: SharedString::default(),
+            author_email: SharedString::default(),
+            commit_timestamp: 0,
+            refs: Vec::new(),
+        }
+    }
+
+    fn edge(from_lane: usize, to_lane: usize, half: GraphHalf) -> GraphEdge {
+        GraphEdge {
+            from_lane,
+            to_lane,
+            half,
+        }
+    }
+
+    #[test]
+    fn test_linear_history() {
+        let mut graph = GraphBuilder::default();
+        let rows = [entry("c", &["b"]), entry("b", &["a"]), entry("a", &[])]
+            .iter()
+            .map(|entry| graph.push(entry))
+            .collect::<Vec<_>>();
+
+        assert!(rows.iter().all(|row| row.lane == 0 && row.lane_count == 1));
+        assert_eq!(rows[0].edges, [edge(0, 0, GraphHalf::Lower)]);
+        assert_eq!(
+            rows[1].edges,
+            [edge(0, 0, GraphHalf::Upper), edge(0, 0, GraphHalf::Lower)]
+        );
+        assert_eq!(rows[2].edges, [edge(0, 0, GraphHalf::Upper)]);
+        assert!(graph.lanes.is_empty());
+    }
+
+    #[test]
+    fn test_branch_and_merge() {
+        //   m
+        //   |\
+        //   | f
+        //   b |
+        //   |/
+        //   a
+        let mut graph = GraphBuilder::default();
+        let rows = [
+            entry("m", &["b", "f"]),
+            entry("f", &["a"]),
+            entry("b", &["a"]),
+            entry("a", &[]),
+        ]
+        .iter()
+        .map(|entry| graph.push(entry))
+        .collect::<Vec<_>>();
+
+        assert_eq!(rows[0].lane, 0);
+        assert_eq!(rows[0].lane_count, 2);
+        assert_eq!(
+            rows[0].edges,
+            [edge(0, 1, GraphHalf::Lower), edge(0, 0, GraphHalf::Lower)]
+        );
+
+        assert_eq!(rows[1].lane, 1);
+        assert_eq!(
+            rows[1].edges,
+            [
+                edge(0, 0, GraphHalf::Upper),
+                edge(1, 1, GraphHalf::Upper),
+                edge(0, 0, GraphHalf::Lower),
+                edge(1, 1, GraphHalf::Lower),
+            ]
+        );
+
+        // Both lanes are waiting for `a`, so the second lane ends here.
+        assert_eq!(rows[2].lane, 0);
+        assert_eq!(rows[3].lane, 0);
+        assert_eq!(
+            rows[3].edges,
+            [edge(0, 0, GraphHalf::Upper), edge(1, 0, GraphHalf::Upper)]
+        );
+        assert!(graph.lanes.is_empty());
+    }
+
+    #[test]
+    fn test_free_lanes_are_reused() {
+        let mut graph = GraphBuilder::default();
+        graph.push(&entry("x", &["a"]));
+        graph.push(&entry("y", &["b"]));
+        graph.push(&entry("a", &[]));
+        let row = graph.push(&entry("z", &["b"]));
+        assert_eq!(row.lane, 0);
+        assert_eq!(
+            graph.lanes,
+            [Some(SharedString::from("b")), Some(SharedString::from("b"))]
+        );
+    }
+}
@@ -13,6 +13,9 @@ use project::{ConflictRegion, ConflictSet, ConflictSetUpdate, ProjectItem as _};
 use std::{ops::Range, sync::Arc};
 use ui::{ActiveTheme, Element as _, Styled, Window, prelude::*};
 use util::{ResultExt as _, debug_panic, maybe};
+use workspace::notifications::DetachAndPromptErr;
+
+use crate::merge_editor::MergeEditor;
 
 pub(crate) struct ConflictAddon {
     buffers: HashMap<BufferId, BufferConflicts>,
@@ -454,9 +457,41 @@ fn render_conflict_buttons(
                     }
                 }),
         )
+        .child(
+            Button::new("merge-editor", "Open Merge Editor")
+                .label_size(LabelSize::Small)
+                .on_click({
+                    let buffer_id = conflict.ours.end.buffer_id;
+                    move |_, window, cx| {
+                        open_merge_editor(editor.clone(), buffer_id, window, cx);
+                    }
+                }),
+        )
         .into_any()
 }
 
+fn open_merge_editor(
+    editor: WeakEntity<Editor>,
+    buffer_id: Option<BufferId>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    maybe!({
+        let editor = editor.upgrade()?;
+        let workspace = editor.read(cx).workspace()?;
+        let buffer = editor.read(cx).buffer().read(cx).buffer(buffer_id?)?;
+        workspace.update(cx, |workspace, cx| {
+            MergeEditor::open(buffer, workspace, window, cx).detach_and_prompt_err(
+                "Failed to open merge editor",
+                window,
+                cx,
+                |e, _, _| Some(e.to_string()),
+            );
+        });
+        Some(())
+    });
+}
+
 pub(crate) fn resolve_conflict(
     editor: WeakEntity<Editor>,
     excerpt_id: ExcerptId,
@@ -3,6 +3,7 @@ use crate::commit_modal::CommitModal;
 use crate::commit_tooltip::CommitTooltip;
 use crate::commit_view::CommitView;
 use crate::git_panel_settings::StatusStyle;
+use crate::merge_editor::MergeEditor;
 use crate::project_diff::{self, Diff, ProjectDiff};
 use crate::remote_output::{self, RemoteAction, SuccessMessage};
 use crate::{branch_picker, picker_prompt, render_remote_button};
@@ -21,12 +22,16 @@ use futures::StreamExt as _;
 use git::blame::ParsedCommitMessage;
 use git::repository::{
     Branch, CommitDetails, CommitOptions, CommitSummary, DiffType, FetchOptions, GitCommitter,
-    PushOptions, Remote, RemoteCommandOutput, ResetMode, Upstream, UpstreamTracking,
-    UpstreamTrackingStatus, get_git_committer,
+    InProgressOperation, PushOptions, Remote, RemoteCommandOutput, ResetMode, Upstream,
+    UpstreamTracking, UpstreamTrackingStatus, get_git_committer,
 };
 use git::status::StageStatus;
+use git::{
+    AbortOperation, ContinueOperation, ExpandCommitEditor, RestoreTrackedFiles, SkipOperation,
+    StageAll, StashAll, StashPop, TrashUntrackedFiles, UnstageAll, ViewHistory, ViewStash,
+    ViewTags,
+};
 use git::{Amend, Signoff, ToggleStaged, repository::RepoPath, status::FileStatus};
-use git::{ExpandCommitEditor, RestoreTrackedFiles, StageAll, TrashUntrackedFiles, UnstageAll};
 use gpui::{
     Action, Animation, AnimationExt as _, AsyncApp, AsyncWindowContext, Axis, ClickEvent, Corner,
     DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, KeyContext,
@@ -140,6 +145,16 @@ fn git_panel_context_menu(
             )
             .separator()
             .action("Open Diff", project_diff::Diff.boxed_clone())
+            .action("View History", ViewHistory.boxed_clone())
+            .action("View Tags…", ViewTags.boxed_clone())
+            .separator()
+            .action_disabled_when(
+                !state.has_tracked_changes && !state.has_new_changes,
+                "Stash All",
+                StashAll.boxed_clone(),
+            )
+            .action("Pop Stash", StashPop.boxed_clone())
+            .action("View Stash…", ViewStash.boxed_clone())
             .separator()
             .action_disabled_when(
                 !state.has_tracked_changes,
@@ -963,6 +978,41 @@ impl GitPanel {
         });
     }
 
+    fn open_merge_editor(
+        &mut self,
+        _: &git::OpenMergeEditor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        maybe!({
+            let entry = self.entries.get(self.selected_entry?)?.status_entry()?;
+            let active_repo = self.active_repository.as_ref()?;
+            let path = active_repo
+                .read(cx)
+                .repo_path_to_project_path(&entry.repo_path, cx)?;
+            let workspace = self.workspace.clone();
+            let open_buffer = self
+                .project
+                .update(cx, |project, cx| project.open_buffer(path, cx));
+            cx.spawn_in(window, async move |_, cx| {
+                let buffer = open_buffer.await?;
+                workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        MergeEditor::open(buffer, workspace, window, cx)
+                    })?
+                    .await?;
+                anyhow::Ok(())
+            })
+            .detach_and_prompt_err(
+                "Failed to open merge editor",
+                window,
+                cx,
+                |e, _, _| Some(e.to_string()),
+            );
+            Some(())
+        });
+    }
+
     fn revert_selected(
         &mut self,
         action: &git::RestoreFile,
@@ -1291,6 +1341,138 @@ impl GitPanel {
         self.change_file_stage(false, entries, cx);
     }
 
+    pub fn stash_all(&mut self, _: &StashAll, _window: &mut Window, cx: &mut Context<Self>) {
+        self.stash_paths(Vec::new(), cx);
+    }
+
+    fn stash_selected(&mut self, _: &git::StashFile, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(status_entry) = self
+            .get_selected_entry()
+            .and_then(|entry| entry.status_entry())
+        else {
+            return;
+        };
+        self.stash_paths(vec![status_entry.repo_path.clone()], cx);
+    }
+
+    fn stash_paths(&mut self, paths: Vec<RepoPath>, cx: &mut Context<Self>) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+        telemetry::event!("Git Stashed");
+
+        cx.spawn(async move |this, cx| {
+            let result = maybe!(async {
+                active_repository
+                    .update(cx, |repo, _| repo.stash_paths(paths, None))?
+                    .await?
+            })
+            .await;
+            this.update(cx, |this, cx| {
+                if let Err(e) = result {
+                    this.show_error_toast("stash", e, cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub fn stash_pop(&mut self, _: &StashPop, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+        telemetry::event!("Git Stash Popped");
+
+        cx.spawn(async move |this, cx| {
+            let result = maybe!(async {
+                active_repository
+                    .update(cx, |repo, _| repo.stash_pop(0))?
+                    .await?
+            })
+            .await;
+            this.update(cx, |this, cx| {
+                if let Err(e) = result {
+                    this.show_error_toast("stash pop", e, cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub fn continue_operation(
+        &mut self,
+        _: &ContinueOperation,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.has_unstaged_conflicts() {
+            self.show_error_toast(
+                "continue",
+                anyhow::anyhow!(
+                    "There are still conflicts. You must stage these before continuing"
+                ),
+                cx,
+            );
+            return;
+        }
+        self.control_operation("continue", cx, |repo, operation| {
+            repo.continue_operation(operation)
+        });
+    }
+
+    pub fn skip_operation(
+        &mut self,
+        _: &SkipOperation,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.control_operation("skip", cx, |repo, operation| repo.skip_operation(operation));
+    }
+
+    pub fn abort_operation(
+        &mut self,
+        _: &AbortOperation,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.control_operation("abort", cx, |repo, operation| {
+            repo.abort_operation(operation)
+        });
+    }
+
+    fn control_operation(
+        &mut self,
+        action: &'static str,
+        cx: &mut Context<Self>,
+        f: impl FnOnce(
+            &mut Repository,
+            InProgressOperation,
+        ) -> futures::channel::oneshot::Receiver<anyhow::Result<()>>
+        + 'static,
+    ) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+        let Some(operation) = active_repository.read(cx).merge.operation else {
+            return;
+        };
+
+        cx.spawn(async move |this, cx| {
+            let result = maybe!(async {
+                active_repository
+                    .update(cx, |repo, _| f(repo, operation))?
+                    .await?
+            })
+            .await;
+            this.update(cx, |this, cx| {
+                if let Err(e) = result {
+                    this.show_error_toast(action, e, cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn toggle_staged_for_entry(
         &mut self,
         entry: &GitListEntry,
@@ -1872,6 +2054,7 @@ impl GitPanel {
 
                 let request = LanguageModelRequest {
                     thread_id: None,
+                    profile_id: None,
                     prompt_id: None,
                     intent: Some(CompletionIntent::GenerateGitCommitMessage),
                     mode: None,
@@ -1884,6 +2067,7 @@ impl GitPanel {
                     tool_choice: None,
                     stop: Vec::new(),
                     temperature,
+                    max_tokens: None,
                     thinking_allowed: false,
                 };
 
@@ -2262,6 +2446,58 @@ impl GitPanel {
         !self.project.read(cx).is_via_collab()
     }
 
+    pub(crate) fn push_tag(
+        &mut self,
+        tag: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.can_push_and_pull(cx) {
+            return;
+        }
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let remote = self.get_remote(true, window, cx);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let remote = match remote.await {
+                Ok(Some(remote)) => remote,
+                Ok(None) => {
+                    return Ok(());
+                }
+                Err(e) => {
+                    log::error!("Failed to get current remote: {}", e);
+                    this.update(cx, |this, cx| this.show_error_toast("push", e, cx))
+                        .ok();
+                    return Ok(());
+                }
+            };
+
+            let askpass_delegate = this.update_in(cx, |this, window, cx| {
+                this.askpass_delegate(format!("git push {}", remote.name), window, cx)
+            })?;
+
+            let push = repo.update(cx, |repo, _| {
+                repo.push_tag(tag.clone(), remote.name.clone(), askpass_delegate)
+            })?;
+
+            let remote_output = push.await?;
+
+            let action = RemoteAction::PushTag(tag, remote);
+            this.update(cx, |this, cx| match remote_output {
+                Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
+                Err(e) => {
+                    log::error!("Error while pushing tag {:?}", e);
+                    this.show_error_toast(action.name(), e, cx)
+                }
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn get_remote(
         &mut self,
         always_select: bool,
@@ -3416,6 +3652,54 @@ impl GitPanel {
             ))
     }
 
+    fn render_in_progress_operation(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let active_repository = self.active_repository.as_ref()?;
+        let operation = active_repository.read(cx).merge.operation?;
+        let has_unstaged_conflicts = self.has_unstaged_conflicts();
+        let message = if has_unstaged_conflicts {
+            format!("{} stopped due to conflicts.", operation.display_name())
+        } else {
+            format!("{} in progress.", operation.display_name())
+        };
+
+        Some(
+            h_flex()
+                .py_1p5()
+                .px_2()
+                .gap_1p5()
+                .justify_between()
+                .border_t_1()
+                .border_color(cx.theme().colors().border.opacity(0.8))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(Label::new(message).size(LabelSize::Small).truncate()),
+                )
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(panel_button("Abort").on_click(cx.listener(
+                            |this, _, window, cx| this.abort_operation(&AbortOperation, window, cx),
+                        )))
+                        .when(operation.can_skip(), |this| {
+                            this.child(panel_button("Skip").on_click(cx.listener(
+                                |this, _, window, cx| {
+                                    this.skip_operation(&SkipOperation, window, cx)
+                                },
+                            )))
+                        })
+                        .child(
+                            panel_filled_button("Continue")
+                                .disabled(has_unstaged_conflicts)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.continue_operation(&ContinueOperation, window, cx)
+                                })),
+                        ),
+                ),
+        )
+    }
+
     fn render_pending_amend(&self, cx: &mut Context<Self>) -> impl IntoElement {
         h_flex()
             .py_1p5()
@@ -3932,14 +4216,19 @@ impl GitPanel {
         } else {
             "Restore File"
         };
+        let is_conflicted = entry.status.is_conflicted();
         let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
             context_menu
                 .context(self.focus_handle.clone())
                 .action(stage_title, ToggleStaged.boxed_clone())
                 .action(restore_title, git::RestoreFile::default().boxed_clone())
+                .action("Stash File", git::StashFile.boxed_clone())
                 .separator()
                 .action("Open Diff", Confirm.boxed_clone())
                 .action("Open File", SecondaryConfirm.boxed_clone())
+                .when(is_conflicted, |menu| {
+                    menu.action("Open Merge Editor", git::OpenMergeEditor.boxed_clone())
+                })
         });
         self.selected_entry = Some(ix);
         self.set_context_menu(context_menu, position, window, cx);
@@ -4348,6 +4637,12 @@ impl Render for GitPanel {
                     .on_action(cx.listener(GitPanel::toggle_signoff_enabled))
                     .on_action(cx.listener(Self::stage_all))
                     .on_action(cx.listener(Self::unstage_all))
+                    .on_action(cx.listener(Self::stash_all))
+                    .on_action(cx.listener(Self::stash_selected))
+                    .on_action(cx.listener(Self::stash_pop))
+                    .on_action(cx.listener(Self::continue_operation))
+                    .on_action(cx.listener(Self::skip_operation))
+                    .on_action(cx.listener(Self::abort_operation))
                     .on_action(cx.listener(Self::stage_selected))
                     .on_action(cx.listener(Self::unstage_selected))
                     .on_action(cx.listener(Self::restore_tracked_files))
@@ -4362,6 +4657,7 @@ impl Render for GitPanel {
             .on_action(cx.listener(Self::close_panel))
             .on_action(cx.listener(Self::open_diff))
             .on_action(cx.listener(Self::open_file))
+            .on_action(cx.listener(Self::open_merge_editor))
             .on_action(cx.listener(Self::focus_changes_list))
             .on_action(cx.listener(Self::focus_editor))
             .on_action(cx.listener(Self::expand_commit_editor))
@@ -4391,6 +4687,7 @@ impl Render for GitPanel {
                             this.child(self.render_empty_state(cx).into_any_element())
                         }
                     })
+                    .children(self.render_in_progress_operation(cx))
                     .children(self.render_footer(window, cx))
                     .when(self.amend_pending, |this| {
                         this.child(self.render_pending_amend(cx))
@@ -0,0 +1,873 @@
+//! MergeEditor resolves a conflicted file with a three-way view of the base, ours and theirs
+//! versions from the index, above an editable result.
+
+use anyhow::Result;
+use collections::HashMap;
+use editor::{
+    Editor, EditorEvent, RowHighlightOptions,
+    display_map::{BlockPlacement, BlockProperties, BlockStyle},
+};
+use git::{
+    merge::{MergeChunk, MergeChunkKind, merge_chunks, merged_text},
+    repository::{ConflictStages, RepoPath},
+};
+use gpui::{
+    AnyElement, AnyView, App, Context, Entity, EventEmitter, FocusHandle, Focusable, Hsla,
+    IntoElement, Render, Subscription, Task, WeakEntity, Window, actions,
+};
+use language::{Buffer, Point, ToOffset as _};
+use multi_buffer::MultiBuffer;
+use project::{ConflictRegion, ConflictSet, Project, git_store::Repository};
+use std::{
+    any::{Any, TypeId},
+    sync::Arc,
+};
+use ui::{Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Item, ItemNavHistory, Workspace,
+    item::{ItemEvent, SaveOptions, TabContentParams},
+    notifications::DetachAndPromptErr,
+    searchable::SearchableItemHandle,
+};
+
+actions!(
+    merge_editor,
+    [
+        /// Resolves the conflict at the cursor with our version.
+        AcceptOurs,
+        /// Resolves the conflict at the cursor with their version.
+        AcceptTheirs,
+        /// Resolves the conflict at the cursor with our version followed by theirs.
+        AcceptBoth,
+        /// Resolves the conflict at the cursor with the common ancestor's version.
+        AcceptBase,
+        /// Rebuilds the result from the index, discarding manual resolutions.
+        ResetResult,
+        /// Saves the result and marks the file as resolved by staging it.
+        MarkResolved,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::OpenMergeEditor, window, cx| {
+        let Some(buffer) = workspace
+            .active_item_as::<Editor>(cx)
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+        else {
+            return;
+        };
+        MergeEditor::open(buffer, workspace, window, cx).detach_and_prompt_err(
+            "Failed to open merge editor",
+            window,
+            cx,
+            |e, _, _| Some(e.to_string()),
+        );
+    });
+}
+
+enum MergeChangeHighlight {}
+enum MergeConflictHighlight {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MergeSide {
+    Ours,
+    Theirs,
+    Both,
+    Base,
+}
+
+pub struct MergeEditor {
+    result_buffer: Entity<Buffer>,
+    result_editor: Entity<Editor>,
+    base_editor: Entity<Editor>,
+    ours_editor: Entity<Editor>,
+    theirs_editor: Entity<Editor>,
+    repository: Entity<Repository>,
+    repo_path: RepoPath,
+    base_text: String,
+    ours_text: String,
+    theirs_text: String,
+    chunks: Vec<MergeChunk>,
+    /// The conflicts left in the result, in order, reparsed whenever the result is edited.
+    conflicts: Arc<[ConflictRegion]>,
+    /// The start of the conflict in the result for each unresolved conflicting chunk, by index.
+    chunk_anchors: HashMap<usize, language::Anchor>,
+    reparse_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl MergeEditor {
+    /// Opens a merge editor for a conflicted buffer, or activates an existing one.
+    pub fn open(
+        buffer: Entity<Buffer>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Result<Entity<Self>>> {
+        if let Some(existing) = workspace
+            .items_of_type::<Self>(cx)
+            .find(|item| item.read(cx).result_buffer == buffer)
+        {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return Task::ready(Ok(existing));
+        }
+
+        let project = workspace.project().clone();
+        let buffer_id = buffer.read(cx).remote_id();
+        let Some((repository, repo_path)) = project
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_buffer_id(buffer_id, cx)
+        else {
+            return Task::ready(Err(anyhow::anyhow!("File is not in a git repository")));
+        };
+        let stages = repository.update(cx, |repository, _| {
+            repository.load_conflict_stages(repo_path.clone())
+        });
+
+        cx.spawn_in(window, async move |workspace, cx| {
+            let stages = stages.await??;
+            workspace.update_in(cx, |workspace, window, cx| {
+                let merge_editor = cx.new(|cx| {
+                    Self::new(buffer, repository, repo_path, stages, project, window, cx)
+                });
+                workspace.add_item_to_active_pane(
+                    Box::new(merge_editor.clone()),
+                    None,
+                    true,
+                    window,
+                    cx,
+                );
+                merge_editor
+            })
+        })
+    }
+
+    fn new(
+        result_buffer: Entity<Buffer>,
+        repository: Entity<Repository>,
+        repo_path: RepoPath,
+        stages: ConflictStages,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let base_text = stages.base.unwrap_or_default();
+        let ours_text = stages.ours.unwrap_or_default();
+        let theirs_text = stages.theirs.unwrap_or_default();
+        let chunks = merge_chunks(&base_text, &ours_text, &theirs_text);
+
+        let language = result_buffer.read(cx).language().cloned();
+        let side_editor = |text: &str, window: &mut Window, cx: &mut Context<Self>| {
+            let buffer = cx.new(|cx| {
+                let mut buffer = Buffer::local(text, cx);
+                buffer.set_language(language.clone(), cx);
+                buffer
+            });
+            cx.new(|cx| {
+                let mut editor = Editor::for_buffer(buffer, Some(project.clone()), window, cx);
+                editor.set_read_only(true);
+                editor.disable_diagnostics(cx);
+                editor
+            })
+        };
+        let base_editor = side_editor(&base_text, window, cx);
+        let ours_editor = side_editor(&ours_text, window, cx);
+        let theirs_editor = side_editor(&theirs_text, window, cx);
+        let result_multibuffer = cx.new(|cx| MultiBuffer::singleton(result_buffer.clone(), cx));
+        let result_editor = cx.new(|cx| {
+            Editor::for_multibuffer(result_multibuffer, Some(project.clone()), window, cx)
+        });
+
+        let _subscriptions = vec![
+            cx.subscribe(&result_buffer, |this, _, event, cx| {
+                if let language::BufferEvent::Edited = event {
+                    this.reparse_conflicts(cx);
+                }
+            }),
+            cx.subscribe(&result_editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(event.clone())
+            }),
+        ];
+
+        let conflicts = ConflictSet::parse(&result_buffer.read(cx).text_snapshot()).conflicts;
+        let mut this = Self {
+            result_buffer,
+            result_editor,
+            base_editor,
+            ours_editor,
+            theirs_editor,
+            repository,
+            repo_path,
+            base_text,
+            ours_text,
+            theirs_text,
+            chunks,
+            conflicts,
+            chunk_anchors: HashMap::default(),
+            reparse_task: Task::ready(()),
+            _subscriptions,
+        };
+
+        // Replace git's conflict markers with our own merge, which includes the base version of
+        // each conflict. Leave the buffer alone if it has unsaved changes or was already resolved.
+        if !this.result_buffer.read(cx).is_dirty() && !this.conflicts.is_empty() {
+            this.reset_result(&ResetResult, window, cx);
+        } else {
+            this.pair_chunks_with_conflicts();
+        }
+        this.decorate_side_editors(cx);
+        this
+    }
+
+    fn decorate_side_editors(&mut self, cx: &mut Context<Self>) {
+        let this = cx.weak_entity();
+        let colors = cx.theme().colors();
+        let change_background = colors.version_control_modified.opacity(0.12);
+        let ours_background = colors.version_control_conflict_marker_ours;
+        let theirs_background = colors.version_control_conflict_marker_theirs;
+        let base_background = colors.version_control_conflict_marker_theirs.opacity(0.5);
+
+        let panes = [
+            (self.ours_editor.clone(), MergeSide::Ours, ours_background),
+            (self.base_editor.clone(), MergeSide::Base, base_background),
+            (
+                self.theirs_editor.clone(),
+                MergeSide::Theirs,
+                theirs_background,
+            ),
+        ];
+        for (editor, side, conflict_background) in panes {
+            let chunks = self.chunks.clone();
+            editor.update(cx, |editor, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let mut blocks = Vec::new();
+                for (ix, chunk) in chunks.iter().enumerate() {
+                    let rows = match side {
+                        MergeSide::Ours | MergeSide::Both => &chunk.ours,
+                        MergeSide::Theirs => &chunk.theirs,
+                        MergeSide::Base => &chunk.base,
+                    };
+                    let changed_here = match (chunk.kind, side) {
+                        (MergeChunkKind::Unchanged, _) => false,
+                        (MergeChunkKind::Conflict, _) => true,
+                        (MergeChunkKind::Ours, side) => side == MergeSide::Ours,
+                        (MergeChunkKind::Theirs, side) => side == MergeSide::Theirs,
+                        (MergeChunkKind::Both, side) => side != MergeSide::Base,
+                    };
+                    if !changed_here {
+                        continue;
+                    }
+
+                    let start = snapshot.anchor_before(Point::new(rows.start, 0));
+                    if !rows.is_empty() {
+                        let end = snapshot.anchor_before(Point::new(rows.end - 1, 0));
+                        let background: Hsla = if chunk.is_conflict() {
+                            conflict_background
+                        } else {
+                            change_background
+                        };
+                        if chunk.is_conflict() {
+                            editor.highlight_rows::<MergeConflictHighlight>(
+                                start..end,
+                                background,
+                                RowHighlightOptions::default(),
+                                cx,
+                            );
+                        } else {
+                            editor.highlight_rows::<MergeChangeHighlight>(
+                                start..end,
+                                background,
+                                RowHighlightOptions::default(),
+                                cx,
+                            );
+                        }
+                    }
+
+                    if chunk.is_conflict() {
+                        let this = this.clone();
+                        blocks.push(BlockProperties {
+                            placement: BlockPlacement::Above(start),
+                            height: Some(1),
+                            style: BlockStyle::Fixed,
+                            render: Arc::new(move |cx| {
+                                render_accept_buttons(this.clone(), ix, side, cx)
+                            }),
+                            priority: 0,
+                        });
+                    }
+                }
+                editor.insert_blocks(blocks, None, cx);
+            });
+        }
+    }
+
+    fn reparse_conflicts(&mut self, cx: &mut Context<Self>) {
+        let snapshot = self.result_buffer.read(cx).text_snapshot();
+        self.reparse_task = cx.spawn(async move |this, cx| {
+            let conflicts = cx
+                .background_spawn(async move { ConflictSet::parse(&snapshot).conflicts })
+                .await;
+            this.update(cx, |this, cx| {
+                this.conflicts = conflicts;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    /// Pairs the conflicting chunks with the conflicts in the result by position. This only
+    /// holds while the result has exactly one conflict per conflicting chunk, as it does right
+    /// after the result is rebuilt; afterwards each chunk follows its conflict's anchor.
+    fn pair_chunks_with_conflicts(&mut self) {
+        self.chunk_anchors.clear();
+        let conflicting_chunks = self
+            .chunks
+            .iter()
+            .enumerate()
+            .filter(|(_, chunk)| chunk.is_conflict())
+            .map(|(ix, _)| ix)
+            .collect::<Vec<_>>();
+        if conflicting_chunks.len() != self.conflicts.len() {
+            return;
+        }
+        self.chunk_anchors = conflicting_chunks
+            .into_iter()
+            .zip(self.conflicts.iter())
+            .map(|(ix, conflict)| (ix, conflict.range.start))
+            .collect();
+    }
+
+    /// Finds the conflict in the result that corresponds to a conflicting chunk.
+    fn conflict_for_chunk(&self, ix: usize, cx: &App) -> Option<ConflictRegion> {
+        let anchor = self.chunk_anchors.get(&ix)?;
+        let snapshot = self.result_buffer.read(cx).text_snapshot();
+        self.conflicts
+            .iter()
+            .find(|conflict| {
+                conflict.range.start.cmp(anchor, &snapshot).is_le()
+                    && conflict.range.end.cmp(anchor, &snapshot).is_ge()
+            })
+            .cloned()
+    }
+
+    fn accept_chunk(&mut self, ix: usize, side: MergeSide, cx: &mut Context<Self>) {
+        let Some(conflict) = self.conflict_for_chunk(ix, cx) else {
+            return;
+        };
+        self.resolve(&conflict, side, cx);
+    }
+
+    fn accept_at_cursor(&mut self, side: MergeSide, cx: &mut Context<Self>) {
+        let snapshot = self.result_buffer.read(cx).snapshot();
+        let cursor = self.result_editor.update(cx, |editor, cx| {
+            editor.selections.newest::<usize>(cx).head()
+        });
+        let conflict = self
+            .conflicts
+            .iter()
+            .find(|conflict| conflict.range.end.to_offset(&snapshot) >= cursor)
+            .cloned();
+        if let Some(conflict) = conflict {
+            self.resolve(&conflict, side, cx);
+        }
+    }
+
+    fn resolve(&mut self, conflict: &ConflictRegion, side: MergeSide, cx: &mut Context<Self>) {
+        let ranges = match side {
+            MergeSide::Ours => vec![conflict.ours.clone()],
+            MergeSide::Theirs => vec![conflict.theirs.clone()],
+            MergeSide::Both => vec![conflict.ours.clone(), conflict.theirs.clone()],
+            MergeSide::Base => match &conflict.base {
+                Some(base) => vec![base.clone()],
+                None => return,
+            },
+        };
+        let snapshot = self.result_buffer.read(cx).text_snapshot();
+        self.chunk_anchors.retain(|_, anchor| {
+            conflict.range.start.cmp(anchor, &snapshot).is_gt()
+                || conflict.range.end.cmp(anchor, &snapshot).is_lt()
+        });
+        self.conflicts = self
+            .conflicts
+            .iter()
+            .filter(|other| *other != conflict)
+            .cloned()
+            .collect();
+        conflict.resolve(self.result_buffer.clone(), &ranges, cx);
+        cx.notify();
+    }
+
+    fn accept_ours(&mut self, _: &AcceptOurs, _: &mut Window, cx: &mut Context<Self>) {
+        self.accept_at_cursor(MergeSide::Ours, cx);
+    }
+
+    fn accept_theirs(&mut self, _: &AcceptTheirs, _: &mut Window, cx: &mut Context<Self>) {
+        self.accept_at_cursor(MergeSide::Theirs, cx);
+    }
+
+    fn accept_both(&mut self, _: &AcceptBoth, _: &mut Window, cx: &mut Context<Self>) {
+        self.accept_at_cursor(MergeSide::Both, cx);
+    }
+
+    fn accept_base(&mut self, _: &AcceptBase, _: &mut Window, cx: &mut Context<Self>) {
+        self.accept_at_cursor(MergeSide::Base, cx);
+    }
+
+    fn reset_result(&mut self, _: &ResetResult, _: &mut Window, cx: &mut Context<Self>) {
+        let text = merged_text(
+            &self.base_text,
+            &self.ours_text,
+            &self.theirs_text,
+            &self.chunks,
+        );
+        self.result_buffer.update(cx, |buffer, cx| {
+            buffer.edit([(0..buffer.len(), text)], None, cx);
+        });
+        self.conflicts = ConflictSet::parse(&self.result_buffer.read(cx).text_snapshot()).conflicts;
+        self.pair_chunks_with_conflicts();
+        cx.notify();
+    }
+
+    fn mark_resolved(&mut self, _: &MarkResolved, window: &mut Window, cx: &mut Context<Self>) {
+        let remaining = self.conflicts.len();
+        let confirmation = (remaining > 0).then(|| {
+            window.prompt(
+                PromptLevel::Warning,
+                &format!("The result still has {remaining} unresolved conflicts."),
+                Some("Mark the file as resolved anyway?"),
+                &["Mark as Resolved", "Cancel"],
+                cx,
+            )
+        });
+        let repository = self.repository.clone();
+        let repo_path = self.repo_path.clone();
+        cx.spawn(async move |_, cx| {
+            if let Some(confirmation) = confirmation {
+                if confirmation.await? != 0 {
+                    return Ok(());
+                }
+            }
+            // Staging saves the buffer first.
+            repository
+                .update(cx, |repository, cx| {
+                    repository.stage_entries(vec![repo_path], cx)
+                })?
+                .await
+        })
+        .detach_and_prompt_err(
+            "Failed to mark file as resolved",
+            window,
+            cx,
+            |e, _, _| Some(e.to_string()),
+        );
+    }
+
+    fn file_name(&self) -> SharedString {
+        self.repo_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.repo_path.to_string_lossy().to_string())
+            .into()
+    }
+
+    fn render_pane(
+        label: &'static str,
+        editor: &Entity<Editor>,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .size_full()
+            .border_r_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(label).size(LabelSize::Small).color(Color::Muted)),
+            )
+            .child(div().flex_1().min_h_0().child(editor.clone()))
+    }
+}
+
+fn render_accept_buttons(
+    this: WeakEntity<MergeEditor>,
+    ix: usize,
+    side: MergeSide,
+    cx: &mut editor::display_map::BlockContext,
+) -> AnyElement {
+    let button = |id: &'static str, label: &'static str, side: MergeSide| {
+        let this = this.clone();
+        Button::new(id, label)
+            .label_size(LabelSize::Small)
+            .on_click(move |_, _, cx| {
+                this.update(cx, |this, cx| this.accept_chunk(ix, side, cx))
+                    .log_err();
+            })
+    };
+    let buttons = match side {
+        MergeSide::Ours | MergeSide::Both => h_flex()
+            .gap_1()
+            .child(button("accept-ours", "Accept Ours", MergeSide::Ours))
+            .child(button("accept-both", "Accept Both", MergeSide::Both)),
+        MergeSide::Theirs => h_flex()
+            .gap_1()
+            .child(button("accept-theirs", "Accept Theirs", MergeSide::Theirs))
+            .child(button("accept-both", "Accept Both", MergeSide::Both)),
+        MergeSide::Base => {
+            h_flex()
+                .gap_1()
+                .child(button("accept-base", "Accept Base", MergeSide::Base))
+        }
+    };
+    h_flex()
+        .id(cx.block_id)
+        .h(cx.line_height)
+        .ml(cx.margins.gutter.width)
+        .items_end()
+        .bg(cx.theme().colors().editor_background)
+        .child(buttons)
+        .into_any()
+}
+
+impl EventEmitter<EditorEvent> for MergeEditor {}
+
+impl Focusable for MergeEditor {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.result_editor.focus_handle(cx)
+    }
+}
+
+impl Item for MergeEditor {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _: &App) -> SharedString {
+        format!("Merge {}", self.file_name()).into()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(format!("Merge {}", self.repo_path.display()).into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Merge Editor Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.result_editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn is_singleton(&self, _: &App) -> bool {
+        false
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.result_editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.result_editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.result_editor.for_each_project_item(cx, f)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.result_editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.result_buffer.read(cx).is_dirty()
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.result_editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.result_editor
+            .update(cx, |editor, cx| editor.save(options, project, window, cx))
+    }
+}
+
+impl Render for MergeEditor {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let remaining = self.conflicts.len();
+        let focus_handle = self.result_editor.focus_handle(cx);
+        let status = match remaining {
+            0 => "All conflicts resolved".to_string(),
+            1 => "1 conflict remaining".to_string(),
+            n => format!("{n} conflicts remaining"),
+        };
+
+        v_flex()
+            .key_context("MergeEditor")
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::accept_ours))
+            .on_action(cx.listener(Self::accept_theirs))
+            .on_action(cx.listener(Self::accept_both))
+            .on_action(cx.listener(Self::accept_base))
+            .on_action(cx.listener(Self::reset_result))
+            .on_action(cx.listener(Self::mark_resolved))
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        Label::new(status)
+                            .size(LabelSize::Small)
+                            .color(if remaining == 0 {
+                                Color::Success
+                            } else {
+                                Color::Warning
+                            }),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Button::new("reset-result", "Reset Result")
+                                    .label_size(LabelSize::Small)
+                                    .tooltip(Tooltip::for_action_title_in(
+                                        "Rebuild the result from the index",
+                                        &ResetResult,
+                                        &focus_handle,
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.reset_result(&ResetResult, window, cx)
+                                    })),
+                            )
+                            .child(
+                                Button::new("mark-resolved", "Mark as Resolved")
+                                    .label_size(LabelSize::Small)
+                                    .style(ButtonStyle::Filled)
+                                    .tooltip(Tooltip::for_action_title_in(
+                                        "Save and stage the result",
+                                        &MarkResolved,
+                                        &focus_handle,
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.mark_resolved(&MarkResolved, window, cx)
+                                    })),
+                            ),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Self::render_pane("Ours", &self.ours_editor, cx))
+                    .child(Self::render_pane("Base", &self.base_editor, cx))
+                    .child(Self::render_pane("Theirs", &self.theirs_editor, cx)),
+            )
+            .child(h_flex().flex_1().min_h_0().child(Self::render_pane(
+                "Result",
+                &self.result_editor,
+                cx,
+            )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init(cx);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_accept_chunks_with_identical_conflicts(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "file.txt": "a\n<<<<<<< HEAD\ny\n=======\nz\n>>>>>>> other\nb\n<<<<<<< HEAD\ny\n=======\nz\n>>>>>>> other\nc\nd\n",
+            }),
+        )
+        .await;
+        fs.with_git_state(path!("/project/.git").as_ref(), true, |state| {
+            state.conflict_stages.insert(
+                "file.txt".into(),
+                ConflictStages {
+                    base: Some("a\nx\nb\nx\nc\nd\n".into()),
+                    ours: Some("a\ny\nb\ny\nc\nd\n".into()),
+                    theirs: Some("a\nz\nb\nz\nc\nD\n".into()),
+                },
+            );
+        })
+        .unwrap();
+
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        cx.run_until_parked();
+
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/project/file.txt"), cx)
+            })
+            .await
+            .unwrap();
+        let merge_editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                MergeEditor::open(buffer.clone(), workspace, window, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        // Their change to the last line doesn't overlap ours, so it is merged automatically.
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "a\n<<<<<<< ours\ny\n||||||| base\nx\n=======\nz\n>>>>>>> theirs\nb\n<<<<<<< ours\ny\n||||||| base\nx\n=======\nz\n>>>>>>> theirs\nc\nD\n"
+        );
+        let conflicting_chunks = merge_editor.read_with(cx, |merge_editor, _| {
+            assert_eq!(merge_editor.conflicts.len(), 2);
+            merge_editor
+                .chunks
+                .iter()
+                .enumerate()
+                .filter(|(_, chunk)| chunk.is_conflict())
+                .map(|(ix, _)| ix)
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(conflicting_chunks.len(), 2);
+
+        // Both conflicts have the same text, so each chunk must resolve its own conflict.
+        merge_editor.update(cx, |merge_editor, cx| {
+            merge_editor.accept_chunk(conflicting_chunks[1], MergeSide::Theirs, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "a\n<<<<<<< ours\ny\n||||||| base\nx\n=======\nz\n>>>>>>> theirs\nb\nz\nc\nD\n"
+        );
+
+        // Accepting a chunk that is already resolved does nothing.
+        merge_editor.update(cx, |merge_editor, cx| {
+            merge_editor.accept_chunk(conflicting_chunks[1], MergeSide::Ours, cx);
+            merge_editor.accept_chunk(conflicting_chunks[0], MergeSide::Ours, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "a\ny\nb\nz\nc\nD\n"
+        );
+        merge_editor.read_with(cx, |merge_editor, _| {
+            assert!(merge_editor.conflicts.is_empty());
+        });
+
+        // Resetting brings back both conflicts, each paired with its chunk again.
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.reset_result(&ResetResult, window, cx);
+            merge_editor.accept_chunk(conflicting_chunks[0], MergeSide::Both, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "a\ny\nz\nb\n<<<<<<< ours\ny\n||||||| base\nx\n=======\nz\n>>>>>>> theirs\nc\nD\n"
+        );
+        merge_editor.read_with(cx, |merge_editor, _| {
+            assert_eq!(merge_editor.conflicts.len(), 1);
+        });
+    }
+}
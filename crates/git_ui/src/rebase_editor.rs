@@ -0,0 +1,482 @@
+//! RebaseEditor lets the user reorder, reword, squash and drop commits before starting an
+//! interactive rebase.
+
+use anyhow::Context as _;
+use collections::HashMap;
+use editor::Editor;
+use git::{
+    SHORT_SHA_LENGTH,
+    repository::{LogOptions, RebaseAction, RebaseTodoEntry},
+};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, KeyContext,
+    ScrollHandle, SharedString, Task, Window, actions,
+};
+use project::git_store::Repository;
+use ui::{Headline, HeadlineSize, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+actions!(
+    rebase_editor,
+    [
+        /// Moves the selected commit one step earlier in the rebase.
+        MoveUp,
+        /// Moves the selected commit one step later in the rebase.
+        MoveDown,
+        /// Picks the selected commit as is.
+        Pick,
+        /// Picks the selected commit with a new message.
+        Reword,
+        /// Melds the selected commit into the previous one, keeping both messages.
+        Squash,
+        /// Melds the selected commit into the previous one, discarding its message.
+        Fixup,
+        /// Removes the selected commit from the branch.
+        DropCommit,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::InteractiveRebase, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        let upstream = repository
+            .read(cx)
+            .branch
+            .as_ref()
+            .and_then(|branch| branch.upstream.as_ref())
+            .map(|upstream| upstream.ref_name.to_string());
+        let Some(upstream) = upstream else {
+            workspace.show_error(&"The current branch has no upstream to rebase onto", cx);
+            return;
+        };
+        RebaseEditor::toggle(workspace, repository, upstream, window, cx);
+    });
+}
+
+pub struct RebaseEditor {
+    repository: Entity<Repository>,
+    upstream: SharedString,
+    entries: Vec<RebaseTodoEntry>,
+    /// The message editors of reworded commits, by sha.
+    message_editors: HashMap<SharedString, Entity<Editor>>,
+    selected_index: usize,
+    scroll_handle: ScrollHandle,
+    focus_handle: FocusHandle,
+    _load_entries: Task<()>,
+}
+
+impl RebaseEditor {
+    /// Opens the todo list for rebasing the current branch onto `upstream`.
+    pub fn toggle(
+        workspace: &mut Workspace,
+        repository: Entity<Repository>,
+        upstream: String,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        workspace.toggle_modal(window, cx, |_, cx| Self::new(repository, upstream, cx));
+    }
+
+    fn new(repository: Entity<Repository>, upstream: String, cx: &mut Context<Self>) -> Self {
+        let request = repository.update(cx, |repository, _| {
+            repository.log(LogOptions {
+                revision_range: Some(format!("{upstream}..HEAD")),
+                ..Default::default()
+            })
+        });
+        let load_entries = cx.spawn(async move |this, cx| {
+            let Some(log) = request.await.ok().and_then(|log| log.log_err()) else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                // The log lists the most recent commit first, while the todo list is applied
+                // from the oldest commit.
+                this.entries = log
+                    .into_iter()
+                    .rev()
+                    .filter(|entry| entry.parent_shas.len() <= 1)
+                    .map(|entry| RebaseTodoEntry {
+                        action: RebaseAction::Pick,
+                        sha: entry.sha,
+                        subject: entry.subject,
+                    })
+                    .collect();
+                cx.notify();
+            })
+            .ok();
+        });
+
+        Self {
+            repository,
+            upstream: upstream.into(),
+            entries: Vec::new(),
+            message_editors: HashMap::default(),
+            selected_index: 0,
+            scroll_handle: ScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            _load_entries: load_entries,
+        }
+    }
+
+    fn dispatch_context(&self, window: &Window) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("RebaseEditor");
+        if self.focus_handle.is_focused(window) {
+            dispatch_context.add("menu");
+            dispatch_context.add("TodoList");
+        }
+        dispatch_context
+    }
+
+    fn select(&mut self, ix: usize, cx: &mut Context<Self>) {
+        self.selected_index = ix;
+        self.scroll_handle.scroll_to_item(ix);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_index + 1 < self.entries.len() {
+            self.select(self.selected_index + 1, cx);
+        }
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(ix) = self.selected_index.checked_sub(1) {
+            self.select(ix, cx);
+        }
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.selected_index;
+        if ix > 0 && ix < self.entries.len() {
+            self.entries.swap(ix, ix - 1);
+            self.select(ix - 1, cx);
+        }
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.selected_index;
+        if ix + 1 < self.entries.len() {
+            self.entries.swap(ix, ix + 1);
+            self.select(ix + 1, cx);
+        }
+    }
+
+    fn pick(&mut self, _: &Pick, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(self.selected_index, RebaseAction::Pick, window, cx);
+    }
+
+    fn reword(&mut self, _: &Reword, window: &mut Window, cx: &mut Context<Self>) {
+        // The message is read back from its editor when the rebase starts.
+        self.set_action(
+            self.selected_index,
+            RebaseAction::Reword(String::new()),
+            window,
+            cx,
+        );
+    }
+
+    fn squash(&mut self, _: &Squash, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(self.selected_index, RebaseAction::Squash, window, cx);
+    }
+
+    fn fixup(&mut self, _: &Fixup, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(self.selected_index, RebaseAction::Fixup, window, cx);
+    }
+
+    fn drop_commit(&mut self, _: &DropCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(self.selected_index, RebaseAction::Drop, window, cx);
+    }
+
+    fn cycle_action(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        let action = match entry.action {
+            RebaseAction::Pick => RebaseAction::Reword(String::new()),
+            RebaseAction::Reword(_) => RebaseAction::Squash,
+            RebaseAction::Squash => RebaseAction::Fixup,
+            RebaseAction::Fixup => RebaseAction::Drop,
+            RebaseAction::Drop => RebaseAction::Pick,
+        };
+        self.selected_index = ix;
+        self.set_action(ix, action, window, cx);
+    }
+
+    fn set_action(
+        &mut self,
+        ix: usize,
+        action: RebaseAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self.entries.get_mut(ix) else {
+            return;
+        };
+        let rewording = matches!(action, RebaseAction::Reword(_));
+        entry.action = action;
+        if !rewording {
+            self.message_editors.remove(&entry.sha);
+            cx.notify();
+            return;
+        }
+
+        let sha = entry.sha.clone();
+        let subject = entry.subject.clone();
+        let editor = self
+            .message_editors
+            .entry(sha.clone())
+            .or_insert_with(|| {
+                let editor = cx.new(|cx| {
+                    let mut editor = Editor::auto_height(1, 6, window, cx);
+                    editor.set_text(subject.as_ref(), window, cx);
+                    editor
+                });
+
+                // Replace the subject with the full message once it's loaded, unless the user
+                // already started editing it.
+                let details = self
+                    .repository
+                    .update(cx, |repository, _| repository.show(sha.to_string()));
+                let weak_editor = editor.downgrade();
+                cx.spawn_in(window, async move |_, cx| {
+                    let details = details.await??;
+                    weak_editor.update_in(cx, |editor, window, cx| {
+                        if editor.text(cx) == subject.as_ref() {
+                            editor.set_text(details.message.trim_end(), window, cx);
+                        }
+                    })?;
+                    anyhow::Ok(())
+                })
+                .detach_and_log_err(cx);
+
+                editor
+            })
+            .clone();
+        window.focus(&editor.focus_handle(cx));
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.focus_handle.is_focused(window) {
+            cx.emit(DismissEvent);
+        } else {
+            window.focus(&self.focus_handle);
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        self.start(window, cx);
+    }
+
+    fn start(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let todo = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut entry = entry.clone();
+                if let RebaseAction::Reword(message) = &mut entry.action {
+                    *message = self
+                        .message_editors
+                        .get(&entry.sha)
+                        .map(|editor| editor.read(cx).text(cx))
+                        .unwrap_or_else(|| entry.subject.to_string());
+                }
+                entry
+            })
+            .collect::<Vec<_>>();
+        if todo.is_empty() {
+            cx.emit(DismissEvent);
+            return;
+        }
+
+        let upstream = self.upstream.to_string();
+        let request = self
+            .repository
+            .update(cx, |repository, _| repository.rebase(upstream, Some(todo)));
+        cx.spawn(async move |_, _| {
+            request.await.context("rebase was canceled")??;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to rebase", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let entry = &self.entries[ix];
+        let selected = ix == self.selected_index;
+        let dropped = entry.action == RebaseAction::Drop;
+        let message_editor = self.message_editors.get(&entry.sha).cloned();
+
+        let subject = Label::new(entry.subject.clone())
+            .single_line()
+            .truncate()
+            .when(dropped, |label| label.strikethrough().color(Color::Muted));
+
+        v_flex()
+            .id(("todo-entry", ix))
+            .w_full()
+            .px_2()
+            .py_0p5()
+            .when(selected, |this| {
+                this.bg(cx.theme().colors().element_selected)
+            })
+            .on_click(cx.listener(move |this, _, window, cx| {
+                window.focus(&this.focus_handle);
+                this.select(ix, cx);
+            }))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Button::new(("todo-action", ix), action_label(&entry.action))
+                            .label_size(LabelSize::Small)
+                            .color(action_color(&entry.action))
+                            .tooltip(Tooltip::text("Change Action"))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                cx.stop_propagation();
+                                this.cycle_action(ix, window, cx);
+                            })),
+                    )
+                    .child(
+                        Label::new(entry.sha[..SHORT_SHA_LENGTH.min(entry.sha.len())].to_string())
+                            .color(Color::Muted)
+                            .buffer_font(cx),
+                    )
+                    .child(div().flex_1().min_w_0().child(subject)),
+            )
+            .children(message_editor.map(|editor| {
+                div()
+                    .ml_8()
+                    .my_1()
+                    .px_1()
+                    .rounded_sm()
+                    .bg(cx.theme().colors().editor_background)
+                    .child(editor)
+            }))
+            .into_any_element()
+    }
+}
+
+fn action_label(action: &RebaseAction) -> &'static str {
+    match action {
+        RebaseAction::Pick => "pick",
+        RebaseAction::Reword(_) => "reword",
+        RebaseAction::Squash => "squash",
+        RebaseAction::Fixup => "fixup",
+        RebaseAction::Drop => "drop",
+    }
+}
+
+fn action_color(action: &RebaseAction) -> Color {
+    match action {
+        RebaseAction::Pick => Color::Default,
+        RebaseAction::Reword(_) => Color::Accent,
+        RebaseAction::Squash | RebaseAction::Fixup => Color::Warning,
+        RebaseAction::Drop => Color::Error,
+    }
+}
+
+impl ModalView for RebaseEditor {}
+impl EventEmitter<DismissEvent> for RebaseEditor {}
+
+impl Focusable for RebaseEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for RebaseEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entry_count = self.entries.len();
+        v_flex()
+            .key_context(self.dispatch_context(window))
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::pick))
+            .on_action(cx.listener(Self::reword))
+            .on_action(cx.listener(Self::squash))
+            .on_action(cx.listener(Self::fixup))
+            .on_action(cx.listener(Self::drop_commit))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_3(cx)
+            .w(rems(40.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .gap_1p5()
+                    .child(Icon::new(IconName::GitBranch).size(IconSize::XSmall))
+                    .child(
+                        Headline::new(format!("Rebase onto {}", self.upstream))
+                            .size(HeadlineSize::XSmall),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .h(rems(24.))
+                    .border_y_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .map(|this| {
+                        if entry_count == 0 {
+                            this.items_center()
+                                .justify_center()
+                                .child(Label::new("No commits to rebase").color(Color::Muted))
+                        } else {
+                            this.child(
+                                v_flex()
+                                    .id("rebase-todo")
+                                    .size_full()
+                                    .overflow_y_scroll()
+                                    .track_scroll(&self.scroll_handle)
+                                    .children((0..entry_count).map(|ix| self.render_entry(ix, cx))),
+                            )
+                        }
+                    }),
+            )
+            .child(
+                h_flex()
+                    .p_1p5()
+                    .gap_1()
+                    .justify_between()
+                    .child(
+                        Label::new("Oldest commit first")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_0p5()
+                            .child(
+                                Button::new("cancel-rebase", "Cancel")
+                                    .on_click(cx.listener(|_, _, _, cx| cx.emit(DismissEvent))),
+                            )
+                            .child(
+                                Button::new("start-rebase", "Start Rebase")
+                                    .style(ButtonStyle::Filled)
+                                    .disabled(entry_count == 0)
+                                    .on_click(
+                                        cx.listener(|this, _, window, cx| this.start(window, cx)),
+                                    ),
+                            ),
+                    ),
+            )
+    }
+}
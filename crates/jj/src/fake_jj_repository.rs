@@ -0,0 +1,480 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, Result, anyhow};
+use gpui::SharedString;
+use parking_lot::Mutex;
+
+use crate::{Bookmark, Change, FileStatus, JujutsuRepository, StatusEntry};
+
+/// An in-memory [`JujutsuRepository`] for tests.
+///
+/// It understands a small subset of revsets: `@`, `@-`, `all()`, `::@`, change ids
+/// (or unique prefixes of them) and bookmark names. Rewriting a change doesn't
+/// rebase the contents of its descendants.
+pub struct FakeJujutsuRepository {
+    state: Mutex<FakeJujutsuRepositoryState>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeChange {
+    pub change_id: String,
+    pub commit_id: String,
+    pub parents: Vec<String>,
+    pub description: String,
+    pub files: BTreeMap<PathBuf, String>,
+}
+
+#[derive(Debug)]
+pub struct FakeJujutsuRepositoryState {
+    /// All visible changes, oldest first. The first entry is always the root change.
+    pub changes: Vec<FakeChange>,
+    pub working_copy: String,
+    pub bookmarks: BTreeMap<String, String>,
+    next_id: usize,
+}
+
+const ROOT_CHANGE_ID: &str = "zzzzzzzzzzzz";
+
+impl Default for FakeJujutsuRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeJujutsuRepository {
+    /// Creates a repository containing the root change and an empty working-copy change on top of it.
+    pub fn new() -> Self {
+        let mut state = FakeJujutsuRepositoryState {
+            changes: vec![FakeChange {
+                change_id: ROOT_CHANGE_ID.to_string(),
+                commit_id: "0".repeat(40),
+                parents: Vec::new(),
+                description: String::new(),
+                files: BTreeMap::new(),
+            }],
+            working_copy: String::new(),
+            bookmarks: BTreeMap::new(),
+            next_id: 1,
+        };
+        state.working_copy = state.create_change(vec![ROOT_CHANGE_ID.to_string()], String::new());
+        Self {
+            state: Mutex::new(state),
+        }
+    }
+
+    pub fn with_state<T>(&self, f: impl FnOnce(&mut FakeJujutsuRepositoryState) -> T) -> T {
+        f(&mut self.state.lock())
+    }
+
+    /// Simulates editing a file in the working copy. Passing `None` deletes it.
+    pub fn set_working_copy_file(&self, path: impl Into<PathBuf>, content: Option<&str>) {
+        self.with_state(|state| {
+            let working_copy = state.working_copy.clone();
+            state.rewrite(&working_copy, |change| match content {
+                Some(content) => {
+                    change.files.insert(path.into(), content.to_string());
+                }
+                None => {
+                    change.files.remove(&path.into());
+                }
+            });
+        })
+    }
+
+    pub fn set_bookmark(&self, name: &str, revision: &str) -> Result<()> {
+        self.with_state(|state| {
+            let change_id = state.resolve(revision)?;
+            state.bookmarks.insert(name.to_string(), change_id);
+            Ok(())
+        })
+    }
+}
+
+impl FakeJujutsuRepositoryState {
+    fn change(&self, change_id: &str) -> Option<&FakeChange> {
+        self.changes
+            .iter()
+            .find(|change| change.change_id == change_id)
+    }
+
+    fn change_mut(&mut self, change_id: &str) -> Option<&mut FakeChange> {
+        self.changes
+            .iter_mut()
+            .find(|change| change.change_id == change_id)
+    }
+
+    fn first_parent_files(&self, change: &FakeChange) -> BTreeMap<PathBuf, String> {
+        change
+            .parents
+            .first()
+            .and_then(|parent| self.change(parent))
+            .map(|parent| parent.files.clone())
+            .unwrap_or_default()
+    }
+
+    fn next_commit_id(&mut self) -> String {
+        self.next_id += 1;
+        format!("{:040x}", self.next_id)
+    }
+
+    /// Creates a change on top of `parents`, with the contents of the first parent.
+    fn create_change(&mut self, parents: Vec<String>, description: String) -> String {
+        const DIGITS: &[u8] = b"kmnopqrstuvwxy";
+        let mut n = self.next_id;
+        let change_id = (0..12)
+            .map(|_| {
+                let digit = DIGITS[n % DIGITS.len()] as char;
+                n /= DIGITS.len();
+                digit
+            })
+            .collect::<String>();
+        let commit_id = self.next_commit_id();
+        let files = parents
+            .first()
+            .and_then(|parent| self.change(parent))
+            .map(|parent| parent.files.clone())
+            .unwrap_or_default();
+        self.changes.push(FakeChange {
+            change_id: change_id.clone(),
+            commit_id,
+            parents,
+            description,
+            files,
+        });
+        change_id
+    }
+
+    fn rewrite(&mut self, change_id: &str, f: impl FnOnce(&mut FakeChange)) {
+        let commit_id = self.next_commit_id();
+        if let Some(change) = self.change_mut(change_id) {
+            f(change);
+            change.commit_id = commit_id;
+        }
+    }
+
+    fn remove_change(&mut self, change_id: &str) -> Result<()> {
+        let index = self
+            .changes
+            .iter()
+            .position(|change| change.change_id == change_id)
+            .with_context(|| format!("no such change {change_id}"))?;
+        let removed = self.changes.remove(index);
+        for change in &mut self.changes {
+            if let Some(position) = change.parents.iter().position(|p| p == change_id) {
+                change
+                    .parents
+                    .splice(position..=position, removed.parents.iter().cloned());
+            }
+        }
+        for target in self.bookmarks.values_mut() {
+            if target == change_id
+                && let Some(parent) = removed.parents.first()
+            {
+                *target = parent.clone();
+            }
+        }
+        if self.working_copy == change_id {
+            self.working_copy = self.create_change(removed.parents, String::new());
+        }
+        Ok(())
+    }
+
+    /// Resolves a revset that must evaluate to exactly one change.
+    fn resolve(&self, revision: &str) -> Result<String> {
+        let mut change_ids = self.evaluate(revision)?;
+        anyhow::ensure!(
+            change_ids.len() == 1,
+            "revset {revision:?} resolved to {} changes",
+            change_ids.len()
+        );
+        Ok(change_ids.remove(0))
+    }
+
+    /// Evaluates a revset, returning matching change ids newest first.
+    fn evaluate(&self, revset: &str) -> Result<Vec<String>> {
+        let revset = revset.trim();
+        match revset {
+            "@" => Ok(vec![self.working_copy.clone()]),
+            "@-" => Ok(self
+                .change(&self.working_copy)
+                .map(|change| change.parents.clone())
+                .unwrap_or_default()),
+            "all()" => Ok(self
+                .changes
+                .iter()
+                .rev()
+                .map(|change| change.change_id.clone())
+                .collect()),
+            "root()" => Ok(vec![ROOT_CHANGE_ID.to_string()]),
+            "::@" => {
+                let mut ancestors = Vec::new();
+                let mut pending = vec![self.working_copy.clone()];
+                while let Some(change_id) = pending.pop() {
+                    if ancestors.contains(&change_id) {
+                        continue;
+                    }
+                    if let Some(change) = self.change(&change_id) {
+                        pending.extend(change.parents.iter().cloned());
+                    }
+                    ancestors.push(change_id);
+                }
+                Ok(self
+                    .changes
+                    .iter()
+                    .rev()
+                    .filter(|change| ancestors.contains(&change.change_id))
+                    .map(|change| change.change_id.clone())
+                    .collect())
+            }
+            _ => {
+                if let Some(change_id) = self.bookmarks.get(revset) {
+                    return Ok(vec![change_id.clone()]);
+                }
+                let mut matches = self
+                    .changes
+                    .iter()
+                    .filter(|change| change.change_id.starts_with(revset));
+                match (matches.next(), matches.next()) {
+                    (Some(change), None) => Ok(vec![change.change_id.clone()]),
+                    (Some(_), Some(_)) => Err(anyhow!("change id prefix {revset:?} is ambiguous")),
+                    (None, _) => Err(anyhow!("revision {revset:?} doesn't exist")),
+                }
+            }
+        }
+    }
+
+    fn to_change(&self, change: &FakeChange) -> Change {
+        Change {
+            change_id: change.change_id.clone().into(),
+            commit_id: change.commit_id.clone().into(),
+            parent_change_ids: change
+                .parents
+                .iter()
+                .map(|parent| SharedString::from(parent.clone()))
+                .collect(),
+            description: change.description.clone().into(),
+            author_name: "Test Author".into(),
+            author_email: "test@example.com".into(),
+            timestamp: 0,
+            bookmarks: self
+                .bookmarks
+                .iter()
+                .filter(|(_, target)| **target == change.change_id)
+                .map(|(name, _)| SharedString::from(name.clone()))
+                .collect(),
+            is_empty: change.files == self.first_parent_files(change),
+            is_working_copy: change.change_id == self.working_copy,
+            has_conflict: false,
+        }
+    }
+}
+
+impl JujutsuRepository for FakeJujutsuRepository {
+    fn list_bookmarks(&self) -> Vec<Bookmark> {
+        self.with_state(|state| {
+            state
+                .bookmarks
+                .keys()
+                .map(|name| Bookmark {
+                    ref_name: name.clone().into(),
+                })
+                .collect()
+        })
+    }
+
+    fn log(&self, revset: &str, limit: Option<usize>) -> Result<Vec<Change>> {
+        self.with_state(|state| {
+            let change_ids = state.evaluate(revset)?;
+            Ok(change_ids
+                .iter()
+                .filter_map(|change_id| state.change(change_id))
+                .take(limit.unwrap_or(usize::MAX))
+                .map(|change| state.to_change(change))
+                .collect())
+        })
+    }
+
+    fn status(&self) -> Result<Vec<StatusEntry>> {
+        self.with_state(|state| {
+            let working_copy = state
+                .change(&state.working_copy)
+                .context("missing working-copy change")?;
+            let parent_files = state.first_parent_files(working_copy);
+
+            let mut entries = Vec::new();
+            for (path, content) in &working_copy.files {
+                let status = match parent_files.get(path) {
+                    None => FileStatus::Added,
+                    Some(parent_content) if parent_content != content => FileStatus::Modified,
+                    Some(_) => continue,
+                };
+                entries.push(StatusEntry {
+                    path: path.clone(),
+                    status,
+                });
+            }
+            for path in parent_files.keys() {
+                if !working_copy.files.contains_key(path) {
+                    entries.push(StatusEntry {
+                        path: path.clone(),
+                        status: FileStatus::Deleted,
+                    });
+                }
+            }
+            entries.sort_by(|a, b| a.path.cmp(&b.path));
+            Ok(entries)
+        })
+    }
+
+    fn load_parent_text(&self, path: &Path) -> Result<Option<String>> {
+        self.with_state(|state| {
+            let working_copy = state
+                .change(&state.working_copy)
+                .context("missing working-copy change")?;
+            Ok(state.first_parent_files(working_copy).remove(path))
+        })
+    }
+
+    fn describe(&self, revision: &str, message: &str) -> Result<()> {
+        self.with_state(|state| {
+            let change_id = state.resolve(revision)?;
+            anyhow::ensure!(
+                change_id != ROOT_CHANGE_ID,
+                "cannot rewrite the root change"
+            );
+            state.rewrite(&change_id, |change| {
+                change.description = message.to_string()
+            });
+            Ok(())
+        })
+    }
+
+    fn new_change(&self, parents: &[&str], message: Option<&str>) -> Result<()> {
+        self.with_state(|state| {
+            let parents = if parents.is_empty() {
+                vec![state.working_copy.clone()]
+            } else {
+                parents
+                    .iter()
+                    .map(|parent| state.resolve(parent))
+                    .collect::<Result<Vec<_>>>()?
+            };
+            let description = message.unwrap_or_default().to_string();
+            state.working_copy = state.create_change(parents, description);
+            Ok(())
+        })
+    }
+
+    fn squash(&self, from: &str, into: &str) -> Result<()> {
+        self.with_state(|state| {
+            let from = state.resolve(from)?;
+            let into = state.resolve(into)?;
+            anyhow::ensure!(from != into, "cannot squash a change into itself");
+            anyhow::ensure!(into != ROOT_CHANGE_ID, "cannot rewrite the root change");
+
+            let source = state.change(&from).context("missing source change")?;
+            let source_files = source.files.clone();
+            let source_parent_files = state.first_parent_files(source);
+            state.rewrite(&into, |destination| {
+                for (path, content) in &source_files {
+                    if source_parent_files.get(path) != Some(content) {
+                        destination.files.insert(path.clone(), content.clone());
+                    }
+                }
+                for path in source_parent_files.keys() {
+                    if !source_files.contains_key(path) {
+                        destination.files.remove(path);
+                    }
+                }
+            });
+            state.remove_change(&from)
+        })
+    }
+
+    fn abandon(&self, revision: &str) -> Result<()> {
+        self.with_state(|state| {
+            let change_id = state.resolve(revision)?;
+            anyhow::ensure!(
+                change_id != ROOT_CHANGE_ID,
+                "cannot abandon the root change"
+            );
+            state.remove_change(&change_id)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_fake_working_copy_status() {
+        let repo = FakeJujutsuRepository::new();
+        repo.set_working_copy_file("a.txt", Some("one\n"));
+        repo.set_working_copy_file("b.txt", Some("two\n"));
+        repo.new_change(&[], Some("second")).unwrap();
+
+        assert_eq!(repo.status().unwrap(), Vec::new());
+
+        repo.set_working_copy_file("a.txt", Some("one!\n"));
+        repo.set_working_copy_file("b.txt", None);
+        repo.set_working_copy_file("c.txt", Some("three\n"));
+        assert_eq!(
+            repo.status().unwrap(),
+            vec![
+                StatusEntry {
+                    path: "a.txt".into(),
+                    status: FileStatus::Modified
+                },
+                StatusEntry {
+                    path: "b.txt".into(),
+                    status: FileStatus::Deleted
+                },
+                StatusEntry {
+                    path: "c.txt".into(),
+                    status: FileStatus::Added
+                },
+            ]
+        );
+        assert_eq!(
+            repo.load_parent_text(Path::new("a.txt")).unwrap(),
+            Some("one\n".to_string())
+        );
+        assert_eq!(repo.load_parent_text(Path::new("c.txt")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_fake_describe_squash_and_abandon() {
+        let repo = FakeJujutsuRepository::new();
+        repo.set_working_copy_file("a.txt", Some("one\n"));
+        repo.describe("@", "first").unwrap();
+        repo.set_bookmark("main", "@").unwrap();
+        repo.new_change(&["main"], None).unwrap();
+        repo.set_working_copy_file("a.txt", Some("two\n"));
+
+        let log = repo.log("::@", None).unwrap();
+        assert_eq!(log.len(), 3);
+        assert!(log[0].is_working_copy);
+        assert_eq!(log[1].description.as_ref(), "first");
+        assert_eq!(log[1].bookmarks, vec![SharedString::from("main")]);
+        assert_eq!(repo.log("all()", Some(1)).unwrap().len(), 1);
+
+        repo.squash("@", "@-").unwrap();
+        let log = repo.log("::@", None).unwrap();
+        assert_eq!(log.len(), 3);
+        assert!(log[0].is_empty);
+        assert_eq!(repo.status().unwrap(), Vec::new());
+        assert_eq!(
+            repo.load_parent_text(Path::new("a.txt")).unwrap(),
+            Some("two\n".to_string())
+        );
+
+        repo.abandon("@-").unwrap();
+        let log = repo.log("::@", None).unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[1].change_id.as_ref(), ROOT_CHANGE_ID);
+        assert!(repo.abandon("root()").is_err());
+    }
+}
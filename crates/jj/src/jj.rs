@@ -1,5 +1,7 @@
+mod fake_jj_repository;
 mod jj_repository;
 mod jj_store;
 
+pub use fake_jj_repository::*;
 pub use jj_repository::*;
 pub use jj_store::*;
@@ -1,24 +1,87 @@
-use std::path::Path;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Context as _, Result, anyhow};
 use gpui::SharedString;
 use jj_lib::config::StackedConfig;
-use jj_lib::repo::StoreFactories;
+use jj_lib::repo::{ReadonlyRepo, RepoLoader, StoreFactories};
 use jj_lib::settings::UserSettings;
 use jj_lib::workspace::{self, DefaultWorkspaceLoaderFactory, WorkspaceLoaderFactory};
+use parking_lot::Mutex;
+use util::command::new_std_command;
 
 #[derive(Debug, Clone)]
 pub struct Bookmark {
     pub ref_name: SharedString,
 }
 
+/// A single change (commit) in the Jujutsu change log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub change_id: SharedString,
+    pub commit_id: SharedString,
+    pub parent_change_ids: Vec<SharedString>,
+    pub description: SharedString,
+    pub author_name: SharedString,
+    pub author_email: SharedString,
+    /// Author timestamp, in seconds since the Unix epoch.
+    pub timestamp: i64,
+    pub bookmarks: Vec<SharedString>,
+    pub is_empty: bool,
+    pub is_working_copy: bool,
+    pub has_conflict: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileStatus {
+    Added,
+    Modified,
+    Deleted,
+    Conflicted,
+}
+
+/// The status of a single path in the working-copy change, relative to its parent.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StatusEntry {
+    /// The path, relative to the workspace root.
+    pub path: PathBuf,
+    pub status: FileStatus,
+}
+
 pub trait JujutsuRepository: Send + Sync {
     fn list_bookmarks(&self) -> Vec<Bookmark>;
+
+    /// Returns the changes selected by `revset`, in the order `jj log` would show them.
+    fn log(&self, revset: &str, limit: Option<usize>) -> Result<Vec<Change>>;
+
+    /// Returns the changed paths of the working-copy change.
+    fn status(&self) -> Result<Vec<StatusEntry>>;
+
+    /// Returns the contents of `path` in the parent of the working-copy change,
+    /// or `None` if the path doesn't exist there.
+    ///
+    /// This is the base text used when diffing a buffer in a Jujutsu workspace.
+    fn load_parent_text(&self, path: &Path) -> Result<Option<String>>;
+
+    /// Replaces the description of `revision`.
+    fn describe(&self, revision: &str, message: &str) -> Result<()>;
+
+    /// Creates a new change on top of `parents` and makes it the working copy.
+    fn new_change(&self, parents: &[&str], message: Option<&str>) -> Result<()>;
+
+    /// Moves the changes in `from` into `into`, keeping the description of `into`.
+    fn squash(&self, from: &str, into: &str) -> Result<()>;
+
+    /// Abandons `revision`, rebasing its descendants onto its parents.
+    fn abandon(&self, revision: &str) -> Result<()>;
 }
 
 pub struct RealJujutsuRepository {
-    repository: Arc<jj_lib::repo::ReadonlyRepo>,
+    workspace_root: PathBuf,
+    repo_loader: RepoLoader,
+    repository: Mutex<Arc<ReadonlyRepo>>,
 }
 
 impl RealJujutsuRepository {
@@ -35,10 +98,15 @@ impl RealJujutsuRepository {
             &workspace::default_working_copy_factories(),
         )?;
 
-        let repo_loader = workspace.repo_loader();
+        let workspace_root = workspace.workspace_root().to_path_buf();
+        let repo_loader = workspace.repo_loader().clone();
         let repository = repo_loader.load_at_head()?;
 
-        Ok(Self { repository })
+        Ok(Self {
+            workspace_root,
+            repo_loader,
+            repository: Mutex::new(repository),
+        })
     }
 
     fn find_workspace_dir(cwd: &Path) -> &Path {
@@ -46,12 +114,54 @@ impl RealJujutsuRepository {
             .find(|path| path.join(".jj").is_dir())
             .unwrap_or(cwd)
     }
+
+    /// Reloads the repository at the latest operation, so that changes made
+    /// through the `jj` binary are visible to the in-process view.
+    fn reload(&self) -> Result<()> {
+        let repository = self.repo_loader.load_at_head()?;
+        *self.repository.lock() = repository;
+        Ok(())
+    }
+
+    fn run<S>(&self, args: impl IntoIterator<Item = S>) -> Result<String>
+    where
+        S: AsRef<OsStr>,
+    {
+        let output = new_std_command("jj")
+            .current_dir(&self.workspace_root)
+            .args(["--color=never", "--no-pager"])
+            .args(args)
+            .stdin(Stdio::null())
+            .output()
+            .context("starting jj process")?;
+        anyhow::ensure!(
+            output.status.success(),
+            "jj command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    /// Runs a command that rewrites the repository and reloads the view afterwards.
+    fn run_mutation<S>(&self, args: impl IntoIterator<Item = S>) -> Result<()>
+    where
+        S: AsRef<OsStr>,
+    {
+        self.run(args)?;
+        self.reload()
+    }
+
+    fn conflicted_paths(&self) -> Result<Vec<PathBuf>> {
+        let output = self.run(["resolve", "--list", "-r", "@"])?;
+        Ok(output.lines().filter_map(parse_resolve_list_line).collect())
+    }
 }
 
 impl JujutsuRepository for RealJujutsuRepository {
     fn list_bookmarks(&self) -> Vec<Bookmark> {
         let bookmarks = self
             .repository
+            .lock()
             .view()
             .bookmarks()
             .map(|(ref_name, _target)| Bookmark {
@@ -61,12 +171,295 @@ impl JujutsuRepository for RealJujutsuRepository {
 
         bookmarks
     }
+
+    fn log(&self, revset: &str, limit: Option<usize>) -> Result<Vec<Change>> {
+        let mut args = vec![
+            "log".to_string(),
+            "--no-graph".to_string(),
+            "-r".to_string(),
+            revset.to_string(),
+            "-T".to_string(),
+            LOG_TEMPLATE.to_string(),
+        ];
+        if let Some(limit) = limit {
+            args.push("--limit".to_string());
+            args.push(limit.to_string());
+        }
+        let output = self.run(args)?;
+        parse_log_output(&output)
+    }
+
+    fn status(&self) -> Result<Vec<StatusEntry>> {
+        let output = self.run(["diff", "--summary", "-r", "@"])?;
+        let mut entries = parse_diff_summary(&output)?;
+
+        let has_conflict = self.run(["log", "--no-graph", "-r", "@", "-T", "conflict"])?;
+        if has_conflict.trim() == "true" {
+            for path in self.conflicted_paths()? {
+                entries.retain(|entry| entry.path != path);
+                entries.push(StatusEntry {
+                    path,
+                    status: FileStatus::Conflicted,
+                });
+            }
+        }
+
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(entries)
+    }
+
+    fn load_parent_text(&self, path: &Path) -> Result<Option<String>> {
+        let fileset = fileset_for_path(path)?;
+        let listing = self.run(["file", "list", "-r", "@-", &fileset])?;
+        if listing.trim().is_empty() {
+            return Ok(None);
+        }
+        self.run(["file", "show", "-r", "@-", &fileset]).map(Some)
+    }
+
+    fn describe(&self, revision: &str, message: &str) -> Result<()> {
+        self.run_mutation(["describe", revision, "-m", message])
+    }
+
+    fn new_change(&self, parents: &[&str], message: Option<&str>) -> Result<()> {
+        let mut args = vec!["new"];
+        args.extend_from_slice(parents);
+        if let Some(message) = message {
+            args.extend(["-m", message]);
+        }
+        self.run_mutation(args)
+    }
+
+    fn squash(&self, from: &str, into: &str) -> Result<()> {
+        self.run_mutation([
+            "squash",
+            "--from",
+            from,
+            "--into",
+            into,
+            "--use-destination-message",
+        ])
+    }
+
+    fn abandon(&self, revision: &str) -> Result<()> {
+        self.run_mutation(["abandon", revision])
+    }
 }
 
-pub struct FakeJujutsuRepository {}
+/// The number of NUL-separated fields emitted by [`LOG_TEMPLATE`] for each change.
+const LOG_FIELD_COUNT: usize = 11;
 
-impl JujutsuRepository for FakeJujutsuRepository {
-    fn list_bookmarks(&self) -> Vec<Bookmark> {
-        Vec::new()
+const LOG_TEMPLATE: &str = concat!(
+    r#"change_id ++ "\0" ++ "#,
+    r#"commit_id ++ "\0" ++ "#,
+    r#"parents.map(|c| c.change_id()).join(",") ++ "\0" ++ "#,
+    r#"description ++ "\0" ++ "#,
+    r#"author.name() ++ "\0" ++ "#,
+    r#"author.email() ++ "\0" ++ "#,
+    r#"author.timestamp().format("%s") ++ "\0" ++ "#,
+    r#"local_bookmarks.map(|b| b.name()).join(",") ++ "\0" ++ "#,
+    r#"if(empty, "1", "0") ++ "\0" ++ "#,
+    r#"if(current_working_copy, "1", "0") ++ "\0" ++ "#,
+    r#"if(conflict, "1", "0") ++ "\0""#,
+);
+
+fn parse_log_output(output: &str) -> Result<Vec<Change>> {
+    let fields = output.split('\0').collect::<Vec<_>>();
+    // Every change is terminated by a NUL, so the final field is always empty.
+    let (trailing, fields) = fields.split_last().context("empty jj log output")?;
+    anyhow::ensure!(
+        trailing.trim().is_empty() && fields.len() % LOG_FIELD_COUNT == 0,
+        "unexpected jj log output: {output:?}"
+    );
+
+    fields
+        .chunks(LOG_FIELD_COUNT)
+        .map(|fields| {
+            let split_list = |field: &str| {
+                field
+                    .split(',')
+                    .filter(|item| !item.is_empty())
+                    .map(|item| SharedString::from(item.to_string()))
+                    .collect::<Vec<_>>()
+            };
+            Ok(Change {
+                change_id: fields[0].to_string().into(),
+                commit_id: fields[1].to_string().into(),
+                parent_change_ids: split_list(fields[2]),
+                description: fields[3].trim_end().to_string().into(),
+                author_name: fields[4].to_string().into(),
+                author_email: fields[5].to_string().into(),
+                timestamp: fields[6].parse().context("parsing change timestamp")?,
+                bookmarks: split_list(fields[7]),
+                is_empty: fields[8] == "1",
+                is_working_copy: fields[9] == "1",
+                has_conflict: fields[10] == "1",
+            })
+        })
+        .collect()
+}
+
+/// Parses the output of `jj diff --summary`.
+///
+/// Renames are reported as a deletion of the old path and an addition of the
+/// new path, and copies as an addition of the new path.
+fn parse_diff_summary(output: &str) -> Result<Vec<StatusEntry>> {
+    let mut entries = Vec::new();
+    for line in output.lines().filter(|line| !line.is_empty()) {
+        let (kind, path) = line
+            .split_once(' ')
+            .with_context(|| format!("unexpected jj diff summary line: {line:?}"))?;
+        match kind {
+            "A" => entries.push(StatusEntry {
+                path: path.into(),
+                status: FileStatus::Added,
+            }),
+            "M" => entries.push(StatusEntry {
+                path: path.into(),
+                status: FileStatus::Modified,
+            }),
+            "D" => entries.push(StatusEntry {
+                path: path.into(),
+                status: FileStatus::Deleted,
+            }),
+            "R" | "C" => {
+                let (old_path, new_path) = parse_copied_path(path)
+                    .with_context(|| format!("unexpected jj diff summary line: {line:?}"))?;
+                if kind == "R" {
+                    entries.push(StatusEntry {
+                        path: old_path,
+                        status: FileStatus::Deleted,
+                    });
+                }
+                entries.push(StatusEntry {
+                    path: new_path,
+                    status: FileStatus::Added,
+                });
+            }
+            _ => return Err(anyhow!("unexpected jj diff summary line: {line:?}")),
+        }
+    }
+    Ok(entries)
+}
+
+/// Parses a rename or copy such as `src/{old.rs => new.rs}` or `old.rs => new.rs`.
+fn parse_copied_path(path: &str) -> Option<(PathBuf, PathBuf)> {
+    if let (Some(open), Some(close)) = (path.find('{'), path.rfind('}')) {
+        let (prefix, suffix) = (&path[..open], &path[close + 1..]);
+        let (old, new) = path[open + 1..close].split_once(" => ")?;
+        let join = |middle: &str| {
+            let joined = format!("{prefix}{middle}{suffix}");
+            PathBuf::from(joined.replace("//", "/").trim_start_matches('/'))
+        };
+        Some((join(old), join(new)))
+    } else {
+        let (old, new) = path.split_once(" => ")?;
+        Some((old.into(), new.into()))
+    }
+}
+
+/// Parses a line of `jj resolve --list`, such as `src/lib.rs    2-sided conflict`.
+fn parse_resolve_list_line(line: &str) -> Option<PathBuf> {
+    let sided = line.rfind("-sided conflict")?;
+    let path_end = line[..sided].rfind(' ')?;
+    let path = line[..path_end].trim_end();
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// Returns a fileset expression that matches exactly `path`, relative to the workspace root.
+fn fileset_for_path(path: &Path) -> Result<String> {
+    let path = path
+        .to_str()
+        .with_context(|| format!("path is not valid UTF-8: {path:?}"))?;
+    let escaped = path.replace('\\', "\\\\").replace('"', "\\\"");
+    Ok(format!("root-file:\"{escaped}\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_log_output() {
+        let output = concat!(
+            "kxqpqmtw\0a1b2c3\0zzzzzzzz\0Fix the thing\n\0Jane\0jane@example.com\01700000000\0main,dev\00\01\00\0",
+            "zzzzzzzz\0000000\0\0\0\0\00\0\01\00\00\0",
+        );
+        let changes = parse_log_output(output).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].change_id.as_ref(), "kxqpqmtw");
+        assert_eq!(changes[0].description.as_ref(), "Fix the thing");
+        assert_eq!(
+            changes[0].parent_change_ids,
+            vec![SharedString::from("zzzzzzzz")]
+        );
+        assert_eq!(
+            changes[0].bookmarks,
+            vec![SharedString::from("main"), SharedString::from("dev")]
+        );
+        assert_eq!(changes[0].timestamp, 1700000000);
+        assert!(changes[0].is_working_copy);
+        assert!(!changes[0].is_empty);
+        assert!(changes[1].is_empty);
+        assert!(changes[1].parent_change_ids.is_empty());
+
+        assert!(parse_log_output("kxqpqmtw\0a1b2c3\0").is_err());
+    }
+
+    #[test]
+    fn test_parse_diff_summary() {
+        let output = "M src/main.rs\nA new file.txt\nD gone.rs\nR src/{old.rs => new.rs}\nC a.txt => b.txt\n";
+        let entries = parse_diff_summary(output).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                StatusEntry {
+                    path: "src/main.rs".into(),
+                    status: FileStatus::Modified
+                },
+                StatusEntry {
+                    path: "new file.txt".into(),
+                    status: FileStatus::Added
+                },
+                StatusEntry {
+                    path: "gone.rs".into(),
+                    status: FileStatus::Deleted
+                },
+                StatusEntry {
+                    path: "src/old.rs".into(),
+                    status: FileStatus::Deleted
+                },
+                StatusEntry {
+                    path: "src/new.rs".into(),
+                    status: FileStatus::Added
+                },
+                StatusEntry {
+                    path: "b.txt".into(),
+                    status: FileStatus::Added
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_resolve_list_line() {
+        assert_eq!(
+            parse_resolve_list_line("src/lib.rs    2-sided conflict"),
+            Some(PathBuf::from("src/lib.rs"))
+        );
+        assert_eq!(
+            parse_resolve_list_line("a b.txt 2-sided conflict including 1 deletion"),
+            Some(PathBuf::from("a b.txt"))
+        );
+        assert_eq!(parse_resolve_list_line("nothing here"), None);
+    }
+
+    #[test]
+    fn test_fileset_for_path() {
+        assert_eq!(
+            fileset_for_path(Path::new("src/\"quoted\".rs")).unwrap(),
+            r#"root-file:"src/\"quoted\".rs""#
+        );
     }
 }
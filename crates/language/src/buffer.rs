@@ -121,6 +121,8 @@ pub struct Buffer {
     completion_triggers_timestamp: clock::Lamport,
     deferred_ops: OperationQueue<Operation>,
     capability: Capability,
+    /// Whether the buffer is in large-file mode, in which it isn't parsed.
+    large_file_mode: bool,
     has_conflict: bool,
     /// Memoize calls to has_changes_since(saved_version).
     /// The contents of a cell are (self.version, has_changes) at the time of a last call.
@@ -313,6 +315,8 @@ pub enum BufferEvent {
     DiagnosticsUpdated,
     /// The buffer gained or lost editing capabilities.
     CapabilityChanged,
+    /// The buffer entered or left large-file mode.
+    LargeFileModeChanged,
     /// The buffer was explicitly requested to close.
     Closed,
     /// The buffer was discarded when closing.
@@ -528,6 +532,17 @@ pub enum CharKind {
     Word,
 }
 
+/// A class of text, determined by the syntax nodes that contain it.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum TextKind {
+    /// Text outside of comments and string literals.
+    Code,
+    /// Text within a comment.
+    Comment,
+    /// Text within a string literal.
+    String,
+}
+
 /// A runnable is a set of data about a region that could be resolved into a task
 pub struct Runnable {
     pub tags: SmallVec<[RunnableTag; 1]>,
@@ -841,6 +856,7 @@ impl Buffer {
         ));
         this.saved_version = proto::deserialize_version(&message.saved_version);
         this.saved_mtime = message.saved_mtime.map(|time| time.into());
+        this.large_file_mode = message.large_file_mode;
         Ok(this)
     }
 
@@ -853,6 +869,7 @@ impl Buffer {
             line_ending: proto::serialize_line_ending(self.line_ending()) as i32,
             saved_version: proto::serialize_version(&self.saved_version),
             saved_mtime: self.saved_mtime.map(|time| time.into()),
+            large_file_mode: self.large_file_mode,
         }
     }
 
@@ -922,6 +939,30 @@ impl Buffer {
         self.capability == Capability::ReadOnly
     }
 
+    /// Whether the buffer is in large-file mode, in which syntax parsing, language servers and
+    /// git diffs are skipped and lines are only soft wrapped once shown, to keep very large files
+    /// responsive.
+    pub fn is_in_large_file_mode(&self) -> bool {
+        self.large_file_mode
+    }
+
+    /// Puts the buffer in or out of large-file mode, parsing it when full features are enabled.
+    pub fn set_large_file_mode(&mut self, large_file_mode: bool, cx: &mut Context<Self>) {
+        if self.large_file_mode == large_file_mode {
+            return;
+        }
+        self.large_file_mode = large_file_mode;
+        self.non_text_state_update_count += 1;
+        if large_file_mode {
+            self.reparse = None;
+            self.syntax_map.lock().clear(&self.text);
+            self.parse_status.0.send(ParseStatus::Idle).unwrap();
+        } else {
+            self.reparse(cx);
+        }
+        cx.emit(BufferEvent::LargeFileModeChanged);
+    }
+
     /// Builds a [`Buffer`] with the given underlying [`TextBuffer`], diff base, [`File`] and [`Capability`].
     pub fn build(buffer: TextBuffer, file: Option<Arc<dyn File>>, capability: Capability) -> Self {
         let saved_mtime = file.as_ref().and_then(|file| file.disk_state().mtime());
@@ -955,6 +996,7 @@ impl Buffer {
             completion_triggers_per_language_server: Default::default(),
             completion_triggers_timestamp: Default::default(),
             deferred_ops: OperationQueue::new(),
+            large_file_mode: false,
             has_conflict: false,
             change_bits: Default::default(),
             _subscriptions: Vec::new(),
@@ -1271,6 +1313,15 @@ impl Buffer {
         cx.notify();
     }
 
+    /// Marks the given version of the buffer as matching the file on disk, without signaling a save.
+    /// Used when the buffer's history is rebuilt on top of text that was already saved.
+    pub fn set_saved_version(&mut self, version: clock::Global, cx: &mut Context<Self>) {
+        self.saved_version = version;
+        self.has_unsaved_edits
+            .set((self.saved_version.clone(), false));
+        cx.notify();
+    }
+
     /// This method is called to signal that the buffer has been discarded.
     pub fn discarded(&self, cx: &mut Context<Self>) {
         cx.emit(BufferEvent::Discarded);
@@ -1480,7 +1531,7 @@ impl Buffer {
     /// for the same buffer, we only initiate a new parse if we are not already
     /// parsing in the background.
     pub fn reparse(&mut self, cx: &mut Context<Self>) {
-        if self.reparse.is_some() {
+        if self.reparse.is_some() || self.large_file_mode {
             return;
         }
         let language = if let Some(language) = self.language.clone() {
@@ -2731,6 +2782,26 @@ impl Buffer {
         redone
     }
 
+    /// Moves the buffer to the given state of its undo tree, or to its initial state for `None`.
+    pub fn undo_tree_goto(
+        &mut self,
+        target: Option<TransactionId>,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let was_dirty = self.is_dirty();
+        let old_version = self.version.clone();
+
+        let operations = self.text.undo_tree_goto(target);
+        let moved = !operations.is_empty();
+        for operation in operations {
+            self.send_operation(Operation::Buffer(operation), true, cx);
+        }
+        if moved {
+            self.did_edit(&old_version, was_dirty, cx)
+        }
+        moved
+    }
+
     /// Override current completion triggers with the user-provided completion triggers.
     pub fn set_completion_triggers(
         &mut self,
@@ -3306,6 +3377,32 @@ impl BufferSnapshot {
         CharClassifier::new(self.language_scope_at(point))
     }
 
+    /// Returns whether the given position is within a comment, a string literal or code,
+    /// based on the kinds of the syntax nodes containing it.
+    ///
+    /// Outer syntax layers take precedence, so that text injected into a comment or a string
+    /// (e.g. Markdown in doc comments) is still considered to be part of it.
+    pub fn text_kind_at<D: ToOffset>(&self, position: D) -> TextKind {
+        let offset = position.to_offset(self);
+        for layer in self
+            .syntax
+            .layers_for_range(offset..offset, &self.text, false)
+            .filter(|layer| layer.node().end_byte() > offset)
+        {
+            let mut node = layer.node().descendant_for_byte_range(offset, offset);
+            while let Some(current) = node {
+                let kind = current.kind();
+                if kind.contains("comment") {
+                    return TextKind::Comment;
+                } else if kind.contains("string") {
+                    return TextKind::String;
+                }
+                node = current.parent();
+            }
+        }
+        TextKind::Code
+    }
+
     /// Returns the [`LanguageScope`] at the given location.
     pub fn language_scope_at<D: ToOffset>(&self, position: D) -> Option<LanguageScope> {
         let offset = position.to_offset(self);
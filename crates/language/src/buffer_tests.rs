@@ -677,6 +677,37 @@ async fn test_resetting_language(cx: &mut gpui::TestAppContext) {
     assert_eq!(get_tree_sexp(&buffer, cx), "(document (object))");
 }
 
+#[gpui::test]
+async fn test_large_file_mode(cx: &mut gpui::TestAppContext) {
+    let buffer = cx.new(|cx| {
+        let mut buffer = Buffer::local("fn a() {}", cx);
+        buffer.set_large_file_mode(true, cx);
+        buffer.set_language(Some(Arc::new(rust_lang())), cx);
+        buffer
+    });
+
+    // The buffer isn't parsed while in large-file mode, even after edits.
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "// a\n")], None, cx));
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert!(buffer.is_in_large_file_mode());
+        assert!(!buffer.is_parsing());
+        let snapshot = buffer.snapshot();
+        assert!(snapshot.syntax.layers(buffer.as_text_snapshot()).is_empty());
+    });
+
+    buffer.update(cx, |buffer, cx| buffer.set_large_file_mode(false, cx));
+    cx.executor().run_until_parked();
+    assert_eq!(
+        get_tree_sexp(&buffer, cx),
+        concat!(
+            "(source_file (line_comment) (function_item name: (identifier) ",
+            "parameters: (parameters) ",
+            "body: (block)))"
+        )
+    );
+}
+
 #[gpui::test]
 async fn test_outline(cx: &mut gpui::TestAppContext) {
     let text = r#"
@@ -780,6 +811,56 @@ async fn test_outline(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    let text = r#"
+        fn main() {
+            let a = foo(1, bar(2));
+            let b = foo(x, x);
+            // foo(3, 4)
+            baz(foo(y, z));
+        }
+    "#
+    .unindent();
+
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(Arc::new(rust_lang()), cx));
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    let matched_text = |query: &StructuralQuery| {
+        query
+            .matches(&snapshot, 0..snapshot.len())
+            .into_iter()
+            .map(|mat| snapshot.text_for_range(mat.range).collect::<String>())
+            .collect::<Vec<_>>()
+    };
+
+    // Metavariables match any node, and comments are not searched.
+    let template = StructuralQuery::new("foo($a, $b)").unwrap();
+    assert_eq!(template.required_text(), Some("foo"));
+    assert_eq!(
+        matched_text(&template),
+        ["foo(1, bar(2))", "foo(x, x)", "foo(y, z)"]
+    );
+
+    // Repeated metavariables must match the same text.
+    let repeated = StructuralQuery::new("foo($a, $a)").unwrap();
+    assert_eq!(matched_text(&repeated), ["foo(x, x)"]);
+
+    // Tree-sitter queries use their `@match` capture, if any.
+    let query = StructuralQuery::new(
+        "(call_expression function: (identifier) @name (#eq? @name \"baz\")) @match",
+    )
+    .unwrap();
+    assert!(query.is_s_expression());
+    assert_eq!(matched_text(&query), ["baz(foo(y, z))"]);
+
+    // Replacements reuse the captured text.
+    let mat = &template.matches(&snapshot, 0..snapshot.len())[0];
+    assert_eq!(
+        StructuralQuery::expand_replacement("qux(${b}, $a) $$", mat, &snapshot),
+        "qux(bar(2), 1) $"
+    );
+}
+
 #[gpui::test]
 async fn test_outline_nodes_with_newlines(cx: &mut gpui::TestAppContext) {
     let text = r#"
@@ -2492,6 +2573,43 @@ fn test_language_scope_at_with_rust(cx: &mut App) {
     });
 }
 
+#[gpui::test]
+fn test_text_kind_at(cx: &mut App) {
+    init_settings(cx, |_| {});
+
+    cx.new(|cx| {
+        let text = r#"
+            // call foo
+            fn main() {
+                foo("foo /* bar */"); /* foo */
+            }
+        "#
+        .unindent();
+
+        let buffer = Buffer::local(text.clone(), cx).with_language(Arc::new(rust_lang()), cx);
+        let snapshot = buffer.snapshot();
+        let kinds = text
+            .match_indices("foo")
+            .map(|(offset, _)| snapshot.text_kind_at(offset))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                TextKind::Comment,
+                TextKind::Code,
+                TextKind::String,
+                TextKind::Comment
+            ]
+        );
+        assert_eq!(
+            snapshot.text_kind_at(text.find("bar").unwrap()),
+            TextKind::String
+        );
+
+        buffer
+    });
+}
+
 #[gpui::test]
 fn test_language_scope_at_with_combined_injections(cx: &mut App) {
     init_settings(cx, |_| {});
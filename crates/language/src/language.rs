@@ -13,6 +13,7 @@ mod language_registry;
 pub mod language_settings;
 mod manifest;
 mod outline;
+mod structural_query;
 pub mod proto;
 mod syntax_map;
 mod task_context;
@@ -80,6 +81,7 @@ pub use language_registry::{
 };
 pub use lsp::{LanguageServerId, LanguageServerName};
 pub use outline::*;
+pub use structural_query::{StructuralMatch, StructuralQuery};
 pub use syntax_map::{OwnedSyntaxLayer, SyntaxLayer, ToTreeSitterPoint, TreeSitterOptions};
 pub use text::{AnchorRangeExt, LineEnding};
 pub use tree_sitter::{Node, Parser, Tree, TreeCursor};
@@ -0,0 +1,398 @@
+use crate::{
+    BufferSnapshot, Grammar, GrammarId, syntax_map::TextProvider, with_parser, with_query_cursor,
+};
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use parking_lot::Mutex;
+use std::{fmt, ops::Range, sync::Arc};
+use streaming_iterator::StreamingIterator as _;
+use tree_sitter::{Node, Query, Tree};
+
+/// Placeholder identifiers that stand in for metavariables when parsing a code template.
+const METAVARIABLE_PREFIX: &str = "zed_metavariable_";
+
+/// The name of the capture that determines the range of an S-expression match.
+const MATCH_CAPTURE: &str = "match";
+
+/// A syntax-aware search pattern, matched against the syntax trees of a buffer.
+///
+/// The pattern is either a Tree-sitter query S-expression, such as `(call_expression function: (identifier) @name)`,
+/// or a snippet of code in the buffer's language, such as `foo($a, $b)`, in which each `$name` metavariable matches
+/// any single syntax node. A metavariable used more than once must match the same text each time.
+pub struct StructuralQuery {
+    source: Arc<str>,
+    compiled: Mutex<HashMap<GrammarId, Option<Arc<CompiledPattern>>>>,
+}
+
+/// A match of a [`StructuralQuery`] in a buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructuralMatch {
+    pub range: Range<usize>,
+    /// The named captures or metavariables of the match, along with the ranges of the nodes they matched.
+    pub captures: Vec<(Arc<str>, Range<usize>)>,
+}
+
+enum CompiledPattern {
+    Query(Query),
+    Template(Template),
+}
+
+struct Template {
+    tree: Tree,
+    text: String,
+    /// The byte range of the template's root node within `text`.
+    range: Range<usize>,
+}
+
+impl StructuralQuery {
+    pub fn new(source: impl Into<Arc<str>>) -> Result<Self> {
+        let source = source.into();
+        anyhow::ensure!(
+            !source.trim().is_empty(),
+            "structural search pattern is empty"
+        );
+        Ok(Self {
+            source,
+            compiled: Mutex::default(),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether the pattern looks like a Tree-sitter query rather than a code template.
+    pub fn is_s_expression(&self) -> bool {
+        let source = self.source.trim_start();
+        (source.starts_with('(') || source.starts_with('[')) && !source.contains('$')
+    }
+
+    /// Returns the longest piece of literal text that every match contains, if any.
+    pub fn required_text(&self) -> Option<&str> {
+        if self.is_s_expression() {
+            return None;
+        }
+        let mut longest: Option<&str> = None;
+        for word in self.source.split(|c: char| !is_identifier_char(c)) {
+            let offset = word.as_ptr() as usize - self.source.as_ptr() as usize;
+            let is_metavariable = self.source[..offset].ends_with('$');
+            if word.len() > 1
+                && !is_metavariable
+                && longest.map_or(true, |longest| word.len() > longest.len())
+            {
+                longest = Some(word);
+            }
+        }
+        longest
+    }
+
+    /// Finds the non-overlapping matches that lie within the given byte range of the buffer, in all of its syntax layers.
+    pub fn matches(&self, buffer: &BufferSnapshot, range: Range<usize>) -> Vec<StructuralMatch> {
+        let mut matches = Vec::new();
+        for layer in buffer.syntax_layers() {
+            let root = layer.node();
+            if root.end_byte() < range.start || root.start_byte() > range.end {
+                continue;
+            }
+            let Some(grammar) = layer.language.grammar() else {
+                continue;
+            };
+            let Some(pattern) = self.compile(grammar) else {
+                continue;
+            };
+            match pattern.as_ref() {
+                CompiledPattern::Query(query) => {
+                    query_matches(query, root, buffer, range.clone(), &mut matches)
+                }
+                CompiledPattern::Template(template) => {
+                    template.find_matches(root, buffer, range.clone(), &mut matches)
+                }
+            }
+        }
+
+        matches.sort_by(|a, b| {
+            a.range
+                .start
+                .cmp(&b.range.start)
+                .then_with(|| b.range.end.cmp(&a.range.end))
+        });
+        let mut end = 0;
+        matches.retain(|mat| {
+            let keep = mat.range.start >= end && !mat.range.is_empty();
+            if keep {
+                end = mat.range.end;
+            }
+            keep
+        });
+        matches
+    }
+
+    /// Expands a replacement template for the match, substituting the text of its captures
+    /// for `$name` and `${name}` references. `$$` inserts a literal `$`.
+    pub fn expand_replacement(
+        replacement: &str,
+        mat: &StructuralMatch,
+        buffer: &BufferSnapshot,
+    ) -> String {
+        let mut expanded = String::with_capacity(replacement.len());
+        let mut rest = replacement;
+        while let Some(ix) = rest.find('$') {
+            expanded.push_str(&rest[..ix]);
+            rest = &rest[ix + 1..];
+            if let Some(stripped) = rest.strip_prefix('$') {
+                expanded.push('$');
+                rest = stripped;
+                continue;
+            }
+            let (name, remainder) = if let Some(braced) = rest.strip_prefix('{') {
+                match braced.find('}') {
+                    Some(end) => (&braced[..end], &braced[end + 1..]),
+                    None => ("", rest),
+                }
+            } else {
+                let end = rest
+                    .find(|c: char| !is_identifier_char(c))
+                    .unwrap_or(rest.len());
+                (&rest[..end], &rest[end..])
+            };
+            match mat
+                .captures
+                .iter()
+                .find(|(capture, _)| capture.as_ref() == name)
+            {
+                Some((_, range)) if !name.is_empty() => {
+                    expanded.extend(buffer.text_for_range(range.clone()));
+                    rest = remainder;
+                }
+                _ => expanded.push('$'),
+            }
+        }
+        expanded.push_str(rest);
+        expanded
+    }
+
+    fn compile(&self, grammar: &Grammar) -> Option<Arc<CompiledPattern>> {
+        self.compiled
+            .lock()
+            .entry(grammar.id())
+            .or_insert_with(|| match compile_pattern(&self.source, grammar) {
+                Ok(pattern) => Some(Arc::new(pattern)),
+                Err(error) => {
+                    log::debug!("failed to compile structural search pattern: {error:#}");
+                    None
+                }
+            })
+            .clone()
+    }
+}
+
+impl fmt::Debug for StructuralQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StructuralQuery")
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+fn compile_pattern(source: &str, grammar: &Grammar) -> Result<CompiledPattern> {
+    let trimmed = source.trim_start();
+    if trimmed.starts_with('(') || trimmed.starts_with('[') {
+        match Query::new(&grammar.ts_language, source) {
+            Ok(query) => return Ok(CompiledPattern::Query(query)),
+            Err(error) if !source.contains('$') => {
+                if let Ok(template) = Template::new(source, grammar) {
+                    return Ok(CompiledPattern::Template(template));
+                }
+                return Err(anyhow!(error));
+            }
+            Err(_) => {}
+        }
+    }
+    Ok(CompiledPattern::Template(Template::new(source, grammar)?))
+}
+
+fn query_matches(
+    query: &Query,
+    root: Node,
+    buffer: &BufferSnapshot,
+    range: Range<usize>,
+    matches: &mut Vec<StructuralMatch>,
+) {
+    with_query_cursor(|cursor| {
+        cursor.set_byte_range(range.clone());
+        let capture_names = query.capture_names();
+        let mut query_matches = cursor.matches(query, root, TextProvider(buffer.as_rope()));
+        while let Some(query_match) = query_matches.next() {
+            let mut match_range: Option<Range<usize>> = None;
+            let mut captures = Vec::<(Arc<str>, Range<usize>)>::new();
+            for capture in query_match.captures {
+                let name = capture_names[capture.index as usize];
+                let node_range = capture.node.byte_range();
+                if name == MATCH_CAPTURE {
+                    match_range = Some(node_range.clone());
+                } else if name.starts_with('_') {
+                    continue;
+                }
+                if !captures.iter().any(|(capture, _)| capture.as_ref() == name) {
+                    captures.push((name.into(), node_range));
+                }
+            }
+            let match_range = match_range.or_else(|| {
+                let start = captures.iter().map(|(_, range)| range.start).min()?;
+                let end = captures.iter().map(|(_, range)| range.end).max()?;
+                Some(start..end)
+            });
+            if let Some(match_range) = match_range {
+                if range.start <= match_range.start && match_range.end <= range.end {
+                    matches.push(StructuralMatch {
+                        range: match_range,
+                        captures,
+                    });
+                }
+            }
+        }
+    });
+}
+
+impl Template {
+    fn new(source: &str, grammar: &Grammar) -> Result<Self> {
+        let mut text = String::with_capacity(source.len());
+        let mut rest = source;
+        while let Some(ix) = rest.find('$') {
+            text.push_str(&rest[..ix]);
+            rest = &rest[ix + 1..];
+            let name_len = rest
+                .find(|c: char| !is_identifier_char(c))
+                .unwrap_or(rest.len());
+            anyhow::ensure!(name_len > 0, "expected a metavariable name after `$`");
+            text.push_str(METAVARIABLE_PREFIX);
+            text.push_str(&rest[..name_len]);
+            rest = &rest[name_len..];
+        }
+        text.push_str(rest);
+
+        let tree = with_parser(|parser| {
+            parser.set_language(&grammar.ts_language).ok()?;
+            parser.parse(&text, None)
+        })
+        .context("failed to parse structural search template")?;
+
+        let start = text.len() - text.trim_start().len();
+        let end = text.trim_end().len();
+        let node = tree
+            .root_node()
+            .descendant_for_byte_range(start, end)
+            .context("structural search template is empty")?;
+        anyhow::ensure!(
+            !node.has_error(),
+            "structural search template is not valid syntax"
+        );
+        let range = node.byte_range();
+        let template = Self { tree, text, range };
+        anyhow::ensure!(
+            template.metavariable(node).is_none(),
+            "structural search template must contain more than a metavariable"
+        );
+        Ok(template)
+    }
+
+    fn root(&self) -> Node<'_> {
+        self.tree
+            .root_node()
+            .descendant_for_byte_range(self.range.start, self.range.end)
+            .unwrap_or_else(|| self.tree.root_node())
+    }
+
+    fn metavariable(&self, node: Node) -> Option<&str> {
+        self.text[node.byte_range()]
+            .strip_prefix(METAVARIABLE_PREFIX)
+            .filter(|name| name.chars().all(is_identifier_char))
+    }
+
+    fn find_matches(
+        &self,
+        root: Node,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+        matches: &mut Vec<StructuralMatch>,
+    ) {
+        let pattern = self.root();
+        let mut cursor = root.walk();
+        loop {
+            let node = cursor.node();
+            let mut descend = node.start_byte() < range.end && node.end_byte() > range.start;
+            if descend
+                && node.kind_id() == pattern.kind_id()
+                && range.start <= node.start_byte()
+                && node.end_byte() <= range.end
+            {
+                let mut captures = Vec::new();
+                if self.match_node(pattern, node, buffer, &mut captures) {
+                    matches.push(StructuralMatch {
+                        range: node.byte_range(),
+                        captures,
+                    });
+                    descend = false;
+                }
+            }
+
+            if descend && cursor.goto_first_child() {
+                continue;
+            }
+            while !cursor.goto_next_sibling() {
+                if !cursor.goto_parent() {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn match_node(
+        &self,
+        pattern: Node,
+        node: Node,
+        buffer: &BufferSnapshot,
+        captures: &mut Vec<(Arc<str>, Range<usize>)>,
+    ) -> bool {
+        if let Some(name) = self.metavariable(pattern) {
+            if let Some((_, range)) = captures
+                .iter()
+                .find(|(capture, _)| capture.as_ref() == name)
+            {
+                let captured = buffer.text_for_range(range.clone()).collect::<String>();
+                return captured == node_text(buffer, node);
+            }
+            captures.push((name.into(), node.byte_range()));
+            return true;
+        }
+        if pattern.kind_id() != node.kind_id() {
+            return false;
+        }
+
+        let pattern_children = significant_children(pattern);
+        let node_children = significant_children(node);
+        if pattern_children.is_empty() && node_children.is_empty() {
+            return self.text[pattern.byte_range()] == node_text(buffer, node);
+        }
+        pattern_children.len() == node_children.len()
+            && pattern_children
+                .into_iter()
+                .zip(node_children)
+                .all(|(pattern, node)| self.match_node(pattern, node, buffer, captures))
+    }
+}
+
+fn significant_children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|child| !child.is_extra() && !child.is_missing())
+        .collect()
+}
+
+fn node_text(buffer: &BufferSnapshot, node: Node) -> String {
+    buffer.text_for_range(node.byte_range()).collect()
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
@@ -383,6 +383,9 @@ pub enum LanguageModelToolChoice {
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
 pub struct LanguageModelRequest {
     pub thread_id: Option<String>,
+    /// The agent profile of the thread making the request, whose settings apply to the tools the
+    /// model uses.
+    pub profile_id: Option<String>,
     pub prompt_id: Option<String>,
     pub intent: Option<CompletionIntent>,
     pub mode: Option<CompletionMode>,
@@ -391,9 +394,24 @@ pub struct LanguageModelRequest {
     pub tool_choice: Option<LanguageModelToolChoice>,
     pub stop: Vec<String>,
     pub temperature: Option<f32>,
+    /// The maximum number of tokens to generate, below the model's own limit.
+    pub max_tokens: Option<u64>,
     pub thinking_allowed: bool,
 }
 
+impl LanguageModelRequest {
+    /// Returns the maximum number of tokens to generate given the model's own limit, which is
+    /// lowered to the request's when it has one.
+    pub fn max_output_tokens(&self, model_max_output_tokens: Option<u64>) -> Option<u64> {
+        match (self.max_tokens, model_max_output_tokens) {
+            (Some(max_tokens), Some(model_max_output_tokens)) => {
+                Some(max_tokens.min(model_max_output_tokens))
+            }
+            (max_tokens, model_max_output_tokens) => max_tokens.or(model_max_output_tokens),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct LanguageModelResponseMessage {
     pub role: Option<Role>,
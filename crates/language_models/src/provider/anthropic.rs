@@ -517,6 +517,7 @@ pub fn into_anthropic(
     max_output_tokens: u64,
     mode: AnthropicModelMode,
 ) -> anthropic::Request {
+    let max_output_tokens = max_output_tokens.min(request.max_tokens.unwrap_or(u64::MAX));
     let mut new_messages: Vec<anthropic::Message> = Vec::new();
     let mut system_message = String::new();
 
@@ -1103,6 +1104,7 @@ mod tests {
                 cache: true,
             }],
             thread_id: None,
+            profile_id: None,
             prompt_id: None,
             intent: None,
             mode: None,
@@ -1110,6 +1112,7 @@ mod tests {
             temperature: None,
             tools: vec![],
             tool_choice: None,
+            max_tokens: None,
             thinking_allowed: true,
         };
 
@@ -620,6 +620,7 @@ pub fn into_bedrock(
     mode: BedrockModelMode,
     supports_caching: bool,
 ) -> Result<bedrock::Request> {
+    let max_output_tokens = max_output_tokens.min(request.max_tokens.unwrap_or(u64::MAX));
     let mut new_messages: Vec<BedrockMessage> = Vec::new();
     let mut system_message = String::new();
 
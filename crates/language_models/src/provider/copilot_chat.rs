@@ -626,6 +626,7 @@ fn into_copilot_chat(
             LanguageModelToolChoice::Any => copilot::copilot_chat::ToolChoice::Any,
             LanguageModelToolChoice::None => copilot::copilot_chat::ToolChoice::None,
         }),
+        max_tokens: request.max_tokens,
     })
 }
 
@@ -387,6 +387,7 @@ pub fn into_mistral(
     model: String,
     max_output_tokens: Option<u64>,
 ) -> mistral::Request {
+    let max_output_tokens = request.max_output_tokens(max_output_tokens);
     let stream = true;
 
     let mut messages = Vec::new();
@@ -907,10 +908,12 @@ mod tests {
             tools: vec![],
             tool_choice: None,
             thread_id: None,
+            profile_id: None,
             prompt_id: None,
             intent: None,
             mode: None,
             stop: vec![],
+            max_tokens: None,
             thinking_allowed: true,
         };
 
@@ -940,10 +943,12 @@ mod tests {
             tool_choice: None,
             temperature: None,
             thread_id: None,
+            profile_id: None,
             prompt_id: None,
             intent: None,
             mode: None,
             stop: vec![],
+            max_tokens: None,
             thinking_allowed: true,
         };
 
@@ -369,6 +369,7 @@ pub fn into_open_ai(
     supports_parallel_tool_calls: bool,
     max_output_tokens: Option<u64>,
 ) -> open_ai::Request {
+    let max_output_tokens = request.max_output_tokens(max_output_tokens);
     let stream = !model_id.starts_with("o1-");
 
     let mut messages = Vec::new();
@@ -987,6 +988,7 @@ mod tests {
     fn tiktoken_rs_support(cx: &TestAppContext) {
         let request = LanguageModelRequest {
             thread_id: None,
+            profile_id: None,
             prompt_id: None,
             intent: None,
             mode: None,
@@ -999,6 +1001,7 @@ mod tests {
             tool_choice: None,
             stop: vec![],
             temperature: None,
+            max_tokens: None,
             thinking_allowed: true,
         };
 
@@ -444,6 +444,7 @@ pub fn into_open_router(
     model: &Model,
     max_output_tokens: Option<u64>,
 ) -> open_router::Request {
+    let max_output_tokens = request.max_output_tokens(max_output_tokens);
     let mut messages = Vec::new();
     for message in request.messages {
         for content in message.content {
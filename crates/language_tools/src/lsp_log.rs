@@ -1283,6 +1283,8 @@ impl SearchableItem for LspLogView {
             case: true,
             word: true,
             regex: true,
+            structural: false,
+            syntax: false,
             find_in_results: false,
             // LSP log is read-only.
             replacement: false,
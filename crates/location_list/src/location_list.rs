@@ -0,0 +1,315 @@
+mod location_list_panel;
+mod sources;
+
+use std::path::PathBuf;
+
+use gpui::{App, SharedString, actions};
+use language::{DiagnosticSeverity, Location};
+use workspace::Workspace;
+
+pub use location_list_panel::LocationListPanel;
+
+actions!(
+    location_list,
+    [
+        /// Opens the location list panel.
+        Deploy,
+        /// Toggles focus on the location list panel.
+        ToggleFocus,
+        /// Fills a new location list with the errors and warnings of the project.
+        FromDiagnostics,
+        /// Fills a new location list with the matches of the project search.
+        FromSearchResults,
+        /// Fills a new location list with the problems found in the output of the task running in
+        /// the active terminal by its problem matchers.
+        FromTaskOutput,
+        /// Fills a new location list with the references to the symbol under the cursor.
+        FromReferences,
+        /// Fills a new location list from a file of `path:line:column: text` lines.
+        OpenFile,
+        /// Opens the next location in the current list. When there's no list yet, it's filled
+        /// with the diagnostics of the project first.
+        Next,
+        /// Opens the previous location in the current list.
+        Previous,
+        /// Opens the selected location in the current list.
+        OpenCurrent,
+        /// Switches to the previous, older list in the history.
+        Older,
+        /// Switches to the next, newer list in the history.
+        Newer,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        location_list_panel::register(workspace);
+    })
+    .detach();
+}
+
+/// The number of lists kept in the history, as in Vim.
+const MAX_HISTORY_LEN: usize = 10;
+
+/// A location in a [`LocationList`].
+#[derive(Clone, Debug)]
+pub struct LocationListEntry {
+    pub location: Location,
+    /// The path of the location's buffer, starting with the name of its worktree.
+    pub path: PathBuf,
+    pub text: String,
+    pub severity: Option<DiagnosticSeverity>,
+}
+
+impl LocationListEntry {
+    pub fn new(location: Location, text: impl Into<String>, cx: &App) -> Self {
+        let path = location
+            .buffer
+            .read(cx)
+            .file()
+            .map(|file| file.full_path(cx))
+            .unwrap_or_default();
+        Self {
+            location,
+            path,
+            text: text.into(),
+            severity: None,
+        }
+    }
+
+    pub fn with_severity(mut self, severity: DiagnosticSeverity) -> Self {
+        self.severity = Some(severity);
+        self
+    }
+
+    fn matches(&self, filter: &str) -> bool {
+        filter.is_empty()
+            || self.text.to_lowercase().contains(filter)
+            || self.path.to_string_lossy().to_lowercase().contains(filter)
+    }
+}
+
+/// A titled list of locations, with a selected entry and a filter narrowing the entries that
+/// are shown and navigated.
+#[derive(Clone, Debug)]
+pub struct LocationList {
+    pub title: SharedString,
+    entries: Vec<LocationListEntry>,
+    selected: Option<usize>,
+    filter: String,
+    visible_entries: Vec<usize>,
+}
+
+impl LocationList {
+    pub fn new(title: impl Into<SharedString>, entries: Vec<LocationListEntry>) -> Self {
+        let visible_entries = (0..entries.len()).collect();
+        Self {
+            title: title.into(),
+            entries,
+            selected: None,
+            filter: String::new(),
+            visible_entries,
+        }
+    }
+
+    pub fn entries(&self) -> &[LocationListEntry] {
+        &self.entries
+    }
+
+    /// The indices of the entries that match the filter.
+    pub fn visible_entries(&self) -> &[usize] {
+        &self.visible_entries
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn selected_entry(&self) -> Option<&LocationListEntry> {
+        self.entries.get(self.selected?)
+    }
+
+    pub fn select(&mut self, ix: usize) {
+        if ix < self.entries.len() {
+            self.selected = Some(ix);
+        }
+    }
+
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    /// Only shows and navigates the entries whose path or text contain the filter, ignoring case.
+    pub fn set_filter(&mut self, filter: &str) {
+        self.filter = filter.to_string();
+        let filter = filter.to_lowercase();
+        self.visible_entries = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.matches(&filter))
+            .map(|(ix, _)| ix)
+            .collect();
+    }
+
+    /// Selects the first visible entry after the selected one, or the first visible entry when
+    /// none is selected. Like Vim, it stops at the end of the list rather than wrapping.
+    pub fn select_next(&mut self) -> Option<usize> {
+        let next = match self.selected {
+            Some(selected) => self
+                .visible_entries
+                .iter()
+                .copied()
+                .find(|ix| *ix > selected)?,
+            None => self.visible_entries.first().copied()?,
+        };
+        self.selected = Some(next);
+        Some(next)
+    }
+
+    /// Selects the last visible entry before the selected one.
+    pub fn select_previous(&mut self) -> Option<usize> {
+        let selected = self.selected?;
+        let previous = self
+            .visible_entries
+            .iter()
+            .copied()
+            .rfind(|ix| *ix < selected)?;
+        self.selected = Some(previous);
+        Some(previous)
+    }
+}
+
+/// The lists filled most recently, navigated with `:colder` and `:cnewer` in Vim.
+#[derive(Default)]
+pub struct LocationListHistory {
+    lists: Vec<LocationList>,
+    current: usize,
+}
+
+impl LocationListHistory {
+    /// Adds a list after the current one and makes it current. Like Vim, the lists newer than
+    /// the current one are dropped, as are the oldest lists beyond [`MAX_HISTORY_LEN`].
+    pub fn push(&mut self, list: LocationList) {
+        if !self.lists.is_empty() {
+            self.lists.truncate(self.current + 1);
+        }
+        self.lists.push(list);
+        if self.lists.len() > MAX_HISTORY_LEN {
+            self.lists.remove(0);
+        }
+        self.current = self.lists.len() - 1;
+    }
+
+    pub fn current(&self) -> Option<&LocationList> {
+        self.lists.get(self.current)
+    }
+
+    pub fn current_mut(&mut self) -> Option<&mut LocationList> {
+        self.lists.get_mut(self.current)
+    }
+
+    /// The position of the current list in the history, starting at one for the oldest list.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        (!self.lists.is_empty()).then(|| (self.current + 1, self.lists.len()))
+    }
+
+    pub fn older(&mut self) -> bool {
+        if self.current == 0 {
+            return false;
+        }
+        self.current -= 1;
+        true
+    }
+
+    pub fn newer(&mut self) -> bool {
+        if self.current + 1 >= self.lists.len() {
+            return false;
+        }
+        self.current += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use language::{Buffer, Point};
+
+    fn list(title: &str, lines: &[&str], cx: &mut TestAppContext) -> LocationList {
+        let buffer = cx.new(|cx| Buffer::local(lines.join("\n"), cx));
+        cx.update(|cx| {
+            let snapshot = buffer.read(cx).snapshot();
+            let entries = lines
+                .iter()
+                .enumerate()
+                .map(|(row, line)| {
+                    let anchor = snapshot.anchor_before(Point::new(row as u32, 0));
+                    let location = Location {
+                        buffer: buffer.clone(),
+                        range: anchor..anchor,
+                    };
+                    LocationListEntry::new(location, *line, cx)
+                })
+                .collect();
+            LocationList::new(title.to_string(), entries)
+        })
+    }
+
+    #[gpui::test]
+    fn test_navigation_with_filter(cx: &mut TestAppContext) {
+        let mut list = list(
+            "Diagnostics",
+            &["unused import", "mismatched types", "unused variable", "x"],
+            cx,
+        );
+        assert_eq!(list.select_previous(), None);
+        assert_eq!(list.select_next(), Some(0));
+        assert_eq!(list.select_next(), Some(1));
+
+        list.set_filter("UNUSED");
+        assert_eq!(list.visible_entries(), &[0, 2]);
+        assert_eq!(list.select_next(), Some(2));
+        assert_eq!(list.select_next(), None);
+        assert_eq!(list.selected(), Some(2));
+        assert_eq!(list.select_previous(), Some(0));
+        assert_eq!(list.select_previous(), None);
+
+        list.set_filter("");
+        assert_eq!(list.visible_entries(), &[0, 1, 2, 3]);
+        assert_eq!(list.select_next(), Some(1));
+    }
+
+    #[gpui::test]
+    fn test_history(cx: &mut TestAppContext) {
+        let mut history = LocationListHistory::default();
+        assert!(history.current().is_none());
+        assert!(!history.older());
+        assert!(!history.newer());
+
+        for title in ["first", "second", "third"] {
+            history.push(list(title, &["a"], cx));
+        }
+        assert_eq!(history.position(), Some((3, 3)));
+        assert!(history.older());
+        assert!(history.older());
+        assert!(!history.older());
+        assert_eq!(history.current().unwrap().title, "first");
+        assert!(history.newer());
+        assert_eq!(history.current().unwrap().title, "second");
+
+        // Filling a list from an older one drops the newer lists.
+        history.push(list("fourth", &["a"], cx));
+        assert_eq!(history.position(), Some((3, 3)));
+        assert!(history.older());
+        assert_eq!(history.current().unwrap().title, "second");
+
+        for ix in 0..MAX_HISTORY_LEN {
+            history.push(list(&ix.to_string(), &["a"], cx));
+        }
+        assert_eq!(history.position(), Some((MAX_HISTORY_LEN, MAX_HISTORY_LEN)));
+        while history.older() {}
+        assert_eq!(history.current().unwrap().title, "0");
+    }
+}
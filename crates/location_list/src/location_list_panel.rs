@@ -0,0 +1,521 @@
+use std::ops::Range;
+
+use anyhow::Result;
+use editor::{Editor, EditorEvent};
+use file_icons::FileIcons;
+use gpui::{
+    Action, App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable, Pixels,
+    Render, ScrollStrategy, Subscription, Task, UniformListScrollHandle, WeakEntity, Window, px,
+    uniform_list,
+};
+use language::{DiagnosticSeverity, ToPoint as _};
+use project::{DirectoryLister, PathPromptOptions};
+use ui::{ListItem, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+    notifications::DetachAndPromptErr,
+};
+
+use crate::{
+    Deploy, FromDiagnostics, FromReferences, FromSearchResults, FromTaskOutput, LocationList,
+    LocationListHistory, Newer, Next, Older, OpenCurrent, OpenFile, Previous, ToggleFocus, sources,
+};
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+        workspace.toggle_panel_focus::<LocationListPanel>(window, cx);
+    });
+    workspace.register_action(|workspace, _: &Deploy, window, cx| {
+        workspace.focus_panel::<LocationListPanel>(window, cx);
+    });
+    workspace.register_action(|workspace, _: &FromDiagnostics, window, cx| {
+        let list = sources::diagnostics(workspace, cx);
+        fill(workspace, list, window, cx);
+    });
+    workspace.register_action(|workspace, _: &FromSearchResults, window, cx| {
+        if let Some(list) = sources::search_results(workspace, cx) {
+            fill(workspace, Task::ready(Ok(list)), window, cx);
+        }
+    });
+    workspace.register_action(|workspace, _: &FromTaskOutput, window, cx| {
+        if let Some(list) = sources::task_output(workspace, cx) {
+            fill(workspace, list, window, cx);
+        }
+    });
+    workspace.register_action(|workspace, _: &FromReferences, window, cx| {
+        if let Some(list) = sources::references(workspace, cx) {
+            fill(workspace, list, window, cx);
+        }
+    });
+    workspace.register_action(|workspace, _: &OpenFile, window, cx| {
+        if !workspace.project().read(cx).is_local() {
+            return;
+        }
+        let prompt = workspace.prompt_for_open_path(
+            PathPromptOptions {
+                files: true,
+                directories: false,
+                multiple: false,
+            },
+            DirectoryLister::Local(
+                workspace.project().clone(),
+                workspace.app_state().fs.clone(),
+            ),
+            window,
+            cx,
+        );
+        cx.spawn_in(window, async move |workspace, cx| {
+            let Some(abs_path) = prompt.await?.and_then(|mut paths| paths.pop()) else {
+                return anyhow::Ok(());
+            };
+            workspace.update_in(cx, |workspace, window, cx| {
+                let list = sources::file(workspace, abs_path, cx);
+                fill(workspace, list, window, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    });
+    workspace.register_action(|workspace, action: &Next, window, cx| {
+        if let Some(panel) = workspace.panel::<LocationListPanel>(cx) {
+            panel.update(cx, |panel, cx| panel.select_next(action, window, cx));
+        }
+    });
+    workspace.register_action(|workspace, action: &Previous, window, cx| {
+        if let Some(panel) = workspace.panel::<LocationListPanel>(cx) {
+            panel.update(cx, |panel, cx| panel.select_previous(action, window, cx));
+        }
+    });
+    workspace.register_action(|workspace, action: &OpenCurrent, window, cx| {
+        if let Some(panel) = workspace.panel::<LocationListPanel>(cx) {
+            panel.update(cx, |panel, cx| panel.open_current(action, window, cx));
+        }
+    });
+    workspace.register_action(|workspace, action: &Older, window, cx| {
+        if let Some(panel) = workspace.panel::<LocationListPanel>(cx) {
+            panel.update(cx, |panel, cx| panel.older(action, window, cx));
+        }
+    });
+    workspace.register_action(|workspace, action: &Newer, window, cx| {
+        if let Some(panel) = workspace.panel::<LocationListPanel>(cx) {
+            panel.update(cx, |panel, cx| panel.newer(action, window, cx));
+        }
+    });
+}
+
+/// Shows the list once it's filled, as the new current list of the panel.
+fn fill(
+    workspace: &mut Workspace,
+    list: Task<Result<LocationList>>,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(panel) = workspace.panel::<LocationListPanel>(cx) else {
+        return;
+    };
+    cx.spawn_in(window, async move |workspace, cx| {
+        let list = list.await?;
+        panel.update_in(cx, |panel, window, cx| panel.push_list(list, window, cx))?;
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.open_panel::<LocationListPanel>(window, cx);
+        })
+    })
+    .detach_and_prompt_err("Failed to fill the location list", window, cx, |_, _, _| {
+        None
+    });
+}
+
+pub struct LocationListPanel {
+    workspace: WeakEntity<Workspace>,
+    focus_handle: FocusHandle,
+    position: DockPosition,
+    size: Option<Pixels>,
+    history: LocationListHistory,
+    filter_editor: Entity<Editor>,
+    scroll_handle: UniformListScrollHandle,
+    pending_fill: Option<Task<()>>,
+    _subscription: Subscription,
+}
+
+impl LocationListPanel {
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: &mut AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            workspace.update_in(cx, |_, window, cx| {
+                let workspace = cx.entity().downgrade();
+                cx.new(|cx| Self::new(workspace, window, cx))
+            })
+        })
+    }
+
+    fn new(workspace: WeakEntity<Workspace>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let filter_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Filter locations…", cx);
+            editor
+        });
+        let subscription = cx.subscribe_in(&filter_editor, window, |this, _, event, _, cx| {
+            if let EditorEvent::BufferEdited = event {
+                let filter = this.filter_editor.read(cx).text(cx);
+                if let Some(list) = this.history.current_mut() {
+                    list.set_filter(&filter);
+                }
+                cx.notify();
+            }
+        });
+        Self {
+            workspace,
+            focus_handle: cx.focus_handle(),
+            position: DockPosition::Bottom,
+            size: None,
+            history: LocationListHistory::default(),
+            filter_editor,
+            scroll_handle: UniformListScrollHandle::new(),
+            pending_fill: None,
+            _subscription: subscription,
+        }
+    }
+
+    pub fn current_list(&self) -> Option<&LocationList> {
+        self.history.current()
+    }
+
+    /// Adds a list to the history and makes it the current list.
+    pub fn push_list(&mut self, list: LocationList, window: &mut Window, cx: &mut Context<Self>) {
+        self.history.push(list);
+        self.current_list_changed(window, cx);
+    }
+
+    fn current_list_changed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let filter = self
+            .history
+            .current()
+            .map(|list| list.filter().to_string())
+            .unwrap_or_default();
+        self.filter_editor.update(cx, |editor, cx| {
+            editor.set_text(filter, window, cx);
+        });
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &Next, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(list) = self.history.current_mut() else {
+            self.fill_from_diagnostics_and_open(window, cx);
+            return;
+        };
+        if let Some(ix) = list.select_next() {
+            self.open_entry(ix, window, cx);
+        }
+    }
+
+    fn select_previous(&mut self, _: &Previous, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(list) = self.history.current_mut() else {
+            return;
+        };
+        if let Some(ix) = list.select_previous() {
+            self.open_entry(ix, window, cx);
+        }
+    }
+
+    fn open_current(&mut self, _: &OpenCurrent, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(list) = self.history.current_mut() else {
+            return;
+        };
+        let Some(ix) = list.selected().or_else(|| list.select_next()) else {
+            return;
+        };
+        self.open_entry(ix, window, cx);
+    }
+
+    fn older(&mut self, _: &Older, window: &mut Window, cx: &mut Context<Self>) {
+        if self.history.older() {
+            self.current_list_changed(window, cx);
+        }
+    }
+
+    fn newer(&mut self, _: &Newer, window: &mut Window, cx: &mut Context<Self>) {
+        if self.history.newer() {
+            self.current_list_changed(window, cx);
+        }
+    }
+
+    fn fill_from_diagnostics_and_open(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.pending_fill.is_some() {
+            return;
+        }
+        let Some(list) = self
+            .workspace
+            .read_with(cx, |workspace, cx| sources::diagnostics(workspace, cx))
+            .log_err()
+        else {
+            return;
+        };
+        self.pending_fill = Some(cx.spawn_in(window, async move |this, cx| {
+            let list = list.await.log_err();
+            this.update_in(cx, |this, window, cx| {
+                this.pending_fill = None;
+                if let Some(list) = list {
+                    this.push_list(list, window, cx);
+                    this.select_next(&Next, window, cx);
+                }
+            })
+            .log_err();
+        }));
+    }
+
+    fn open_entry(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(list) = self.history.current_mut() else {
+            return;
+        };
+        list.select(ix);
+        let Some(entry) = list.entries().get(ix) else {
+            return;
+        };
+        if let Some(visible_ix) = list.visible_entries().iter().position(|entry| *entry == ix) {
+            self.scroll_handle
+                .scroll_to_item(visible_ix, ScrollStrategy::Center);
+        }
+        let location = entry.location.clone();
+        cx.notify();
+
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let range = {
+                    let snapshot = location.buffer.read(cx).snapshot();
+                    location.range.start.to_point(&snapshot)..location.range.end.to_point(&snapshot)
+                };
+                let pane = workspace.active_pane().clone();
+                let editor = workspace.open_project_item::<Editor>(
+                    pane,
+                    location.buffer,
+                    true,
+                    true,
+                    window,
+                    cx,
+                );
+                editor.update(cx, |editor, cx| {
+                    editor.go_to_singleton_buffer_range(range, window, cx);
+                });
+            })
+            .log_err();
+    }
+
+    fn render_entries(
+        &mut self,
+        range: Range<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let Some(list) = self.history.current() else {
+            return Vec::new();
+        };
+        list.visible_entries()[range]
+            .iter()
+            .filter_map(|ix| {
+                let ix = *ix;
+                let entry = list.entries().get(ix)?;
+                let position = entry
+                    .location
+                    .range
+                    .start
+                    .to_point(entry.location.buffer.read(cx));
+                let start_slot = match entry.severity {
+                    Some(severity) => severity_icon(severity),
+                    None => match FileIcons::get_icon(&entry.path, cx) {
+                        Some(icon) => Icon::from_path(icon).color(Color::Muted),
+                        None => Icon::new(IconName::File).color(Color::Muted),
+                    },
+                };
+                Some(
+                    ListItem::new(("location", ix))
+                        .toggle_state(list.selected() == Some(ix))
+                        .on_click(
+                            cx.listener(move |this, _, window, cx| this.open_entry(ix, window, cx)),
+                        )
+                        .start_slot(start_slot.size(IconSize::Small))
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(
+                                    Label::new(format!(
+                                        "{}:{}:{}",
+                                        entry.path.display(),
+                                        position.row + 1,
+                                        position.column + 1
+                                    ))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                                )
+                                .child(Label::new(entry.text.clone()).truncate()),
+                        )
+                        .into_any_element(),
+                )
+            })
+            .collect()
+    }
+
+    fn render_header(&self, list: &LocationList, cx: &mut Context<Self>) -> impl IntoElement {
+        let (position, len) = self.history.position().unwrap_or_default();
+        let count = if list.filter().is_empty() {
+            format!("{} locations", list.entries().len())
+        } else {
+            format!(
+                "{} of {} locations",
+                list.visible_entries().len(),
+                list.entries().len()
+            )
+        };
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new("older-location-list", IconName::ArrowLeft)
+                            .icon_size(IconSize::Small)
+                            .disabled(position <= 1)
+                            .tooltip(Tooltip::for_action_title("Older List", &Older))
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.older(&Older, window, cx)),
+                            ),
+                    )
+                    .child(
+                        IconButton::new("newer-location-list", IconName::ArrowRight)
+                            .icon_size(IconSize::Small)
+                            .disabled(position >= len)
+                            .tooltip(Tooltip::for_action_title("Newer List", &Newer))
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.newer(&Newer, window, cx)),
+                            ),
+                    )
+                    .child(Label::new(list.title.clone()).size(LabelSize::Small))
+                    .child(
+                        Label::new(format!("{count} · list {position} of {len}"))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(div().w(rems(16.)).child(self.filter_editor.clone()))
+    }
+}
+
+fn severity_icon(severity: DiagnosticSeverity) -> Icon {
+    match severity {
+        DiagnosticSeverity::ERROR => Icon::new(IconName::XCircle).color(Color::Error),
+        DiagnosticSeverity::WARNING => Icon::new(IconName::Warning).color(Color::Warning),
+        _ => Icon::new(IconName::Info).color(Color::Muted),
+    }
+}
+
+impl EventEmitter<PanelEvent> for LocationListPanel {}
+
+impl Focusable for LocationListPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for LocationListPanel {
+    fn persistent_name() -> &'static str {
+        "LocationListPanel"
+    }
+
+    fn position(&self, _: &Window, _: &App) -> DockPosition {
+        self.position
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        self.position = position;
+        cx.notify();
+    }
+
+    fn size(&self, _: &Window, _: &App) -> Pixels {
+        self.size.unwrap_or(px(240.))
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.size = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, _: &App) -> Option<IconName> {
+        Some(IconName::ListTree)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Location List Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        11
+    }
+}
+
+impl Render for LocationListPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = match self.history.current() {
+            None => v_flex()
+                .size_full()
+                .items_center()
+                .justify_center()
+                .gap_2()
+                .child(
+                    Label::new(
+                        "Fill a location list from diagnostics, search results, task output or references",
+                    )
+                    .color(Color::Muted),
+                )
+                .child(
+                    Button::new("location-list-from-diagnostics", "Load Diagnostics")
+                        .label_size(LabelSize::Small)
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(FromDiagnostics.boxed_clone(), cx)
+                        }),
+                )
+                .into_any_element(),
+            Some(list) => v_flex()
+                .size_full()
+                .child(self.render_header(list, cx))
+                .child(
+                    uniform_list(
+                        "location-list",
+                        list.visible_entries().len(),
+                        cx.processor(Self::render_entries),
+                    )
+                    .track_scroll(self.scroll_handle.clone())
+                    .flex_1()
+                    .size_full(),
+                )
+                .into_any_element(),
+        };
+
+        v_flex()
+            .id("location-list-panel")
+            .key_context("LocationListPanel")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::open_current))
+            .on_action(cx.listener(Self::older))
+            .on_action(cx.listener(Self::newer))
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(content)
+    }
+}
@@ -0,0 +1,230 @@
+use std::path::PathBuf;
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashSet;
+use editor::Editor;
+use gpui::{App, Entity, Task};
+use language::{
+    Anchor, BufferSnapshot, DiagnosticSeverity, LanguageServerId, Location, ToPoint as _,
+};
+use project::Project;
+use search::{ProjectSearchView, quickfix::QuickfixEntry};
+use terminal_view::{TerminalView, terminal_panel::TerminalPanel};
+use util::ResultExt as _;
+use workspace::Workspace;
+
+use crate::{LocationList, LocationListEntry};
+
+/// Fills a list with the primary errors and warnings reported for the project.
+pub(crate) fn diagnostics(workspace: &Workspace, cx: &App) -> Task<Result<LocationList>> {
+    diagnostic_entries(
+        workspace.project().clone(),
+        "Diagnostics".to_string(),
+        None,
+        cx,
+    )
+}
+
+/// Fills a list with the matches of the active project search, or of the first one open.
+pub(crate) fn search_results(workspace: &Workspace, cx: &App) -> Option<LocationList> {
+    let search_view = workspace
+        .active_item_as::<ProjectSearchView>(cx)
+        .or_else(|| workspace.items_of_type::<ProjectSearchView>(cx).next())?;
+    let search_view = search_view.read(cx);
+    let entries = search_view
+        .match_locations(cx)
+        .into_iter()
+        .map(|location| {
+            let text = line_text(&location.buffer.read(cx).snapshot(), location.range.start);
+            LocationListEntry::new(location, text, cx)
+        })
+        .collect();
+    let title = format!("Search: {}", search_view.search_query_text(cx));
+    Some(LocationList::new(title, entries))
+}
+
+/// Fills a list with the errors and warnings found by the problem matchers of the task running
+/// in the active terminal.
+pub(crate) fn task_output(workspace: &Workspace, cx: &App) -> Option<Task<Result<LocationList>>> {
+    let terminal_view = workspace.active_item_as::<TerminalView>(cx).or_else(|| {
+        workspace
+            .panel::<TerminalPanel>(cx)?
+            .read(cx)
+            .active_terminal_view(cx)
+    })?;
+    let label = terminal_view
+        .read(cx)
+        .terminal()
+        .read(cx)
+        .task()?
+        .label
+        .clone();
+    let project = workspace.project().clone();
+    let server_id = project
+        .read(cx)
+        .lsp_store()
+        .read(cx)
+        .task_diagnostics_server(&label);
+    let Some(server_id) = server_id else {
+        return Some(Task::ready(Err(anyhow!(
+            "{label} has no problem matchers to find locations in its output"
+        ))));
+    };
+    Some(diagnostic_entries(
+        project,
+        format!("Output of {label}"),
+        Some(server_id),
+        cx,
+    ))
+}
+
+/// Fills a list with the references to the symbol under the newest cursor of the active editor.
+pub(crate) fn references(
+    workspace: &Workspace,
+    cx: &mut App,
+) -> Option<Task<Result<LocationList>>> {
+    let editor = workspace.active_item_as::<Editor>(cx)?;
+    let head = editor.read(cx).selections.newest_anchor().head();
+    let (buffer, position) = editor
+        .read(cx)
+        .buffer()
+        .read(cx)
+        .text_anchor_for_position(head, cx)?;
+    let snapshot = buffer.read(cx).snapshot();
+    let (word_range, _) = snapshot.surrounding_word(position, false);
+    let word = snapshot.text_for_range(word_range).collect::<String>();
+    let references = workspace
+        .project()
+        .update(cx, |project, cx| project.references(&buffer, position, cx));
+    Some(cx.spawn(async move |cx| {
+        let locations = references.await?;
+        cx.update(|cx| {
+            let entries = locations
+                .into_iter()
+                .map(|location| {
+                    let text =
+                        line_text(&location.buffer.read(cx).snapshot(), location.range.start);
+                    LocationListEntry::new(location, text, cx)
+                })
+                .collect();
+            LocationList::new(format!("References to {word}"), entries)
+        })
+    }))
+}
+
+/// Fills a list from a file of `path:line:column: text` lines.
+pub(crate) fn file(
+    workspace: &Workspace,
+    abs_path: PathBuf,
+    cx: &App,
+) -> Task<Result<LocationList>> {
+    let fs = workspace.app_state().fs.clone();
+    let project = workspace.project().clone();
+    cx.spawn(async move |cx| {
+        let contents = fs
+            .load(&abs_path)
+            .await
+            .with_context(|| format!("loading {abs_path:?}"))?;
+        let title = abs_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        cx.update(|cx| quickfix_entries(project, title, QuickfixEntry::parse_all(&contents), cx))?
+            .await
+    })
+}
+
+/// Lists the primary errors and warnings of the project, only the ones reported by the given
+/// language server if there is one.
+fn diagnostic_entries(
+    project: Entity<Project>,
+    title: String,
+    server_id: Option<LanguageServerId>,
+    cx: &App,
+) -> Task<Result<LocationList>> {
+    let mut seen_paths = HashSet::default();
+    let paths = project
+        .read(cx)
+        .diagnostic_summaries(false, cx)
+        .filter(|(_, path_server_id, summary)| {
+            server_id.is_none_or(|server_id| server_id == *path_server_id)
+                && summary.error_count + summary.warning_count > 0
+        })
+        .map(|(path, _, _)| path)
+        .filter(|path| seen_paths.insert(path.clone()))
+        .collect::<Vec<_>>();
+    cx.spawn(async move |cx| {
+        let mut entries = Vec::new();
+        for path in paths {
+            let Some(buffer) = project
+                .update(cx, |project, cx| project.open_buffer(path, cx))?
+                .await
+                .log_err()
+            else {
+                continue;
+            };
+            let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+            cx.update(|cx| {
+                for (_, group) in snapshot.diagnostic_groups(server_id) {
+                    let diagnostic = &group.entries[group.primary_ix];
+                    let severity = diagnostic.diagnostic.severity;
+                    if severity > DiagnosticSeverity::WARNING {
+                        continue;
+                    }
+                    let message = diagnostic
+                        .diagnostic
+                        .message
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string();
+                    let location = Location {
+                        buffer: buffer.clone(),
+                        range: diagnostic.range.clone(),
+                    };
+                    entries.push(
+                        LocationListEntry::new(location, message, cx).with_severity(severity),
+                    );
+                }
+            })?;
+        }
+        if server_id.is_some() && entries.is_empty() {
+            return Err(anyhow!("{title} contains no locations in this project"));
+        }
+        Ok(LocationList::new(title, entries))
+    })
+}
+
+fn quickfix_entries(
+    project: Entity<Project>,
+    title: String,
+    entries: Vec<QuickfixEntry>,
+    cx: &App,
+) -> Task<Result<LocationList>> {
+    let locations = search::quickfix::resolve_entries(project, entries, cx);
+    cx.spawn(async move |cx| {
+        let locations = locations.await;
+        if locations.is_empty() {
+            return Err(anyhow!("{title} contains no locations in this project"));
+        }
+        cx.update(|cx| {
+            let entries = locations
+                .into_iter()
+                .map(|(entry, location)| LocationListEntry::new(location, entry.text, cx))
+                .collect();
+            LocationList::new(title, entries)
+        })
+    })
+}
+
+fn line_text(snapshot: &BufferSnapshot, position: Anchor) -> String {
+    let row = position.to_point(snapshot).row;
+    snapshot
+        .text_for_range(
+            language::Point::new(row, 0)..language::Point::new(row, snapshot.line_len(row)),
+        )
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
@@ -0,0 +1,649 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result};
+use editor::{Editor, EditorEvent, SelectionEffects, scroll::Autoscroll};
+use fs::Fs;
+use futures::StreamExt as _;
+use gpui::{
+    App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable, HighlightStyle,
+    PathPromptOptions, Subscription, Task, WeakEntity, Window, actions,
+};
+use language::{Buffer, BufferSnapshot, Capability, Point, language_settings::SoftWrap};
+use multi_buffer::Anchor;
+use regex::Regex;
+use text::LineEnding;
+use ui::{Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    DirectoryLister, Workspace,
+    item::{Item, ItemEvent},
+};
+
+actions!(
+    log_viewer,
+    [
+        /// Prompts for a file and opens it in a read-only log viewer that follows appends.
+        OpenFile,
+        /// Opens the active file in a read-only log viewer that follows appends.
+        OpenActiveFile,
+        /// Toggles scrolling to the bottom of the log when lines are appended.
+        ToggleFollow
+    ]
+);
+
+const WATCH_LATENCY: Duration = Duration::from_millis(100);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        LogViewer::register(workspace);
+    })
+    .detach();
+}
+
+/// A read-only view of a log file, which appends the lines written to the file as it grows
+/// instead of reloading it.
+pub struct LogViewer {
+    abs_path: PathBuf,
+    buffer: Entity<Buffer>,
+    editor: Entity<Editor>,
+    filter_editor: Entity<Editor>,
+    filter: Option<Regex>,
+    filter_error: Option<SharedString>,
+    /// The last row matching the filter, from which the fold of the trailing non-matching rows starts.
+    last_matching_row: Option<u32>,
+    level_ranges: [Vec<Range<Anchor>>; LogLevel::COUNT],
+    /// The number of bytes of the file that have been appended to the buffer. Only complete lines
+    /// are appended, so a line being written is picked up once its newline is.
+    loaded_len: u64,
+    follow: bool,
+    focus_handle: FocusHandle,
+    _watch_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl LogViewer {
+    fn register(workspace: &mut Workspace) {
+        workspace.register_action(|workspace, _: &OpenActiveFile, window, cx| {
+            let Some(abs_path) = workspace
+                .active_item_as::<Editor>(cx)
+                .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+                .and_then(|buffer| project::File::from_dyn(buffer.read(cx).file()))
+                .map(|file| file.abs_path(cx))
+            else {
+                return;
+            };
+            Self::open(workspace, abs_path, window, cx);
+        });
+        workspace.register_action(|workspace, _: &OpenFile, window, cx| {
+            let prompt = workspace.prompt_for_open_path(
+                PathPromptOptions {
+                    files: true,
+                    directories: false,
+                    multiple: false,
+                },
+                DirectoryLister::Local(
+                    workspace.project().clone(),
+                    workspace.app_state().fs.clone(),
+                ),
+                window,
+                cx,
+            );
+            cx.spawn_in(window, async move |workspace, cx| {
+                let Some(abs_path) = prompt.await?.and_then(|mut paths| paths.pop()) else {
+                    return anyhow::Ok(());
+                };
+                workspace.update_in(cx, |workspace, window, cx| {
+                    Self::open(workspace, abs_path, window, cx);
+                })
+            })
+            .detach_and_log_err(cx);
+        });
+    }
+
+    fn open(
+        workspace: &mut Workspace,
+        abs_path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        if !workspace.project().read(cx).is_local() {
+            return;
+        }
+        let existing = workspace
+            .active_pane()
+            .read(cx)
+            .items_of_type::<LogViewer>()
+            .find(|viewer| viewer.read(cx).abs_path == abs_path);
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+        let fs = workspace.app_state().fs.clone();
+        let viewer = cx.new(|cx| LogViewer::new(abs_path, fs, window, cx));
+        workspace.add_item_to_active_pane(Box::new(viewer), None, true, window, cx);
+    }
+
+    pub fn new(
+        abs_path: PathBuf,
+        fs: Arc<dyn Fs>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let buffer = cx.new(|cx| {
+            let mut buffer = Buffer::local("", cx);
+            buffer.set_capability(Capability::ReadOnly, cx);
+            buffer
+        });
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::for_buffer(buffer.clone(), None, window, cx);
+            editor.hide_minimap_by_default(window, cx);
+            editor.set_show_git_diff_gutter(false, cx);
+            editor.set_show_runnables(false, cx);
+            editor.set_show_breakpoints(false, cx);
+            editor.set_show_edit_predictions(Some(false), window, cx);
+            editor.set_soft_wrap_mode(SoftWrap::None, cx);
+            editor
+        });
+        let filter_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Filter lines by regex…", cx);
+            editor
+        });
+        let filter_subscription =
+            cx.subscribe_in(&filter_editor, window, |this, _, event, window, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    let query = this.filter_editor.read(cx).text(cx);
+                    this.set_filter(&query, window, cx);
+                }
+            });
+
+        let focus_handle = cx.focus_handle();
+        let focus_subscription = cx.on_focus(&focus_handle, window, |this, window, cx| {
+            window.focus(&this.editor.focus_handle(cx));
+        });
+
+        let watch_task = cx.spawn_in(window, {
+            let abs_path = abs_path.clone();
+            async move |this, cx| {
+                let (mut events, _watcher) = fs.watch(&abs_path, WATCH_LATENCY).await;
+                Self::load_appended_lines(&this, fs.as_ref(), &abs_path, cx)
+                    .await
+                    .log_err();
+                while events.next().await.is_some() {
+                    if Self::load_appended_lines(&this, fs.as_ref(), &abs_path, cx)
+                        .await
+                        .log_err()
+                        .is_none()
+                    {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Self {
+            abs_path,
+            buffer,
+            editor,
+            filter_editor,
+            filter: None,
+            filter_error: None,
+            last_matching_row: None,
+            level_ranges: Default::default(),
+            loaded_len: 0,
+            follow: true,
+            focus_handle,
+            _watch_task: watch_task,
+            _subscriptions: vec![filter_subscription, focus_subscription],
+        }
+    }
+
+    pub fn abs_path(&self) -> &Path {
+        &self.abs_path
+    }
+
+    async fn load_appended_lines(
+        this: &WeakEntity<Self>,
+        fs: &dyn Fs,
+        abs_path: &Path,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<()> {
+        let Some(metadata) = fs.metadata(abs_path).await? else {
+            // The file was removed, e.g. while being rotated. Keep showing what was read until
+            // it is created again.
+            return Ok(());
+        };
+        let mut offset = this.read_with(cx, |this, _| this.loaded_len)?;
+        if metadata.len < offset {
+            // The file was truncated or replaced, so start over.
+            this.update_in(cx, |this, window, cx| this.clear(window, cx))?;
+            offset = 0;
+        }
+        if metadata.len == offset {
+            return Ok(());
+        }
+
+        let bytes = fs
+            .load_bytes_from(abs_path, offset)
+            .await
+            .with_context(|| format!("reading {abs_path:?}"))?;
+        let Some(last_newline) = bytes.iter().rposition(|byte| *byte == b'\n') else {
+            return Ok(());
+        };
+        let mut text = String::from_utf8_lossy(&bytes[..=last_newline]).into_owned();
+        LineEnding::normalize(&mut text);
+        this.update_in(cx, |this, window, cx| {
+            if this.loaded_len == offset {
+                this.loaded_len = offset + last_newline as u64 + 1;
+                this.append_lines(&text, window, cx);
+            }
+        })
+    }
+
+    fn clear(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.loaded_len = 0;
+        self.last_matching_row = None;
+        self.level_ranges = Default::default();
+        self.update_level_highlights(cx);
+        self.editor.update(cx, |editor, cx| {
+            editor.unfold_ranges(&[0..editor.buffer().read(cx).len(cx)], true, false, cx);
+            editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                s.select_ranges([0..0])
+            });
+        });
+        self.buffer.update(cx, |buffer, cx| {
+            let len = buffer.len();
+            Self::edit_without_history(buffer, 0..len, "", cx);
+        });
+    }
+
+    /// Appends complete lines of the file to the buffer, highlighting their log levels and
+    /// folding the ones that don't match the filter.
+    fn append_lines(&mut self, text: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let start_row = self.buffer.read(cx).max_point().row;
+        if self.filter.is_some() && start_row > 0 && self.last_matching_row != Some(start_row - 1) {
+            // Remove the fold of the trailing non-matching rows, so that it can be extended
+            // over the appended ones.
+            let snapshot = self.buffer.read(cx).snapshot();
+            let trailing_row_end = Point::new(start_row - 1, snapshot.line_len(start_row - 1));
+            self.editor.update(cx, |editor, cx| {
+                editor.unfold_ranges(&[trailing_row_end..trailing_row_end], true, false, cx);
+            });
+        }
+
+        self.buffer.update(cx, |buffer, cx| {
+            let len = buffer.len();
+            Self::edit_without_history(buffer, len..len, text, cx);
+        });
+        let snapshot = self.buffer.read(cx).snapshot();
+        let end_row = snapshot.max_point().row;
+
+        let multi_buffer_snapshot = self.editor.read(cx).buffer().read(cx).snapshot(cx);
+        for row in start_row..end_row {
+            let line = line_text(&snapshot, row);
+            if let Some(level) = LogLevel::detect(&line) {
+                let range = multi_buffer_snapshot.anchor_before(Point::new(row, 0))
+                    ..multi_buffer_snapshot.anchor_after(Point::new(row, line.len() as u32));
+                self.level_ranges[level as usize].push(range);
+            }
+        }
+        self.update_level_highlights(cx);
+
+        if let Some(filter) = &self.filter {
+            let fold_start_row = self.last_matching_row.map_or(0, |row| row + 1);
+            let (folds, last_matching_row) =
+                non_matching_folds(&snapshot, filter, fold_start_row..end_row);
+            self.last_matching_row = last_matching_row.or(self.last_matching_row);
+            self.editor.update(cx, |editor, cx| {
+                editor.fold_ranges(folds, false, window, cx);
+            });
+        }
+
+        if self.follow {
+            self.scroll_to_bottom(window, cx);
+        }
+    }
+
+    /// Edits the buffer without recording the edit in its undo history, which would otherwise
+    /// grow with every append.
+    fn edit_without_history(
+        buffer: &mut Buffer,
+        range: Range<usize>,
+        text: &str,
+        cx: &mut Context<Buffer>,
+    ) {
+        buffer.start_transaction();
+        buffer.edit([(range, text)], None, cx);
+        if let Some(transaction_id) = buffer.end_transaction(cx) {
+            buffer.forget_transaction(transaction_id);
+        }
+    }
+
+    fn update_level_highlights(&mut self, cx: &mut Context<Self>) {
+        let colors = cx.theme().status();
+        let styles = LogLevel::ALL.map(|level| {
+            let color = match level {
+                LogLevel::Error => colors.error,
+                LogLevel::Warning => colors.warning,
+                LogLevel::Debug => cx.theme().colors().text_muted,
+            };
+            HighlightStyle {
+                color: Some(color),
+                ..Default::default()
+            }
+        });
+        self.editor.update(cx, |editor, cx| {
+            for level in LogLevel::ALL {
+                editor.highlight_text_key::<LogLevelHighlight>(
+                    level as usize,
+                    self.level_ranges[level as usize].clone(),
+                    styles[level as usize],
+                    cx,
+                );
+            }
+        });
+    }
+
+    fn set_filter(&mut self, query: &str, window: &mut Window, cx: &mut Context<Self>) {
+        self.filter = None;
+        self.filter_error = None;
+        self.last_matching_row = None;
+        if !query.is_empty() {
+            match Regex::new(query) {
+                Ok(regex) => self.filter = Some(regex),
+                Err(error) => self.filter_error = Some(error.to_string().into()),
+            }
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot();
+        let folds = self.filter.as_ref().map(|filter| {
+            let (folds, last_matching_row) =
+                non_matching_folds(&snapshot, filter, 0..snapshot.max_point().row);
+            self.last_matching_row = last_matching_row;
+            folds
+        });
+        self.editor.update(cx, |editor, cx| {
+            editor.unfold_ranges(&[0..snapshot.len()], true, false, cx);
+            if let Some(folds) = folds {
+                editor.fold_ranges(folds, false, window, cx);
+            }
+        });
+        if self.follow {
+            self.scroll_to_bottom(window, cx);
+        }
+        cx.notify();
+    }
+
+    fn toggle_follow(&mut self, _: &ToggleFollow, window: &mut Window, cx: &mut Context<Self>) {
+        self.follow = !self.follow;
+        if self.follow {
+            self.scroll_to_bottom(window, cx);
+        }
+        cx.notify();
+    }
+
+    fn scroll_to_bottom(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            let end = editor.buffer().read(cx).len(cx);
+            editor.change_selections(
+                SelectionEffects::scroll(Autoscroll::bottom()),
+                window,
+                cx,
+                |s| s.select_ranges([end..end]),
+            );
+        });
+    }
+}
+
+enum LogLevelHighlight {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LogLevel {
+    Error,
+    Warning,
+    Debug,
+}
+
+impl LogLevel {
+    const COUNT: usize = 3;
+    const ALL: [LogLevel; Self::COUNT] = [LogLevel::Error, LogLevel::Warning, LogLevel::Debug];
+
+    /// Detects the level of a log line from the first level keyword in it. Info lines are left
+    /// unhighlighted.
+    fn detect(line: &str) -> Option<Self> {
+        for word in line.split(|c: char| !c.is_ascii_alphabetic()) {
+            match word {
+                "FATAL" | "CRITICAL" | "ERROR" => return Some(LogLevel::Error),
+                "WARN" | "WARNING" => return Some(LogLevel::Warning),
+                "DEBUG" | "TRACE" => return Some(LogLevel::Debug),
+                "INFO" => return None,
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+fn line_text(snapshot: &BufferSnapshot, row: u32) -> String {
+    snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .collect()
+}
+
+/// Returns the ranges to fold so that only the rows matching the filter remain visible, along
+/// with the last matching row. Each run of non-matching rows is folded into the end of the
+/// matching row before it, or into the start of the next row if it starts the buffer.
+fn non_matching_folds(
+    snapshot: &BufferSnapshot,
+    filter: &Regex,
+    rows: Range<u32>,
+) -> (Vec<Range<Point>>, Option<u32>) {
+    let mut folds = Vec::new();
+    let mut last_matching_row = None;
+    let mut run_start = None;
+    let mut push_fold = |run_start: u32, run_end: u32| {
+        folds.push(if run_start == 0 {
+            Point::zero()..Point::new(run_end + 1, 0)
+        } else {
+            Point::new(run_start - 1, snapshot.line_len(run_start - 1))
+                ..Point::new(run_end, snapshot.line_len(run_end))
+        });
+    };
+    for row in rows.clone() {
+        if filter.is_match(&line_text(snapshot, row)) {
+            if let Some(run_start) = run_start.take() {
+                push_fold(run_start, row - 1);
+            }
+            last_matching_row = Some(row);
+        } else if run_start.is_none() {
+            run_start = Some(row);
+        }
+    }
+    if let Some(run_start) = run_start {
+        push_fold(run_start, rows.end - 1);
+    }
+    (folds, last_matching_row)
+}
+
+impl Focusable for LogViewer {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<ItemEvent> for LogViewer {}
+
+impl Item for LogViewer {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::ScrollText))
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        self.abs_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned().into())
+            .unwrap_or_else(|| "Log".into())
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(self.abs_path.to_string_lossy().into_owned().into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+impl Render for LogViewer {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let border_color = if self.filter_error.is_some() {
+            cx.theme().status().error_border
+        } else {
+            cx.theme().colors().border
+        };
+        v_flex()
+            .key_context("LogViewer")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::toggle_follow))
+            .size_full()
+            .child(
+                h_flex()
+                    .p_1()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .bg(cx.theme().colors().toolbar_background)
+                    .child(Icon::new(IconName::Filter).color(Color::Muted))
+                    .child(
+                        h_flex()
+                            .flex_1()
+                            .px_1()
+                            .py_0p5()
+                            .border_1()
+                            .border_color(border_color)
+                            .rounded_sm()
+                            .child(self.filter_editor.clone()),
+                    )
+                    .children(self.filter_error.clone().map(|error| {
+                        Label::new(error)
+                            .size(LabelSize::Small)
+                            .color(Color::Error)
+                            .single_line()
+                    }))
+                    .child(
+                        IconButton::new("log-viewer-follow", IconName::ArrowDown)
+                            .toggle_state(self.follow)
+                            .tooltip(Tooltip::for_action_title("Follow", &ToggleFollow))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.toggle_follow(&ToggleFollow, window, cx)
+                            })),
+                    ),
+            )
+            .child(div().flex_1().child(self.editor.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::Project;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    #[test]
+    fn test_detect_log_level() {
+        assert_eq!(
+            LogLevel::detect("2024-01-01 ERROR failed to connect"),
+            Some(LogLevel::Error)
+        );
+        assert_eq!(
+            LogLevel::detect("[WARN] retrying after ERROR"),
+            Some(LogLevel::Warning)
+        );
+        assert_eq!(
+            LogLevel::detect("level=DEBUG msg=ok"),
+            Some(LogLevel::Debug)
+        );
+        assert_eq!(LogLevel::detect("INFO no ERROR here"), None);
+        assert_eq!(LogLevel::detect("ERRORS are not levels"), None);
+    }
+
+    #[gpui::test]
+    async fn test_following_appends(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+        });
+
+        let fs = fs::FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/logs"),
+            json!({ "app.log": "INFO started\nERROR failed\npartial" }),
+        )
+        .await;
+        let abs_path = PathBuf::from(path!("/logs/app.log"));
+
+        let window =
+            cx.add_window(|window, cx| LogViewer::new(abs_path.clone(), fs.clone(), window, cx));
+        let viewer = window.root(cx).unwrap();
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        cx.run_until_parked();
+        viewer.read_with(cx, |viewer, cx| {
+            assert_eq!(
+                viewer.buffer.read(cx).text(),
+                "INFO started\nERROR failed\n"
+            );
+            assert_eq!(viewer.level_ranges[LogLevel::Error as usize].len(), 1);
+        });
+
+        fs.write(
+            abs_path.as_path(),
+            b"INFO started\nERROR failed\npartial line\nWARN retrying\n",
+        )
+        .await
+        .unwrap();
+        cx.run_until_parked();
+        viewer.read_with(cx, |viewer, cx| {
+            assert_eq!(
+                viewer.buffer.read(cx).text(),
+                "INFO started\nERROR failed\npartial line\nWARN retrying\n"
+            );
+            assert_eq!(viewer.level_ranges[LogLevel::Warning as usize].len(), 1);
+        });
+
+        viewer.update_in(cx, |viewer, window, cx| {
+            viewer.set_filter("ERROR|WARN", window, cx);
+            assert_eq!(viewer.last_matching_row, Some(3));
+            viewer.editor.update(cx, |editor, cx| {
+                assert_eq!(editor.display_text(cx), "⋯ERROR failed⋯\nWARN retrying\n");
+            });
+        });
+
+        fs.write(abs_path.as_path(), b"INFO restarted\n")
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        viewer.read_with(cx, |viewer, cx| {
+            assert_eq!(viewer.buffer.read(cx).text(), "INFO restarted\n");
+            assert_eq!(viewer.last_matching_row, None);
+        });
+    }
+}
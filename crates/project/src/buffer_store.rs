@@ -1,6 +1,7 @@
 use crate::{
     ProjectItem as _, ProjectPath,
     lsp_store::OpenLspBufferHandle,
+    project_settings::ProjectSettings,
     search::SearchQuery,
     worktree_store::{WorktreeStore, WorktreeStoreEvent},
 };
@@ -23,6 +24,7 @@ use rpc::{
     AnyProtoClient, ErrorExt as _, TypedEnvelope,
     proto::{self, ToProto},
 };
+use settings::{Settings as _, SettingsLocation};
 use smol::channel::Receiver;
 use std::{io, path::Path, pin::pin, sync::Arc, time::Instant};
 use text::BufferId;
@@ -604,16 +606,28 @@ impl LocalBufferStore {
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<Entity<Buffer>>> {
         let load_buffer = worktree.update(cx, |worktree, cx| {
+            let large_files = ProjectSettings::get(
+                Some(SettingsLocation {
+                    worktree_id: worktree.id(),
+                    path: &path,
+                }),
+                cx,
+            )
+            .large_files;
             let load_file = worktree.load_file(path.as_ref(), cx);
             let reservation = cx.reserve_entity();
             let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
             cx.spawn(async move |_, cx| {
                 let loaded = load_file.await?;
+                let is_large = large_files.is_large(loaded.text.len());
                 let text_buffer = cx
                     .background_spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
                     .await;
-                cx.insert_entity(reservation, |_| {
-                    Buffer::build(text_buffer, Some(loaded.file), Capability::ReadWrite)
+                cx.insert_entity(reservation, |cx| {
+                    let mut buffer =
+                        Buffer::build(text_buffer, Some(loaded.file), Capability::ReadWrite);
+                    buffer.set_large_file_mode(is_large, cx);
+                    buffer
                 })
             })
         });
@@ -3,18 +3,31 @@ pub mod registry;
 
 use std::{path::Path, sync::Arc};
 
-use anyhow::{Context as _, Result};
+use anyhow::{Context as _, Result, anyhow};
 use collections::{HashMap, HashSet};
-use context_server::{ContextServer, ContextServerCommand, ContextServerId};
+use context_server::{
+    ContextServer, ContextServerCommand, ContextServerId, ContextServerUrl,
+    types::{
+        CreateElicitationParams, CreateElicitationResult, CreateMessageRequest,
+        CreateMessageResult, ListRootsResponse, ResourcesSubscribeParams, Root,
+        notifications::{ResourcesListChanged, ResourcesUpdated, RootsListChanged},
+        requests::{CreateElicitation, CreateMessage, ListRoots, ResourcesSubscribe},
+    },
+};
 use futures::{FutureExt as _, future::join_all};
-use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity, actions};
+use gpui::{
+    App, AsyncApp, Context, Entity, EventEmitter, SharedString, Subscription, Task, WeakEntity,
+    actions,
+};
 use registry::ContextServerDescriptorRegistry;
 use settings::{Settings as _, SettingsStore};
+use smol::channel::Sender;
+use url::Url;
 use util::ResultExt as _;
 
 use crate::{
     project_settings::{ContextServerSettings, ProjectSettings},
-    worktree_store::WorktreeStore,
+    worktree_store::{WorktreeStore, WorktreeStoreEvent},
 };
 
 pub fn init(cx: &mut App) {
@@ -98,13 +111,18 @@ pub enum ContextServerConfiguration {
         command: ContextServerCommand,
         settings: serde_json::Value,
     },
+    Http {
+        url: ContextServerUrl,
+    },
 }
 
 impl ContextServerConfiguration {
-    pub fn command(&self) -> &ContextServerCommand {
+    /// The command the server is run with, or `None` for servers reached over HTTP.
+    pub fn command(&self) -> Option<&ContextServerCommand> {
         match self {
-            ContextServerConfiguration::Custom { command } => command,
-            ContextServerConfiguration::Extension { command, .. } => command,
+            ContextServerConfiguration::Custom { command } => Some(command),
+            ContextServerConfiguration::Extension { command, .. } => Some(command),
+            ContextServerConfiguration::Http { .. } => None,
         }
     }
 
@@ -133,6 +151,9 @@ impl ContextServerConfiguration {
 
                 Some(ContextServerConfiguration::Extension { command, settings })
             }
+            ContextServerSettings::Http { enabled: _, url } => {
+                Some(ContextServerConfiguration::Http { url })
+            }
         }
     }
 }
@@ -156,6 +177,50 @@ pub enum Event {
         server_id: ContextServerId,
         status: ContextServerStatus,
     },
+    /// A server asked to sample the user's language model, which should be confirmed with the
+    /// user before responding.
+    SamplingRequested(ContextServerSamplingRequest),
+    /// A server asked the user for information.
+    ElicitationRequested(ContextServerElicitationRequest),
+    /// A server's resources or resource templates changed, and should be listed again.
+    ResourcesChanged { server_id: ContextServerId },
+    /// The contents of a resource the client subscribed to changed, and should be read again.
+    ResourceUpdated {
+        server_id: ContextServerId,
+        uri: SharedString,
+    },
+}
+
+/// A request from a context server for a completion of the given messages.
+///
+/// The request is answered with an error once every copy of it is dropped without a response.
+#[derive(Clone, Debug)]
+pub struct ContextServerSamplingRequest {
+    pub server_id: ContextServerId,
+    pub params: Arc<CreateMessageRequest>,
+    response_channel: Sender<Result<CreateMessageResult>>,
+}
+
+impl ContextServerSamplingRequest {
+    pub fn respond(&self, response: Result<CreateMessageResult>) {
+        self.response_channel.try_send(response).ok();
+    }
+}
+
+/// A request from a context server for the user to fill in the given fields.
+///
+/// The request is answered with an error once every copy of it is dropped without a response.
+#[derive(Clone, Debug)]
+pub struct ContextServerElicitationRequest {
+    pub server_id: ContextServerId,
+    pub params: Arc<CreateElicitationParams>,
+    response_channel: Sender<CreateElicitationResult>,
+}
+
+impl ContextServerElicitationRequest {
+    pub fn respond(&self, response: CreateElicitationResult) {
+        self.response_channel.try_send(response).ok();
+    }
 }
 
 impl EventEmitter<Event> for ContextServerStore {}
@@ -191,18 +256,12 @@ impl ContextServerStore {
 
     #[cfg(any(test, feature = "test-support"))]
     pub fn test_maintain_server_loop(
-        context_server_factory: ContextServerFactory,
+        context_server_factory: Option<ContextServerFactory>,
         registry: Entity<ContextServerDescriptorRegistry>,
         worktree_store: Entity<WorktreeStore>,
         cx: &mut Context<Self>,
     ) -> Self {
-        Self::new_internal(
-            true,
-            Some(context_server_factory),
-            registry,
-            worktree_store,
-            cx,
-        )
+        Self::new_internal(true, context_server_factory, registry, worktree_store, cx)
     }
 
     fn new_internal(
@@ -212,7 +271,7 @@ impl ContextServerStore {
         worktree_store: Entity<WorktreeStore>,
         cx: &mut Context<Self>,
     ) -> Self {
-        let subscriptions = if maintain_server_loop {
+        let mut subscriptions = if maintain_server_loop {
             vec![
                 cx.observe(&registry, |this, _registry, cx| {
                     this.available_context_servers_changed(cx);
@@ -229,6 +288,14 @@ impl ContextServerStore {
         } else {
             Vec::new()
         };
+        subscriptions.push(cx.subscribe(&worktree_store, |this, _, event, _| {
+            if matches!(
+                event,
+                WorktreeStoreEvent::WorktreeAdded(_) | WorktreeStoreEvent::WorktreeRemoved(..)
+            ) {
+                this.roots_changed();
+            }
+        }));
 
         let mut this = Self {
             _subscriptions: subscriptions,
@@ -360,7 +427,7 @@ impl ContextServerStore {
             let configuration = state.configuration();
 
             self.stop_server(&state.server().id(), cx)?;
-            let new_server = self.create_context_server(id.clone(), configuration.clone())?;
+            let new_server = self.create_context_server(id.clone(), configuration.clone(), cx)?;
             self.run_server(new_server, configuration, cx);
         }
         Ok(())
@@ -380,6 +447,7 @@ impl ContextServerStore {
             self.stop_server(&id, cx).log_err();
         }
 
+        self.register_handlers(&server, cx);
         let task = cx.spawn({
             let id = server.id();
             let server = server.clone();
@@ -445,18 +513,164 @@ impl ContextServerStore {
         Ok(())
     }
 
+    /// Serves the requests servers make of the editor: the roots of the project, and the sampling
+    /// and elicitation requests that are left to the UI to answer. Changes to a server's resources
+    /// are forwarded as events.
+    fn register_handlers(&self, server: &ContextServer, cx: &mut Context<Self>) {
+        let worktree_store = self.worktree_store.downgrade();
+        server.on_request::<ListRoots, _>(move |_, cx| {
+            let roots =
+                worktree_store.read_with(&cx, |worktree_store, cx| Self::roots(worktree_store, cx));
+            Task::ready(roots.map(|roots| ListRootsResponse { roots, meta: None }))
+        });
+
+        let this = cx.weak_entity();
+        let server_id = server.id();
+        server.on_request::<CreateMessage, _>({
+            let this = this.clone();
+            let server_id = server_id.clone();
+            move |params, mut cx| {
+                let (response_channel, response_rx) = smol::channel::bounded(1);
+                let request = ContextServerSamplingRequest {
+                    server_id: server_id.clone(),
+                    params: Arc::new(params),
+                    response_channel,
+                };
+                if let Err(error) =
+                    this.update(&mut cx, |_, cx| cx.emit(Event::SamplingRequested(request)))
+                {
+                    return Task::ready(Err(error));
+                }
+                cx.background_executor().spawn(async move {
+                    response_rx
+                        .recv()
+                        .await
+                        .map_err(|_| anyhow!("sampling request was dismissed"))?
+                })
+            }
+        });
+        server.on_request::<CreateElicitation, _>({
+            let this = this.clone();
+            let server_id = server_id.clone();
+            move |params, mut cx| {
+                let (response_channel, response_rx) = smol::channel::bounded(1);
+                let request = ContextServerElicitationRequest {
+                    server_id: server_id.clone(),
+                    params: Arc::new(params),
+                    response_channel,
+                };
+                if let Err(error) = this.update(&mut cx, |_, cx| {
+                    cx.emit(Event::ElicitationRequested(request))
+                }) {
+                    return Task::ready(Err(error));
+                }
+                cx.background_executor().spawn(async move {
+                    response_rx
+                        .recv()
+                        .await
+                        .map_err(|_| anyhow!("elicitation request was dismissed"))
+                })
+            }
+        });
+        server.on_notification::<ResourcesListChanged, _>({
+            let this = this.clone();
+            let server_id = server_id.clone();
+            move |_, mut cx| {
+                this.update(&mut cx, |_, cx| {
+                    cx.emit(Event::ResourcesChanged {
+                        server_id: server_id.clone(),
+                    })
+                })
+                .ok();
+            }
+        });
+        server.on_notification::<ResourcesUpdated, _>(move |params, mut cx| {
+            this.update(&mut cx, |_, cx| {
+                cx.emit(Event::ResourceUpdated {
+                    server_id: server_id.clone(),
+                    uri: params.uri.into(),
+                })
+            })
+            .ok();
+        });
+    }
+
+    /// Asks the server to notify the client when the given resource changes, if it supports
+    /// resource subscriptions.
+    pub fn subscribe_to_resource(
+        &self,
+        server_id: &ContextServerId,
+        uri: &str,
+        cx: &App,
+    ) -> Task<Result<()>> {
+        let Some(client) = self
+            .get_running_server(server_id)
+            .and_then(|server| server.client())
+        else {
+            return Task::ready(Err(anyhow!("context server {server_id} is not running")));
+        };
+        let supports_subscriptions = client
+            .initialize
+            .capabilities
+            .resources
+            .as_ref()
+            .and_then(|resources| resources.subscribe)
+            .unwrap_or(false);
+        if !supports_subscriptions {
+            return Task::ready(Ok(()));
+        }
+        let uri = match Url::parse(uri) {
+            Ok(uri) => uri,
+            Err(error) => return Task::ready(Err(error.into())),
+        };
+        cx.background_spawn(async move {
+            client
+                .request::<ResourcesSubscribe>(ResourcesSubscribeParams { uri, meta: None })
+                .await
+        })
+    }
+
+    /// The visible worktrees of the project, which servers may limit themselves to.
+    fn roots(worktree_store: &WorktreeStore, cx: &App) -> Vec<Root> {
+        worktree_store
+            .visible_worktrees(cx)
+            .filter_map(|worktree| {
+                let worktree = worktree.read(cx);
+                Some(Root {
+                    uri: Url::from_file_path(worktree.abs_path()).ok()?,
+                    name: Some(worktree.root_name().to_string()),
+                })
+            })
+            .collect()
+    }
+
+    fn roots_changed(&self) {
+        for server in self.running_servers() {
+            if let Some(client) = server.client() {
+                client.notify::<RootsListChanged>(()).log_err();
+            }
+        }
+    }
+
     fn create_context_server(
         &self,
         id: ContextServerId,
         configuration: Arc<ContextServerConfiguration>,
+        cx: &App,
     ) -> Result<Arc<ContextServer>> {
         if let Some(factory) = self.context_server_factory.as_ref() {
-            Ok(factory(id, configuration))
-        } else {
-            Ok(Arc::new(ContextServer::stdio(
+            return Ok(factory(id, configuration));
+        }
+        match configuration.as_ref() {
+            ContextServerConfiguration::Custom { command }
+            | ContextServerConfiguration::Extension { command, .. } => {
+                Ok(Arc::new(ContextServer::stdio(id, command.clone())))
+            }
+            ContextServerConfiguration::Http { url } => Ok(Arc::new(ContextServer::http(
                 id,
-                configuration.command().clone(),
-            )))
+                url.clone(),
+                cx.http_client(),
+            ))),
         }
     }
 
@@ -553,7 +767,7 @@ impl ContextServerStore {
         let mut servers_to_remove = HashSet::default();
         let mut servers_to_stop = HashSet::default();
 
-        this.update(cx, |this, _cx| {
+        this.update(cx, |this, cx| {
             for server_id in this.servers.keys() {
                 // All servers that are not in desired_servers should be removed from the store.
                 // This can happen if the user removed a server from the context server settings.
@@ -573,7 +787,7 @@ impl ContextServerStore {
                 if existing_config.as_deref() != Some(&config) || is_stopped {
                     let config = Arc::new(config);
                     if let Some(server) = this
-                        .create_context_server(id.clone(), config.clone())
+                        .create_context_server(id.clone(), config.clone(), cx)
                         .log_err()
                     {
                         servers_to_start.push((server, config));
@@ -607,7 +821,14 @@ mod tests {
         FakeFs, Project, context_server_store::registry::ContextServerDescriptor,
         project_settings::ProjectSettings,
     };
-    use context_server::test::create_fake_transport;
+    use context_server::{
+        HttpTransportKind,
+        test::{FakeHttpServer, FakeTransport, create_fake_http_server, create_fake_transport},
+        types::{
+            Implementation, InitializeResponse, MessageContent, Notification as _, ProtocolVersion,
+            ResourcesCapabilities, Role, SamplingMessage, ServerCapabilities, requests::Initialize,
+        },
+    };
     use gpui::{AppContext, TestAppContext, UpdateGlobal as _};
     use serde_json::json;
     use std::{cell::RefCell, rc::Rc};
@@ -834,12 +1055,12 @@ mod tests {
         });
         let store = cx.new(|cx| {
             ContextServerStore::test_maintain_server_loop(
-                Box::new(move |id, _| {
+                Some(Box::new(move |id, _| {
                     Arc::new(ContextServer::new(
                         id.clone(),
                         Arc::new(create_fake_transport(id.0.to_string(), executor.clone())),
                     ))
-                }),
+                })),
                 registry.clone(),
                 project.read(cx).worktree_store(),
                 cx,
@@ -1066,12 +1287,12 @@ mod tests {
         let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
         let store = cx.new(|cx| {
             ContextServerStore::test_maintain_server_loop(
-                Box::new(move |id, _| {
+                Some(Box::new(move |id, _| {
                     Arc::new(ContextServer::new(
                         id.clone(),
                         Arc::new(create_fake_transport(id.0.to_string(), executor.clone())),
                     ))
-                }),
+                })),
                 registry.clone(),
                 project.read(cx).worktree_store(),
                 cx,
@@ -1145,6 +1366,255 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    async fn test_context_server_client_requests(cx: &mut TestAppContext) {
+        const SERVER_1_ID: &'static str = "mcp-1";
+
+        let (fs, project) = setup_context_server_test(
+            cx,
+            json!({"code.rs": ""}),
+            vec![(SERVER_1_ID.into(), dummy_server_settings())],
+        )
+        .await;
+
+        let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
+        let store = cx.new(|cx| {
+            ContextServerStore::test(registry.clone(), project.read(cx).worktree_store(), cx)
+        });
+
+        let transport = Arc::new(create_fake_transport(SERVER_1_ID, cx.executor()));
+        let server_1 = Arc::new(ContextServer::new(
+            ContextServerId(SERVER_1_ID.into()),
+            transport.clone(),
+        ));
+        store.update(cx, |store, cx| store.start_server(server_1, cx));
+        cx.run_until_parked();
+
+        // Servers are given the visible worktrees of the project as roots, and told when they
+        // change.
+        let roots = transport.request::<ListRoots>(()).await.unwrap().roots;
+        assert_eq!(
+            roots
+                .iter()
+                .map(|root| root.name.as_deref())
+                .collect::<Vec<_>>(),
+            [Some("test")]
+        );
+        assert_eq!(roots[0].uri, Url::from_file_path(path!("/test")).unwrap());
+
+        fs.insert_tree(path!("/other"), json!({})).await;
+        project
+            .update(cx, |project, cx| {
+                project.find_or_create_worktree(path!("/other"), true, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert!(
+            transport
+                .notifications()
+                .contains(&RootsListChanged::METHOD.to_string())
+        );
+        let roots = transport.request::<ListRoots>(()).await.unwrap().roots;
+        assert_eq!(
+            roots
+                .iter()
+                .map(|root| root.name.as_deref())
+                .collect::<Vec<_>>(),
+            [Some("test"), Some("other")]
+        );
+
+        // Sampling requests are answered by the subscribers of the store.
+        let subscription = cx.update(|cx| {
+            cx.subscribe(&store, |_, event, _| {
+                if let Event::SamplingRequested(request) = event {
+                    request.respond(Ok(CreateMessageResult {
+                        role: Role::Assistant,
+                        content: MessageContent::Text {
+                            text: "Hello".to_string(),
+                            annotations: None,
+                        },
+                        model: "fake-model".to_string(),
+                        stop_reason: None,
+                    }));
+                }
+            })
+        });
+        let sampling_params = || CreateMessageRequest {
+            messages: vec![SamplingMessage {
+                role: Role::User,
+                content: MessageContent::Text {
+                    text: "Hi".to_string(),
+                    annotations: None,
+                },
+            }],
+            model_preferences: None,
+            system_prompt: None,
+            include_context: None,
+            temperature: None,
+            max_tokens: 100,
+            stop_sequences: None,
+            metadata: None,
+        };
+        let result = transport
+            .request::<CreateMessage>(sampling_params())
+            .await
+            .unwrap();
+        assert_eq!(result.model, "fake-model");
+
+        // Without a subscriber to answer them, requests fail.
+        drop(subscription);
+        let error = transport
+            .request::<CreateMessage>(sampling_params())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("dismissed"));
+
+        // Changes to the server's resources are forwarded as events.
+        let resources_changed = Rc::new(RefCell::new(false));
+        let _subscription = cx.update(|cx| {
+            let resources_changed = resources_changed.clone();
+            cx.subscribe(&store, move |_, event, _| {
+                if let Event::ResourcesChanged { server_id } = event {
+                    assert_eq!(server_id.0.as_ref(), SERVER_1_ID);
+                    *resources_changed.borrow_mut() = true;
+                }
+            })
+        });
+        transport.notify::<ResourcesListChanged>(());
+        cx.run_until_parked();
+        assert!(*resources_changed.borrow());
+    }
+
+    #[gpui::test]
+    async fn test_context_server_resource_subscriptions(cx: &mut TestAppContext) {
+        const SERVER_1_ID: &'static str = "mcp-1";
+        const RESOURCE_URI: &'static str = "file:///notes.md";
+
+        let (_fs, project) = setup_context_server_test(
+            cx,
+            json!({"code.rs": ""}),
+            vec![(SERVER_1_ID.into(), dummy_server_settings())],
+        )
+        .await;
+
+        let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
+        let store = cx.new(|cx| {
+            ContextServerStore::test(registry.clone(), project.read(cx).worktree_store(), cx)
+        });
+
+        let subscribed_uris = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let transport = Arc::new(
+            FakeTransport::new(cx.executor())
+                .on_request::<Initialize>(|_| InitializeResponse {
+                    protocol_version: ProtocolVersion(
+                        context_server::types::LATEST_PROTOCOL_VERSION.to_string(),
+                    ),
+                    server_info: Implementation {
+                        name: SERVER_1_ID.to_string(),
+                        version: "1.0.0".to_string(),
+                    },
+                    capabilities: ServerCapabilities {
+                        resources: Some(ResourcesCapabilities {
+                            subscribe: Some(true),
+                            list_changed: None,
+                        }),
+                        ..Default::default()
+                    },
+                    meta: None,
+                })
+                .on_request::<ResourcesSubscribe>({
+                    let subscribed_uris = subscribed_uris.clone();
+                    move |params| subscribed_uris.lock().push(params.uri.to_string())
+                }),
+        );
+        let server_1 = Arc::new(ContextServer::new(
+            ContextServerId(SERVER_1_ID.into()),
+            transport.clone(),
+        ));
+        store.update(cx, |store, cx| store.start_server(server_1, cx));
+        cx.run_until_parked();
+
+        // Servers that support subscriptions are asked to report changes to the resource.
+        store
+            .update(cx, |store, cx| {
+                store.subscribe_to_resource(&ContextServerId(SERVER_1_ID.into()), RESOURCE_URI, cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(*subscribed_uris.lock(), [RESOURCE_URI]);
+
+        // Which are forwarded as events.
+        let updated_uris = Rc::new(RefCell::new(Vec::new()));
+        let _subscription = cx.update(|cx| {
+            let updated_uris = updated_uris.clone();
+            cx.subscribe(&store, move |_, event, _| {
+                if let Event::ResourceUpdated { server_id, uri } = event {
+                    assert_eq!(server_id.0.as_ref(), SERVER_1_ID);
+                    updated_uris.borrow_mut().push(uri.clone());
+                }
+            })
+        });
+        transport.notify::<ResourcesUpdated>(context_server::types::ResourcesUpdatedParams {
+            uri: RESOURCE_URI.to_string(),
+        });
+        cx.run_until_parked();
+        assert_eq!(*updated_uris.borrow(), [SharedString::from(RESOURCE_URI)]);
+    }
+
+    #[gpui::test]
+    async fn test_context_server_over_http(cx: &mut TestAppContext) {
+        const SERVER_1_ID: &'static str = "mcp-1";
+
+        let server_1_id = ContextServerId(SERVER_1_ID.into());
+        let http_server = create_fake_http_server(SERVER_1_ID, HttpTransportKind::StreamableHttp);
+
+        let (_fs, project) = setup_context_server_test(
+            cx,
+            json!({"code.rs": ""}),
+            vec![(
+                SERVER_1_ID.into(),
+                ContextServerSettings::Http {
+                    enabled: true,
+                    url: ContextServerUrl {
+                        url: FakeHttpServer::URL.to_string(),
+                        headers: [("Authorization".to_string(), "Bearer secret".to_string())]
+                            .into_iter()
+                            .collect(),
+                        transport: HttpTransportKind::StreamableHttp,
+                    },
+                },
+            )],
+        )
+        .await;
+        cx.update(|cx| cx.set_http_client(http_server.http_client()));
+
+        let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
+        let store = cx.new(|cx| {
+            ContextServerStore::test_maintain_server_loop(
+                None,
+                registry.clone(),
+                project.read(cx).worktree_store(),
+                cx,
+            )
+        });
+
+        let _server_events = assert_server_events(
+            &store,
+            vec![
+                (server_1_id.clone(), ContextServerStatus::Starting),
+                (server_1_id.clone(), ContextServerStatus::Running),
+            ],
+            cx,
+        );
+        cx.run_until_parked();
+
+        assert_eq!(http_server.session_id().as_deref(), Some("session-1"));
+        assert!(http_server.requests().iter().all(|request| {
+            request.headers.get("authorization").map(String::as_str) == Some("Bearer secret")
+        }));
+    }
+
     fn set_context_server_configuration(
         context_servers: Vec<(Arc<str>, ContextServerSettings)>,
         cx: &mut TestAppContext,
@@ -175,6 +175,8 @@ pub struct LocalLspStore {
     registered_buffers: HashMap<BufferId, usize>,
     buffers_opened_in_servers: HashMap<BufferId, HashSet<LanguageServerId>>,
     buffer_pull_diagnostics_result_ids: HashMap<LanguageServerId, HashMap<PathBuf, Option<String>>>,
+    /// The ids under which the diagnostics reported by tasks are stored, by task label.
+    task_diagnostics_servers: HashMap<String, LanguageServerId>,
 }
 
 impl LocalLspStore {
@@ -2345,6 +2347,9 @@ impl LocalLspStore {
     ) {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id();
+        if buffer.is_in_large_file_mode() {
+            return;
+        }
 
         let Some(file) = File::from_dyn(buffer.file()) else {
             return;
@@ -3796,6 +3801,7 @@ impl LspStore {
                 registered_buffers: HashMap::default(),
                 buffers_opened_in_servers: HashMap::default(),
                 buffer_pull_diagnostics_result_ids: HashMap::default(),
+                task_diagnostics_servers: HashMap::default(),
             }),
             last_formatting_failure: None,
             downstream_client: None,
@@ -3998,6 +4004,19 @@ impl LspStore {
                 self.on_buffer_saved(buffer, cx);
             }
 
+            language::BufferEvent::LargeFileModeChanged => {
+                let buffer_id = buffer.read(cx).remote_id();
+                if let Some(local) = self.as_local_mut() {
+                    if local.registered_buffers.contains_key(&buffer_id) {
+                        local.register_buffer_with_language_servers(
+                            &buffer,
+                            HashSet::default(),
+                            cx,
+                        );
+                    }
+                }
+            }
+
             _ => {}
         }
     }
@@ -7666,6 +7685,111 @@ impl LspStore {
         Ok(())
     }
 
+    /// Returns the id under which the diagnostics reported by the task with the given label are
+    /// stored, if the task has reported any.
+    pub fn task_diagnostics_server(&self, task_label: &str) -> Option<LanguageServerId> {
+        self.as_local()?
+            .task_diagnostics_servers
+            .get(task_label)
+            .copied()
+    }
+
+    /// Replaces the diagnostics reported by the task with the given label with the `problems`
+    /// found in its output. Relative paths are resolved against `cwd`, or the first visible worktree.
+    pub fn update_task_diagnostics(
+        &mut self,
+        task_label: &str,
+        cwd: Option<&Path>,
+        problems: Vec<task::Problem>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let base_dir = cwd.map(Path::to_path_buf).or_else(|| {
+            let worktree_store = self.worktree_store.read(cx);
+            let worktree = worktree_store.visible_worktrees(cx).next()?;
+            Some(worktree.read(cx).abs_path().to_path_buf())
+        });
+        let local = self
+            .as_local_mut()
+            .context("cannot update task diagnostics on a remote LspStore")?;
+        let languages = local.languages.clone();
+        let server_id = *local
+            .task_diagnostics_servers
+            .entry(task_label.to_string())
+            .or_insert_with(|| languages.next_language_server_id());
+
+        let mut diagnostics_by_path = HashMap::<PathBuf, Vec<_>>::default();
+        for problem in problems {
+            let abs_path = if problem.path.is_absolute() {
+                problem.path
+            } else if let Some(base_dir) = &base_dir {
+                base_dir.join(&problem.path)
+            } else {
+                continue;
+            };
+            let (row, column) = problem.start;
+            let start = PointUtf16::new(row, column);
+            let end = problem
+                .end
+                .map_or(start, |(row, column)| PointUtf16::new(row, column));
+            let severity = match problem.severity {
+                task::ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                task::ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                task::ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                task::ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+            };
+            diagnostics_by_path
+                .entry(abs_path)
+                .or_default()
+                .push(DiagnosticEntry {
+                    range: Unclipped(start)..Unclipped(end.max(start)),
+                    diagnostic: Diagnostic {
+                        source: Some(task_label.to_string()),
+                        code: problem.code.map(lsp::NumberOrString::String),
+                        severity,
+                        message: problem.message,
+                        group_id: post_inc(&mut local.next_diagnostic_group_id),
+                        is_primary: true,
+                        is_disk_based: true,
+                        ..Diagnostic::default()
+                    },
+                });
+        }
+
+        // Clear the problems the previous run of the task reported in files that have none now.
+        let worktree_store = self.worktree_store.read(cx);
+        let stale_paths = self
+            .diagnostic_summaries
+            .iter()
+            .filter_map(|(worktree_id, summaries)| {
+                let worktree = worktree_store.worktree_for_id(*worktree_id, cx)?;
+                let worktree_abs_path = worktree.read(cx).abs_path();
+                Some(
+                    summaries
+                        .iter()
+                        .filter(|(_, summaries)| summaries.contains_key(&server_id))
+                        .map(move |(path, _)| worktree_abs_path.join(path)),
+                )
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+        for abs_path in stale_paths {
+            diagnostics_by_path.entry(abs_path).or_default();
+        }
+
+        for (abs_path, diagnostics) in diagnostics_by_path {
+            self.merge_diagnostic_entries(
+                server_id,
+                abs_path,
+                None,
+                None,
+                diagnostics,
+                |_, _, _| false,
+                cx,
+            )?;
+        }
+        Ok(())
+    }
+
     fn update_worktree_diagnostics(
         &mut self,
         worktree_id: WorktreeId,
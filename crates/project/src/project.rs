@@ -76,8 +76,9 @@ use gpui::{
 use itertools::Itertools;
 use language::{
     Buffer, BufferEvent, Capability, CodeLabel, CursorShape, DiagnosticSourceKind, Language,
-    LanguageName, LanguageRegistry, PointUtf16, ToOffset, ToPointUtf16, Toolchain, ToolchainList,
-    Transaction, Unclipped, language_settings::InlayHintKind, proto::split_operations,
+    LanguageName, LanguageRegistry, ParseStatus, PointUtf16, ToOffset, ToPointUtf16, Toolchain,
+    ToolchainList, Transaction, Unclipped, language_settings::InlayHintKind,
+    proto::split_operations,
 };
 use lsp::{
     CodeActionKind, CompletionContext, CompletionItemKind, DocumentHighlightKind, InsertTextMode,
@@ -95,7 +96,7 @@ use rpc::{
     AnyProtoClient, ErrorCode,
     proto::{FromProto, LanguageServerPromptResponse, SSH_PROJECT_ID, ToProto},
 };
-use search::{SearchInputKind, SearchQuery, SearchResult};
+use search::{SearchInputKind, SearchQuery, SearchResult, SyntaxFilter};
 use search_history::SearchHistory;
 use settings::{InvalidSettingsError, Settings, SettingsLocation, SettingsStore};
 use smol::channel::Receiver;
@@ -3822,6 +3823,14 @@ impl Project {
                 let mut chunk_results = Vec::with_capacity(matching_buffer_chunk.len());
                 for buffer in matching_buffer_chunk {
                     let query = query.clone();
+                    if query.is_structural() || query.syntax_filter() != SyntaxFilter::Any {
+                        // Structural and syntax-filtered queries match against syntax trees, so wait for them to be up to date.
+                        let mut parse_status =
+                            buffer.read_with(cx, |buffer, _| buffer.parse_status())?;
+                        while *parse_status.borrow() != ParseStatus::Idle {
+                            parse_status.changed().await?;
+                        }
+                    }
                     let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
                     chunk_results.push(cx.background_spawn(async move {
                         let ranges = query
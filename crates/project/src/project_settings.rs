@@ -1,6 +1,6 @@
 use anyhow::Context as _;
 use collections::HashMap;
-use context_server::ContextServerCommand;
+use context_server::{ContextServerCommand, ContextServerUrl};
 use dap::adapters::DebugAdapterName;
 use fs::Fs;
 use futures::StreamExt as _;
@@ -79,6 +79,10 @@ pub struct ProjectSettings {
     /// Configuration for session-related features
     #[serde(default)]
     pub session: SessionSettings,
+
+    /// Configuration for opening large files
+    #[serde(default)]
+    pub large_files: LargeFileSettings,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -110,6 +114,15 @@ pub enum ContextServerSettings {
         /// are supported.
         settings: serde_json::Value,
     },
+    /// A context server running as an HTTP service, such as a remote server.
+    Http {
+        /// Whether the context server is enabled.
+        #[serde(default = "default_true")]
+        enabled: bool,
+
+        #[serde(flatten)]
+        url: ContextServerUrl,
+    },
 }
 
 /// Common language server settings.
@@ -134,6 +147,7 @@ impl ContextServerSettings {
         match self {
             ContextServerSettings::Custom { enabled, .. } => *enabled,
             ContextServerSettings::Extension { enabled, .. } => *enabled,
+            ContextServerSettings::Http { enabled, .. } => *enabled,
         }
     }
 
@@ -141,6 +155,7 @@ impl ContextServerSettings {
         match self {
             ContextServerSettings::Custom { enabled: e, .. } => *e = enabled,
             ContextServerSettings::Extension { enabled: e, .. } => *e = enabled,
+            ContextServerSettings::Http { enabled: e, .. } => *e = enabled,
         }
     }
 }
@@ -547,6 +562,31 @@ impl Default for SessionSettings {
     }
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LargeFileSettings {
+    /// The size, in megabytes, above which files are opened in large-file mode.
+    /// In that mode, syntax highlighting, language servers and git diffs are skipped,
+    /// and only the lines that are shown are soft wrapped, until full features are
+    /// enabled for the buffer.
+    /// Set to 0 to open all files with full features.
+    ///
+    /// Default: 20
+    pub threshold_mb: u64,
+}
+
+impl LargeFileSettings {
+    /// Returns whether a file of the given size, in bytes, should be opened in large-file mode.
+    pub fn is_large(&self, size: usize) -> bool {
+        self.threshold_mb > 0 && size as u64 > self.threshold_mb * 1024 * 1024
+    }
+}
+
+impl Default for LargeFileSettings {
+    fn default() -> Self {
+        Self { threshold_mb: 20 }
+    }
+}
+
 impl Settings for ProjectSettings {
     const KEY: Option<&'static str> = None;
 
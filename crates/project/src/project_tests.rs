@@ -12,15 +12,15 @@ use fs::FakeFs;
 use futures::{StreamExt, future};
 use git::{
     GitHostingProviderRegistry,
-    repository::RepoPath,
+    repository::{InProgressOperation, RepoPath},
     status::{StatusCode, TrackedStatus},
 };
 use git2::RepositoryInitOptions;
 use gpui::{App, BackgroundExecutor, SemanticVersion, UpdateGlobal};
 use http_client::Url;
 use language::{
-    Diagnostic, DiagnosticEntry, DiagnosticSet, DiskState, FakeLspAdapter, LanguageConfig,
-    LanguageMatcher, LanguageName, LineEnding, OffsetRangeExt, Point, ToPoint,
+    Buffer, Capability, Diagnostic, DiagnosticEntry, DiagnosticSet, DiskState, FakeLspAdapter,
+    LanguageConfig, LanguageMatcher, LanguageName, LineEnding, OffsetRangeExt, Point, ToPoint,
     language_settings::{AllLanguageSettings, LanguageSettingsContent, language_settings},
     tree_sitter_rust, tree_sitter_typescript,
 };
@@ -989,6 +989,67 @@ async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_opening_large_files(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<project_settings::ProjectSettings>(cx, |settings| {
+                settings.large_files.threshold_mb = 1;
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "large.rs": "const A: i32 = 1;\n".repeat(64 * 1024),
+            "small.rs": "const A: i32 = 1;\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let large_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/large.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let small_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/small.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    large_buffer.read_with(cx, |buffer, _| assert!(buffer.is_in_large_file_mode()));
+    small_buffer.read_with(cx, |buffer, _| assert!(!buffer.is_in_large_file_mode()));
+
+    // Large files aren't diffed against git.
+    let large_diff = project
+        .update(cx, |project, cx| {
+            project.open_uncommitted_diff(large_buffer.clone(), cx)
+        })
+        .await;
+    assert!(large_diff.is_err());
+    let small_diff = project
+        .update(cx, |project, cx| {
+            project.open_uncommitted_diff(small_buffer.clone(), cx)
+        })
+        .await;
+    assert!(small_diff.is_ok());
+
+    // Replicas of the buffer start out in large-file mode too.
+    let replica = cx.new(|cx| {
+        let state = large_buffer.read(cx).to_proto(cx);
+        Buffer::from_proto(1, Capability::ReadWrite, state, None).unwrap()
+    });
+    replica.read_with(cx, |buffer, _| assert!(buffer.is_in_large_file_mode()));
+}
+
 #[gpui::test]
 async fn test_reporting_fs_changes_to_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1542,6 +1603,116 @@ async fn test_omitted_diagnostics(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_task_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "let a = 1;",
+            "b.rs": "let b = 2;",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let problem = |path: &str, column: u32, severity: task::ProblemSeverity| task::Problem {
+        owner: "rustc".to_string(),
+        path: PathBuf::from(path),
+        start: (0, column),
+        end: None,
+        severity,
+        code: None,
+        message: "unused variable".to_string(),
+    };
+    lsp_store.update(cx, |lsp_store, cx| {
+        lsp_store
+            .update_task_diagnostics(
+                "cargo check",
+                Some(Path::new(path!("/dir"))),
+                vec![
+                    problem("a.rs", 4, task::ProblemSeverity::Warning),
+                    problem(path!("/dir/b.rs"), 4, task::ProblemSeverity::Error),
+                ],
+                cx,
+            )
+            .unwrap();
+    });
+
+    buffer.update(cx, |buffer, _| {
+        let chunks = chunks_with_diagnostics(buffer, 0..buffer.len());
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|(s, d)| (s.as_str(), *d))
+                .collect::<Vec<_>>(),
+            &[
+                ("let ", None),
+                ("a", Some(DiagnosticSeverity::WARNING)),
+                (" = 1;", None),
+            ]
+        );
+        let diagnostic = buffer
+            .snapshot()
+            .diagnostics_in_range::<_, usize>(0..buffer.len(), false)
+            .next()
+            .unwrap();
+        assert_eq!(diagnostic.diagnostic.source.as_deref(), Some("cargo check"));
+    });
+    project.update(cx, |project, cx| {
+        assert_eq!(
+            project.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 1,
+                warning_count: 1,
+            }
+        );
+    });
+
+    // Rerunning the task replaces its problems, clearing the files it no longer reports problems in.
+    lsp_store.update(cx, |lsp_store, cx| {
+        lsp_store
+            .update_task_diagnostics(
+                "cargo check",
+                Some(Path::new(path!("/dir"))),
+                vec![problem("a.rs", 8, task::ProblemSeverity::Error)],
+                cx,
+            )
+            .unwrap();
+    });
+    let server_id = lsp_store
+        .read_with(cx, |lsp_store, _| {
+            lsp_store.task_diagnostics_server("cargo check")
+        })
+        .unwrap();
+    project.update(cx, |project, cx| {
+        assert_eq!(
+            project
+                .diagnostic_summaries(false, cx)
+                .map(|(path, server_id, summary)| (path.path, server_id, summary))
+                .collect::<Vec<_>>(),
+            vec![(
+                Arc::from(Path::new("a.rs")),
+                server_id,
+                DiagnosticSummary {
+                    error_count: 1,
+                    warning_count: 0,
+                }
+            )]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_disk_based_diagnostics_progress(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -5883,6 +6054,64 @@ async fn test_search_with_unicode(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_search_with_syntax_filter(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "main.rs": "// TODO: foo\nfn foo() { bar(\"foo\"); }\n",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+
+    let query = |syntax_filter| {
+        SearchQuery::text(
+            "foo",
+            false,
+            true,
+            false,
+            Default::default(),
+            Default::default(),
+            false,
+            None,
+        )
+        .unwrap()
+        .with_syntax_filter(syntax_filter)
+    };
+    assert_eq!(
+        search(&project, query(SyntaxFilter::Any), cx)
+            .await
+            .unwrap(),
+        HashMap::from_iter([(
+            path!("dir/main.rs").to_string(),
+            vec![9..12, 16..19, 29..32]
+        )])
+    );
+    assert_eq!(
+        search(&project, query(SyntaxFilter::CodeOnly), cx)
+            .await
+            .unwrap(),
+        HashMap::from_iter([(path!("dir/main.rs").to_string(), vec![16..19])])
+    );
+    assert_eq!(
+        search(&project, query(SyntaxFilter::CommentsOnly), cx)
+            .await
+            .unwrap(),
+        HashMap::from_iter([(path!("dir/main.rs").to_string(), vec![9..12])])
+    );
+    assert_eq!(
+        search(&project, query(SyntaxFilter::StringsOnly), cx)
+            .await
+            .unwrap(),
+        HashMap::from_iter([(path!("dir/main.rs").to_string(), vec![29..32])])
+    );
+}
+
 #[gpui::test]
 async fn test_create_entry(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -8823,6 +9052,75 @@ async fn test_git_worktrees_and_submodules(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_cherry_pick_stopped_on_conflict(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".git": {},
+            "a.txt": "a",
+        }),
+    )
+    .await;
+    fs.with_git_state(path!("/project/.git").as_ref(), false, |state| {
+        state.simulated_sequencer_conflict = Some("a.txt".into());
+    })
+    .unwrap();
+
+    let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+    project
+        .update(cx, |project, cx| project.git_scans_complete(cx))
+        .await;
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+
+    repository
+        .update(cx, |repository, _| {
+            repository.cherry_pick(vec!["abcd".to_string()])
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    cx.run_until_parked();
+    repository.read_with(cx, |repository, _| {
+        assert_eq!(
+            repository.snapshot().merge.operation,
+            Some(InProgressOperation::CherryPick)
+        );
+    });
+
+    // Another operation can't start until this one is resolved.
+    let result = repository
+        .update(cx, |repository, _| {
+            repository.revert(vec!["abcd".to_string()])
+        })
+        .await
+        .unwrap();
+    assert!(result.is_err());
+
+    repository
+        .update(cx, |repository, _| {
+            repository.abort_operation(InProgressOperation::CherryPick)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    cx.run_until_parked();
+    repository.read_with(cx, |repository, _| {
+        assert_eq!(repository.snapshot().merge.operation, None);
+    });
+
+    let commands = fs
+        .with_git_state(path!("/project/.git").as_ref(), false, |state| {
+            state.sequencer_commands.clone()
+        })
+        .unwrap();
+    assert_eq!(commands, [["cherry-pick", "abcd"]]);
+}
+
 #[gpui::test]
 async fn test_repository_deduplication(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3,7 +3,7 @@ use anyhow::Result;
 use client::proto;
 use fancy_regex::{Captures, Regex, RegexBuilder};
 use gpui::Entity;
-use language::{Buffer, BufferSnapshot, CharKind};
+use language::{Buffer, BufferSnapshot, CharKind, StructuralQuery, TextKind};
 use smol::future::yield_now;
 use std::{
     borrow::Cow,
@@ -31,6 +31,49 @@ pub enum SearchInputKind {
     Exclude,
 }
 
+/// Restricts the matches of a [`SearchQuery`] by the syntax at their start.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SyntaxFilter {
+    /// Matches anywhere.
+    #[default]
+    Any,
+    /// Only matches outside of comments and string literals.
+    CodeOnly,
+    /// Only matches within comments.
+    CommentsOnly,
+    /// Only matches within string literals.
+    StringsOnly,
+}
+
+impl SyntaxFilter {
+    pub fn matches(&self, text_kind: TextKind) -> bool {
+        match self {
+            SyntaxFilter::Any => true,
+            SyntaxFilter::CodeOnly => text_kind == TextKind::Code,
+            SyntaxFilter::CommentsOnly => text_kind == TextKind::Comment,
+            SyntaxFilter::StringsOnly => text_kind == TextKind::String,
+        }
+    }
+
+    pub fn from_proto(filter: proto::SearchSyntaxFilter) -> Self {
+        match filter {
+            proto::SearchSyntaxFilter::Any => SyntaxFilter::Any,
+            proto::SearchSyntaxFilter::CodeOnly => SyntaxFilter::CodeOnly,
+            proto::SearchSyntaxFilter::CommentsOnly => SyntaxFilter::CommentsOnly,
+            proto::SearchSyntaxFilter::StringsOnly => SyntaxFilter::StringsOnly,
+        }
+    }
+
+    pub fn to_proto(&self) -> proto::SearchSyntaxFilter {
+        match self {
+            SyntaxFilter::Any => proto::SearchSyntaxFilter::Any,
+            SyntaxFilter::CodeOnly => proto::SearchSyntaxFilter::CodeOnly,
+            SyntaxFilter::CommentsOnly => proto::SearchSyntaxFilter::CommentsOnly,
+            SyntaxFilter::StringsOnly => proto::SearchSyntaxFilter::StringsOnly,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SearchInputs {
     query: Arc<str>,
@@ -38,6 +81,7 @@ pub struct SearchInputs {
     files_to_exclude: PathMatcher,
     match_full_paths: bool,
     buffers: Option<Vec<Entity<Buffer>>>,
+    syntax_filter: SyntaxFilter,
 }
 
 impl SearchInputs {
@@ -53,6 +97,9 @@ impl SearchInputs {
     pub fn buffers(&self) -> &Option<Vec<Entity<Buffer>>> {
         &self.buffers
     }
+    pub fn syntax_filter(&self) -> SyntaxFilter {
+        self.syntax_filter
+    }
 }
 #[derive(Clone, Debug)]
 pub enum SearchQuery {
@@ -75,6 +122,13 @@ pub enum SearchQuery {
         one_match_per_line: bool,
         inner: SearchInputs,
     },
+
+    Structural {
+        query: Arc<StructuralQuery>,
+        replacement: Option<String>,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 static WORD_MATCH_TEST: LazyLock<Regex> = LazyLock::new(|| {
@@ -124,6 +178,7 @@ impl SearchQuery {
             files_to_include,
             match_full_paths,
             buffers,
+            syntax_filter: SyntaxFilter::Any,
         };
         Ok(Self::Text {
             search,
@@ -179,6 +234,7 @@ impl SearchQuery {
             files_to_include,
             match_full_paths,
             buffers,
+            syntax_filter: SyntaxFilter::Any,
         };
         Ok(Self::Regex {
             regex,
@@ -192,8 +248,46 @@ impl SearchQuery {
         })
     }
 
+    /// Create a structural query, matching a Tree-sitter query or a code template with `$metavariables`
+    /// against the syntax trees of buffers.
+    pub fn structural(
+        query: impl ToString,
+        include_ignored: bool,
+        files_to_include: PathMatcher,
+        files_to_exclude: PathMatcher,
+        match_full_paths: bool,
+        buffers: Option<Vec<Entity<Buffer>>>,
+    ) -> Result<Self> {
+        let query = query.to_string();
+        let structural_query = StructuralQuery::new(query.as_str())?;
+        let inner = SearchInputs {
+            query: query.into(),
+            files_to_exclude,
+            files_to_include,
+            match_full_paths,
+            buffers,
+            syntax_filter: SyntaxFilter::Any,
+        };
+        Ok(Self::Structural {
+            query: Arc::new(structural_query),
+            replacement: None,
+            include_ignored,
+            inner,
+        })
+    }
+
     pub fn from_proto(message: proto::SearchQuery) -> Result<Self> {
-        if message.regex {
+        let syntax_filter = SyntaxFilter::from_proto(message.syntax_filter());
+        let query = if message.structural {
+            Self::structural(
+                message.query,
+                message.include_ignored,
+                deserialize_path_matches(&message.files_to_include)?,
+                deserialize_path_matches(&message.files_to_exclude)?,
+                message.match_full_paths,
+                None, // search opened only don't need search remote
+            )
+        } else if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -216,7 +310,8 @@ impl SearchQuery {
                 false,
                 None, // search opened only don't need search remote
             )
-        }
+        }?;
+        Ok(query.with_syntax_filter(syntax_filter))
     }
 
     pub fn with_replacement(mut self, new_replacement: String) -> Self {
@@ -228,6 +323,10 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Structural {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
@@ -235,16 +334,30 @@ impl SearchQuery {
         }
     }
 
+    /// Restricts the matches of this query to the given kind of syntax.
+    pub fn with_syntax_filter(mut self, syntax_filter: SyntaxFilter) -> Self {
+        match self {
+            Self::Text { ref mut inner, .. }
+            | Self::Regex { ref mut inner, .. }
+            | Self::Structural { ref mut inner, .. } => {
+                inner.syntax_filter = syntax_filter;
+                self
+            }
+        }
+    }
+
     pub fn to_proto(&self) -> proto::SearchQuery {
         proto::SearchQuery {
             query: self.as_str().to_string(),
             regex: self.is_regex(),
+            structural: self.is_structural(),
             whole_word: self.whole_word(),
             case_sensitive: self.case_sensitive(),
             include_ignored: self.include_ignored(),
             files_to_include: self.files_to_include().sources().join(","),
             files_to_exclude: self.files_to_exclude().sources().join(","),
             match_full_paths: self.match_full_paths(),
+            syntax_filter: self.syntax_filter().to_proto().into(),
         }
     }
 
@@ -285,14 +398,22 @@ impl SearchQuery {
                     Ok(false)
                 }
             }
+            Self::Structural { query, .. } => match query.required_text() {
+                Some(required_text) => {
+                    let mut text = String::new();
+                    reader.read_to_string(&mut text)?;
+                    Ok(text.contains(required_text))
+                }
+                None => Ok(true),
+            },
         }
     }
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
-            }
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Structural { replacement, .. } => replacement.as_deref(),
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
@@ -319,9 +440,37 @@ impl SearchQuery {
                     None
                 }
             }
+            // Structural replacements depend on the syntax of the match, see `structural_replacement_for`.
+            SearchQuery::Structural { .. } => None,
         }
     }
 
+    /// Replaces a structural search hit at the given `range` of the buffer, substituting the text
+    /// captured by the match for `$name` references in the replacement.
+    pub fn structural_replacement_for(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<String> {
+        let SearchQuery::Structural {
+            query,
+            replacement: Some(replacement),
+            ..
+        } = self
+        else {
+            return None;
+        };
+        let mat = query
+            .matches(buffer, range.clone())
+            .into_iter()
+            .find(|mat| mat.range == range)?;
+        Some(StructuralQuery::expand_replacement(
+            replacement,
+            &mat,
+            buffer,
+        ))
+    }
+
     pub async fn search(
         &self,
         buffer: &BufferSnapshot,
@@ -333,6 +482,17 @@ impl SearchQuery {
             return Default::default();
         }
 
+        if let Self::Structural { query, .. } = self {
+            let range = subrange.unwrap_or(0..buffer.len());
+            let syntax_filter = self.syntax_filter();
+            return query
+                .matches(buffer, range.clone())
+                .into_iter()
+                .filter(|mat| syntax_filter.matches(buffer.text_kind_at(mat.range.start)))
+                .map(|mat| mat.range.start - range.start..mat.range.end - range.start)
+                .collect();
+        }
+
         let range_offset = subrange.as_ref().map(|r| r.start).unwrap_or(0);
         let rope = if let Some(range) = subrange {
             buffer.as_rope().slice(range)
@@ -417,6 +577,14 @@ impl SearchQuery {
                     }
                 }
             }
+            Self::Structural { .. } => {}
+        }
+
+        let syntax_filter = self.syntax_filter();
+        if syntax_filter != SyntaxFilter::Any {
+            matches.retain(|range| {
+                syntax_filter.matches(buffer.text_kind_at(range_offset + range.start))
+            });
         }
 
         matches
@@ -434,6 +602,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -441,6 +610,7 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            Self::Structural { .. } => true,
         }
     }
 
@@ -451,6 +621,9 @@ impl SearchQuery {
             } => *include_ignored,
             Self::Regex {
                 include_ignored, ..
+            }
+            | Self::Structural {
+                include_ignored, ..
             } => *include_ignored,
         }
     }
@@ -459,6 +632,10 @@ impl SearchQuery {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Structural { .. })
+    }
+
     pub fn files_to_include(&self) -> &PathMatcher {
         self.as_inner().files_to_include()
     }
@@ -484,6 +661,10 @@ impl SearchQuery {
         self.as_inner().match_full_paths
     }
 
+    pub fn syntax_filter(&self) -> SyntaxFilter {
+        self.as_inner().syntax_filter
+    }
+
     /// Check match full paths to determine whether you're required to pass a fully qualified
     /// project path (starts with a project root).
     pub fn match_path(&self, file_path: &Path) -> bool {
@@ -502,20 +683,22 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. }
+            | Self::Text { inner, .. }
+            | Self::Structural { inner, .. } => inner,
         }
     }
 
     /// Whether this search should replace only one match per line, instead of
     /// all matches.
-    /// Returns `None` for text searches, as only regex searches support this
+    /// Returns `None` for text and structural searches, as only regex searches support this
     /// option.
     pub fn one_match_per_line(&self) -> Option<bool> {
         match self {
             Self::Regex {
                 one_match_per_line, ..
             } => Some(*one_match_per_line),
-            Self::Text { .. } => None,
+            Self::Text { .. } | Self::Structural { .. } => None,
         }
     }
 }
@@ -553,6 +736,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn structural_query_proto_round_trip() {
+        let query = SearchQuery::structural(
+            "foo($a, $b)",
+            true,
+            PathMatcher::new(&["*.rs".to_owned()]).unwrap(),
+            PathMatcher::default(),
+            false,
+            None,
+        )
+        .unwrap();
+        let message = query.to_proto();
+        assert!(message.structural);
+        assert!(!message.regex);
+
+        let query = SearchQuery::from_proto(message).unwrap();
+        assert!(query.is_structural());
+        assert!(query.include_ignored());
+        assert_eq!(query.as_str(), "foo($a, $b)");
+        assert_eq!(query.files_to_include().sources(), ["*.rs"]);
+    }
+
+    #[test]
+    fn syntax_filter_proto_round_trip() {
+        let query = SearchQuery::text(
+            "foo",
+            false,
+            false,
+            false,
+            PathMatcher::default(),
+            PathMatcher::default(),
+            false,
+            None,
+        )
+        .unwrap()
+        .with_syntax_filter(SyntaxFilter::CommentsOnly);
+        let query = SearchQuery::from_proto(query.to_proto()).unwrap();
+        assert_eq!(query.syntax_filter(), SyntaxFilter::CommentsOnly);
+    }
+
     #[test]
     fn path_matcher_creation_for_globs() {
         for invalid_glob in ["dir/[].txt", "dir/[a-z.txt", "dir/{file"] {
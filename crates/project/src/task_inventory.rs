@@ -23,7 +23,7 @@ use paths::{debug_task_file_name, task_file_name};
 use settings::{InvalidSettingsError, parse_json_with_comments};
 use task::{
     DebugScenario, ResolvedTask, TaskContext, TaskId, TaskTemplate, TaskTemplates, TaskVariables,
-    VariableName,
+    TestResult, TestStatus, VariableName,
 };
 use text::{BufferId, Point, ToPoint};
 use util::{NumericPrefixWithSuffix, ResultExt as _, paths::PathExt as _, post_inc};
@@ -31,6 +31,14 @@ use worktree::WorktreeId;
 
 use crate::{task_store::TaskSettingsLocation, worktree_store::WorktreeStore};
 
+fn combined_test_status(a: TestStatus, b: TestStatus) -> TestStatus {
+    match (a, b) {
+        (TestStatus::Failed, _) | (_, TestStatus::Failed) => TestStatus::Failed,
+        (TestStatus::Passed, _) | (_, TestStatus::Passed) => TestStatus::Passed,
+        (TestStatus::Ignored, TestStatus::Ignored) => TestStatus::Ignored,
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct DebugScenarioContext {
     pub task_context: TaskContext,
@@ -45,6 +53,10 @@ pub struct Inventory {
     last_scheduled_scenarios: VecDeque<(DebugScenario, DebugScenarioContext)>,
     templates_from_settings: InventoryFor<TaskTemplate>,
     scenarios_from_settings: InventoryFor<DebugScenario>,
+    /// The statuses in the latest test run by worktree and worktree-relative file, then by the
+    /// full path of each test and of each group containing tests, e.g. `tests` and `tests::adds`.
+    /// Tests without a reported file are stored under no path.
+    test_statuses: HashMap<(Option<WorktreeId>, Option<Arc<Path>>), HashMap<String, TestStatus>>,
 }
 
 impl std::fmt::Debug for Inventory {
@@ -249,6 +261,7 @@ impl Inventory {
             last_scheduled_scenarios: VecDeque::default(),
             templates_from_settings: InventoryFor::default(),
             scenarios_from_settings: InventoryFor::default(),
+            test_statuses: HashMap::default(),
         })
     }
 
@@ -607,6 +620,52 @@ impl Inventory {
         }
     }
 
+    /// Records the results of the latest test run, replacing the statuses of previous runs.
+    /// Each result comes with the worktree it ran in and the worktree-relative file defining it, if known.
+    pub fn set_test_results<'a>(
+        &mut self,
+        results: impl IntoIterator<Item = (Option<WorktreeId>, Option<Arc<Path>>, &'a TestResult)>,
+    ) {
+        self.test_statuses.clear();
+        for (worktree_id, path, result) in results {
+            // Index the test by its full path, and by the path of every module, class or package
+            // containing it, so that runnables for whole groups of tests can show their combined status.
+            // Suites named after cargo targets, like `unittests src/lib.rs`, are not such groups.
+            let segments = result
+                .suite
+                .iter()
+                .filter(|suite| !suite.contains(' '))
+                .flat_map(|suite| suite.split(['.', '/']))
+                .chain(result.name.split([':', '.', '/']))
+                .filter(|segment| !segment.is_empty())
+                .collect::<Vec<_>>();
+            let statuses = self.test_statuses.entry((worktree_id, path)).or_default();
+            for len in 1..=segments.len() {
+                statuses
+                    .entry(segments[..len].join("::"))
+                    .and_modify(|status| *status = combined_test_status(*status, result.status))
+                    .or_insert(result.status);
+            }
+        }
+    }
+
+    /// Returns the combined status in the latest test run of the tests, or groups of tests, named `name`
+    /// in the given file. Tests run without a reported file match any file in their worktree.
+    pub fn test_status(
+        &self,
+        worktree_id: Option<WorktreeId>,
+        path: &Path,
+        name: &str,
+    ) -> Option<TestStatus> {
+        [(worktree_id, Some(Arc::from(path))), (worktree_id, None)]
+            .iter()
+            .filter_map(|key| self.test_statuses.get(key))
+            .flatten()
+            .filter(|(test_path, _)| test_path.rsplit("::").next() == Some(name))
+            .map(|(_, status)| *status)
+            .reduce(combined_test_status)
+    }
+
     /// Deletes a resolved task from history, using its id.
     /// A similar may still resurface in `used_and_current_resolved_tasks` when its [`TaskTemplate`] is resolved again.
     pub fn delete_previously_used(&mut self, id: &TaskId) {
@@ -1293,6 +1352,80 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_test_statuses(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let inventory = cx.update(|cx| Inventory::new(fs, cx));
+        let result = |name: &str, status| TestResult {
+            name: name.to_owned(),
+            suite: None,
+            status,
+            duration: None,
+            output: String::new(),
+            location: None,
+        };
+
+        let worktree_id = Some(WorktreeId::from_usize(1));
+        let other_worktree_id = Some(WorktreeId::from_usize(2));
+        let math: Option<Arc<Path>> = Some(Path::new("src/math.rs").into());
+        let strings: Option<Arc<Path>> = Some(Path::new("src/strings.rs").into());
+        let math_path = Path::new("src/math.rs");
+        let strings_path = Path::new("src/strings.rs");
+
+        inventory.update(cx, |inventory, _| {
+            let results = [
+                result("math::tests::adds", TestStatus::Passed),
+                result("math::tests::subtracts", TestStatus::Failed),
+                result("strings::tests::adds", TestStatus::Failed),
+                result("other::skipped", TestStatus::Ignored),
+            ];
+            inventory.set_test_results([
+                (worktree_id, math.clone(), &results[0]),
+                (worktree_id, math.clone(), &results[1]),
+                (worktree_id, strings, &results[2]),
+                (worktree_id, None, &results[3]),
+            ]);
+            assert_eq!(
+                inventory.test_status(worktree_id, math_path, "adds"),
+                Some(TestStatus::Passed)
+            );
+            assert_eq!(
+                inventory.test_status(worktree_id, strings_path, "adds"),
+                Some(TestStatus::Failed)
+            );
+            assert_eq!(
+                inventory.test_status(worktree_id, math_path, "subtracts"),
+                Some(TestStatus::Failed)
+            );
+            assert_eq!(
+                inventory.test_status(worktree_id, math_path, "tests"),
+                Some(TestStatus::Failed)
+            );
+            // Tests without a file apply to the whole worktree, but not to other worktrees.
+            assert_eq!(
+                inventory.test_status(worktree_id, strings_path, "other"),
+                Some(TestStatus::Ignored)
+            );
+            assert_eq!(
+                inventory.test_status(other_worktree_id, math_path, "adds"),
+                None
+            );
+            assert_eq!(
+                inventory.test_status(worktree_id, math_path, "missing"),
+                None
+            );
+
+            let rerun = [result("math::tests::subtracts", TestStatus::Passed)];
+            inventory.set_test_results([(worktree_id, math, &rerun[0])]);
+            assert_eq!(
+                inventory.test_status(worktree_id, math_path, "tests"),
+                Some(TestStatus::Passed)
+            );
+            assert_eq!(inventory.test_status(worktree_id, math_path, "adds"), None);
+        });
+    }
+
     #[gpui::test]
     async fn test_reloading_debug_scenarios(cx: &mut TestAppContext) {
         init_test(cx);
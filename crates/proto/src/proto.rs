@@ -306,6 +306,31 @@ messages!(
     (GitDiff, Background),
     (GitDiffResponse, Background),
     (GitInit, Background),
+    (GitStashPush, Background),
+    (GitStashList, Background),
+    (GitStashListResponse, Background),
+    (GitStashApply, Background),
+    (GitStashPop, Background),
+    (GitStashDrop, Background),
+    (GitLog, Background),
+    (GitLogResponse, Background),
+    (GitCherryPick, Background),
+    (GitRevert, Background),
+    (GitRebase, Background),
+    (GitControlOperation, Background),
+    (GitDeleteBranch, Background),
+    (GitRenameBranch, Background),
+    (GitGetTags, Background),
+    (GitTagsResponse, Background),
+    (GitCreateTag, Background),
+    (GitDeleteTag, Background),
+    (GitPushTag, Background),
+    (GitGetWorktrees, Background),
+    (GitWorktreesResponse, Background),
+    (GitCreateWorktree, Background),
+    (GitRemoveWorktree, Background),
+    (GitLoadConflictStages, Background),
+    (GitConflictStagesResponse, Background),
     (GetDebugAdapterBinary, Background),
     (DebugAdapterBinary, Background),
     (RunDebugLocators, Background),
@@ -475,6 +500,26 @@ request_messages!(
     (CheckForPushedCommits, CheckForPushedCommitsResponse),
     (GitDiff, GitDiffResponse),
     (GitInit, Ack),
+    (GitStashPush, Ack),
+    (GitStashList, GitStashListResponse),
+    (GitStashApply, Ack),
+    (GitStashPop, Ack),
+    (GitStashDrop, Ack),
+    (GitLog, GitLogResponse),
+    (GitCherryPick, Ack),
+    (GitRevert, Ack),
+    (GitRebase, Ack),
+    (GitControlOperation, Ack),
+    (GitDeleteBranch, Ack),
+    (GitRenameBranch, Ack),
+    (GitGetTags, GitTagsResponse),
+    (GitCreateTag, Ack),
+    (GitDeleteTag, Ack),
+    (GitPushTag, RemoteMessageResponse),
+    (GitGetWorktrees, GitWorktreesResponse),
+    (GitCreateWorktree, Ack),
+    (GitRemoveWorktree, Ack),
+    (GitLoadConflictStages, GitConflictStagesResponse),
     (ToggleBreakpoint, Ack),
     (GetDebugAdapterBinary, DebugAdapterBinary),
     (RunDebugLocators, DebugRequest),
@@ -603,6 +648,26 @@ entity_messages!(
     CheckForPushedCommits,
     GitDiff,
     GitInit,
+    GitStashPush,
+    GitStashList,
+    GitStashApply,
+    GitStashPop,
+    GitStashDrop,
+    GitLog,
+    GitCherryPick,
+    GitRevert,
+    GitRebase,
+    GitControlOperation,
+    GitDeleteBranch,
+    GitRenameBranch,
+    GitGetTags,
+    GitCreateTag,
+    GitDeleteTag,
+    GitPushTag,
+    GitGetWorktrees,
+    GitCreateWorktree,
+    GitRemoveWorktree,
+    GitLoadConflictStages,
     BreakpointsForFile,
     ToggleBreakpoint,
     RunDebugLocators,
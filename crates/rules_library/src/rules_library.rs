@@ -969,6 +969,7 @@ impl RulesLibrary {
                             model.count_tokens(
                                 LanguageModelRequest {
                                     thread_id: None,
+                                    profile_id: None,
                                     prompt_id: None,
                                     intent: None,
                                     mode: None,
@@ -981,6 +982,7 @@ impl RulesLibrary {
                                     tool_choice: None,
                                     stop: Vec::new(),
                                     temperature: None,
+                                    max_tokens: None,
                                     thinking_allowed: true,
                                 },
                                 cx,
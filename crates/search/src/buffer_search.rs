@@ -2,8 +2,9 @@ mod registrar;
 
 use crate::{
     FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions,
-    SelectAllMatches, SelectNextMatch, SelectPreviousMatch, ToggleCaseSensitive, ToggleRegex,
-    ToggleReplace, ToggleSelection, ToggleWholeWord, search_bar::render_nav_button,
+    SelectAllMatches, SelectNextMatch, SelectPreviousMatch, ToggleCaseSensitive, ToggleCodeOnly,
+    ToggleCommentsOnly, ToggleRegex, ToggleReplace, ToggleSelection, ToggleStringsOnly,
+    ToggleStructural, ToggleWholeWord, search_bar::render_nav_button,
 };
 use any_vec::AnyVec;
 use anyhow::Context as _;
@@ -294,7 +295,34 @@ impl Render for BufferSearchBar {
                                             this.toggle_regex(&ToggleRegex, window, cx)
                                         }),
                                     )
-                                })),
+                                }))
+                                .children(supported_options.structural.then(|| {
+                                    self.render_search_option_button(
+                                        SearchOptions::STRUCTURAL,
+                                        focus_handle.clone(),
+                                        cx.listener(|this, _, window, cx| {
+                                            this.toggle_structural(&ToggleStructural, window, cx)
+                                        }),
+                                    )
+                                }))
+                                .when(supported_options.syntax, |el| {
+                                    el.children(
+                                        [
+                                            SearchOptions::CODE_ONLY,
+                                            SearchOptions::COMMENTS_ONLY,
+                                            SearchOptions::STRINGS_ONLY,
+                                        ]
+                                        .map(|option| {
+                                            self.render_search_option_button(
+                                                option,
+                                                focus_handle.clone(),
+                                                cx.listener(move |this, _, window, cx| {
+                                                    this.toggle_search_option(option, window, cx)
+                                                }),
+                                            )
+                                        }),
+                                    )
+                                }),
                         )
                     }),
             )
@@ -522,6 +550,9 @@ impl Render for BufferSearchBar {
             .when(self.supported_options(cx).regex, |this| {
                 this.on_action(cx.listener(Self::toggle_regex))
             })
+            .when(self.supported_options(cx).structural, |this| {
+                this.on_action(cx.listener(Self::toggle_structural))
+            })
             .when(self.supported_options(cx).selection, |this| {
                 this.on_action(cx.listener(Self::toggle_selection))
             })
@@ -623,6 +654,28 @@ impl BufferSearchBar {
                 this.toggle_regex(action, window, cx);
             }
         }));
+        registrar.register_handler(ForDeployed(
+            |this, action: &ToggleStructural, window, cx| {
+                if this.supported_options(cx).structural {
+                    this.toggle_structural(action, window, cx);
+                }
+            },
+        ));
+        registrar.register_handler(ForDeployed(|this, _: &ToggleCodeOnly, window, cx| {
+            if this.supported_options(cx).syntax {
+                this.toggle_search_option(SearchOptions::CODE_ONLY, window, cx);
+            }
+        }));
+        registrar.register_handler(ForDeployed(|this, _: &ToggleCommentsOnly, window, cx| {
+            if this.supported_options(cx).syntax {
+                this.toggle_search_option(SearchOptions::COMMENTS_ONLY, window, cx);
+            }
+        }));
+        registrar.register_handler(ForDeployed(|this, _: &ToggleStringsOnly, window, cx| {
+            if this.supported_options(cx).syntax {
+                this.toggle_search_option(SearchOptions::STRINGS_ONLY, window, cx);
+            }
+        }));
         registrar.register_handler(ForDeployed(|this, action: &ToggleSelection, window, cx| {
             if this.supported_options(cx).selection {
                 this.toggle_selection(action, window, cx);
@@ -990,7 +1043,7 @@ impl BufferSearchBar {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        self.search_options.toggle(search_option);
+        self.search_options.toggle_option(search_option);
         self.default_options = self.search_options;
         drop(self.update_matches(false, window, cx));
         self.adjust_query_regex_language(cx);
@@ -1228,6 +1281,15 @@ impl BufferSearchBar {
         self.toggle_search_option(SearchOptions::REGEX, window, cx)
     }
 
+    fn toggle_structural(
+        &mut self,
+        _: &ToggleStructural,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_search_option(SearchOptions::STRUCTURAL, window, cx)
+    }
+
     fn clear_active_searchable_item_matches(&mut self, window: &mut Window, cx: &mut App) {
         if let Some(active_searchable_item) = self.active_searchable_item.as_ref() {
             self.active_match_index = None;
@@ -1281,7 +1343,26 @@ impl BufferSearchBar {
                 {
                     search
                 } else {
-                    if self.search_options.contains(SearchOptions::REGEX) {
+                    if self.search_options.contains(SearchOptions::STRUCTURAL) {
+                        match SearchQuery::structural(
+                            query,
+                            false,
+                            Default::default(),
+                            Default::default(),
+                            false,
+                            None,
+                        ) {
+                            Ok(query) => query
+                                .with_replacement(self.replacement(cx))
+                                .with_syntax_filter(self.search_options.syntax_filter()),
+                            Err(e) => {
+                                self.query_error = Some(e.to_string());
+                                self.clear_active_searchable_item_matches(window, cx);
+                                cx.notify();
+                                return done_rx;
+                            }
+                        }
+                    } else if self.search_options.contains(SearchOptions::REGEX) {
                         match SearchQuery::regex(
                             query,
                             self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -1294,7 +1375,9 @@ impl BufferSearchBar {
                             false,
                             None,
                         ) {
-                            Ok(query) => query.with_replacement(self.replacement(cx)),
+                            Ok(query) => query
+                                .with_replacement(self.replacement(cx))
+                                .with_syntax_filter(self.search_options.syntax_filter()),
                             Err(e) => {
                                 self.query_error = Some(e.to_string());
                                 self.clear_active_searchable_item_matches(window, cx);
@@ -1313,7 +1396,9 @@ impl BufferSearchBar {
                             false,
                             None,
                         ) {
-                            Ok(query) => query.with_replacement(self.replacement(cx)),
+                            Ok(query) => query
+                                .with_replacement(self.replacement(cx))
+                                .with_syntax_filter(self.search_options.syntax_filter()),
                             Err(e) => {
                                 self.query_error = Some(e.to_string());
                                 self.clear_active_searchable_item_matches(window, cx);
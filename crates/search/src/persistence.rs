@@ -0,0 +1,129 @@
+use anyhow::Result;
+use db::{
+    define_connection, query,
+    sqlez::{bindable::Column, statement::Statement},
+    sqlez_macros::sql,
+};
+
+use crate::SearchOptions;
+
+/// The inputs of a project search, saved by name so that it can be run again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    pub files_to_include: String,
+    pub files_to_exclude: String,
+    pub options: SearchOptions,
+}
+
+impl Column for SavedSearch {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let (name, next_index): (String, i32) = Column::column(statement, start_index)?;
+        let (query, next_index): (String, i32) = Column::column(statement, next_index)?;
+        let (files_to_include, next_index): (String, i32) = Column::column(statement, next_index)?;
+        let (files_to_exclude, next_index): (String, i32) = Column::column(statement, next_index)?;
+        let (options, next_index): (u16, i32) = Column::column(statement, next_index)?;
+        let saved_search = Self {
+            name,
+            query,
+            files_to_include,
+            files_to_exclude,
+            options: SearchOptions::from_bits_truncate(options),
+        };
+        Ok((saved_search, next_index))
+    }
+}
+
+define_connection!(pub static ref SAVED_SEARCHES: SavedSearchesDb<()> =
+    &[sql!(
+        CREATE TABLE IF NOT EXISTS saved_searches(
+            name TEXT PRIMARY KEY,
+            query TEXT NOT NULL,
+            files_to_include TEXT NOT NULL,
+            files_to_exclude TEXT NOT NULL,
+            options INTEGER NOT NULL
+        ) STRICT;
+    )];
+);
+
+impl SavedSearchesDb {
+    pub async fn save_search(&self, saved_search: SavedSearch) -> Result<()> {
+        self.save_search_internal(
+            saved_search.name,
+            saved_search.query,
+            saved_search.files_to_include,
+            saved_search.files_to_exclude,
+            saved_search.options.bits(),
+        )
+        .await
+    }
+
+    query! {
+        async fn save_search_internal(
+            name: String,
+            query: String,
+            files_to_include: String,
+            files_to_exclude: String,
+            options: u16
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO saved_searches
+                (name, query, files_to_include, files_to_exclude, options)
+            VALUES (?, ?, ?, ?, ?)
+        }
+    }
+
+    query! {
+        pub fn saved_searches() -> Result<Vec<SavedSearch>> {
+            SELECT name, query, files_to_include, files_to_exclude, options
+            FROM saved_searches
+            ORDER BY name
+        }
+    }
+
+    query! {
+        pub async fn delete_saved_search(name: String) -> Result<()> {
+            DELETE FROM saved_searches WHERE name = ?
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_saved_searches() {
+        let db = SavedSearchesDb::open_test_db("test_saved_searches").await;
+        assert_eq!(db.saved_searches().unwrap(), Vec::new());
+
+        let todos = SavedSearch {
+            name: "todos".into(),
+            query: "TODO|FIXME".into(),
+            files_to_include: "crates/**".into(),
+            files_to_exclude: String::new(),
+            options: SearchOptions::REGEX | SearchOptions::COMMENTS_ONLY,
+        };
+        let unwraps = SavedSearch {
+            name: "unwraps".into(),
+            query: ".unwrap()".into(),
+            files_to_include: String::new(),
+            files_to_exclude: "*_tests.rs".into(),
+            options: SearchOptions::CASE_SENSITIVE,
+        };
+        db.save_search(unwraps.clone()).await.unwrap();
+        db.save_search(todos.clone()).await.unwrap();
+        assert_eq!(
+            db.saved_searches().unwrap(),
+            vec![todos.clone(), unwraps.clone()]
+        );
+
+        let todos = SavedSearch {
+            query: "TODO".into(),
+            ..todos
+        };
+        db.save_search(todos.clone()).await.unwrap();
+        db.delete_saved_search(unwraps.name).await.unwrap();
+        assert_eq!(db.saved_searches().unwrap(), vec![todos]);
+    }
+}
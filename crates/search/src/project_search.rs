@@ -1,7 +1,9 @@
 use crate::{
-    BufferSearchBar, FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext,
-    SearchOptions, SelectNextMatch, SelectPreviousMatch, ToggleCaseSensitive, ToggleIncludeIgnored,
-    ToggleRegex, ToggleReplace, ToggleWholeWord, buffer_search::Deploy,
+    BufferSearchBar, FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll,
+    ReplaceAllWithPreview, ReplaceNext, SavedSearch, SearchOptions, SelectNextMatch,
+    SelectPreviousMatch, ToggleCaseSensitive, ToggleCodeOnly, ToggleCommentsOnly,
+    ToggleIncludeIgnored, ToggleRegex, ToggleReplace, ToggleStringsOnly, ToggleStructural,
+    ToggleWholeWord, buffer_search::Deploy, replace_preview::ReplacePreview,
 };
 use anyhow::Context as _;
 use collections::{HashMap, HashSet};
@@ -16,7 +18,7 @@ use gpui::{
     Render, SharedString, Styled, Subscription, Task, TextStyle, UpdateGlobal, WeakEntity, Window,
     actions, div,
 };
-use language::{Buffer, Language};
+use language::{Buffer, Language, Location, ToPoint as _};
 use menu::Confirm;
 use project::{
     Project, ProjectPath,
@@ -94,6 +96,30 @@ pub fn init(cx: &mut App) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleRegex, window, cx| {
             search_bar.toggle_search_option(SearchOptions::REGEX, window, cx);
         });
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleStructural, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::STRUCTURAL, window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleCodeOnly, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::CODE_ONLY, window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleCommentsOnly, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::COMMENTS_ONLY, window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleStringsOnly, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::STRINGS_ONLY, window, cx);
+            },
+        );
         register_workspace_action(
             workspace,
             move |search_bar, action: &ToggleReplace, window, cx| {
@@ -664,7 +690,7 @@ impl ProjectSearchView {
     }
 
     fn toggle_search_option(&mut self, option: SearchOptions, cx: &mut Context<Self>) {
-        self.search_options.toggle(option);
+        self.search_options.toggle_option(option);
         ActiveSettings::update_global(cx, |settings, cx| {
             settings.0.insert(
                 self.entity.read(cx).project.downgrade(),
@@ -727,6 +753,36 @@ impl ProjectSearchView {
         });
     }
 
+    fn replace_all_with_preview(
+        &mut self,
+        _: &ReplaceAllWithPreview,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(query) = self.entity.read(cx).active_query.as_ref() else {
+            return;
+        };
+        let query = query.clone().with_replacement(self.replacement(cx));
+        let match_ranges = self.entity.read(cx).match_ranges.clone();
+        if match_ranges.is_empty() {
+            return;
+        }
+
+        let project = self.entity.read(cx).project.clone();
+        let results_editor = self.results_editor.clone();
+        let preview = cx.new(|cx| {
+            ReplacePreview::new(&query, &match_ranges, &results_editor, project, window, cx)
+        });
+        let workspace = self.workspace.clone();
+        window.defer(cx, move |window, cx| {
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.add_item_to_active_pane(Box::new(preview), None, true, window, cx);
+                })
+                .ok();
+        });
+    }
+
     pub fn new(
         workspace: WeakEntity<Workspace>,
         entity: Entity<ProjectSearch>,
@@ -916,6 +972,42 @@ impl ProjectSearchView {
         });
     }
 
+    /// Opens a new search with the inputs of the given saved search and runs it.
+    pub fn open_saved_search(
+        workspace: &mut Workspace,
+        saved_search: &SavedSearch,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let weak_workspace = cx.entity().downgrade();
+        let settings = ProjectSearchSettings {
+            search_options: saved_search.options,
+            filters_enabled: !saved_search.files_to_include.is_empty()
+                || !saved_search.files_to_exclude.is_empty(),
+        };
+        let entity = cx.new(|cx| ProjectSearch::new(workspace.project().clone(), cx));
+        let search =
+            cx.new(|cx| ProjectSearchView::new(weak_workspace, entity, window, cx, Some(settings)));
+        workspace.add_item_to_active_pane(Box::new(search.clone()), None, true, window, cx);
+        search.update(cx, |search, cx| {
+            search.set_search_editor(SearchInputKind::Query, &saved_search.query, window, cx);
+            search.set_search_editor(
+                SearchInputKind::Include,
+                &saved_search.files_to_include,
+                window,
+                cx,
+            );
+            search.set_search_editor(
+                SearchInputKind::Exclude,
+                &saved_search.files_to_exclude,
+                window,
+                cx,
+            );
+            search.adjust_query_regex_language(cx);
+            search.search(cx);
+        });
+    }
+
     /// Re-activate the most recently activated search in this pane or the most recent if it has been closed.
     /// If no search exists in the workspace, create a new one.
     pub fn deploy_search(
@@ -1135,6 +1227,64 @@ impl ProjectSearchView {
         self.query_editor.read(cx).text(cx)
     }
 
+    /// Returns the inputs of this search, to be saved under the given name.
+    pub fn saved_search(&self, name: String, cx: &App) -> SavedSearch {
+        SavedSearch {
+            name,
+            query: self.search_query_text(cx),
+            files_to_include: self.included_files_editor.read(cx).text(cx),
+            files_to_exclude: self.excluded_files_editor.read(cx).text(cx),
+            options: self.search_options,
+        }
+    }
+
+    /// Returns a quickfix entry for each of the matches of this search.
+    pub fn quickfix_entries(&self, cx: &App) -> Vec<QuickfixEntry> {
+        let search = self.entity.read(cx);
+        let include_root_name = search.project.read(cx).visible_worktrees(cx).count() > 1;
+        let snapshot = search.excerpts.read(cx).snapshot(cx);
+        search
+            .match_ranges
+            .iter()
+            .filter_map(|range| {
+                let buffer = snapshot.buffer_for_excerpt(range.start.excerpt_id)?;
+                let file = buffer.file()?;
+                let position = range.start.text_anchor.to_point(buffer);
+                let path = if include_root_name {
+                    file.full_path(cx)
+                } else {
+                    file.path().to_path_buf()
+                };
+                let line_end = language::Point::new(position.row, buffer.line_len(position.row));
+                Some(QuickfixEntry {
+                    path,
+                    row: position.row,
+                    column: position.column,
+                    text: buffer
+                        .text_for_range(language::Point::new(position.row, 0)..line_end)
+                        .collect(),
+                })
+            })
+            .collect()
+    }
+
+    /// The locations of the matches of the latest search, in the order they're shown.
+    pub fn match_locations(&self, cx: &App) -> Vec<Location> {
+        let search = self.entity.read(cx);
+        let excerpts = search.excerpts.read(cx);
+        search
+            .match_ranges
+            .iter()
+            .filter_map(|range| {
+                let buffer = excerpts.buffer(range.start.buffer_id?)?;
+                Some(Location {
+                    buffer,
+                    range: range.start.text_anchor..range.end.text_anchor,
+                })
+            })
+            .collect()
+    }
+
     fn build_search_query(&mut self, cx: &mut Context<Self>) -> Option<SearchQuery> {
         // Do not bail early in this function, as we want to fill out `self.panels_with_errors`.
         let text = self.query_editor.read(cx).text(cx);
@@ -1202,7 +1352,35 @@ impl ProjectSearchView {
             .count()
             > 1;
 
-        let query = if self.search_options.contains(SearchOptions::REGEX) {
+        let query = if self.search_options.contains(SearchOptions::STRUCTURAL) {
+            match SearchQuery::structural(
+                text,
+                self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+                included_files,
+                excluded_files,
+                match_full_paths,
+                open_buffers,
+            ) {
+                Ok(query) => {
+                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
+                    if should_unmark_error {
+                        cx.notify();
+                    }
+                    self.query_error = None;
+
+                    Some(query)
+                }
+                Err(e) => {
+                    let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
+                    if should_mark_error {
+                        cx.notify();
+                    }
+                    self.query_error = Some(e.to_string());
+
+                    None
+                }
+            }
+        } else if self.search_options.contains(SearchOptions::REGEX) {
             match SearchQuery::regex(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -1269,7 +1447,7 @@ impl ProjectSearchView {
         if query.as_ref().is_some_and(|query| query.is_empty()) {
             return None;
         }
-        query
+        query.map(|query| query.with_syntax_filter(self.search_options.syntax_filter()))
     }
 
     fn open_buffers(&self, cx: &mut Context<Self>) -> Vec<Entity<Buffer>> {
@@ -2010,7 +2188,30 @@ impl Render for ProjectSearchBar {
                         cx.listener(|this, _, window, cx| {
                             this.toggle_search_option(SearchOptions::REGEX, window, cx);
                         }),
-                    )),
+                    ))
+                    .child(SearchOptions::STRUCTURAL.as_button(
+                        self.is_option_enabled(SearchOptions::STRUCTURAL, cx),
+                        focus_handle.clone(),
+                        cx.listener(|this, _, window, cx| {
+                            this.toggle_search_option(SearchOptions::STRUCTURAL, window, cx);
+                        }),
+                    ))
+                    .children(
+                        [
+                            SearchOptions::CODE_ONLY,
+                            SearchOptions::COMMENTS_ONLY,
+                            SearchOptions::STRINGS_ONLY,
+                        ]
+                        .map(|option| {
+                            option.as_button(
+                                self.is_option_enabled(option, cx),
+                                focus_handle.clone(),
+                                cx.listener(move |this, _, window, cx| {
+                                    this.toggle_search_option(option, window, cx);
+                                }),
+                            )
+                        }),
+                    ),
             );
 
         let mode_column = h_flex()
@@ -2223,6 +2424,33 @@ impl Render for ProjectSearchBar {
                                     }
                                 }),
                         )
+                        .child(
+                            IconButton::new("project-search-replace-preview", IconName::Diff)
+                                .shape(IconButtonShape::Square)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    if let Some(search) = this.active_project_search.as_ref() {
+                                        search.update(cx, |this, cx| {
+                                            this.replace_all_with_preview(
+                                                &ReplaceAllWithPreview,
+                                                window,
+                                                cx,
+                                            );
+                                        })
+                                    }
+                                }))
+                                .tooltip({
+                                    let focus_handle = focus_handle.clone();
+                                    move |window, cx| {
+                                        Tooltip::for_action_in(
+                                            "Preview Replacing All Matches",
+                                            &ReplaceAllWithPreview,
+                                            &focus_handle,
+                                            window,
+                                            cx,
+                                        )
+                                    }
+                                }),
+                        )
                     });
 
             h_flex()
@@ -2353,6 +2581,13 @@ impl Render for ProjectSearchBar {
                     })
                 }
             }))
+            .on_action(cx.listener(|this, action, window, cx| {
+                if let Some(search) = this.active_project_search.as_ref() {
+                    search.update(cx, |this, cx| {
+                        this.replace_all_with_preview(action, window, cx);
+                    })
+                }
+            }))
             .when(search.filters_enabled, |this| {
                 this.on_action(cx.listener(|this, _: &ToggleIncludeIgnored, window, cx| {
                     this.toggle_search_option(SearchOptions::INCLUDE_IGNORED, window, cx);
@@ -2601,6 +2836,48 @@ pub mod tests {
             .unwrap();
     }
 
+    #[gpui::test]
+    async fn test_project_search_quickfix_entries(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+                "three.rs": "const THREE: usize = one::ONE;\n    // two::TWO",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let window = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let workspace = window.root(cx).unwrap();
+        let search = cx.new(|cx| ProjectSearch::new(project.clone(), cx));
+        let search_view = cx.add_window(|window, cx| {
+            ProjectSearchView::new(workspace.downgrade(), search.clone(), window, cx, None)
+        });
+
+        perform_search(search_view, "TWO", cx);
+        search_view
+            .update(cx, |search_view, _, cx| {
+                let entries = search_view
+                    .quickfix_entries(cx)
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    entries,
+                    [
+                        "three.rs:2:8:     // two::TWO",
+                        "three.rs:2:13:     // two::TWO",
+                        "two.rs:1:7: const TWO: usize = one::ONE + one::ONE;",
+                    ]
+                );
+            })
+            .unwrap();
+    }
+
     #[gpui::test]
     async fn test_deploy_project_search_focus(cx: &mut TestAppContext) {
         init_test(cx);
@@ -0,0 +1,297 @@
+use std::{fmt, path::PathBuf, sync::LazyLock};
+
+use anyhow::{Context as _, anyhow};
+use editor::{Editor, MultibufferSelectionMode};
+use gpui::{App, Context, Entity, Task, Window, actions};
+use language::{Bias, Location, Point};
+use project::{DirectoryLister, PathPromptOptions, Project};
+use regex::Regex;
+use util::ResultExt as _;
+use workspace::{Workspace, notifications::DetachAndPromptErr};
+
+use crate::ProjectSearchView;
+
+actions!(
+    project_search,
+    [
+        /// Exports the results of the active project search to a quickfix file, with one
+        /// `path:line:column: text` line per match.
+        ExportResults,
+        /// Opens a quickfix file of `path:line:column: text` lines as a navigable list of results.
+        OpenResultsFile
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ExportResults, window, cx| {
+            let Some(search_view) = workspace.active_item_as::<ProjectSearchView>(cx) else {
+                cx.propagate();
+                return;
+            };
+            let entries = search_view.read(cx).quickfix_entries(cx);
+            export_results(workspace, &entries, window, cx);
+        });
+        workspace.register_action(|workspace, _: &OpenResultsFile, window, cx| {
+            if !workspace.project().read(cx).is_local() {
+                return;
+            }
+            let prompt = workspace.prompt_for_open_path(
+                PathPromptOptions {
+                    files: true,
+                    directories: false,
+                    multiple: false,
+                },
+                DirectoryLister::Local(
+                    workspace.project().clone(),
+                    workspace.app_state().fs.clone(),
+                ),
+                window,
+                cx,
+            );
+            cx.spawn_in(window, async move |workspace, cx| {
+                let Some(abs_path) = prompt.await?.and_then(|mut paths| paths.pop()) else {
+                    return anyhow::Ok(());
+                };
+                workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        open_results_file(workspace, abs_path, window, cx)
+                    })?
+                    .await
+            })
+            .detach_and_prompt_err(
+                "Failed to open results file",
+                window,
+                cx,
+                |_, _, _| None,
+            );
+        });
+    })
+    .detach();
+}
+
+/// A location in a quickfix file, formatted as `path:line:column: text` with one-based line
+/// and column numbers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuickfixEntry {
+    pub path: PathBuf,
+    /// The zero-based row of the location.
+    pub row: u32,
+    /// The zero-based column of the location, in bytes.
+    pub column: u32,
+    pub text: String,
+}
+
+impl QuickfixEntry {
+    /// Parses a `path:line:column: text` line. The column and the text are optional, as in
+    /// `path:line: text` or `path:line:column`.
+    pub fn parse(line: &str) -> Option<Self> {
+        static QUICKFIX_LINE_REGEX: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"^(.+?):(\d+):(?:(\d+):?)?\s?(.*)$").unwrap());
+        let captures = QUICKFIX_LINE_REGEX.captures(line.trim_end_matches('\r'))?;
+        let line_number = captures.get(2)?.as_str().parse::<u32>().ok()?;
+        let column_number = match captures.get(3) {
+            Some(column) => column.as_str().parse::<u32>().ok()?,
+            None => 1,
+        };
+        Some(Self {
+            path: PathBuf::from(captures.get(1)?.as_str()),
+            row: line_number.saturating_sub(1),
+            column: column_number.saturating_sub(1),
+            text: captures
+                .get(4)
+                .map(|text| text.as_str().to_string())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Parses the lines of a quickfix file, skipping the ones that aren't locations.
+    pub fn parse_all(text: &str) -> Vec<Self> {
+        text.lines().filter_map(Self::parse).collect()
+    }
+}
+
+impl fmt::Display for QuickfixEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.path.display(),
+            self.row + 1,
+            self.column + 1,
+            self.text
+        )
+    }
+}
+
+fn export_results(
+    workspace: &mut Workspace,
+    entries: &[QuickfixEntry],
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    if entries.is_empty() || !workspace.project().read(cx).is_local() {
+        return;
+    }
+    let mut contents = String::new();
+    for entry in entries {
+        contents.push_str(&entry.to_string());
+        contents.push('\n');
+    }
+    let fs = workspace.app_state().fs.clone();
+    let prompt = workspace.prompt_for_new_path(
+        DirectoryLister::Local(workspace.project().clone(), fs.clone()),
+        window,
+        cx,
+    );
+    cx.spawn_in(window, async move |_, cx| {
+        let Some(abs_path) = prompt.await?.into_iter().flatten().next() else {
+            return anyhow::Ok(());
+        };
+        cx.background_spawn(async move { fs.atomic_write(abs_path, contents).await })
+            .await
+    })
+    .detach_and_prompt_err("Failed to export search results", window, cx, |_, _, _| {
+        None
+    });
+}
+
+/// Opens the locations listed in the quickfix file at `abs_path` in a multibuffer. Relative
+/// paths are resolved against the worktrees of the project.
+pub fn open_results_file(
+    workspace: &mut Workspace,
+    abs_path: PathBuf,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Task<anyhow::Result<()>> {
+    let fs = workspace.app_state().fs.clone();
+    let project = workspace.project().clone();
+    cx.spawn_in(window, async move |workspace, cx| {
+        let contents = fs
+            .load(&abs_path)
+            .await
+            .with_context(|| format!("loading {abs_path:?}"))?;
+        let entries = QuickfixEntry::parse_all(&contents);
+        let locations = cx
+            .update(|_, cx| resolve_entries(project, entries, cx))?
+            .await
+            .into_iter()
+            .map(|(_, location)| location)
+            .collect::<Vec<_>>();
+        if locations.is_empty() {
+            return Err(anyhow!(
+                "{abs_path:?} contains no locations in this project"
+            ));
+        }
+
+        let file_name = abs_path.file_name().unwrap_or_default().to_string_lossy();
+        let title = format!("Results from {file_name}");
+        workspace.update_in(cx, |workspace, window, cx| {
+            Editor::open_locations_in_multibuffer(
+                workspace,
+                locations,
+                title,
+                false,
+                MultibufferSelectionMode::First,
+                window,
+                cx,
+            );
+        })
+    })
+}
+
+/// Opens the buffers of the given entries and pairs each entry with its location, skipping the
+/// entries that can't be opened. Relative paths are resolved against the worktrees of the
+/// project.
+pub fn resolve_entries(
+    project: Entity<Project>,
+    entries: Vec<QuickfixEntry>,
+    cx: &App,
+) -> Task<Vec<(QuickfixEntry, Location)>> {
+    cx.spawn(async move |cx| {
+        let mut locations = Vec::new();
+        for entry in entries {
+            let open_buffer = project
+                .update(cx, |project, cx| {
+                    if let Some(project_path) = project.find_project_path(&entry.path, cx) {
+                        Some(project.open_buffer(project_path, cx))
+                    } else if entry.path.is_absolute() {
+                        Some(project.open_local_buffer(&entry.path, cx))
+                    } else {
+                        None
+                    }
+                })
+                .ok()
+                .flatten();
+            let Some(buffer) = open_buffer else {
+                continue;
+            };
+            let Some(buffer) = buffer.await.log_err() else {
+                continue;
+            };
+            let Some(range) = buffer
+                .read_with(cx, |buffer, _| {
+                    let position =
+                        buffer.clip_point(Point::new(entry.row, entry.column), Bias::Left);
+                    let anchor = buffer.anchor_before(position);
+                    anchor..anchor
+                })
+                .log_err()
+            else {
+                continue;
+            };
+            locations.push((entry, Location { buffer, range }));
+        }
+        locations
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quickfix_entries() {
+        let text = "\
+src/main.rs:12:5: let foo = bar(baz);
+src/lib.rs:3: pub mod foo;
+C:\\src\\foo.rs:1:1:fn foo() {}
+not a location
+";
+        assert_eq!(
+            QuickfixEntry::parse_all(text),
+            [
+                QuickfixEntry {
+                    path: PathBuf::from("src/main.rs"),
+                    row: 11,
+                    column: 4,
+                    text: "let foo = bar(baz);".into(),
+                },
+                QuickfixEntry {
+                    path: PathBuf::from("src/lib.rs"),
+                    row: 2,
+                    column: 0,
+                    text: "pub mod foo;".into(),
+                },
+                QuickfixEntry {
+                    path: PathBuf::from("C:\\src\\foo.rs"),
+                    row: 0,
+                    column: 0,
+                    text: "fn foo() {}".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quickfix_entry_round_trip() {
+        let entry = QuickfixEntry {
+            path: PathBuf::from("src/main.rs"),
+            row: 41,
+            column: 7,
+            text: "    foo: 1,".into(),
+        };
+        assert_eq!(entry.to_string(), "src/main.rs:42:8:     foo: 1,");
+        assert_eq!(QuickfixEntry::parse(&entry.to_string()), Some(entry));
+    }
+}
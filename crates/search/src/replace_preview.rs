@@ -0,0 +1,609 @@
+use crate::{
+    AcceptFileReplacements, AcceptReplacement, ApplyReplacements, RejectFileReplacements,
+    RejectReplacement,
+};
+use collections::HashMap;
+use editor::{
+    Anchor, Editor, ProposedChangeLocation, ProposedChangesEditor, items::replacement_for_match,
+};
+use gpui::{
+    AnyView, App, Context, Entity, EventEmitter, FocusHandle, Focusable, Subscription, WeakEntity,
+    Window,
+};
+use language::{Buffer, BufferId, Point, TextBufferSnapshot, ToOffset as _};
+use project::{Project, search::SearchQuery};
+use std::{any::TypeId, ops::Range, sync::Arc};
+use ui::{KeyBinding, Tooltip, prelude::*};
+use workspace::{
+    item::{Item, ItemEvent},
+    searchable::SearchableItemHandle,
+};
+
+/// A preview of replacing every match of a project search, shown as proposed changes to branches
+/// of the matching buffers. Replacements can be accepted or rejected individually or per file,
+/// and the accepted ones are applied to the buffers as a single transaction of the search results.
+pub struct ReplacePreview {
+    proposed_changes_editor: Entity<ProposedChangesEditor>,
+    editor: Entity<Editor>,
+    results_editor: WeakEntity<Editor>,
+    files: Vec<PreviewFile>,
+    title: SharedString,
+    _subscription: Subscription,
+}
+
+struct PreviewFile {
+    branch: Entity<Buffer>,
+    replacements: Vec<Replacement>,
+}
+
+/// A match of the search in a branch buffer, which contains either its replacement or the
+/// original text, depending on whether the replacement is accepted.
+struct Replacement {
+    range: Range<language::Anchor>,
+    original: Arc<str>,
+    replacement: Arc<str>,
+}
+
+impl Replacement {
+    fn is_accepted(&self, snapshot: &TextBufferSnapshot) -> bool {
+        snapshot
+            .text_for_range(self.range.clone())
+            .collect::<String>()
+            == *self.replacement
+    }
+}
+
+impl ReplacePreview {
+    pub fn new(
+        query: &SearchQuery,
+        match_ranges: &[Range<Anchor>],
+        results_editor: &Entity<Editor>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let matches_by_buffer = matches_by_buffer(query, match_ranges, results_editor, cx);
+        let locations = matches_by_buffer
+            .iter()
+            .map(|(buffer, matches)| ProposedChangeLocation {
+                buffer: buffer.clone(),
+                ranges: line_ranges(buffer.read(cx), matches),
+            })
+            .collect::<Vec<_>>();
+
+        let title: SharedString = format!(
+            "Replace “{}” → “{}”",
+            query.as_str(),
+            query.replacement().unwrap_or_default()
+        )
+        .into();
+        let proposed_changes_editor = cx.new(|cx| {
+            ProposedChangesEditor::new(title.clone(), locations, Some(project), window, cx)
+        });
+
+        let files = matches_by_buffer
+            .into_iter()
+            .filter_map(|(buffer, matches)| {
+                let branch = proposed_changes_editor
+                    .read(cx)
+                    .branch_buffer_for_base(&buffer)?;
+                let replacements = branch.update(cx, |branch, cx| {
+                    let originals = matches
+                        .iter()
+                        .map(|(range, _)| {
+                            Arc::from(branch.text_for_range(range.clone()).collect::<String>())
+                        })
+                        .collect::<Vec<Arc<str>>>();
+                    branch.edit(matches.iter().cloned(), None, cx);
+
+                    let mut delta = 0_isize;
+                    matches
+                        .into_iter()
+                        .zip(originals)
+                        .map(|((range, replacement), original)| {
+                            let start = (range.start as isize + delta) as usize;
+                            let end = start + replacement.len();
+                            delta += replacement.len() as isize - range.len() as isize;
+                            Replacement {
+                                range: branch.anchor_before(start)..branch.anchor_after(end),
+                                original,
+                                replacement,
+                            }
+                        })
+                        .collect()
+                });
+                Some(PreviewFile {
+                    branch,
+                    replacements,
+                })
+            })
+            .collect();
+
+        let editor = proposed_changes_editor.read(cx).editor().clone();
+        let subscription = cx.observe(&proposed_changes_editor, |_, _, cx| cx.notify());
+        Self {
+            proposed_changes_editor,
+            editor,
+            results_editor: results_editor.downgrade(),
+            files,
+            title,
+            _subscription: subscription,
+        }
+    }
+
+    /// Returns the number of accepted replacements and the total number of replacements.
+    fn replacement_counts(&self, cx: &App) -> (usize, usize) {
+        let mut accepted = 0;
+        let mut total = 0;
+        for file in &self.files {
+            let snapshot = file.branch.read(cx).text_snapshot();
+            total += file.replacements.len();
+            accepted += file
+                .replacements
+                .iter()
+                .filter(|replacement| replacement.is_accepted(&snapshot))
+                .count();
+        }
+        (accepted, total)
+    }
+
+    fn accept_replacement(
+        &mut self,
+        _: &AcceptReplacement,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_replacements_accepted(true, false, cx);
+    }
+
+    fn reject_replacement(
+        &mut self,
+        _: &RejectReplacement,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_replacements_accepted(false, false, cx);
+    }
+
+    fn accept_file_replacements(
+        &mut self,
+        _: &AcceptFileReplacements,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_replacements_accepted(true, true, cx);
+    }
+
+    fn reject_file_replacements(
+        &mut self,
+        _: &RejectFileReplacements,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_replacements_accepted(false, true, cx);
+    }
+
+    /// Accepts or rejects the replacements intersecting the selections, or all replacements of
+    /// the files containing them, by putting the replacement or the original text back in the
+    /// branch buffers.
+    fn set_replacements_accepted(
+        &mut self,
+        accepted: bool,
+        whole_files: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let editor = self.editor.clone();
+        let selections = editor.update(cx, |editor, cx| editor.selections.all::<usize>(cx));
+        let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+        let mut selected_ranges = HashMap::<BufferId, Vec<Range<usize>>>::default();
+        for selection in selections {
+            for (buffer, range, _) in
+                snapshot.range_to_buffer_ranges(selection.start..selection.end)
+            {
+                selected_ranges
+                    .entry(buffer.remote_id())
+                    .or_default()
+                    .push(range);
+            }
+        }
+
+        for file in &self.files {
+            let branch = file.branch.read(cx);
+            let Some(file_selected_ranges) = selected_ranges.get(&branch.remote_id()) else {
+                continue;
+            };
+            let edits = file
+                .replacements
+                .iter()
+                .filter_map(|replacement| {
+                    let range = replacement.range.start.to_offset(branch)
+                        ..replacement.range.end.to_offset(branch);
+                    let is_selected = whole_files
+                        || file_selected_ranges.iter().any(|selected_range| {
+                            range.start <= selected_range.end && selected_range.start <= range.end
+                        });
+                    if !is_selected || replacement.is_accepted(branch) == accepted {
+                        return None;
+                    }
+                    let text = if accepted {
+                        replacement.replacement.clone()
+                    } else {
+                        replacement.original.clone()
+                    };
+                    Some((range, text))
+                })
+                .collect::<Vec<_>>();
+            file.branch.update(cx, |branch, cx| {
+                branch.edit(edits, None, cx);
+            });
+        }
+        cx.notify();
+    }
+
+    /// Applies the branches' changes to their base buffers within one transaction of the search
+    /// results editor, so that they can be undone together, and closes the preview.
+    fn apply_replacements(
+        &mut self,
+        _: &ApplyReplacements,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let branches = self
+            .files
+            .iter()
+            .map(|file| file.branch.clone())
+            .collect::<Vec<_>>();
+        let merge_branches = move |cx: &mut App| {
+            for branch in &branches {
+                branch.update(cx, |branch, cx| branch.merge_into_base(Vec::new(), cx));
+            }
+        };
+        if let Some(results_editor) = self.results_editor.upgrade() {
+            results_editor.update(cx, |editor, cx| {
+                editor.transact(window, cx, |_, _, cx| merge_branches(cx));
+            });
+        } else {
+            merge_branches(cx);
+        }
+        cx.emit(ItemEvent::CloseItem);
+    }
+}
+
+/// Groups the matches by buffer, in the order of the search results, along with the text
+/// replacing each of them.
+fn matches_by_buffer(
+    query: &SearchQuery,
+    match_ranges: &[Range<Anchor>],
+    results_editor: &Entity<Editor>,
+    cx: &App,
+) -> Vec<(Entity<Buffer>, Vec<(Range<usize>, Arc<str>)>)> {
+    let multi_buffer = results_editor.read(cx).buffer().read(cx);
+    let snapshot = multi_buffer.snapshot(cx);
+    let mut buffer_indices = HashMap::<BufferId, usize>::default();
+    let mut matches_by_buffer = Vec::<(Entity<Buffer>, Vec<_>)>::new();
+    for range in match_ranges {
+        let Some(replacement) = replacement_for_match(&snapshot, range, query) else {
+            continue;
+        };
+        let buffer_ranges = snapshot.range_to_buffer_ranges(range.clone());
+        let [(buffer_snapshot, buffer_range, _)] = buffer_ranges.as_slice() else {
+            continue;
+        };
+        let buffer_id = buffer_snapshot.remote_id();
+        let Some(buffer) = multi_buffer.buffer(buffer_id) else {
+            continue;
+        };
+        let ix = *buffer_indices.entry(buffer_id).or_insert_with(|| {
+            matches_by_buffer.push((buffer, Vec::new()));
+            matches_by_buffer.len() - 1
+        });
+        matches_by_buffer[ix]
+            .1
+            .push((buffer_range.clone(), replacement));
+    }
+    matches_by_buffer
+}
+
+/// Expands the matches of a buffer to the lines containing them, merging adjacent lines.
+fn line_ranges(buffer: &Buffer, matches: &[(Range<usize>, Arc<str>)]) -> Vec<Range<Point>> {
+    let mut ranges: Vec<Range<Point>> = Vec::new();
+    for (range, _) in matches {
+        let start_row = buffer.offset_to_point(range.start).row;
+        let end_row = buffer.offset_to_point(range.end).row;
+        if let Some(last) = ranges.last_mut() {
+            if last.end.row + 1 >= start_row {
+                last.end = last.end.max(Point::new(end_row, buffer.line_len(end_row)));
+                continue;
+            }
+        }
+        ranges.push(Point::new(start_row, 0)..Point::new(end_row, buffer.line_len(end_row)));
+    }
+    ranges
+}
+
+impl Render for ReplacePreview {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let (accepted, total) = self.replacement_counts(cx);
+        let focus_handle = self.focus_handle(cx);
+        let file_count = self.files.len();
+
+        v_flex()
+            .key_context("ReplacePreview")
+            .on_action(cx.listener(Self::accept_replacement))
+            .on_action(cx.listener(Self::reject_replacement))
+            .on_action(cx.listener(Self::accept_file_replacements))
+            .on_action(cx.listener(Self::reject_file_replacements))
+            .on_action(cx.listener(Self::apply_replacements))
+            .size_full()
+            .child(
+                h_flex()
+                    .p_1()
+                    .pl_2()
+                    .gap_1()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .bg(cx.theme().colors().toolbar_background)
+                    .child(
+                        Label::new(format!(
+                            "{accepted} of {total} replacements accepted in {file_count} {}",
+                            if file_count == 1 { "file" } else { "files" }
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Button::new("accept-replacement", "Accept")
+                                    .tooltip(Tooltip::for_action_title_in(
+                                        "Accept Replacements at Cursor",
+                                        &AcceptReplacement,
+                                        &focus_handle,
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.accept_replacement(&AcceptReplacement, window, cx)
+                                    })),
+                            )
+                            .child(
+                                Button::new("reject-replacement", "Reject")
+                                    .tooltip(Tooltip::for_action_title_in(
+                                        "Reject Replacements at Cursor",
+                                        &RejectReplacement,
+                                        &focus_handle,
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.reject_replacement(&RejectReplacement, window, cx)
+                                    })),
+                            )
+                            .child(
+                                Button::new("accept-file-replacements", "Accept File")
+                                    .tooltip(Tooltip::for_action_title_in(
+                                        "Accept All Replacements in File",
+                                        &AcceptFileReplacements,
+                                        &focus_handle,
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.accept_file_replacements(
+                                            &AcceptFileReplacements,
+                                            window,
+                                            cx,
+                                        )
+                                    })),
+                            )
+                            .child(
+                                Button::new("reject-file-replacements", "Reject File")
+                                    .tooltip(Tooltip::for_action_title_in(
+                                        "Reject All Replacements in File",
+                                        &RejectFileReplacements,
+                                        &focus_handle,
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.reject_file_replacements(
+                                            &RejectFileReplacements,
+                                            window,
+                                            cx,
+                                        )
+                                    })),
+                            )
+                            .child(
+                                Button::new("apply-replacements", "Apply")
+                                    .style(ButtonStyle::Filled)
+                                    .disabled(accepted == 0)
+                                    .key_binding(KeyBinding::for_action_in(
+                                        &ApplyReplacements,
+                                        &focus_handle,
+                                        window,
+                                        cx,
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.apply_replacements(&ApplyReplacements, window, cx)
+                                    })),
+                            ),
+                    ),
+            )
+            .child(div().flex_1().child(self.proposed_changes_editor.clone()))
+    }
+}
+
+impl Focusable for ReplacePreview {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.proposed_changes_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<ItemEvent> for ReplacePreview {}
+
+impl Item for ReplacePreview {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Diff))
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        self.title.clone()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Replace Preview Opened")
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::{ExcerptRange, MultiBuffer, SelectionEffects, actions::Undo};
+    use gpui::{TestAppContext, VisualTestContext};
+    use language::Capability;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::{path, paths::PathMatcher};
+    use workspace::Workspace;
+
+    #[gpui::test]
+    async fn test_replace_preview(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings = SettingsStore::test(cx);
+            cx.set_global(settings);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            client::init_settings(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+        });
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "a.rs": "let x = old + old;\n",
+                "b.rs": "old();\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let buffer_a = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/a.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let buffer_b = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/b.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let window = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+
+        let results_editor = cx.new_window_entity(|window, cx| {
+            let multi_buffer = cx.new(|cx| {
+                let mut multi_buffer = MultiBuffer::new(Capability::ReadWrite);
+                for buffer in [&buffer_a, &buffer_b] {
+                    let len = buffer.read(cx).len();
+                    multi_buffer.push_excerpts(buffer.clone(), [ExcerptRange::new(0..len)], cx);
+                }
+                multi_buffer
+            });
+            Editor::for_multibuffer(multi_buffer, Some(project.clone()), window, cx)
+        });
+        let match_ranges = results_editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            snapshot
+                .text()
+                .match_indices("old")
+                .map(|(ix, _)| snapshot.anchor_after(ix)..snapshot.anchor_before(ix + 3))
+                .collect::<Vec<_>>()
+        });
+        let query = SearchQuery::text(
+            "old",
+            false,
+            true,
+            false,
+            PathMatcher::default(),
+            PathMatcher::default(),
+            false,
+            None,
+        )
+        .unwrap()
+        .with_replacement("new".into());
+
+        let preview = cx.new_window_entity(|window, cx| {
+            ReplacePreview::new(
+                &query,
+                &match_ranges,
+                &results_editor,
+                project.clone(),
+                window,
+                cx,
+            )
+        });
+        preview.update_in(cx, |preview, window, cx| {
+            assert_eq!(preview.replacement_counts(cx), (3, 3));
+
+            // Reject the first replacement in `a.rs`, and all the ones in `b.rs`.
+            preview.editor.update(cx, |editor, cx| {
+                editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                    s.select_ranges([8..8])
+                });
+            });
+            preview.reject_replacement(&RejectReplacement, window, cx);
+            preview.editor.update(cx, |editor, cx| {
+                let end = editor.buffer().read(cx).len(cx);
+                editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                    s.select_ranges([end..end])
+                });
+            });
+            preview.reject_file_replacements(&RejectFileReplacements, window, cx);
+            assert_eq!(preview.replacement_counts(cx), (1, 3));
+            assert_eq!(
+                preview.editor.read(cx).text(cx),
+                "let x = old + new;\nold();"
+            );
+
+            preview.apply_replacements(&ApplyReplacements, window, cx);
+        });
+        assert_eq!(
+            buffer_a.read_with(cx, |buffer, _| buffer.text()),
+            "let x = old + new;\n"
+        );
+        assert_eq!(
+            buffer_b.read_with(cx, |buffer, _| buffer.text()),
+            "old();\n"
+        );
+
+        results_editor.update_in(cx, |editor, window, cx| editor.undo(&Undo, window, cx));
+        assert_eq!(
+            buffer_a.read_with(cx, |buffer, _| buffer.text()),
+            "let x = old + old;\n"
+        );
+    }
+}
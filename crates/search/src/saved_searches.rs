@@ -0,0 +1,306 @@
+use std::sync::Arc;
+
+use editor::Editor;
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Task,
+    WeakEntity, Window, actions,
+};
+use picker::{Picker, PickerDelegate};
+use ui::{HighlightedLabel, IconButtonShape, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{ModalView, Workspace};
+
+use crate::{
+    ProjectSearchView,
+    persistence::{SAVED_SEARCHES, SavedSearch},
+};
+
+actions!(
+    project_search,
+    [
+        /// Saves the query, filters and options of the active project search under a name.
+        SaveSearch,
+        /// Runs a saved project search.
+        OpenSavedSearch
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &SaveSearch, window, cx| {
+            let Some(search_view) = workspace.active_item_as::<ProjectSearchView>(cx) else {
+                cx.propagate();
+                return;
+            };
+            let saved_search = search_view.read(cx).saved_search(String::new(), cx);
+            if saved_search.query.is_empty() {
+                return;
+            }
+            workspace.toggle_modal(window, cx, |window, cx| {
+                SaveSearchModal::new(saved_search, window, cx)
+            });
+        });
+        workspace.register_action(|workspace, _: &OpenSavedSearch, window, cx| {
+            let saved_searches = SAVED_SEARCHES
+                .saved_searches()
+                .log_err()
+                .unwrap_or_default();
+            let delegate = SavedSearchesDelegate::new(cx.entity().downgrade(), saved_searches);
+            workspace.toggle_modal(window, cx, |window, cx| {
+                Picker::uniform_list(delegate, window, cx)
+            });
+        });
+    })
+    .detach();
+}
+
+/// Prompts for the name to save a project search under.
+pub struct SaveSearchModal {
+    name_editor: Entity<Editor>,
+    saved_search: SavedSearch,
+}
+
+impl ModalView for SaveSearchModal {}
+
+impl EventEmitter<DismissEvent> for SaveSearchModal {}
+
+impl Focusable for SaveSearchModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl SaveSearchModal {
+    fn new(saved_search: SavedSearch, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let name_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Save search as…", cx);
+            editor
+        });
+        Self {
+            name_editor,
+            saved_search,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_editor.read(cx).text(cx).trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let saved_search = SavedSearch {
+            name,
+            ..self.saved_search.clone()
+        };
+        cx.background_spawn(async move { SAVED_SEARCHES.save_search(saved_search).await })
+            .detach_and_log_err(cx);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for SaveSearchModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("SaveSearchModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.name_editor.clone()),
+            )
+            .child(
+                h_flex().px_2().py_1().child(
+                    Label::new(format!("Query: {}", self.saved_search.query))
+                        .color(Color::Muted)
+                        .truncate(),
+                ),
+            )
+    }
+}
+
+pub struct SavedSearchesDelegate {
+    workspace: WeakEntity<Workspace>,
+    saved_searches: Vec<SavedSearch>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl SavedSearchesDelegate {
+    fn new(workspace: WeakEntity<Workspace>, saved_searches: Vec<SavedSearch>) -> Self {
+        Self {
+            workspace,
+            saved_searches,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    fn delete_saved_search(
+        &mut self,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(mat) = self.matches.get(ix) else {
+            return;
+        };
+        let saved_search = self.saved_searches.remove(mat.candidate_id);
+        cx.background_spawn(
+            async move { SAVED_SEARCHES.delete_saved_search(saved_search.name).await },
+        )
+        .detach_and_log_err(cx);
+        cx.defer_in(window, |picker, window, cx| picker.refresh(window, cx));
+    }
+}
+
+impl PickerDelegate for SavedSearchesDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Run a saved search…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No saved searches".into())
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .saved_searches
+            .iter()
+            .enumerate()
+            .map(|(id, saved_search)| StringMatchCandidate::new(id, &saved_search.name))
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            picker
+                .update(cx, |picker, cx| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                    cx.notify();
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(saved_search) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|mat| self.saved_searches.get(mat.candidate_id))
+            .cloned()
+        else {
+            return;
+        };
+        self.dismissed(window, cx);
+        self.workspace
+            .update(cx, |workspace, cx| {
+                ProjectSearchView::open_saved_search(workspace, &saved_search, window, cx);
+            })
+            .log_err();
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        let saved_search = self.saved_searches.get(mat.candidate_id)?;
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(
+                            saved_search.name.clone(),
+                            mat.positions.clone(),
+                        ))
+                        .child(
+                            Label::new(saved_search.query.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        ),
+                )
+                .end_slot(
+                    IconButton::new(("delete-saved-search", ix), IconName::Trash)
+                        .shape(IconButtonShape::Square)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Delete Saved Search"))
+                        .on_click(cx.listener(move |picker, _, window, cx| {
+                            cx.stop_propagation();
+                            picker.delegate.delete_saved_search(ix, window, cx);
+                        })),
+                ),
+        )
+    }
+}
@@ -2,7 +2,8 @@ use bitflags::bitflags;
 pub use buffer_search::BufferSearchBar;
 use editor::SearchSettings;
 use gpui::{Action, App, FocusHandle, IntoElement, actions};
-use project::search::SearchQuery;
+pub use persistence::SavedSearch;
+use project::search::{SearchQuery, SyntaxFilter};
 pub use project_search::ProjectSearchView;
 use ui::{ButtonStyle, IconButton, IconButtonShape};
 use ui::{Tooltip, prelude::*};
@@ -10,7 +11,11 @@ use workspace::notifications::NotificationId;
 use workspace::{Toast, Workspace};
 
 pub mod buffer_search;
+mod persistence;
 pub mod project_search;
+pub mod quickfix;
+pub mod replace_preview;
+pub mod saved_searches;
 pub(crate) mod search_bar;
 pub mod search_status_button;
 
@@ -18,6 +23,8 @@ pub fn init(cx: &mut App) {
     menu::init();
     buffer_search::init(cx);
     project_search::init(cx);
+    quickfix::init(cx);
+    saved_searches::init(cx);
 }
 
 actions!(
@@ -33,6 +40,14 @@ actions!(
         ToggleIncludeIgnored,
         /// Toggles regular expression mode.
         ToggleRegex,
+        /// Toggles syntax-aware structural search mode.
+        ToggleStructural,
+        /// Toggles matching only outside of comments and strings.
+        ToggleCodeOnly,
+        /// Toggles matching only within comments.
+        ToggleCommentsOnly,
+        /// Toggles matching only within strings.
+        ToggleStringsOnly,
         /// Toggles the replace interface.
         ToggleReplace,
         /// Toggles searching within selection only.
@@ -53,18 +68,35 @@ actions!(
         ReplaceAll,
         /// Replaces the next match.
         ReplaceNext,
+        /// Opens a preview of replacing all matches, where replacements can be accepted or
+        /// rejected before they are applied.
+        ReplaceAllWithPreview,
+        /// Accepts the previewed replacements at the cursor.
+        AcceptReplacement,
+        /// Rejects the previewed replacements at the cursor.
+        RejectReplacement,
+        /// Accepts all previewed replacements in the files at the cursor.
+        AcceptFileReplacements,
+        /// Rejects all previewed replacements in the files at the cursor.
+        RejectFileReplacements,
+        /// Applies the accepted replacements of the preview as a single transaction.
+        ApplyReplacements,
     ]
 );
 
 bitflags! {
     #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
-    pub struct SearchOptions: u8 {
+    pub struct SearchOptions: u16 {
         const NONE = 0b000;
         const WHOLE_WORD = 0b001;
         const CASE_SENSITIVE = 0b010;
         const INCLUDE_IGNORED = 0b100;
         const REGEX = 0b1000;
         const ONE_MATCH_PER_LINE = 0b100000;
+        const STRUCTURAL = 0b1000000;
+        const CODE_ONLY = 0b10000000;
+        const COMMENTS_ONLY = 0b100000000;
+        const STRINGS_ONLY = 0b1000000000;
         /// If set, reverse direction when finding the active match
         const BACKWARDS = 0b10000;
     }
@@ -77,6 +109,10 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => "Match Case Sensitively",
             SearchOptions::INCLUDE_IGNORED => "Also search files ignored by configuration",
             SearchOptions::REGEX => "Use Regular Expressions",
+            SearchOptions::STRUCTURAL => "Match Syntax Patterns",
+            SearchOptions::CODE_ONLY => "Match in Code Only",
+            SearchOptions::COMMENTS_ONLY => "Match in Comments Only",
+            SearchOptions::STRINGS_ONLY => "Match in Strings Only",
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -87,6 +123,10 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => ui::IconName::CaseSensitive,
             SearchOptions::INCLUDE_IGNORED => ui::IconName::Sliders,
             SearchOptions::REGEX => ui::IconName::Regex,
+            SearchOptions::STRUCTURAL => ui::IconName::ListTree,
+            SearchOptions::CODE_ONLY => ui::IconName::Code,
+            SearchOptions::COMMENTS_ONLY => ui::IconName::MessageBubbles,
+            SearchOptions::STRINGS_ONLY => ui::IconName::Quote,
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -97,6 +137,10 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => Box::new(ToggleCaseSensitive),
             SearchOptions::INCLUDE_IGNORED => Box::new(ToggleIncludeIgnored),
             SearchOptions::REGEX => Box::new(ToggleRegex),
+            SearchOptions::STRUCTURAL => Box::new(ToggleStructural),
+            SearchOptions::CODE_ONLY => Box::new(ToggleCodeOnly),
+            SearchOptions::COMMENTS_ONLY => Box::new(ToggleCommentsOnly),
+            SearchOptions::STRINGS_ONLY => Box::new(ToggleStringsOnly),
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -105,12 +149,44 @@ impl SearchOptions {
         SearchOptions::NONE
     }
 
+    /// The options restricting matches by syntax, at most one of which is enabled.
+    pub fn syntax_filters() -> SearchOptions {
+        SearchOptions::CODE_ONLY | SearchOptions::COMMENTS_ONLY | SearchOptions::STRINGS_ONLY
+    }
+
+    /// Toggles the given option, disabling the other syntax filters when enabling one.
+    pub fn toggle_option(&mut self, option: SearchOptions) {
+        if Self::syntax_filters().contains(option) && !self.contains(option) {
+            self.remove(Self::syntax_filters());
+        }
+        self.toggle(option);
+    }
+
+    pub fn syntax_filter(&self) -> SyntaxFilter {
+        if self.contains(SearchOptions::CODE_ONLY) {
+            SyntaxFilter::CodeOnly
+        } else if self.contains(SearchOptions::COMMENTS_ONLY) {
+            SyntaxFilter::CommentsOnly
+        } else if self.contains(SearchOptions::STRINGS_ONLY) {
+            SyntaxFilter::StringsOnly
+        } else {
+            SyntaxFilter::Any
+        }
+    }
+
     pub fn from_query(query: &SearchQuery) -> SearchOptions {
         let mut options = SearchOptions::NONE;
         options.set(SearchOptions::WHOLE_WORD, query.whole_word());
         options.set(SearchOptions::CASE_SENSITIVE, query.case_sensitive());
         options.set(SearchOptions::INCLUDE_IGNORED, query.include_ignored());
         options.set(SearchOptions::REGEX, query.is_regex());
+        options.set(SearchOptions::STRUCTURAL, query.is_structural());
+        match query.syntax_filter() {
+            SyntaxFilter::Any => {}
+            SyntaxFilter::CodeOnly => options.insert(SearchOptions::CODE_ONLY),
+            SyntaxFilter::CommentsOnly => options.insert(SearchOptions::COMMENTS_ONLY),
+            SyntaxFilter::StringsOnly => options.insert(SearchOptions::STRINGS_ONLY),
+        }
         options
     }
 
@@ -558,6 +558,7 @@ impl SummaryIndex {
 
         let request = LanguageModelRequest {
             thread_id: None,
+            profile_id: None,
             prompt_id: None,
             mode: None,
             intent: None,
@@ -570,6 +571,7 @@ impl SummaryIndex {
             tool_choice: None,
             stop: Vec::new(),
             temperature: None,
+            max_tokens: None,
             thinking_allowed: true,
         };
 
@@ -0,0 +1,521 @@
+//! Problem matchers, turning the output of tasks like compilers and linters into diagnostics.
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use util::ResultExt as _;
+
+/// A way to recognize problems reported in a task's output.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcher {
+    /// A built-in matcher, referenced by its name: `$rustc`, `$tsc`, `$gcc` or `$eslint-stylish`.
+    BuiltIn(String),
+    /// A matcher defined with regular expressions.
+    Custom(CustomProblemMatcher),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CustomProblemMatcher {
+    /// A built-in matcher to extend, whose patterns are used when none are given.
+    #[serde(default)]
+    pub base: Option<String>,
+    /// The name of the tool reporting the problems.
+    #[serde(default)]
+    pub owner: String,
+    /// The severity of the problems whose pattern doesn't capture one, defaults to `error`.
+    #[serde(default)]
+    pub severity: Option<ProblemSeverity>,
+    /// The directory relative paths in the output are relative to, defaults to the task's working directory.
+    #[serde(default)]
+    pub file_location: Option<String>,
+    /// Patterns matching consecutive lines of the output, which together describe a problem.
+    #[serde(default)]
+    pub pattern: Vec<ProblemPattern>,
+}
+
+/// A regular expression matching a line of output, along with the indices of the groups it captures.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    pub regexp: String,
+    #[serde(default)]
+    pub file: Option<usize>,
+    #[serde(default)]
+    pub line: Option<usize>,
+    #[serde(default)]
+    pub column: Option<usize>,
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    #[serde(default)]
+    pub severity: Option<usize>,
+    #[serde(default)]
+    pub code: Option<usize>,
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// Whether the last pattern of a matcher may match several lines in a row, each reporting a problem.
+    #[serde(default, rename = "loop")]
+    pub repeat: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    #[default]
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+impl ProblemSeverity {
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        match text.to_ascii_lowercase().as_str() {
+            "error" | "fatal error" => Some(Self::Error),
+            "warning" | "warn" => Some(Self::Warning),
+            "info" | "information" => Some(Self::Info),
+            "hint" | "note" | "help" => Some(Self::Hint),
+            _ => None,
+        }
+    }
+}
+
+/// A problem found in a task's output, with 0-based rows and columns.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// The owner of the matcher that found the problem.
+    pub owner: String,
+    /// A path as reported by the task; relative paths are relative to the task's working directory.
+    pub path: PathBuf,
+    pub start: (u32, u32),
+    pub end: Option<(u32, u32)>,
+    pub severity: ProblemSeverity,
+    pub code: Option<String>,
+    pub message: String,
+}
+
+impl ProblemMatcher {
+    fn resolve(&self) -> Option<CustomProblemMatcher> {
+        fn built_in(name: &str) -> Option<CustomProblemMatcher> {
+            let matcher = built_in_problem_matcher(name);
+            if matcher.is_none() {
+                log::warn!("Unknown problem matcher `{name}`");
+            }
+            matcher
+        }
+
+        match self {
+            Self::BuiltIn(name) => built_in(name),
+            Self::Custom(matcher) => {
+                let Some(base) = &matcher.base else {
+                    return Some(matcher.clone());
+                };
+                let mut base = built_in(base)?;
+                if !matcher.owner.is_empty() {
+                    base.owner = matcher.owner.clone();
+                }
+                if !matcher.pattern.is_empty() {
+                    base.pattern = matcher.pattern.clone();
+                }
+                base.severity = matcher.severity.or(base.severity);
+                base.file_location = matcher.file_location.clone();
+                Some(base)
+            }
+        }
+    }
+
+    /// Substitutes task variables in the paths of the matcher.
+    pub(crate) fn map_file_location(&self, f: impl FnOnce(&str) -> Option<String>) -> Option<Self> {
+        match self {
+            Self::Custom(CustomProblemMatcher {
+                file_location: Some(file_location),
+                ..
+            }) => {
+                let mut matcher = self.clone();
+                if let Self::Custom(custom) = &mut matcher {
+                    custom.file_location = Some(f(file_location)?);
+                }
+                Some(matcher)
+            }
+            _ => Some(self.clone()),
+        }
+    }
+}
+
+fn built_in_problem_matcher(name: &str) -> Option<CustomProblemMatcher> {
+    let pattern = |regexp: &str| ProblemPattern {
+        regexp: regexp.to_owned(),
+        ..ProblemPattern::default()
+    };
+    let matcher = |owner: &str, pattern| CustomProblemMatcher {
+        base: None,
+        owner: owner.to_owned(),
+        severity: None,
+        file_location: None,
+        pattern,
+    };
+    let matcher = match name {
+        "$rustc" => matcher(
+            "rustc",
+            vec![
+                ProblemPattern {
+                    severity: Some(1),
+                    code: Some(2),
+                    message: Some(3),
+                    ..pattern(r"^(warning|error)(?:\[(\w+)\])?: (.*)$")
+                },
+                ProblemPattern {
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    ..pattern(r"^\s*-->\s+(.+?):(\d+):(\d+)$")
+                },
+            ],
+        ),
+        "$tsc" | "$tsc-watch" => matcher(
+            "typescript",
+            vec![ProblemPattern {
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                code: Some(5),
+                message: Some(6),
+                ..pattern(
+                    r"^([^\s].*?)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+(TS\d+)\s*:\s*(.*)$",
+                )
+            }],
+        ),
+        "$gcc" => matcher(
+            "gcc",
+            vec![ProblemPattern {
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                ..pattern(r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error|note):\s+(.*)$")
+            }],
+        ),
+        "$eslint-stylish" => matcher(
+            "eslint",
+            vec![
+                ProblemPattern {
+                    file: Some(1),
+                    ..pattern(r"^([^\s].*)$")
+                },
+                ProblemPattern {
+                    line: Some(1),
+                    column: Some(2),
+                    severity: Some(3),
+                    message: Some(4),
+                    code: Some(5),
+                    repeat: true,
+                    ..pattern(r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.*?)(?:\s\s+(\S*))?$")
+                },
+            ],
+        ),
+        _ => return None,
+    };
+    Some(matcher)
+}
+
+/// The values captured so far while matching the patterns of a matcher.
+#[derive(Clone, Default)]
+struct Captures {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<ProblemSeverity>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl Captures {
+    fn capture(&mut self, pattern: &ProblemPattern, captures: &regex::Captures) {
+        let group = |ix: Option<usize>| {
+            captures
+                .get(ix?)
+                .map(|group| group.as_str())
+                .filter(|text| !text.is_empty())
+        };
+        let number = |ix: Option<usize>| group(ix)?.parse::<u32>().ok();
+        if let Some(file) = group(pattern.file) {
+            self.file = Some(file.to_owned());
+        }
+        if let Some(line) = number(pattern.line) {
+            self.line = Some(line);
+        }
+        if let Some(column) = number(pattern.column) {
+            self.column = Some(column);
+        }
+        if let Some(end_line) = number(pattern.end_line) {
+            self.end_line = Some(end_line);
+        }
+        if let Some(end_column) = number(pattern.end_column) {
+            self.end_column = Some(end_column);
+        }
+        if let Some(severity) = group(pattern.severity).and_then(ProblemSeverity::parse) {
+            self.severity = Some(severity);
+        }
+        if let Some(code) = group(pattern.code) {
+            self.code = Some(code.to_owned());
+        }
+        if let Some(message) = group(pattern.message) {
+            self.message = Some(message.to_owned());
+        }
+    }
+
+    fn problem(self, matcher: &CustomProblemMatcher) -> Option<Problem> {
+        let line = self.line?.saturating_sub(1);
+        let column = self.column.unwrap_or(1).saturating_sub(1);
+        let end = self.end_line.map(|end_line| {
+            (
+                end_line.saturating_sub(1),
+                self.end_column.unwrap_or(1).saturating_sub(1),
+            )
+        });
+        let path = PathBuf::from(self.file?);
+        let path = match &matcher.file_location {
+            Some(file_location) if path.is_relative() => Path::new(file_location).join(path),
+            _ => path,
+        };
+        Some(Problem {
+            owner: matcher.owner.clone(),
+            path,
+            start: (line, column),
+            end,
+            severity: self
+                .severity
+                .or(matcher.severity)
+                .unwrap_or(ProblemSeverity::Error),
+            code: self.code,
+            message: self.message?,
+        })
+    }
+}
+
+/// Finds the problems reported in a task's `output` by any of the `matchers`.
+pub fn match_problems(matchers: &[ProblemMatcher], output: &str) -> Vec<Problem> {
+    let lines = output.lines().collect::<Vec<_>>();
+    let mut problems = Vec::new();
+    for matcher in matchers.iter().filter_map(ProblemMatcher::resolve) {
+        let Some(patterns) = matcher
+            .pattern
+            .iter()
+            .map(|pattern| Some((Regex::new(&pattern.regexp).log_err()?, pattern)))
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue;
+        };
+        let Some(((last_regex, last_pattern), leading)) = patterns.split_last() else {
+            continue;
+        };
+
+        let mut ix = 0;
+        'lines: while ix < lines.len() {
+            let mut captures = Captures::default();
+            let mut end_ix = ix;
+            for (regex, pattern) in leading {
+                match lines.get(end_ix).and_then(|line| regex.captures(line)) {
+                    Some(line_captures) => captures.capture(pattern, &line_captures),
+                    None => {
+                        ix += 1;
+                        continue 'lines;
+                    }
+                }
+                end_ix += 1;
+            }
+
+            let start_ix = end_ix;
+            while let Some(line_captures) =
+                lines.get(end_ix).and_then(|line| last_regex.captures(line))
+            {
+                let mut captures = captures.clone();
+                captures.capture(last_pattern, &line_captures);
+                problems.extend(captures.problem(&matcher));
+                end_ix += 1;
+                if !last_pattern.repeat {
+                    break;
+                }
+            }
+            ix = if end_ix > start_ix { end_ix } else { ix + 1 };
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn built_in(name: &str) -> Vec<ProblemMatcher> {
+        vec![ProblemMatcher::BuiltIn(name.to_owned())]
+    }
+
+    #[test]
+    fn test_rustc_problem_matcher() {
+        let output = "
+   Compiling demo v0.1.0 (/work/demo)
+error[E0308]: mismatched types
+ --> src/main.rs:4:18
+  |
+4 |     let x: u32 = \"a\";
+  |            ---   ^^^ expected `u32`, found `&str`
+
+warning: unused variable: `y`
+  --> src/lib.rs:10:9
+   |
+warning: `demo` (bin \"demo\") generated 1 warning
+";
+        assert_eq!(
+            match_problems(&built_in("$rustc"), output),
+            vec![
+                Problem {
+                    owner: "rustc".into(),
+                    path: PathBuf::from("src/main.rs"),
+                    start: (3, 17),
+                    end: None,
+                    severity: ProblemSeverity::Error,
+                    code: Some("E0308".into()),
+                    message: "mismatched types".into(),
+                },
+                Problem {
+                    owner: "rustc".into(),
+                    path: PathBuf::from("src/lib.rs"),
+                    start: (9, 8),
+                    end: None,
+                    severity: ProblemSeverity::Warning,
+                    code: None,
+                    message: "unused variable: `y`".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tsc_and_gcc_problem_matchers() {
+        let problems = match_problems(
+            &built_in("$tsc"),
+            "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\n",
+        );
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].path, PathBuf::from("src/index.ts"));
+        assert_eq!(problems[0].start, (2, 6));
+        assert_eq!(problems[0].code.as_deref(), Some("TS2322"));
+
+        let problems = match_problems(
+            &built_in("$gcc"),
+            "main.c:5:3: warning: implicit declaration of function 'foo'\nmain.c:9:1: fatal error: expected ';'\n",
+        );
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (problem.start, problem.severity))
+                .collect::<Vec<_>>(),
+            vec![
+                ((4, 2), ProblemSeverity::Warning),
+                ((8, 0), ProblemSeverity::Error),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_looping_problem_matcher() {
+        let output = "
+/work/src/app.js
+  1:10  error    'foo' is defined but never used  no-unused-vars
+  4:1   warning  Unexpected console statement     no-console
+
+/work/src/other.js
+  2:3  error  Missing semicolon  semi
+
+✖ 3 problems (2 errors, 1 warning)
+";
+        let problems = match_problems(&built_in("$eslint-stylish"), output);
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.to_string_lossy().into_owned(),
+                    problem.start,
+                    problem.message.as_str(),
+                    problem.code.as_deref(),
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/work/src/app.js".to_owned(),
+                    (0, 9),
+                    "'foo' is defined but never used",
+                    Some("no-unused-vars"),
+                ),
+                (
+                    "/work/src/app.js".to_owned(),
+                    (3, 0),
+                    "Unexpected console statement",
+                    Some("no-console"),
+                ),
+                (
+                    "/work/src/other.js".to_owned(),
+                    (1, 2),
+                    "Missing semicolon",
+                    Some("semi"),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_problem_matcher() {
+        let matcher: ProblemMatcher = serde_json::from_value(serde_json::json!({
+            "owner": "lint",
+            "severity": "warning",
+            "pattern": [{
+                "regexp": "^(\\S+):(\\d+)-(\\d+): (.*)$",
+                "file": 1,
+                "line": 2,
+                "end_line": 3,
+                "message": 4,
+            }],
+        }))
+        .unwrap();
+        assert_eq!(
+            match_problems(&[matcher], "notes.md:3-5: line too long\nall good\n"),
+            vec![Problem {
+                owner: "lint".into(),
+                path: PathBuf::from("notes.md"),
+                start: (2, 0),
+                end: Some((4, 0)),
+                severity: ProblemSeverity::Warning,
+                code: None,
+                message: "line too long".into(),
+            }]
+        );
+
+        let matcher: ProblemMatcher = serde_json::from_value(serde_json::json!({
+            "base": "$tsc",
+            "file_location": "/work/app",
+        }))
+        .unwrap();
+        let problems = match_problems(
+            &[matcher],
+            "src/index.ts:3:7 - error TS2304: Cannot find name 'foo'.\n",
+        );
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].owner, "typescript");
+        assert_eq!(problems[0].path, PathBuf::from("/work/app/src/index.ts"));
+
+        assert_eq!(
+            match_problems(&built_in("$unknown"), "src/main.rs:1:1: error: oops"),
+            Vec::new()
+        );
+    }
+}
@@ -2,10 +2,13 @@
 
 mod adapter_schema;
 mod debug_format;
+mod problem_matcher;
 mod serde_helpers;
 mod shell_builder;
 pub mod static_source;
+mod task_graph;
 mod task_template;
+mod test_results;
 mod vscode_debug_format;
 mod vscode_format;
 
@@ -22,11 +25,19 @@ pub use debug_format::{
     AttachRequest, BuildTaskDefinition, DebugRequest, DebugScenario, DebugTaskFile, LaunchRequest,
     Request, TcpArgumentsTemplate, ZedDebugConfig,
 };
+pub use problem_matcher::{
+    CustomProblemMatcher, Problem, ProblemMatcher, ProblemPattern, ProblemSeverity, match_problems,
+};
 pub use shell_builder::{DEFAULT_REMOTE_SHELL, ShellBuilder};
+pub use task_graph::{DependsOrder, TaskGraph};
 pub use task_template::{
     DebugArgsRequest, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
     substitute_variables_in_map, substitute_variables_in_str,
 };
+pub use test_results::{
+    TestLocation, TestResult, TestResultsFormat, TestStatus, parse_go_test_json, parse_junit_xml,
+    parse_libtest_json,
+};
 pub use vscode_debug_format::VsCodeDebugTaskFile;
 pub use vscode_format::VsCodeTaskFile;
 pub use zed_actions::RevealTarget;
@@ -74,6 +85,10 @@ pub struct SpawnInTerminal {
     pub show_command: bool,
     /// Whether to show the rerun button in the terminal tab.
     pub show_rerun: bool,
+    /// How to collect structured test results once the task finishes, if it runs tests.
+    pub test_results: Option<TestResultsFormat>,
+    /// Matchers turning the problems reported in the task's output into diagnostics.
+    pub problem_matchers: Vec<ProblemMatcher>,
 }
 
 impl SpawnInTerminal {
@@ -117,6 +132,8 @@ pub struct ResolvedTask {
     pub id: TaskId,
     /// A template the task got resolved from.
     original_task: TaskTemplate,
+    /// The context the task got resolved in.
+    task_context: TaskContext,
     /// Full, unshortened label of the task after all resolutions are made.
     pub resolved_label: String,
     /// Variables that were substituted during the task template resolution.
@@ -132,6 +149,11 @@ impl ResolvedTask {
         &self.original_task
     }
 
+    /// The context the task got resolved in, which its dependencies get resolved in too.
+    pub fn task_context(&self) -> &TaskContext {
+        &self.task_context
+    }
+
     /// Variables that were substituted during the task template resolution.
     pub fn substituted_variables(&self) -> &HashSet<VariableName> {
         &self.substituted_variables
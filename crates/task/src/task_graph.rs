@@ -0,0 +1,171 @@
+use anyhow::{Context as _, Result, bail};
+use collections::HashSet;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{ResolvedTask, TaskId, TaskTemplate};
+
+/// How the tasks a task depends on are run, before the task itself.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Run the dependencies one after another, in the order they are listed.
+    #[default]
+    Sequence,
+    /// Run all dependencies at the same time.
+    Parallel,
+}
+
+/// A resolved task along with the tasks it depends on, which need to succeed before it runs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskGraph {
+    pub task: ResolvedTask,
+    pub order: DependsOrder,
+    pub dependencies: Vec<TaskGraph>,
+}
+
+impl TaskGraph {
+    /// Resolves the dependencies of the `task`, recursively, looking them up by label among the `templates`
+    /// with their id bases. Dependencies are resolved in the same context as the task depending on them.
+    pub fn resolve(task: ResolvedTask, templates: &[(String, TaskTemplate)]) -> Result<Self> {
+        let mut stack = vec![task.original_task().label.clone()];
+        Self::resolve_dependencies(task, templates, &mut stack)
+    }
+
+    fn resolve_dependencies(
+        task: ResolvedTask,
+        templates: &[(String, TaskTemplate)],
+        stack: &mut Vec<String>,
+    ) -> Result<Self> {
+        let template = task.original_task();
+        let mut dependencies = Vec::with_capacity(template.depends_on.len());
+        for label in &template.depends_on {
+            if stack.contains(label) {
+                bail!("Task dependency cycle: {} -> {label}", stack.join(" -> "));
+            }
+            let (id_base, dependency) = templates
+                .iter()
+                .find(|(_, template)| &template.label == label)
+                .with_context(|| {
+                    format!(
+                        "Task `{}` depends on unknown task `{label}`",
+                        template.label
+                    )
+                })?;
+            let resolved = dependency
+                .resolve_task(id_base, task.task_context())
+                .with_context(|| format!("Failed to resolve task `{label}`"))?;
+            stack.push(label.clone());
+            dependencies.push(Self::resolve_dependencies(resolved, templates, stack)?);
+            stack.pop();
+        }
+        Ok(Self {
+            order: template.depends_order,
+            task,
+            dependencies,
+        })
+    }
+
+    /// Returns the labels of the tasks in the graph in the order they run, dependencies first.
+    /// A task that several others depend on runs once, so it is listed once.
+    pub fn labels(&self) -> Vec<&str> {
+        let mut seen = HashSet::default();
+        let mut labels = Vec::new();
+        self.collect_labels(&mut seen, &mut labels);
+        labels
+    }
+
+    fn collect_labels<'a>(&'a self, seen: &mut HashSet<&'a TaskId>, labels: &mut Vec<&'a str>) {
+        if !seen.insert(&self.task.id) {
+            return;
+        }
+        for dependency in &self.dependencies {
+            dependency.collect_labels(seen, labels);
+        }
+        labels.push(self.task.display_label());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TaskContext;
+
+    use super::*;
+
+    fn template(label: &str, command: &str, depends_on: &[&str]) -> (String, TaskTemplate) {
+        (
+            "test".to_string(),
+            TaskTemplate {
+                label: label.to_string(),
+                command: command.to_string(),
+                depends_on: depends_on.iter().map(|label| label.to_string()).collect(),
+                ..TaskTemplate::default()
+            },
+        )
+    }
+
+    fn resolve(templates: &[(String, TaskTemplate)], label: &str) -> Result<TaskGraph> {
+        let (id_base, template) = templates
+            .iter()
+            .find(|(_, template)| template.label == label)
+            .unwrap();
+        let task = template
+            .resolve_task(id_base, &TaskContext::default())
+            .unwrap();
+        TaskGraph::resolve(task, templates)
+    }
+
+    #[test]
+    fn test_resolving_dependencies() {
+        let templates = vec![
+            template("build", "cargo build", &[]),
+            template("lint", "cargo clippy", &[]),
+            template("check", "", &["build", "lint"]),
+            template("run", "cargo run", &["check"]),
+        ];
+
+        let graph = resolve(&templates, "run").unwrap();
+        assert_eq!(graph.labels(), vec!["build", "lint", "check", "run"]);
+        assert_eq!(graph.dependencies.len(), 1);
+        let check = &graph.dependencies[0];
+        assert!(check.task.original_task().is_compound());
+        assert_eq!(check.task.resolved.command, None);
+        assert_eq!(check.order, DependsOrder::Sequence);
+    }
+
+    #[test]
+    fn test_shared_dependencies_are_listed_once() {
+        let templates = vec![
+            template("generate", "make generate", &[]),
+            template("build", "cargo build", &["generate"]),
+            template("lint", "cargo clippy", &["generate"]),
+            template("check", "", &["build", "lint"]),
+        ];
+
+        let graph = resolve(&templates, "check").unwrap();
+        assert_eq!(graph.labels(), vec!["generate", "build", "lint", "check"]);
+        assert_eq!(
+            graph.dependencies[0].dependencies[0].task.id,
+            graph.dependencies[1].dependencies[0].task.id
+        );
+    }
+
+    #[test]
+    fn test_dependency_errors() {
+        let templates = vec![
+            template("a", "echo a", &["b"]),
+            template("b", "echo b", &["c"]),
+            template("c", "echo c", &["a"]),
+            template("d", "echo d", &["missing"]),
+        ];
+
+        assert_eq!(
+            resolve(&templates, "a").unwrap_err().to_string(),
+            "Task dependency cycle: a -> b -> c -> a"
+        );
+        assert_eq!(
+            resolve(&templates, "d").unwrap_err().to_string(),
+            "Task `d` depends on unknown task `missing`"
+        );
+    }
+}
@@ -9,8 +9,9 @@ use util::serde::default_true;
 use util::{ResultExt, truncate_and_remove_front};
 
 use crate::{
-    AttachRequest, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext, TaskId,
-    VariableName, ZED_VARIABLE_NAME_PREFIX, serde_helpers::non_empty_string_vec,
+    AttachRequest, DependsOrder, ProblemMatcher, ResolvedTask, RevealTarget, Shell,
+    SpawnInTerminal, TaskContext, TaskId, TestResultsFormat, VariableName,
+    ZED_VARIABLE_NAME_PREFIX, serde_helpers::non_empty_string_vec,
 };
 
 /// A template definition of a Zed task to run.
@@ -72,6 +73,26 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// The format of the test results the task reports, to show them in the test results panel:
+    /// * `{ "format": "libtest_json" }` — libtest's JSON output, e.g. `cargo test -- -Z unstable-options --format json`
+    /// * `{ "format": "go_test_json" }` — the output of `go test -json`
+    /// * `{ "format": "junit", "path": "report.xml" }` — a JUnit XML report, e.g. `pytest --junitxml=report.xml`
+    #[serde(default)]
+    pub test_results: Option<TestResultsFormat>,
+    /// Matchers turning the problems the task reports in its output into diagnostics:
+    /// * a built-in matcher: `"$rustc"`, `"$tsc"`, `"$gcc"` or `"$eslint-stylish"`
+    /// * a custom matcher: `{ "owner": "lint", "pattern": [{ "regexp": "^(.*):(\\d+): (.*)$", "file": 1, "line": 2, "message": 3 }] }`
+    #[serde(default)]
+    pub problem_matcher: Vec<ProblemMatcher>,
+    /// Labels of the tasks to run before this one, which all need to succeed for it to run.
+    /// A task with dependencies but no command only runs its dependencies.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// How to run the dependencies of the task:
+    /// * `sequence` — one after another, in the order they are listed (default)
+    /// * `parallel` — all at the same time
+    #[serde(default)]
+    pub depends_order: DependsOrder,
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -126,6 +147,11 @@ impl TaskTemplates {
 }
 
 impl TaskTemplate {
+    /// Whether the task only runs the tasks it depends on, without a command of its own.
+    pub fn is_compound(&self) -> bool {
+        self.command.trim().is_empty() && !self.depends_on.is_empty()
+    }
+
     /// Replaces all `VariableName` task variables in the task template string fields.
     /// If any replacement fails or the new string substitutions still have [`ZED_VARIABLE_NAME_PREFIX`],
     /// `None` is returned.
@@ -133,7 +159,7 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty() || (self.command.trim().is_empty() && !self.is_compound()) {
             return None;
         }
 
@@ -209,6 +235,32 @@ impl TaskTemplate {
             &mut substituted_variables,
         )?;
 
+        let test_results = match &self.test_results {
+            Some(TestResultsFormat::Junit { path }) => Some(TestResultsFormat::Junit {
+                path: substitute_all_template_variables_in_str(
+                    path,
+                    &task_variables,
+                    &variable_names,
+                    &mut substituted_variables,
+                )?,
+            }),
+            test_results => test_results.clone(),
+        };
+        let problem_matchers = self
+            .problem_matcher
+            .iter()
+            .map(|matcher| {
+                matcher.map_file_location(|file_location| {
+                    substitute_all_template_variables_in_str(
+                        file_location,
+                        &task_variables,
+                        &variable_names,
+                        &mut substituted_variables,
+                    )
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
         let task_hash = to_hex_hash(self)
             .context("hashing task template")
             .log_err()?;
@@ -241,21 +293,26 @@ impl TaskTemplate {
             id: id.clone(),
             substituted_variables,
             original_task: self.clone(),
+            task_context: cx.clone(),
             resolved_label: full_label.clone(),
             resolved: SpawnInTerminal {
                 id,
                 cwd,
                 full_label,
                 label: human_readable_label,
-                command_label: args_with_substitutions.iter().fold(
-                    command.clone(),
-                    |mut command_label, arg| {
-                        command_label.push(' ');
-                        command_label.push_str(arg);
-                        command_label
-                    },
-                ),
-                command: Some(command),
+                command_label: if self.is_compound() {
+                    self.depends_on.join(", ")
+                } else {
+                    args_with_substitutions.iter().fold(
+                        command.clone(),
+                        |mut command_label, arg| {
+                            command_label.push(' ');
+                            command_label.push_str(arg);
+                            command_label
+                        },
+                    )
+                },
+                command: Some(command).filter(|_| !self.is_compound()),
                 args: args_with_substitutions,
                 env,
                 use_new_terminal: self.use_new_terminal,
@@ -267,6 +324,8 @@ impl TaskTemplate {
                 show_summary: self.show_summary,
                 show_command: self.show_command,
                 show_rerun: true,
+                test_results,
+                problem_matchers,
             },
         })
     }
@@ -0,0 +1,531 @@
+//! Structured results of test runs, parsed from the output of the test runners tasks spawn.
+
+use anyhow::Context as _;
+use collections::HashMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::SpawnInTerminal;
+
+/// The format in which a task reports the results of the tests it runs.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "format", rename_all = "snake_case")]
+pub enum TestResultsFormat {
+    /// libtest's JSON output, printed by `cargo test -- -Z unstable-options --format json`.
+    LibtestJson,
+    /// The JSON event stream printed by `go test -json`.
+    GoTestJson,
+    /// A JUnit XML report written to `path` (relative to the task's working directory),
+    /// e.g. by `pytest --junitxml=path`.
+    Junit { path: String },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Ignored,
+}
+
+/// A position in a source file associated with a test, with a 0-based row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestLocation {
+    /// A path as reported by the test runner; relative paths are relative to the task's working directory.
+    pub path: PathBuf,
+    pub row: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestResult {
+    /// The full name of the test, as understood by the test runner's filters.
+    pub name: String,
+    /// The test binary, package or class the test belongs to.
+    pub suite: Option<String>,
+    pub status: TestStatus,
+    pub duration: Option<Duration>,
+    /// Output captured for the test, including failure messages.
+    pub output: String,
+    pub location: Option<TestLocation>,
+}
+
+impl TestResult {
+    /// The last segment of the test's name, which is usually the name of the test function.
+    pub fn short_name(&self) -> &str {
+        self.name
+            .rsplit(|c| c == ':' || c == '.' || c == '/')
+            .next()
+            .unwrap_or(&self.name)
+    }
+}
+
+impl TestResultsFormat {
+    /// Parses the results of a format that reports them in the task's terminal output.
+    /// Returns `None` for formats that write their results into a separate report.
+    pub fn parse_output(&self, output: &str) -> Option<Vec<TestResult>> {
+        match self {
+            Self::LibtestJson => Some(parse_libtest_json(output)),
+            Self::GoTestJson => Some(parse_go_test_json(output)),
+            Self::Junit { .. } => None,
+        }
+    }
+
+    /// Returns a copy of `task` that only reruns the `failed` tests.
+    pub fn rerun_failed(&self, task: &SpawnInTerminal, failed: &[&TestResult]) -> SpawnInTerminal {
+        let mut task = task.clone();
+        match self {
+            Self::LibtestJson => {
+                // libtest filters come after the `--` that separates cargo's arguments from the test binary's.
+                let filters_ix = match task.args.iter().position(|arg| arg == "--") {
+                    Some(ix) => ix + 1,
+                    None => {
+                        task.args.push("--".to_owned());
+                        task.args.len()
+                    }
+                };
+                let mut filters = failed
+                    .iter()
+                    .map(|test| test.name.clone())
+                    .collect::<Vec<_>>();
+                filters.dedup();
+                if !task.args.iter().any(|arg| arg == "--exact") {
+                    filters.push("--exact".to_owned());
+                }
+                task.args.splice(filters_ix..filters_ix, filters);
+            }
+            Self::GoTestJson => {
+                // `-run` matches each level of subtests separately, so rerun the whole top-level test.
+                let mut names = failed
+                    .iter()
+                    .map(|test| test.name.split('/').next().unwrap_or(&test.name))
+                    .collect::<Vec<_>>();
+                names.sort_unstable();
+                names.dedup();
+                let pattern = format!("'^({})$'", names.join("|"));
+                if let Some(ix) = task.args.iter().position(|arg| arg == "-run") {
+                    task.args.drain(ix..(ix + 2).min(task.args.len()));
+                }
+                task.args.retain(|arg| !arg.starts_with("-run="));
+                task.args.push("-run".to_owned());
+                task.args.push(pattern);
+            }
+            Self::Junit { .. } => {
+                // JUnit reports carry no runner-agnostic way to select tests, rely on pytest's cache instead.
+                if !task
+                    .args
+                    .iter()
+                    .any(|arg| arg == "--last-failed" || arg == "--lf")
+                {
+                    task.args.push("--last-failed".to_owned());
+                }
+            }
+        }
+        task.command_label = task.args.iter().fold(
+            task.command.clone().unwrap_or_default(),
+            |mut label, arg| {
+                label.push(' ');
+                label.push_str(arg);
+                label
+            },
+        );
+        task
+    }
+}
+
+#[derive(Deserialize)]
+struct LibtestEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    event: String,
+    name: Option<String>,
+    exec_time: Option<f64>,
+    stdout: Option<String>,
+    message: Option<String>,
+}
+
+/// Parses libtest's JSON events from the output of `cargo test`.
+/// Cargo's `Running <target>` lines are used to name the suites the tests belong to.
+pub fn parse_libtest_json(output: &str) -> Vec<TestResult> {
+    let mut results = Vec::new();
+    let mut suite = None;
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(target) = line
+            .strip_prefix("Running ")
+            .or_else(|| line.strip_prefix("Doc-tests "))
+        {
+            let target = target.split(" (").next().unwrap_or(target);
+            suite = Some(target.to_owned());
+            continue;
+        }
+        if !line.starts_with('{') {
+            continue;
+        }
+        let Ok(event) = serde_json::from_str::<LibtestEvent>(line) else {
+            continue;
+        };
+        if event.kind != "test" {
+            continue;
+        }
+        let Some(name) = event.name else {
+            continue;
+        };
+        let status = match event.event.as_str() {
+            "ok" => TestStatus::Passed,
+            "failed" => TestStatus::Failed,
+            "ignored" => TestStatus::Ignored,
+            _ => continue,
+        };
+        let mut output = event.stdout.unwrap_or_default();
+        if let Some(message) = event.message {
+            if !output.is_empty() && !output.ends_with('\n') {
+                output.push('\n');
+            }
+            output.push_str(&message);
+        }
+        results.push(TestResult {
+            location: location_in_output(&output),
+            name,
+            suite: suite.clone(),
+            status,
+            duration: event
+                .exec_time
+                .and_then(|secs| Duration::try_from_secs_f64(secs).ok()),
+            output,
+        });
+    }
+    results
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GoTestEvent {
+    action: String,
+    package: Option<String>,
+    test: Option<String>,
+    elapsed: Option<f64>,
+    output: Option<String>,
+}
+
+/// Parses the JSON event stream printed by `go test -json`.
+pub fn parse_go_test_json(output: &str) -> Vec<TestResult> {
+    let mut results = Vec::new();
+    let mut outputs = HashMap::<(Option<String>, String), String>::default();
+    for line in output.lines() {
+        let line = line.trim();
+        if !line.starts_with('{') {
+            continue;
+        }
+        let Ok(event) = serde_json::from_str::<GoTestEvent>(line) else {
+            continue;
+        };
+        // Events without a test describe the package as a whole.
+        let Some(test) = event.test else {
+            continue;
+        };
+        let key = (event.package, test);
+        let status = match event.action.as_str() {
+            "output" => {
+                if let Some(output) = event.output {
+                    outputs.entry(key).or_default().push_str(&output);
+                }
+                continue;
+            }
+            "pass" => TestStatus::Passed,
+            "fail" => TestStatus::Failed,
+            "skip" => TestStatus::Ignored,
+            _ => continue,
+        };
+        let output = outputs.remove(&key).unwrap_or_default();
+        let (suite, name) = key;
+        results.push(TestResult {
+            location: location_in_output(&output),
+            name,
+            suite,
+            status,
+            duration: event
+                .elapsed
+                .and_then(|secs| Duration::try_from_secs_f64(secs).ok()),
+            output,
+        });
+    }
+    results
+}
+
+/// Parses a JUnit XML report, as written by pytest and most other test runners.
+pub fn parse_junit_xml(xml: &str) -> anyhow::Result<Vec<TestResult>> {
+    let document = roxmltree::Document::parse(xml).context("parsing JUnit report")?;
+    let mut results = Vec::new();
+    for testcase in document
+        .descendants()
+        .filter(|node| node.has_tag_name("testcase"))
+    {
+        let Some(name) = testcase.attribute("name") else {
+            continue;
+        };
+        let suite = testcase
+            .attribute("classname")
+            .map(str::to_owned)
+            .or_else(|| {
+                testcase
+                    .ancestors()
+                    .find(|node| node.has_tag_name("testsuite"))
+                    .and_then(|node| node.attribute("name"))
+                    .map(str::to_owned)
+            });
+
+        let mut status = TestStatus::Passed;
+        let mut output = String::new();
+        for child in testcase.children().filter(|node| node.is_element()) {
+            match child.tag_name().name() {
+                "failure" | "error" => status = TestStatus::Failed,
+                "skipped" => status = TestStatus::Ignored,
+                "system-out" | "system-err" => {}
+                _ => continue,
+            }
+            let text = child
+                .text()
+                .filter(|text| !text.trim().is_empty())
+                .or_else(|| child.attribute("message"));
+            if let Some(text) = text {
+                if !output.is_empty() && !output.ends_with('\n') {
+                    output.push('\n');
+                }
+                output.push_str(text);
+            }
+        }
+
+        let declared_location = testcase.attribute("file").map(|file| TestLocation {
+            path: PathBuf::from(file),
+            row: testcase
+                .attribute("line")
+                .and_then(|line| line.parse::<u32>().ok())
+                .map_or(0, |line| line.saturating_sub(1)),
+        });
+        let location = if status == TestStatus::Failed {
+            location_in_output(&output).or(declared_location)
+        } else {
+            declared_location
+        };
+
+        results.push(TestResult {
+            name: name.to_owned(),
+            suite,
+            status,
+            duration: testcase
+                .attribute("time")
+                .and_then(|time| time.parse::<f64>().ok())
+                .and_then(|secs| Duration::try_from_secs_f64(secs).ok()),
+            output,
+            location,
+        });
+    }
+    Ok(results)
+}
+
+/// Finds the first `path:line` reference in a test's output, such as a panic or assertion location.
+fn location_in_output(output: &str) -> Option<TestLocation> {
+    output
+        .split(|c: char| c.is_whitespace() || c == '\'' || c == '"' || c == ',')
+        .find_map(|word| {
+            let mut parts = word.split(':');
+            let path = parts.next()?;
+            let line = parts.next()?.parse::<u32>().ok()?;
+            let (_, extension) = path.rsplit_once('.')?;
+            if extension.is_empty() || !extension.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return None;
+            }
+            if extension.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            Some(TestLocation {
+                path: PathBuf::from(path),
+                row: line.saturating_sub(1),
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_libtest_json() {
+        let output = r#"
+     Running unittests src/lib.rs (target/debug/deps/demo-1234)
+{ "type": "suite", "event": "started", "test_count": 3 }
+{ "type": "test", "event": "started", "name": "tests::adds" }
+{ "type": "test", "name": "tests::adds", "event": "ok", "exec_time": 0.5 }
+{ "type": "test", "event": "started", "name": "tests::subtracts" }
+{ "type": "test", "name": "tests::subtracts", "event": "failed", "stdout": "\nthread 'tests::subtracts' panicked at src/lib.rs:12:9:\nassertion failed\n" }
+{ "type": "test", "event": "ignored", "name": "tests::slow" }
+{ "type": "suite", "event": "failed", "passed": 1, "failed": 1, "ignored": 1 }
+"#;
+        let results = parse_libtest_json(output);
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| (result.name.as_str(), result.status))
+                .collect::<Vec<_>>(),
+            vec![
+                ("tests::adds", TestStatus::Passed),
+                ("tests::subtracts", TestStatus::Failed),
+                ("tests::slow", TestStatus::Ignored),
+            ]
+        );
+        assert_eq!(results[0].suite.as_deref(), Some("unittests src/lib.rs"));
+        assert_eq!(results[0].duration, Some(Duration::from_millis(500)));
+        assert_eq!(results[1].short_name(), "subtracts");
+        assert_eq!(
+            results[1].location,
+            Some(TestLocation {
+                path: PathBuf::from("src/lib.rs"),
+                row: 11,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_go_test_json() {
+        let output = r#"
+{"Action":"run","Package":"example.com/demo","Test":"TestAdd"}
+{"Action":"output","Package":"example.com/demo","Test":"TestAdd","Output":"=== RUN   TestAdd\n"}
+{"Action":"pass","Package":"example.com/demo","Test":"TestAdd","Elapsed":0.25}
+{"Action":"run","Package":"example.com/demo","Test":"TestSub"}
+{"Action":"output","Package":"example.com/demo","Test":"TestSub","Output":"    demo_test.go:14: got 1, want 2\n"}
+{"Action":"fail","Package":"example.com/demo","Test":"TestSub","Elapsed":0}
+{"Action":"fail","Package":"example.com/demo","Elapsed":0.3}
+"#;
+        let results = parse_go_test_json(output);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].name, "TestAdd");
+        assert_eq!(results[0].status, TestStatus::Passed);
+        assert_eq!(results[0].suite.as_deref(), Some("example.com/demo"));
+        assert_eq!(results[0].duration, Some(Duration::from_millis(250)));
+        assert_eq!(results[1].status, TestStatus::Failed);
+        assert_eq!(results[1].output, "    demo_test.go:14: got 1, want 2\n");
+        assert_eq!(
+            results[1].location,
+            Some(TestLocation {
+                path: PathBuf::from("demo_test.go"),
+                row: 13,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_junit_xml() {
+        let report = r#"<?xml version="1.0" encoding="utf-8"?>
+<testsuites>
+  <testsuite name="pytest" tests="3">
+    <testcase classname="tests.test_math" name="test_add" time="0.010" file="tests/test_math.py" line="3" />
+    <testcase classname="tests.test_math" name="test_sub" time="0.020">
+      <failure message="assert 1 == 2">tests/test_math.py:9: AssertionError</failure>
+    </testcase>
+    <testcase classname="tests.test_math" name="test_skip" time="0">
+      <skipped message="not today" />
+    </testcase>
+  </testsuite>
+</testsuites>"#;
+        let results = parse_junit_xml(report).unwrap();
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| (result.name.as_str(), result.status, result.location.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "test_add",
+                    TestStatus::Passed,
+                    Some(TestLocation {
+                        path: PathBuf::from("tests/test_math.py"),
+                        row: 2,
+                    })
+                ),
+                (
+                    "test_sub",
+                    TestStatus::Failed,
+                    Some(TestLocation {
+                        path: PathBuf::from("tests/test_math.py"),
+                        row: 8,
+                    })
+                ),
+                ("test_skip", TestStatus::Ignored, None),
+            ]
+        );
+        assert_eq!(results[1].suite.as_deref(), Some("tests.test_math"));
+        assert_eq!(results[2].output, "not today");
+        assert!(parse_junit_xml("<testsuite>").is_err());
+    }
+
+    #[test]
+    fn test_parse_malformed_durations() {
+        let report = r#"<testsuite name="pytest">
+  <testcase classname="tests" name="test_negative" time="-1" />
+  <testcase classname="tests" name="test_nan" time="NaN" />
+  <testcase classname="tests" name="test_infinite" time="inf" />
+</testsuite>"#;
+        let results = parse_junit_xml(report).unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|result| result.duration.is_none()));
+
+        let output = r#"{ "type": "test", "name": "tests::adds", "event": "ok", "exec_time": -1 }"#;
+        assert_eq!(parse_libtest_json(output)[0].duration, None);
+
+        let output =
+            r#"{"Action":"pass","Package":"example.com/demo","Test":"TestAdd","Elapsed":-1}"#;
+        assert_eq!(parse_go_test_json(output)[0].duration, None);
+    }
+
+    #[test]
+    fn test_rerun_failed() {
+        let failed = |name: &str| TestResult {
+            name: name.to_owned(),
+            suite: None,
+            status: TestStatus::Failed,
+            duration: None,
+            output: String::new(),
+            location: None,
+        };
+        let task = |command: &str, args: &[&str]| SpawnInTerminal {
+            command: Some(command.to_owned()),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            ..SpawnInTerminal::default()
+        };
+
+        let rerun = TestResultsFormat::LibtestJson.rerun_failed(
+            &task(
+                "cargo",
+                &["test", "--", "-Z", "unstable-options", "--format", "json"],
+            ),
+            &[&failed("tests::a"), &failed("tests::b")],
+        );
+        assert_eq!(
+            rerun.command_label,
+            "cargo test -- tests::a tests::b --exact -Z unstable-options --format json"
+        );
+
+        let rerun = TestResultsFormat::GoTestJson.rerun_failed(
+            &task("go", &["test", "-json", "-run", "TestA", "./..."]),
+            &[&failed("TestB/sub"), &failed("TestA")],
+        );
+        assert_eq!(
+            rerun.command_label,
+            "go test -json ./... -run '^(TestA|TestB)$'"
+        );
+
+        let rerun = TestResultsFormat::Junit {
+            path: "report.xml".to_owned(),
+        }
+        .rerun_failed(
+            &task("pytest", &["--junitxml=report.xml"]),
+            &[&failed("test_a")],
+        );
+        assert_eq!(
+            rerun.command_label,
+            "pytest --junitxml=report.xml --last-failed"
+        );
+    }
+}
@@ -1,9 +1,12 @@
-use anyhow::bail;
+use anyhow::{Context as _, bail};
 use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    CustomProblemMatcher, DependsOrder, EnvVariableReplacer, ProblemMatcher, ProblemPattern,
+    ProblemSeverity, TaskTemplate, TaskTemplates, VariableName,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -41,45 +44,213 @@ enum Command {
     },
 }
 
-impl VsCodeTaskDefinition {
-    fn into_zed_format(
-        self,
-        replacer: &EnvVariableReplacer,
-    ) -> anyhow::Result<Option<TaskTemplate>> {
-        if self.other_attributes.contains_key("dependsOn") {
-            log::warn!(
-                "Skipping deserializing of a task `{}` with the unsupported `dependsOn` key",
-                self.label
-            );
-            return Ok(None);
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatchers {
+    One(VsCodeProblemMatcher),
+    Many(Vec<VsCodeProblemMatcher>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatcher {
+    Named(String),
+    Custom(VsCodeCustomProblemMatcher),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeCustomProblemMatcher {
+    base: Option<String>,
+    #[serde(default)]
+    owner: String,
+    severity: Option<String>,
+    file_location: Option<VsCodeFileLocation>,
+    pattern: Option<VsCodeProblemPatterns>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeFileLocation {
+    Kind(String),
+    KindAndPath(Vec<String>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemPatterns {
+    Named(String),
+    One(VsCodeProblemPattern),
+    Many(Vec<VsCodeProblemPattern>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    file: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    message: Option<usize>,
+    #[serde(default, rename = "loop")]
+    repeat: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeDependsOn {
+    One(VsCodeDependency),
+    Many(Vec<VsCodeDependency>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeDependency {
+    Label(String),
+    /// A task contributed by an extension, e.g. `{ "type": "npm", "script": "build" }`, which we can only refer to by label.
+    Task(HashMap<String, serde_json_lenient::Value>),
+}
+
+impl VsCodeDependsOn {
+    fn into_labels(self) -> Vec<String> {
+        let dependencies = match self {
+            Self::One(dependency) => vec![dependency],
+            Self::Many(dependencies) => dependencies,
+        };
+        dependencies
+            .into_iter()
+            .filter_map(|dependency| match dependency {
+                VsCodeDependency::Label(label) => Some(label),
+                VsCodeDependency::Task(task) => {
+                    log::warn!("Skipping unsupported task dependency {task:?}");
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+impl VsCodeProblemMatcher {
+    fn into_zed_format(self, replacer: &EnvVariableReplacer) -> ProblemMatcher {
+        let matcher = match self {
+            Self::Named(name) => return ProblemMatcher::BuiltIn(name),
+            Self::Custom(matcher) => matcher,
+        };
+        let (mut base, pattern) = (matcher.base, matcher.pattern);
+        let pattern = match pattern {
+            None => Vec::new(),
+            Some(VsCodeProblemPatterns::Named(name)) => {
+                base.get_or_insert(name);
+                Vec::new()
+            }
+            Some(VsCodeProblemPatterns::One(pattern)) => vec![pattern.into_zed_format()],
+            Some(VsCodeProblemPatterns::Many(patterns)) => patterns
+                .into_iter()
+                .map(VsCodeProblemPattern::into_zed_format)
+                .collect(),
         };
+        // Code resolves relative paths against the workspace folder, unless told otherwise.
+        let file_location = match matcher.file_location {
+            Some(VsCodeFileLocation::KindAndPath(location)) if location.len() > 1 => {
+                Some(replacer.replace(&location[1]))
+            }
+            Some(VsCodeFileLocation::Kind(kind)) if kind == "absolute" => None,
+            _ => Some(replacer.replace("${workspaceFolder}")),
+        };
+        ProblemMatcher::Custom(CustomProblemMatcher {
+            base,
+            owner: matcher.owner,
+            severity: matcher.severity.as_deref().and_then(ProblemSeverity::parse),
+            file_location,
+            pattern,
+        })
+    }
+}
 
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+impl VsCodeProblemPattern {
+    fn into_zed_format(self) -> ProblemPattern {
+        ProblemPattern {
+            regexp: self.regexp,
+            file: self.file,
+            line: self.line,
+            column: self.column,
+            end_line: self.end_line,
+            end_column: self.end_column,
+            severity: self.severity,
+            code: self.code,
+            message: self.message,
+            repeat: self.repeat,
+        }
+    }
+}
+
+impl VsCodeTaskDefinition {
+    fn into_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<TaskTemplate> {
+        let depends_on = match self.other_attributes.get("dependsOn") {
+            Some(depends_on) => {
+                serde_json_lenient::from_value::<VsCodeDependsOn>(depends_on.clone())
+                    .with_context(|| format!("parsing dependencies of task `{}`", self.label))?
+                    .into_labels()
+            }
+            None => Vec::new(),
+        };
+        let runs_dependencies_in_sequence = self
+            .other_attributes
+            .get("dependsOrder")
+            .is_some_and(|order| order.as_str() == Some("sequence"));
+        // Code runs dependencies in parallel unless told otherwise.
+        let depends_order = if depends_on.is_empty() || runs_dependencies_in_sequence {
+            DependsOrder::Sequence
+        } else {
+            DependsOrder::Parallel
+        };
+        // `type` is not set in tasks that only run their dependencies, hence command is an Option.
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None if !depends_on.is_empty() => (String::new(), Vec::new()),
+            None => bail!("Missing `type` field in task"),
         };
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
         let args = args.into_iter().map(|arg| replacer.replace(&arg)).collect();
+        let problem_matcher = match self.other_attributes.get("problemMatcher") {
+            Some(problem_matcher) => match serde_json_lenient::from_value::<VsCodeProblemMatchers>(
+                problem_matcher.clone(),
+            )
+            .with_context(|| format!("parsing problem matcher of task `{}`", self.label))
+            .log_err()
+            {
+                Some(VsCodeProblemMatchers::One(matcher)) => {
+                    vec![matcher.into_zed_format(replacer)]
+                }
+                Some(VsCodeProblemMatchers::Many(matchers)) => matchers
+                    .into_iter()
+                    .map(|matcher| matcher.into_zed_format(replacer))
+                    .collect(),
+                None => Vec::new(),
+            },
+            None => Vec::new(),
+        };
         let mut template = TaskTemplate {
             label: self.label,
             command,
             args,
+            problem_matcher,
+            depends_on,
+            depends_order,
             ..TaskTemplate::default()
         };
         if let Some(options) = self.options {
             template.cwd = options.cwd.map(|cwd| replacer.replace(&cwd));
             template.env = options.env;
         }
-        Ok(Some(template))
+        Ok(template)
     }
 }
 
@@ -108,12 +279,7 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
         let templates = value
             .tasks
             .into_iter()
-            .filter_map(|vscode_definition| {
-                vscode_definition
-                    .into_zed_format(&replacer)
-                    .log_err()
-                    .flatten()
-            })
+            .filter_map(|vscode_definition| vscode_definition.into_zed_format(&replacer).log_err())
             .collect();
         Ok(Self(templates))
     }
@@ -124,7 +290,8 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        CustomProblemMatcher, DependsOrder, ProblemMatcher, TaskTemplate, TaskTemplates,
+        VsCodeTaskFile,
         vscode_format::{Command, VsCodeTaskDefinition},
     };
 
@@ -227,6 +394,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matcher: vec![ProblemMatcher::BuiltIn("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
@@ -238,18 +406,21 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matcher: vec![ProblemMatcher::BuiltIn("$tsc-watch".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matcher: vec![ProblemMatcher::BuiltIn("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matcher: vec![ProblemMatcher::BuiltIn("$tsc".to_string())],
                 ..Default::default()
             },
         ];
@@ -325,33 +496,64 @@ mod tests {
             .iter()
             .zip(expected)
             .for_each(|(lhs, rhs)| compare_without_other_attributes(lhs.clone(), rhs));
+        let editors_code_matcher = |base: &str| {
+            ProblemMatcher::Custom(CustomProblemMatcher {
+                base: Some(base.to_string()),
+                owner: String::new(),
+                severity: None,
+                file_location: Some("${ZED_WORKTREE_ROOT}/editors/code/".to_string()),
+                pattern: Vec::new(),
+            })
+        };
         let expected = vec![
             TaskTemplate {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matcher: vec![editors_code_matcher("$tsc-watch")],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matcher: vec![editors_code_matcher("$tsc")],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matcher: vec![ProblemMatcher::BuiltIn("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matcher: vec![ProblemMatcher::BuiltIn("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matcher: vec![editors_code_matcher("$tsc")],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                problem_matcher: vec![ProblemMatcher::BuiltIn("$rustc".to_string())],
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                depends_order: DependsOrder::Parallel,
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                problem_matcher: vec![ProblemMatcher::BuiltIn("$rustc".to_string())],
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                depends_order: DependsOrder::Parallel,
                 ..Default::default()
             },
         ];
@@ -596,6 +596,9 @@ impl PickerDelegate for TasksModalDelegate {
         let task_index = self.matches.get(self.selected_index())?.candidate_id;
         let tasks = self.candidates.as_ref()?;
         let (_, task) = tasks.get(task_index)?;
+        if task.original_task().is_compound() {
+            return None;
+        }
         Some(task.resolved.command_label.clone())
     }
 
@@ -0,0 +1,649 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashSet;
+use editor::Editor;
+use gpui::{
+    Action, App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable, Pixels,
+    Render, Subscription, Task, UniformListScrollHandle, WeakEntity, Window, actions, px,
+    uniform_list,
+};
+use language::Point;
+use project::{Project, WorktreeId};
+use task::{SpawnInTerminal, TestResult, TestResultsFormat, TestStatus};
+use ui::{ListItem, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+actions!(
+    test_results_panel,
+    [
+        /// Toggles focus on the test results panel.
+        ToggleFocus,
+        /// Reruns the tests that failed in the latest test run.
+        RerunFailedTests,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+        workspace.toggle_panel_focus::<TestResultsPanel>(window, cx);
+    });
+    workspace.register_action(|workspace, action: &RerunFailedTests, window, cx| {
+        if let Some(panel) = workspace.panel::<TestResultsPanel>(cx) {
+            panel.update(cx, |panel, cx| panel.rerun_failed(action, window, cx));
+        }
+    });
+}
+
+/// The results of the latest run of a task that reports test results.
+struct TestRun {
+    task: SpawnInTerminal,
+    format: TestResultsFormat,
+    results: Vec<TestResult>,
+}
+
+impl TestRun {
+    fn count(&self, status: TestStatus) -> usize {
+        self.results
+            .iter()
+            .filter(|result| result.status == status)
+            .count()
+    }
+}
+
+#[derive(Clone, Debug)]
+enum ListEntry {
+    Suite {
+        name: Option<String>,
+        collapsed: bool,
+        status: TestStatus,
+        duration: Option<Duration>,
+    },
+    Test {
+        ix: usize,
+    },
+}
+
+pub struct TestResultsPanel {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    position: DockPosition,
+    size: Option<Pixels>,
+    run: Option<TestRun>,
+    entries: Vec<ListEntry>,
+    collapsed_suites: HashSet<Option<String>>,
+    selected_test: Option<usize>,
+    rerun_pending: bool,
+    scroll_handle: UniformListScrollHandle,
+    _subscription: Subscription,
+}
+
+impl TestResultsPanel {
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: &mut AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            workspace.update_in(cx, |workspace, window, cx| {
+                let workspace_entity = cx.entity();
+                let project = workspace.project().clone();
+                cx.new(|cx| {
+                    let subscription =
+                        cx.subscribe_in(&workspace_entity, window, Self::handle_workspace_event);
+                    Self {
+                        workspace: workspace_entity.downgrade(),
+                        project,
+                        focus_handle: cx.focus_handle(),
+                        position: DockPosition::Bottom,
+                        size: None,
+                        run: None,
+                        entries: Vec::new(),
+                        collapsed_suites: HashSet::default(),
+                        selected_test: None,
+                        rerun_pending: false,
+                        scroll_handle: UniformListScrollHandle::new(),
+                        _subscription: subscription,
+                    }
+                })
+            })
+        })
+    }
+
+    fn handle_workspace_event(
+        &mut self,
+        _: &Entity<Workspace>,
+        event: &workspace::Event,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let workspace::Event::TestTaskFinished { task, output, .. } = event else {
+            return;
+        };
+        let Some(format) = task.test_results.clone() else {
+            return;
+        };
+        let results = self.load_results(task, &format, output, cx);
+        let task = task.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let results = results.await?;
+            this.update(cx, |this, cx| this.show_results(task, format, results, cx))
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn load_results(
+        &self,
+        task: &SpawnInTerminal,
+        format: &TestResultsFormat,
+        output: &str,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TestResult>>> {
+        if let Some(results) = format.parse_output(output) {
+            return Task::ready(Ok(results));
+        }
+        let TestResultsFormat::Junit { path } = format else {
+            return Task::ready(Ok(Vec::new()));
+        };
+
+        let report_path = self.resolve_path(task, Path::new(path), cx);
+        let project = self.project.read(cx);
+        if project.is_local() {
+            let fs = project.fs().clone();
+            return cx.background_spawn(async move {
+                let report = fs
+                    .load(&report_path)
+                    .await
+                    .with_context(|| format!("loading JUnit report {report_path:?}"))?;
+                task::parse_junit_xml(&report)
+            });
+        }
+
+        // Remote reports can only be read through the project's worktrees.
+        let Some(project_path) = project.find_project_path(&report_path, cx) else {
+            return Task::ready(Err(anyhow!(
+                "JUnit report {report_path:?} is outside of the project"
+            )));
+        };
+        let buffer = self
+            .project
+            .update(cx, |project, cx| project.open_buffer(project_path, cx));
+        cx.spawn(async move |_, cx| {
+            let report = buffer.await?.read_with(cx, |buffer, _| buffer.text())?;
+            task::parse_junit_xml(&report)
+        })
+    }
+
+    /// Resolves a path reported by a test runner against the working directory of its task.
+    fn resolve_path(&self, task: &SpawnInTerminal, path: &Path, cx: &App) -> PathBuf {
+        if path.is_absolute() {
+            return path.to_path_buf();
+        }
+        let cwd = task.cwd.clone().or_else(|| {
+            self.project
+                .read(cx)
+                .visible_worktrees(cx)
+                .next()
+                .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+        });
+        match cwd {
+            Some(cwd) => cwd.join(path),
+            None => path.to_path_buf(),
+        }
+    }
+
+    /// Finds the worktree and worktree-relative file of each result. Results without a location
+    /// are attributed to the worktree the task ran in.
+    fn locate_results<'a>(
+        &self,
+        run: &'a TestRun,
+        cx: &App,
+    ) -> Vec<(Option<WorktreeId>, Option<Arc<Path>>, &'a TestResult)> {
+        let project = self.project.read(cx);
+        let find_worktree = |abs_path: &Path| {
+            project
+                .find_worktree(abs_path, cx)
+                .map(|(worktree, path)| (worktree.read(cx).id(), Arc::from(path.as_path())))
+        };
+        let task_worktree_id = run
+            .task
+            .cwd
+            .as_deref()
+            .and_then(find_worktree)
+            .map(|(worktree_id, _)| worktree_id);
+        run.results
+            .iter()
+            .map(|result| {
+                let located = result.location.as_ref().and_then(|location| {
+                    find_worktree(&self.resolve_path(&run.task, &location.path, cx))
+                });
+                match located {
+                    Some((worktree_id, path)) => (Some(worktree_id), Some(path), result),
+                    None => (task_worktree_id, None, result),
+                }
+            })
+            .collect()
+    }
+
+    fn show_results(
+        &mut self,
+        task: SpawnInTerminal,
+        format: TestResultsFormat,
+        results: Vec<TestResult>,
+        cx: &mut Context<Self>,
+    ) {
+        match self.run.as_mut() {
+            // A rerun of the failed tests only updates their results in the original run.
+            Some(run) if self.rerun_pending && run.task.id == task.id => {
+                for result in results {
+                    match run.results.iter_mut().find(|existing| {
+                        existing.name == result.name && existing.suite == result.suite
+                    }) {
+                        Some(existing) => *existing = result,
+                        None => run.results.push(result),
+                    }
+                }
+            }
+            _ => {
+                self.collapsed_suites.clear();
+                self.run = Some(TestRun {
+                    task,
+                    format,
+                    results,
+                });
+            }
+        }
+        self.rerun_pending = false;
+        self.selected_test = None;
+        self.update_entries();
+
+        if let Some(run) = &self.run {
+            if let Some(inventory) = self
+                .project
+                .read(cx)
+                .task_store()
+                .read(cx)
+                .task_inventory()
+                .cloned()
+            {
+                let results = self.locate_results(run, cx);
+                inventory.update(cx, |inventory, _| inventory.set_test_results(results));
+            }
+        }
+        // Refresh the test statuses shown next to runnables in the gutter.
+        if let Some(workspace) = self.workspace.upgrade() {
+            let editors = workspace
+                .read(cx)
+                .items_of_type::<Editor>(cx)
+                .collect::<Vec<_>>();
+            for editor in editors {
+                editor.update(cx, |_, cx| cx.notify());
+            }
+        }
+        cx.notify();
+    }
+
+    fn update_entries(&mut self) {
+        self.entries.clear();
+        let Some(run) = &self.run else {
+            return;
+        };
+
+        let mut suites = Vec::<(Option<String>, Vec<usize>)>::new();
+        for (ix, result) in run.results.iter().enumerate() {
+            match suites.iter_mut().find(|(suite, _)| *suite == result.suite) {
+                Some((_, tests)) => tests.push(ix),
+                None => suites.push((result.suite.clone(), vec![ix])),
+            }
+        }
+
+        for (name, tests) in suites {
+            let statuses = tests.iter().map(|ix| run.results[*ix].status);
+            let status = if statuses.clone().any(|status| status == TestStatus::Failed) {
+                TestStatus::Failed
+            } else if statuses.clone().any(|status| status == TestStatus::Passed) {
+                TestStatus::Passed
+            } else {
+                TestStatus::Ignored
+            };
+            let duration = tests
+                .iter()
+                .filter_map(|ix| run.results[*ix].duration)
+                .reduce(|total, duration| total + duration);
+            let collapsed = self.collapsed_suites.contains(&name);
+            self.entries.push(ListEntry::Suite {
+                name,
+                collapsed,
+                status,
+                duration,
+            });
+            if !collapsed {
+                self.entries
+                    .extend(tests.into_iter().map(|ix| ListEntry::Test { ix }));
+            }
+        }
+    }
+
+    fn toggle_suite(&mut self, name: Option<String>, cx: &mut Context<Self>) {
+        if !self.collapsed_suites.remove(&name) {
+            self.collapsed_suites.insert(name);
+        }
+        self.update_entries();
+        cx.notify();
+    }
+
+    fn rerun_failed(&mut self, _: &RerunFailedTests, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(run) = &self.run else {
+            return;
+        };
+        let failed = run
+            .results
+            .iter()
+            .filter(|result| result.status == TestStatus::Failed)
+            .collect::<Vec<_>>();
+        if failed.is_empty() {
+            return;
+        }
+        let task = run.format.rerun_failed(&run.task, &failed);
+        self.rerun_pending = true;
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.spawn_task_with_output(task, window, cx)
+            })
+            .log_err();
+    }
+
+    fn rerun_all(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(run) = &self.run else {
+            return;
+        };
+        let task = run.task.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.spawn_task_with_output(task, window, cx)
+            })
+            .log_err();
+    }
+
+    fn open_test(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.selected_test = Some(ix);
+        cx.notify();
+
+        let Some(run) = &self.run else {
+            return;
+        };
+        let Some(location) = run
+            .results
+            .get(ix)
+            .and_then(|result| result.location.clone())
+        else {
+            return;
+        };
+        let abs_path = self.resolve_path(&run.task, &location.path, cx);
+        let worktree = self.project.update(cx, |project, cx| {
+            project.find_or_create_worktree(abs_path, false, cx)
+        });
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            let (worktree, relative_path) = worktree.await?;
+            let worktree_id = worktree.read_with(cx, |worktree, _| worktree.id())?;
+            let item = workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.open_path((worktree_id, relative_path), None, true, window, cx)
+                })?
+                .await?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor.update_in(cx, |editor, window, cx| {
+                    editor.go_to_singleton_buffer_point(Point::new(location.row, 0), window, cx);
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_entries(
+        &mut self,
+        range: Range<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let Some(run) = &self.run else {
+            return Vec::new();
+        };
+        self.entries[range.clone()]
+            .iter()
+            .zip(range)
+            .map(|(entry, entry_ix)| match entry {
+                ListEntry::Suite {
+                    name,
+                    collapsed,
+                    status,
+                    duration,
+                } => {
+                    let suite = name.clone();
+                    ListItem::new(("test-suite", entry_ix))
+                        .toggle(Some(!collapsed))
+                        .on_toggle(
+                            cx.listener(move |this, _, _, cx| this.toggle_suite(suite.clone(), cx)),
+                        )
+                        .on_click({
+                            let suite = name.clone();
+                            cx.listener(move |this, _, _, cx| this.toggle_suite(suite.clone(), cx))
+                        })
+                        .start_slot(status_icon(*status))
+                        .child(Label::new(
+                            name.clone().unwrap_or_else(|| "Tests".to_owned()),
+                        ))
+                        .end_slot::<Label>(duration.map(duration_label))
+                        .into_any_element()
+                }
+                ListEntry::Test { ix } => {
+                    let ix = *ix;
+                    let result = &run.results[ix];
+                    ListItem::new(("test", entry_ix))
+                        .indent_level(1)
+                        .toggle_state(self.selected_test == Some(ix))
+                        .on_click(
+                            cx.listener(move |this, _, window, cx| this.open_test(ix, window, cx)),
+                        )
+                        .start_slot(status_icon(result.status))
+                        .child(Label::new(result.name.clone()))
+                        .end_slot::<Label>(result.duration.map(duration_label))
+                        .into_any_element()
+                }
+            })
+            .collect()
+    }
+
+    fn render_header(&self, run: &TestRun, cx: &mut Context<Self>) -> impl IntoElement {
+        let failed = run.count(TestStatus::Failed);
+        let summary = format!(
+            "{} passed, {} failed, {} ignored",
+            run.count(TestStatus::Passed),
+            failed,
+            run.count(TestStatus::Ignored),
+        );
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(run.task.label.clone()).size(LabelSize::Small))
+                    .child(
+                        Label::new(summary)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new("rerun-all-tests", IconName::Rerun)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Rerun All Tests"))
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.rerun_all(window, cx)),
+                            ),
+                    )
+                    .child(
+                        Button::new("rerun-failed-tests", "Rerun Failed")
+                            .label_size(LabelSize::Small)
+                            .disabled(failed == 0)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.rerun_failed(&RerunFailedTests, window, cx)
+                            })),
+                    ),
+            )
+    }
+}
+
+fn status_icon(status: TestStatus) -> Icon {
+    let (icon, color) = match status {
+        TestStatus::Passed => (IconName::Check, Color::Success),
+        TestStatus::Failed => (IconName::XCircle, Color::Error),
+        TestStatus::Ignored => (IconName::Dash, Color::Muted),
+    };
+    Icon::new(icon).size(IconSize::Small).color(color)
+}
+
+fn duration_label(duration: Duration) -> Label {
+    let text = if duration < Duration::from_secs(1) {
+        format!("{}ms", duration.as_millis())
+    } else {
+        format!("{:.2}s", duration.as_secs_f64())
+    };
+    Label::new(text).size(LabelSize::Small).color(Color::Muted)
+}
+
+impl EventEmitter<PanelEvent> for TestResultsPanel {}
+
+impl Focusable for TestResultsPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for TestResultsPanel {
+    fn persistent_name() -> &'static str {
+        "TestResultsPanel"
+    }
+
+    fn position(&self, _: &Window, _: &App) -> DockPosition {
+        self.position
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        self.position = position;
+        cx.notify();
+    }
+
+    fn size(&self, _: &Window, _: &App) -> Pixels {
+        self.size.unwrap_or(px(300.))
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.size = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, _: &App) -> Option<IconName> {
+        Some(IconName::ListTodo)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Test Results Panel")
+    }
+
+    fn icon_label(&self, _: &Window, _: &App) -> Option<String> {
+        let failed = self.run.as_ref()?.count(TestStatus::Failed);
+        (failed > 0).then(|| failed.to_string())
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        10
+    }
+}
+
+impl Render for TestResultsPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = match &self.run {
+            None => v_flex()
+                .size_full()
+                .items_center()
+                .justify_center()
+                .child(
+                    Label::new("Run a task with `test_results` configured to see its results here")
+                        .color(Color::Muted),
+                )
+                .into_any_element(),
+            Some(run) => {
+                let output = self
+                    .selected_test
+                    .and_then(|ix| run.results.get(ix))
+                    .map(|result| result.output.trim_end().to_owned())
+                    .filter(|output| !output.is_empty());
+                v_flex()
+                    .size_full()
+                    .child(self.render_header(run, cx))
+                    .child(
+                        uniform_list(
+                            "test-results",
+                            self.entries.len(),
+                            cx.processor(Self::render_entries),
+                        )
+                        .track_scroll(self.scroll_handle.clone())
+                        .flex_1()
+                        .size_full(),
+                    )
+                    .when_some(output, |this, output| {
+                        this.child(
+                            div()
+                                .id("test-output")
+                                .max_h(px(240.))
+                                .p_2()
+                                .border_t_1()
+                                .border_color(cx.theme().colors().border)
+                                .overflow_y_scroll()
+                                .font_buffer(cx)
+                                .text_buffer(cx)
+                                .child(output),
+                        )
+                    })
+                    .into_any_element()
+            }
+        };
+
+        v_flex()
+            .id("test-results-panel")
+            .key_context("TestResultsPanel")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::rerun_failed))
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(content)
+    }
+}
@@ -905,6 +905,14 @@ impl TerminalPanel {
         self.assistant_enabled
     }
 
+    /// The terminal shown in the active pane of the panel, if any.
+    pub fn active_terminal_view(&self, cx: &App) -> Option<Entity<TerminalView>> {
+        self.active_pane
+            .read(cx)
+            .active_item()?
+            .downcast::<TerminalView>()
+    }
+
     fn is_enabled(&self, cx: &App) -> bool {
         self.workspace.upgrade().map_or(false, |workspace| {
             is_enabled_in_workspace(workspace.read(cx), cx)
@@ -1489,6 +1497,36 @@ impl workspace::TerminalProvider for TerminalProvider {
             }
         })
     }
+
+    fn spawn_with_output(
+        &self,
+        task: SpawnInTerminal,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Option<Result<(ExitStatus, String)>>> {
+        let terminal_panel = self.0.clone();
+        window.spawn(cx, async move |cx| {
+            let terminal = terminal_panel
+                .update_in(cx, |terminal_panel, window, cx| {
+                    terminal_panel.spawn_task(&task, window, cx)
+                })
+                .ok()?
+                .await;
+            match terminal {
+                Ok(terminal) => {
+                    let exit_status = terminal
+                        .read_with(cx, |terminal, cx| terminal.wait_for_completed_task(cx))
+                        .ok()?
+                        .await?;
+                    let output = terminal
+                        .read_with(cx, |terminal, _| terminal.get_content())
+                        .ok()?;
+                    Some(Ok((exit_status, output)))
+                }
+                Err(e) => Some(Err(e)),
+            }
+        })
+    }
 }
 
 struct InlineAssistTabBarButton {
@@ -1849,6 +1849,8 @@ impl SearchableItem for TerminalView {
             case: false,
             word: false,
             regex: true,
+            structural: false,
+            syntax: false,
             replacement: false,
             selection: false,
             find_in_results: false,
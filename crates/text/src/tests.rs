@@ -621,6 +621,84 @@ fn test_history() {
     assert_eq!(buffer.text(), "X12cde6");
 }
 
+#[test]
+fn test_undo_tree() {
+    let mut now = Instant::now();
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abc");
+
+    let transaction_1 = buffer.start_transaction_at(now).unwrap();
+    buffer.edit([(3..3, "1")]);
+    buffer.end_transaction_at(now);
+    let edited_at_1 = now;
+
+    now += Duration::from_secs(1);
+    let transaction_2 = buffer.start_transaction_at(now).unwrap();
+    buffer.edit([(4..4, "2")]);
+    buffer.end_transaction_at(now);
+    assert_eq!(buffer.text(), "abc12");
+
+    // Editing after an undo starts a new branch, instead of discarding the undone transaction.
+    buffer.undo();
+    now += Duration::from_secs(1);
+    let transaction_3 = buffer.start_transaction_at(now).unwrap();
+    buffer.edit([(4..4, "3")]);
+    buffer.end_transaction_at(now);
+    assert_eq!(buffer.text(), "abc13");
+    assert_eq!(
+        buffer
+            .undo_tree()
+            .into_iter()
+            .map(|node| (node.transaction_id, node.parent))
+            .collect::<Vec<_>>(),
+        vec![
+            (transaction_1, None),
+            (transaction_2, Some(transaction_1)),
+            (transaction_3, Some(transaction_1)),
+        ]
+    );
+    assert_eq!(buffer.undo_tree_position(), Some(transaction_3));
+    assert!(buffer.redo().is_none());
+
+    assert_eq!(
+        buffer
+            .text_for_undo_tree_state(Some(transaction_2))
+            .map(|text| text.to_string()),
+        Some("abc12".to_string())
+    );
+    assert_eq!(
+        buffer
+            .text_for_undo_tree_state(None)
+            .map(|text| text.to_string()),
+        Some("abc".to_string())
+    );
+    assert_eq!(buffer.text(), "abc13");
+
+    assert_eq!(buffer.undo_tree_older(), Some(Some(transaction_2)));
+    buffer.undo_tree_goto(Some(transaction_2));
+    assert_eq!(buffer.text(), "abc12");
+    assert_eq!(buffer.undo_tree_position(), Some(transaction_2));
+    assert_eq!(buffer.undo_tree_newer(), Some(transaction_3));
+
+    // Linear undo and redo follow the branch of the current state.
+    buffer.undo();
+    assert_eq!(buffer.text(), "abc1");
+    buffer.redo();
+    assert_eq!(buffer.text(), "abc12");
+
+    buffer.undo_tree_goto(None);
+    assert_eq!(buffer.text(), "abc");
+    assert_eq!(buffer.undo_tree_older(), None);
+    buffer.undo_tree_goto(Some(transaction_3));
+    assert_eq!(buffer.text(), "abc13");
+    assert_eq!(buffer.undo_tree().len(), 3);
+
+    assert_eq!(buffer.undo_tree_state_at(edited_at_1), Some(transaction_1));
+    assert_eq!(
+        buffer.undo_tree_state_at(edited_at_1 - Duration::from_secs(1)),
+        None
+    );
+}
+
 #[test]
 fn test_finalize_last_transaction() {
     let now = Instant::now();
@@ -140,6 +140,10 @@ impl HistoryEntry {
     pub fn transaction_id(&self) -> TransactionId {
         self.transaction.id
     }
+
+    pub fn transaction(&self) -> &Transaction {
+        &self.transaction
+    }
 }
 
 struct History {
@@ -147,10 +151,23 @@ struct History {
     operations: TreeMap<clock::Lamport, Operation>,
     undo_stack: Vec<HistoryEntry>,
     redo_stack: Vec<HistoryEntry>,
+    /// Entries that were undone and then branched from by a new transaction, along with the
+    /// transaction they were applied on top of. Together with the undo and redo stacks, they
+    /// form the buffer's undo tree.
+    abandoned: Vec<(HistoryEntry, Option<TransactionId>)>,
     transaction_depth: usize,
     group_interval: Duration,
 }
 
+/// A state of the buffer in its undo tree, reached by applying a transaction on top of its parent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UndoTreeNode {
+    pub transaction_id: TransactionId,
+    /// The state the transaction was applied to, or `None` for the buffer's initial state.
+    pub parent: Option<TransactionId>,
+    pub edited_at: Instant,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 struct InsertionSlice {
     edit_id: clock::Lamport,
@@ -191,6 +208,7 @@ impl History {
             operations: Default::default(),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            abandoned: Vec::new(),
             transaction_depth: 0,
             // Don't group transactions in tests unless we opt in, because it's a footgun.
             #[cfg(any(test, feature = "test-support"))]
@@ -244,7 +262,13 @@ impl History {
                 self.undo_stack.pop();
                 None
             } else {
-                self.redo_stack.clear();
+                let parent = self
+                    .undo_stack
+                    .iter()
+                    .rev()
+                    .nth(1)
+                    .map(|entry| entry.transaction.id);
+                self.abandon_redo_stack(parent);
                 let entry = self.undo_stack.last_mut().unwrap();
                 entry.last_edit_at = now;
                 Some(entry)
@@ -299,6 +323,16 @@ impl History {
             if let Some(entry) = entries_to_merge.last_mut() {
                 last_entry.last_edit_at = entry.last_edit_at;
             }
+
+            let last_entry_id = last_entry.transaction.id;
+            for (_, parent) in &mut self.abandoned {
+                if entries_to_merge
+                    .iter()
+                    .any(|entry| Some(entry.transaction.id) == *parent)
+                {
+                    *parent = Some(last_entry_id);
+                }
+            }
         }
 
         self.undo_stack.truncate(new_len);
@@ -320,7 +354,98 @@ impl History {
             last_edit_at: now,
             suppress_grouping: false,
         });
-        self.redo_stack.clear();
+        let parent = self
+            .undo_stack
+            .iter()
+            .rev()
+            .nth(1)
+            .map(|entry| entry.transaction.id);
+        self.abandon_redo_stack(parent);
+    }
+
+    /// Moves the redo stack into the abandoned branches of the undo tree, as the child of `parent`.
+    fn abandon_redo_stack(&mut self, mut parent: Option<TransactionId>) {
+        for entry in self.redo_stack.drain(..).rev() {
+            let transaction_id = entry.transaction.id;
+            self.abandoned.push((entry, parent));
+            parent = Some(transaction_id);
+        }
+    }
+
+    fn reparent_abandoned(&mut self, old_parent: TransactionId, new_parent: Option<TransactionId>) {
+        for (_, parent) in &mut self.abandoned {
+            if *parent == Some(old_parent) {
+                *parent = new_parent;
+            }
+        }
+    }
+
+    fn undo_tree(&self) -> Vec<UndoTreeNode> {
+        let mut nodes = Vec::new();
+        let mut parent = None;
+        for entry in self.undo_stack.iter().chain(self.redo_stack.iter().rev()) {
+            nodes.push(UndoTreeNode {
+                transaction_id: entry.transaction.id,
+                parent,
+                edited_at: entry.last_edit_at,
+            });
+            parent = Some(entry.transaction.id);
+        }
+        nodes.extend(self.abandoned.iter().map(|(entry, parent)| UndoTreeNode {
+            transaction_id: entry.transaction.id,
+            parent: *parent,
+            edited_at: entry.last_edit_at,
+        }));
+        nodes.sort_by_key(|node| node.transaction_id);
+        nodes
+    }
+
+    /// Returns the transactions to apply, starting from the buffer's initial state, to reach the
+    /// given state of the undo tree.
+    fn undo_tree_path(&self, target: Option<TransactionId>) -> Option<Vec<TransactionId>> {
+        let mut path = Vec::new();
+        let mut current = target;
+        while let Some(transaction_id) = current {
+            if let Some(ix) = self
+                .undo_stack
+                .iter()
+                .position(|entry| entry.transaction.id == transaction_id)
+            {
+                path.extend(
+                    self.undo_stack[..=ix]
+                        .iter()
+                        .rev()
+                        .map(|e| e.transaction.id),
+                );
+                break;
+            } else if let Some(ix) = self
+                .redo_stack
+                .iter()
+                .position(|entry| entry.transaction.id == transaction_id)
+            {
+                path.extend(self.redo_stack[ix..].iter().map(|e| e.transaction.id));
+                path.extend(self.undo_stack.iter().rev().map(|e| e.transaction.id));
+                break;
+            } else {
+                let (_, parent) = self
+                    .abandoned
+                    .iter()
+                    .find(|(entry, _)| entry.transaction.id == transaction_id)?;
+                path.push(transaction_id);
+                current = *parent;
+            }
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    fn undo_tree_transaction(&self, transaction_id: TransactionId) -> Option<&Transaction> {
+        self.transaction(transaction_id).or_else(|| {
+            self.abandoned
+                .iter()
+                .find(|(entry, _)| entry.transaction.id == transaction_id)
+                .map(|(entry, _)| &entry.transaction)
+        })
     }
 
     fn push_undo(&mut self, op_id: clock::Lamport) {
@@ -375,13 +500,31 @@ impl History {
             .iter()
             .rposition(|entry| entry.transaction.id == transaction_id)
         {
+            let parent = entry_ix
+                .checked_sub(1)
+                .map(|ix| self.undo_stack[ix].transaction.id);
+            self.reparent_abandoned(transaction_id, parent);
             Some(self.undo_stack.remove(entry_ix).transaction)
         } else if let Some(entry_ix) = self
             .redo_stack
             .iter()
             .rposition(|entry| entry.transaction.id == transaction_id)
         {
+            let parent = self
+                .redo_stack
+                .get(entry_ix + 1)
+                .or(self.undo_stack.last())
+                .map(|entry| entry.transaction.id);
+            self.reparent_abandoned(transaction_id, parent);
             Some(self.redo_stack.remove(entry_ix).transaction)
+        } else if let Some(entry_ix) = self
+            .abandoned
+            .iter()
+            .position(|(entry, _)| entry.transaction.id == transaction_id)
+        {
+            let (entry, parent) = self.abandoned.remove(entry_ix);
+            self.reparent_abandoned(transaction_id, parent);
+            Some(entry.transaction)
         } else {
             None
         }
@@ -1215,108 +1358,8 @@ impl Buffer {
         self.subscriptions.publish_mut(&edits_patch)
     }
 
-    fn fragment_ids_for_edits<'a>(
-        &'a self,
-        edit_ids: impl Iterator<Item = &'a clock::Lamport>,
-    ) -> Vec<&'a Locator> {
-        // Get all of the insertion slices changed by the given edits.
-        let mut insertion_slices = Vec::new();
-        for edit_id in edit_ids {
-            let insertion_slice = InsertionSlice {
-                edit_id: *edit_id,
-                insertion_id: clock::Lamport::default(),
-                range: 0..0,
-            };
-            let slices = self
-                .snapshot
-                .insertion_slices
-                .iter_from(&insertion_slice)
-                .take_while(|slice| slice.edit_id == *edit_id);
-            insertion_slices.extend(slices)
-        }
-        insertion_slices
-            .sort_unstable_by_key(|s| (s.insertion_id, s.range.start, Reverse(s.range.end)));
-
-        // Get all of the fragments corresponding to these insertion slices.
-        let mut fragment_ids = Vec::new();
-        let mut insertions_cursor = self.insertions.cursor::<InsertionFragmentKey>(&());
-        for insertion_slice in &insertion_slices {
-            if insertion_slice.insertion_id != insertions_cursor.start().timestamp
-                || insertion_slice.range.start > insertions_cursor.start().split_offset
-            {
-                insertions_cursor.seek_forward(
-                    &InsertionFragmentKey {
-                        timestamp: insertion_slice.insertion_id,
-                        split_offset: insertion_slice.range.start,
-                    },
-                    Bias::Left,
-                    &(),
-                );
-            }
-            while let Some(item) = insertions_cursor.item() {
-                if item.timestamp != insertion_slice.insertion_id
-                    || item.split_offset >= insertion_slice.range.end
-                {
-                    break;
-                }
-                fragment_ids.push(&item.fragment_id);
-                insertions_cursor.next(&());
-            }
-        }
-        fragment_ids.sort_unstable();
-        fragment_ids
-    }
-
     fn apply_undo(&mut self, undo: &UndoOperation) {
-        self.snapshot.undo_map.insert(undo);
-
-        let mut edits = Patch::default();
-        let mut old_fragments = self.fragments.cursor::<(Option<&Locator>, usize)>(&None);
-        let mut new_fragments = SumTree::new(&None);
-        let mut new_ropes =
-            RopeBuilder::new(self.visible_text.cursor(0), self.deleted_text.cursor(0));
-
-        for fragment_id in self.fragment_ids_for_edits(undo.counts.keys()) {
-            let preceding_fragments = old_fragments.slice(&Some(fragment_id), Bias::Left, &None);
-            new_ropes.append(preceding_fragments.summary().text);
-            new_fragments.append(preceding_fragments, &None);
-
-            if let Some(fragment) = old_fragments.item() {
-                let mut fragment = fragment.clone();
-                let fragment_was_visible = fragment.visible;
-
-                fragment.visible = fragment.is_visible(&self.undo_map);
-                fragment.max_undos.observe(undo.timestamp);
-
-                let old_start = old_fragments.start().1;
-                let new_start = new_fragments.summary().text.visible;
-                if fragment_was_visible && !fragment.visible {
-                    edits.push(Edit {
-                        old: old_start..old_start + fragment.len,
-                        new: new_start..new_start,
-                    });
-                } else if !fragment_was_visible && fragment.visible {
-                    edits.push(Edit {
-                        old: old_start..old_start,
-                        new: new_start..new_start + fragment.len,
-                    });
-                }
-                new_ropes.push_fragment(&fragment, fragment_was_visible);
-                new_fragments.push(fragment, &None);
-
-                old_fragments.next(&None);
-            }
-        }
-
-        let suffix = old_fragments.suffix(&None);
-        new_ropes.append(suffix.summary().text);
-        new_fragments.append(suffix, &None);
-
-        drop(old_fragments);
-        let (visible_text, deleted_text) = new_ropes.finish();
-        self.snapshot.fragments = new_fragments;
-        self.snapshot.visible_text = visible_text;
-        self.snapshot.deleted_text = deleted_text;
+        let edits = self.snapshot.undo_fragments(undo);
         self.subscriptions.publish_mut(&edits);
     }
 
@@ -1357,6 +1400,11 @@ impl Buffer {
         self.history.redo_stack.last()
     }
 
+    /// Returns the entries of the undo stack, oldest first.
+    pub fn undo_stack(&self) -> &[HistoryEntry] {
+        &self.history.undo_stack
+    }
+
     pub fn start_transaction(&mut self) -> Option<TransactionId> {
         self.start_transaction_at(Instant::now())
     }
@@ -1467,6 +1515,146 @@ impl Buffer {
             .collect()
     }
 
+    /// Returns the states of the buffer in its undo tree, in the order they were created.
+    pub fn undo_tree(&self) -> Vec<UndoTreeNode> {
+        self.history.undo_tree()
+    }
+
+    /// Returns the current state of the buffer in its undo tree, or `None` if the buffer is in
+    /// its initial state.
+    pub fn undo_tree_position(&self) -> Option<TransactionId> {
+        self.history
+            .undo_stack
+            .last()
+            .map(|entry| entry.transaction.id)
+    }
+
+    /// Returns the state created right before the current one, across branches of the undo tree,
+    /// or `None` if the buffer is already in its initial state.
+    pub fn undo_tree_older(&self) -> Option<Option<TransactionId>> {
+        let current = self.undo_tree_position()?;
+        Some(
+            self.history
+                .undo_tree()
+                .into_iter()
+                .map(|node| node.transaction_id)
+                .filter(|transaction_id| *transaction_id < current)
+                .next_back(),
+        )
+    }
+
+    /// Returns the state created right after the current one, across branches of the undo tree.
+    pub fn undo_tree_newer(&self) -> Option<TransactionId> {
+        let current = self.undo_tree_position();
+        self.history
+            .undo_tree()
+            .into_iter()
+            .map(|node| node.transaction_id)
+            .find(|transaction_id| current.map_or(true, |current| *transaction_id > current))
+    }
+
+    /// Returns the latest state of the undo tree that was last edited at or before `time`, or
+    /// `None` if there's no such state, which corresponds to the buffer's initial state.
+    pub fn undo_tree_state_at(&self, time: Instant) -> Option<TransactionId> {
+        self.history
+            .undo_tree()
+            .into_iter()
+            .filter(|node| node.edited_at <= time)
+            .map(|node| node.transaction_id)
+            .next_back()
+    }
+
+    /// Moves the buffer to the given state of its undo tree, or to its initial state for `None`,
+    /// undoing and redoing transactions along the way. The branch that is left stays in the tree.
+    pub fn undo_tree_goto(&mut self, target: Option<TransactionId>) -> Vec<Operation> {
+        assert_eq!(self.history.transaction_depth, 0);
+        let Some(path) = self.history.undo_tree_path(target) else {
+            return Vec::new();
+        };
+        let common_len = self
+            .history
+            .undo_stack
+            .iter()
+            .zip(&path)
+            .take_while(|(entry, transaction_id)| entry.transaction.id == **transaction_id)
+            .count();
+
+        let mut operations = Vec::new();
+        while self.history.undo_stack.len() > common_len {
+            operations.extend(self.undo().map(|(_, operation)| operation));
+        }
+        for transaction_id in &path[common_len..] {
+            if self
+                .history
+                .redo_stack
+                .last()
+                .map(|entry| entry.transaction.id)
+                == Some(*transaction_id)
+            {
+                operations.extend(self.redo().map(|(_, operation)| operation));
+            } else {
+                let parent = self.undo_tree_position();
+                self.history.abandon_redo_stack(parent);
+                let Some(entry_ix) = self
+                    .history
+                    .abandoned
+                    .iter()
+                    .position(|(entry, _)| entry.transaction.id == *transaction_id)
+                else {
+                    break;
+                };
+                let (entry, _) = self.history.abandoned.remove(entry_ix);
+                let transaction = entry.transaction.clone();
+                self.history.undo_stack.push(entry);
+                operations.push(self.undo_or_redo(transaction));
+            }
+        }
+        operations
+    }
+
+    /// Returns the text the buffer would have in the given state of its undo tree, without
+    /// changing the buffer.
+    pub fn text_for_undo_tree_state(&self, target: Option<TransactionId>) -> Option<Rope> {
+        let path = self.history.undo_tree_path(target)?;
+        let common_len = self
+            .history
+            .undo_stack
+            .iter()
+            .zip(&path)
+            .take_while(|(entry, transaction_id)| entry.transaction.id == **transaction_id)
+            .count();
+
+        let mut counts = HashMap::default();
+        let toggled =
+            self.history.undo_stack[common_len..]
+                .iter()
+                .map(|entry| &entry.transaction)
+                .chain(path[common_len..].iter().filter_map(|transaction_id| {
+                    self.history.undo_tree_transaction(*transaction_id)
+                }));
+        for transaction in toggled {
+            for edit_id in &transaction.edit_ids {
+                counts.insert(
+                    *edit_id,
+                    self.undo_map.undo_count(*edit_id).saturating_add(1),
+                );
+            }
+        }
+        if counts.is_empty() {
+            return Some(self.visible_text.clone());
+        }
+
+        let mut lamport_clock = self.lamport_clock;
+        let undo = UndoOperation {
+            timestamp: lamport_clock.tick(),
+            version: self.version(),
+            counts,
+        };
+        let mut snapshot = self.snapshot.clone();
+        snapshot.undo_fragments(&undo);
+        Some(snapshot.visible_text)
+    }
+
     fn undo_or_redo(&mut self, transaction: Transaction) -> Operation {
         let mut counts = HashMap::default();
         for edit_id in transaction.edit_ids {
@@ -1850,6 +2038,111 @@ impl Deref for Buffer {
 }
 
 impl BufferSnapshot {
+    fn fragment_ids_for_edits<'a>(
+        &'a self,
+        edit_ids: impl Iterator<Item = &'a clock::Lamport>,
+    ) -> Vec<&'a Locator> {
+        // Get all of the insertion slices changed by the given edits.
+        let mut insertion_slices = Vec::new();
+        for edit_id in edit_ids {
+            let insertion_slice = InsertionSlice {
+                edit_id: *edit_id,
+                insertion_id: clock::Lamport::default(),
+                range: 0..0,
+            };
+            let slices = self
+                .insertion_slices
+                .iter_from(&insertion_slice)
+                .take_while(|slice| slice.edit_id == *edit_id);
+            insertion_slices.extend(slices)
+        }
+        insertion_slices
+            .sort_unstable_by_key(|s| (s.insertion_id, s.range.start, Reverse(s.range.end)));
+
+        // Get all of the fragments corresponding to these insertion slices.
+        let mut fragment_ids = Vec::new();
+        let mut insertions_cursor = self.insertions.cursor::<InsertionFragmentKey>(&());
+        for insertion_slice in &insertion_slices {
+            if insertion_slice.insertion_id != insertions_cursor.start().timestamp
+                || insertion_slice.range.start > insertions_cursor.start().split_offset
+            {
+                insertions_cursor.seek_forward(
+                    &InsertionFragmentKey {
+                        timestamp: insertion_slice.insertion_id,
+                        split_offset: insertion_slice.range.start,
+                    },
+                    Bias::Left,
+                    &(),
+                );
+            }
+            while let Some(item) = insertions_cursor.item() {
+                if item.timestamp != insertion_slice.insertion_id
+                    || item.split_offset >= insertion_slice.range.end
+                {
+                    break;
+                }
+                fragment_ids.push(&item.fragment_id);
+                insertions_cursor.next(&());
+            }
+        }
+        fragment_ids.sort_unstable();
+        fragment_ids
+    }
+
+    /// Applies an undo operation to the fragments and text of the snapshot, returning the resulting edits.
+    fn undo_fragments(&mut self, undo: &UndoOperation) -> Patch<usize> {
+        self.undo_map.insert(undo);
+
+        let mut edits = Patch::default();
+        let mut old_fragments = self.fragments.cursor::<(Option<&Locator>, usize)>(&None);
+        let mut new_fragments = SumTree::new(&None);
+        let mut new_ropes =
+            RopeBuilder::new(self.visible_text.cursor(0), self.deleted_text.cursor(0));
+
+        for fragment_id in self.fragment_ids_for_edits(undo.counts.keys()) {
+            let preceding_fragments = old_fragments.slice(&Some(fragment_id), Bias::Left, &None);
+            new_ropes.append(preceding_fragments.summary().text);
+            new_fragments.append(preceding_fragments, &None);
+
+            if let Some(fragment) = old_fragments.item() {
+                let mut fragment = fragment.clone();
+                let fragment_was_visible = fragment.visible;
+
+                fragment.visible = fragment.is_visible(&self.undo_map);
+                fragment.max_undos.observe(undo.timestamp);
+
+                let old_start = old_fragments.start().1;
+                let new_start = new_fragments.summary().text.visible;
+                if fragment_was_visible && !fragment.visible {
+                    edits.push(Edit {
+                        old: old_start..old_start + fragment.len,
+                        new: new_start..new_start,
+                    });
+                } else if !fragment_was_visible && fragment.visible {
+                    edits.push(Edit {
+                        old: old_start..old_start,
+                        new: new_start..new_start + fragment.len,
+                    });
+                }
+                new_ropes.push_fragment(&fragment, fragment_was_visible);
+                new_fragments.push(fragment, &None);
+
+                old_fragments.next(&None);
+            }
+        }
+
+        let suffix = old_fragments.suffix(&None);
+        new_ropes.append(suffix.summary().text);
+        new_fragments.append(suffix, &None);
+
+        drop(old_fragments);
+        let (visible_text, deleted_text) = new_ropes.finish();
+        self.fragments = new_fragments;
+        self.visible_text = visible_text;
+        self.deleted_text = deleted_text;
+        edits
+    }
+
     pub fn as_rope(&self) -> &Rope {
         &self.visible_text
     }
@@ -0,0 +1,402 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use collections::HashMap;
+use editor::Editor;
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, SharedString,
+    Task, WeakEntity, Window, actions, rems,
+};
+use language::{Buffer, TransactionId, unified_diff};
+use picker::{Picker, PickerDelegate};
+use ui::{ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::ModalView;
+
+actions!(
+    undo_tree,
+    [
+        /// Shows the undo tree of the active buffer, previewing each of its states as a diff.
+        Toggle
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(UndoTreeView::register).detach();
+}
+
+pub fn toggle(editor: Entity<Editor>, window: &mut Window, cx: &mut App) {
+    let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+        return;
+    };
+    if let Some(workspace) = editor.read(cx).workspace() {
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                UndoTreeView::new(editor, buffer, window, cx)
+            });
+        })
+    }
+}
+
+pub struct UndoTreeView {
+    picker: Entity<Picker<UndoTreeDelegate>>,
+    preview: Option<String>,
+}
+
+impl Focusable for UndoTreeView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for UndoTreeView {}
+impl ModalView for UndoTreeView {}
+
+impl Render for UndoTreeView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let preview = self.preview.as_deref().unwrap_or_default();
+        h_flex()
+            .elevation_3(cx)
+            .w(rems(60.))
+            .max_h(vh(0.75, window))
+            .items_start()
+            .child(v_flex().w(rems(24.)).child(self.picker.clone()))
+            .child(
+                v_flex()
+                    .id("undo-tree-preview")
+                    .flex_1()
+                    .h_full()
+                    .max_h(vh(0.75, window))
+                    .overflow_y_scroll()
+                    .p_2()
+                    .border_l_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .when(preview.is_empty(), |this| {
+                        this.child(
+                            Label::new("No changes")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .children(preview.lines().map(|line| {
+                        let color = if line.starts_with("@@") {
+                            Color::Muted
+                        } else if line.starts_with('+') {
+                            Color::Created
+                        } else if line.starts_with('-') {
+                            Color::Deleted
+                        } else {
+                            Color::Default
+                        };
+                        Label::new(line.to_string())
+                            .buffer_font(cx)
+                            .size(LabelSize::Small)
+                            .color(color)
+                    })),
+            )
+    }
+}
+
+impl UndoTreeView {
+    fn register(editor: &mut Editor, _: Option<&mut Window>, cx: &mut Context<Editor>) {
+        if editor.mode().is_full() {
+            let handle = cx.entity().downgrade();
+            editor
+                .register_action(move |_: &Toggle, window, cx| {
+                    if let Some(editor) = handle.upgrade() {
+                        toggle(editor, window, cx);
+                    }
+                })
+                .detach();
+        }
+    }
+
+    fn new(
+        editor: Entity<Editor>,
+        buffer: Entity<Buffer>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = UndoTreeDelegate::new(cx.entity().downgrade(), editor, buffer, cx);
+        let picker = cx.new(|cx| {
+            Picker::uniform_list(delegate, window, cx)
+                .modal(false)
+                .max_height(Some(vh(0.75, window)))
+        });
+        Self {
+            picker,
+            preview: None,
+        }
+    }
+}
+
+/// A state of the buffer in the undo tree, as displayed in the list.
+struct UndoTreeEntry {
+    /// The transaction that produced the state, or `None` for the buffer's initial state.
+    transaction_id: Option<TransactionId>,
+    /// The position of the state in the order states were created, starting at 0 for the initial state.
+    sequence_number: usize,
+    /// The number of branches the state is nested in.
+    depth: usize,
+    edited_at: Option<Instant>,
+}
+
+struct UndoTreeDelegate {
+    undo_tree_view: WeakEntity<UndoTreeView>,
+    editor: Entity<Editor>,
+    buffer: Entity<Buffer>,
+    entries: Vec<UndoTreeEntry>,
+    current_transaction_id: Option<TransactionId>,
+    matches: Vec<usize>,
+    selected_index: usize,
+    preview_task: Task<()>,
+}
+
+impl UndoTreeDelegate {
+    fn new(
+        undo_tree_view: WeakEntity<UndoTreeView>,
+        editor: Entity<Editor>,
+        buffer: Entity<Buffer>,
+        cx: &App,
+    ) -> Self {
+        let buffer_ref = buffer.read(cx);
+        Self {
+            undo_tree_view,
+            entries: undo_tree_entries(buffer_ref),
+            current_transaction_id: buffer_ref.undo_tree_position(),
+            editor,
+            buffer,
+            matches: Vec::new(),
+            selected_index: 0,
+            preview_task: Task::ready(()),
+        }
+    }
+
+    fn update_preview(&mut self, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|ix| self.entries.get(*ix))
+        else {
+            return;
+        };
+        let buffer = self.buffer.read(cx);
+        let old_text = buffer.as_rope().clone();
+        let Some(new_text) = buffer.text_for_undo_tree_state(entry.transaction_id) else {
+            return;
+        };
+        let undo_tree_view = self.undo_tree_view.clone();
+        self.preview_task = cx.spawn(async move |_, cx| {
+            let diff = cx
+                .background_spawn(async move {
+                    unified_diff(&old_text.to_string(), &new_text.to_string())
+                })
+                .await;
+            undo_tree_view
+                .update(cx, |undo_tree_view, cx| {
+                    undo_tree_view.preview = Some(diff);
+                    cx.notify();
+                })
+                .log_err();
+        });
+    }
+}
+
+/// Lists the states of the buffer's undo tree depth-first, oldest first, so that each branch
+/// follows the state it branched from. Branches other than the first one are nested one level deeper.
+fn undo_tree_entries(buffer: &Buffer) -> Vec<UndoTreeEntry> {
+    let nodes = buffer.undo_tree();
+    let mut children = HashMap::<Option<TransactionId>, Vec<usize>>::default();
+    for (ix, node) in nodes.iter().enumerate() {
+        children.entry(node.parent).or_default().push(ix);
+    }
+
+    let mut entries = Vec::with_capacity(nodes.len() + 1);
+    let mut stack = vec![(None, 0)];
+    while let Some((node_ix, depth)) = stack.pop() {
+        let node = node_ix.map(|ix: usize| &nodes[ix]);
+        let transaction_id = node.map(|node| node.transaction_id);
+        entries.push(UndoTreeEntry {
+            transaction_id,
+            sequence_number: node_ix.map_or(0, |ix| ix + 1),
+            depth,
+            edited_at: node.map(|node| node.edited_at),
+        });
+        if let Some(child_ixs) = children.get(&transaction_id) {
+            for (child_ix, node_ix) in child_ixs.iter().enumerate().rev() {
+                let child_depth = if child_ix == 0 { depth } else { depth + 1 };
+                stack.push((Some(*node_ix), child_depth));
+            }
+        }
+    }
+    entries
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    if seconds < 60 {
+        format!("{seconds}s ago")
+    } else if seconds < 60 * 60 {
+        format!("{}m ago", seconds / 60)
+    } else if seconds < 24 * 60 * 60 {
+        format!("{}h ago", seconds / (60 * 60))
+    } else {
+        format!("{}d ago", seconds / (24 * 60 * 60))
+    }
+}
+
+impl PickerDelegate for UndoTreeDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Go to undo state...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+        self.update_preview(cx);
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let query = query.trim();
+        self.matches = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.sequence_number.to_string().starts_with(query))
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = self
+            .matches
+            .iter()
+            .position(|ix| self.entries[*ix].transaction_id == self.current_transaction_id)
+            .unwrap_or(0);
+        self.update_preview(cx);
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(entry) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|ix| self.entries.get(*ix))
+        {
+            let transaction_id = entry.transaction_id;
+            self.editor.update(cx, |editor, cx| {
+                editor.undo_tree_goto(transaction_id, window, cx);
+                window.focus(&editor.focus_handle(cx));
+            });
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.undo_tree_view
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = self.entries.get(*self.matches.get(ix)?)?;
+        let is_current = entry.transaction_id == self.current_transaction_id;
+        let label: SharedString = match entry.edited_at {
+            Some(edited_at) => format!(
+                "{} · {}",
+                entry.sequence_number,
+                format_elapsed(edited_at.elapsed())
+            )
+            .into(),
+            None => "0 · Original".into(),
+        };
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    h_flex()
+                        .pl(rems(entry.depth as f32))
+                        .gap_2()
+                        .child(
+                            Icon::new(if is_current {
+                                IconName::Check
+                            } else {
+                                IconName::Circle
+                            })
+                            .size(IconSize::Small)
+                            .color(if is_current {
+                                Color::Accent
+                            } else {
+                                Color::Muted
+                            }),
+                        )
+                        .child(Label::new(label)),
+                ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    fn test_undo_tree_entries(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("abc", cx));
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(3..3, "1")], None, cx);
+            buffer.finalize_last_transaction();
+            buffer.edit([(4..4, "2")], None, cx);
+            buffer.finalize_last_transaction();
+            buffer.undo(cx);
+            buffer.edit([(4..4, "3")], None, cx);
+            buffer.finalize_last_transaction();
+            buffer.edit([(5..5, "4")], None, cx);
+            buffer.finalize_last_transaction();
+        });
+
+        buffer.read_with(cx, |buffer, _| {
+            let entries = undo_tree_entries(buffer);
+            assert_eq!(
+                entries
+                    .iter()
+                    .map(|entry| (entry.sequence_number, entry.depth))
+                    .collect::<Vec<_>>(),
+                vec![(0, 0), (1, 0), (2, 0), (3, 1), (4, 1)]
+            );
+            assert_eq!(
+                buffer
+                    .text_for_undo_tree_state(entries[2].transaction_id)
+                    .map(|text| text.to_string()),
+                Some("abc12".to_string())
+            );
+        });
+    }
+}
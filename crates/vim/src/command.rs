@@ -23,7 +23,7 @@ use std::{
     process::Stdio,
     str::Chars,
     sync::{Arc, OnceLock},
-    time::Instant,
+    time::{Duration, Instant},
 };
 use task::{HideStrategy, RevealStrategy, SpawnInTerminal, TaskId};
 use ui::ActiveTheme;
@@ -209,6 +209,44 @@ struct VimEdit {
     pub filename: String,
 }
 
+/// Moves through the undo tree by a number of states or an amount of time, as with `:earlier`
+/// and `:later`.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+struct UndoTreeTravel {
+    later: bool,
+    steps: UndoTreeSteps,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum UndoTreeSteps {
+    Count(usize),
+    Duration(Duration),
+}
+
+impl UndoTreeSteps {
+    /// Parses the argument of `:earlier` and `:later`, which is either a count or a number
+    /// followed by `s`, `m`, `h` or `d`.
+    fn parse(args: &str) -> Option<Self> {
+        let unit_ix = args
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(args.len());
+        let (number, unit) = args.split_at(unit_ix);
+        let number = number.parse::<u64>().ok()?;
+        let seconds = match unit {
+            "" => return Some(Self::Count(number as usize)),
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            _ => return None,
+        };
+        Some(Self::Duration(Duration::from_secs(
+            number.checked_mul(seconds)?,
+        )))
+    }
+}
+
 #[derive(Debug)]
 struct WrappedAction(Box<dyn Action>);
 
@@ -447,6 +485,27 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
         });
     });
 
+    Vim::action(editor, cx, |vim, action: &UndoTreeTravel, window, cx| {
+        vim.update_editor(window, cx, |_, editor, window, cx| match action.steps {
+            UndoTreeSteps::Count(count) => {
+                for _ in 0..count {
+                    if action.later {
+                        editor.undo_tree_newer(&editor::actions::UndoTreeNewer, window, cx);
+                    } else {
+                        editor.undo_tree_older(&editor::actions::UndoTreeOlder, window, cx);
+                    }
+                }
+            }
+            UndoTreeSteps::Duration(duration) => {
+                if action.later {
+                    editor.undo_tree_later(duration, window, cx);
+                } else {
+                    editor.undo_tree_earlier(duration, window, cx);
+                }
+            }
+        });
+    });
+
     Vim::action(editor, cx, |vim, _: &CountCommand, window, cx| {
         let Some(workspace) = vim.workspace(window) else {
             return;
@@ -1103,31 +1162,24 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
         .bang(workspace::CloseInactiveTabsAndPanes {
             save_intent: Some(SaveIntent::Skip),
         }),
-        VimCommand::str(("cl", "ist"), "diagnostics::Deploy"),
-        VimCommand::new(("cc", ""), editor::actions::Hover),
-        VimCommand::new(("ll", ""), editor::actions::Hover),
-        VimCommand::new(("cn", "ext"), editor::actions::GoToDiagnostic::default())
-            .range(wrap_count),
-        VimCommand::new(
-            ("cp", "revious"),
-            editor::actions::GoToPreviousDiagnostic::default(),
-        )
-        .range(wrap_count),
-        VimCommand::new(
-            ("cN", "ext"),
-            editor::actions::GoToPreviousDiagnostic::default(),
-        )
-        .range(wrap_count),
-        VimCommand::new(
-            ("lp", "revious"),
-            editor::actions::GoToPreviousDiagnostic::default(),
-        )
-        .range(wrap_count),
-        VimCommand::new(
-            ("lN", "ext"),
-            editor::actions::GoToPreviousDiagnostic::default(),
-        )
-        .range(wrap_count),
+        VimCommand::str(("cl", "ist"), "location_list::Deploy"),
+        VimCommand::str(("cope", "n"), "location_list::Deploy"),
+        VimCommand::str(("lli", "st"), "location_list::Deploy"),
+        VimCommand::str(("lope", "n"), "location_list::Deploy"),
+        VimCommand::str(("cc", ""), "location_list::OpenCurrent"),
+        VimCommand::str(("ll", ""), "location_list::OpenCurrent"),
+        VimCommand::str(("cn", "ext"), "location_list::Next").range(wrap_count),
+        VimCommand::str(("ln", "ext"), "location_list::Next").range(wrap_count),
+        VimCommand::str(("cp", "revious"), "location_list::Previous").range(wrap_count),
+        VimCommand::str(("cN", "ext"), "location_list::Previous").range(wrap_count),
+        VimCommand::str(("lp", "revious"), "location_list::Previous").range(wrap_count),
+        VimCommand::str(("lN", "ext"), "location_list::Previous").range(wrap_count),
+        VimCommand::str(("col", "der"), "location_list::Older").range(wrap_count),
+        VimCommand::str(("cnew", "er"), "location_list::Newer").range(wrap_count),
+        VimCommand::str(("lol", "der"), "location_list::Older").range(wrap_count),
+        VimCommand::str(("lnew", "er"), "location_list::Newer").range(wrap_count),
+        VimCommand::str(("cf", "ile"), "location_list::OpenFile"),
+        VimCommand::str(("lf", "ile"), "location_list::OpenFile"),
         VimCommand::new(("j", "oin"), JoinLines).range(select_range),
         VimCommand::new(("fo", "ld"), editor::actions::FoldSelectedRanges).range(act_on_range),
         VimCommand::new(("foldo", "pen"), editor::actions::UnfoldLines)
@@ -1181,6 +1233,39 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
         VimCommand::str(("opt", "ions"), "zed::OpenDefaultSettings"),
         VimCommand::str(("map", ""), "vim::OpenDefaultKeymap"),
         VimCommand::new(("h", "elp"), OpenDocs),
+        VimCommand::new(
+            ("ea", "rlier"),
+            UndoTreeTravel {
+                later: false,
+                steps: UndoTreeSteps::Count(1),
+            },
+        )
+        .args(|_, args| {
+            Some(
+                UndoTreeTravel {
+                    later: false,
+                    steps: UndoTreeSteps::parse(&args)?,
+                }
+                .boxed_clone(),
+            )
+        }),
+        VimCommand::new(
+            ("lat", "er"),
+            UndoTreeTravel {
+                later: true,
+                steps: UndoTreeSteps::Count(1),
+            },
+        )
+        .args(|_, args| {
+            Some(
+                UndoTreeTravel {
+                    later: true,
+                    steps: UndoTreeSteps::parse(&args)?,
+                }
+                .boxed_clone(),
+            )
+        }),
+        VimCommand::str(("undol", "ist"), "undo_tree::Toggle"),
     ]
 }
 
@@ -1767,6 +1852,8 @@ impl ShellExec {
                     show_summary: false,
                     show_command: false,
                     show_rerun: false,
+                    test_results: None,
+                    problem_matchers: Vec::new(),
                 };
 
                 let task_status = workspace.spawn_in_terminal(spawn_in_terminal, window, cx);
@@ -2298,4 +2385,40 @@ mod test {
         });
         assert!(mark.is_none())
     }
+
+    #[gpui::test]
+    async fn test_undo_tree_commands(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone", Mode::Normal);
+        cx.simulate_keystrokes("x x u");
+        assert_eq!(cx.buffer_text(), "ne");
+        cx.simulate_keystrokes("i X escape");
+        assert_eq!(cx.buffer_text(), "Xne");
+
+        // The state left by the undone change is still reachable.
+        cx.simulate_keystrokes("g -");
+        assert_eq!(cx.buffer_text(), "e");
+        cx.simulate_keystrokes("g -");
+        assert_eq!(cx.buffer_text(), "ne");
+        cx.simulate_keystrokes("g +");
+        assert_eq!(cx.buffer_text(), "e");
+
+        cx.simulate_keystrokes(": l a t e r enter");
+        assert_eq!(cx.buffer_text(), "Xne");
+        cx.simulate_keystrokes(": e a r l i e r space 3 enter");
+        assert_eq!(cx.buffer_text(), "one");
+        cx.simulate_keystrokes(": l a t e r space 1 h enter");
+        assert_eq!(cx.buffer_text(), "Xne");
+
+        // Amounts of time too large to travel by are ignored.
+        cx.simulate_keystrokes(": l a t e r space 1 8 4 4 6 7 4 4 0 7 3 7 0 9 5 5 1 6 1 5 s enter");
+        assert_eq!(cx.buffer_text(), "Xne");
+        cx.simulate_keystrokes(
+            ": e a r l i e r space 1 8 4 4 6 7 4 4 0 7 3 7 0 9 5 5 1 6 1 5 s enter",
+        );
+        assert_eq!(cx.buffer_text(), "Xne");
+        cx.simulate_keystrokes(": e a r l i e r space 9 9 9 9 9 9 9 9 9 9 9 9 9 9 9 9 9 9 d enter");
+        assert_eq!(cx.buffer_text(), "Xne");
+    }
 }
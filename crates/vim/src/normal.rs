@@ -26,7 +26,7 @@ use collections::BTreeSet;
 use convert::ConvertTarget;
 use editor::Editor;
 use editor::{Anchor, SelectionEffects};
-use editor::{Bias, ToPoint};
+use editor::{Bias, BlockSelection, DisplayPoint, ToPoint};
 use editor::{display_map::ToDisplayPoint, movement};
 use gpui::{Context, Window, actions};
 use language::{Point, SelectionGoal};
@@ -92,6 +92,10 @@ actions!(
         Undo,
         /// Redoes the last undone change.
         Redo,
+        /// Moves to the previous text state chronologically, across undo branches.
+        UndoTreeOlder,
+        /// Moves to the next text state chronologically, across undo branches.
+        UndoTreeNewer,
         /// Undoes all changes to the most recently changed line.
         UndoLastLine,
     ]
@@ -213,6 +217,24 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
             }
         });
     });
+    Vim::action(editor, cx, |vim, _: &UndoTreeOlder, window, cx| {
+        let times = Vim::take_count(cx);
+        Vim::take_forced_motion(cx);
+        vim.update_editor(window, cx, |_, editor, window, cx| {
+            for _ in 0..times.unwrap_or(1) {
+                editor.undo_tree_older(&editor::actions::UndoTreeOlder, window, cx);
+            }
+        });
+    });
+    Vim::action(editor, cx, |vim, _: &UndoTreeNewer, window, cx| {
+        let times = Vim::take_count(cx);
+        Vim::take_forced_motion(cx);
+        vim.update_editor(window, cx, |_, editor, window, cx| {
+            for _ in 0..times.unwrap_or(1) {
+                editor.undo_tree_newer(&editor::actions::UndoTreeNewer, window, cx);
+            }
+        });
+    });
     Vim::action(editor, cx, |vim, _: &UndoLastLine, window, cx| {
         Vim::take_forced_motion(cx);
         vim.update_editor(window, cx, |vim, editor, window, cx| {
@@ -545,10 +567,35 @@ impl Vim {
 
     fn insert_after(&mut self, _: &InsertAfter, window: &mut Window, cx: &mut Context<Self>) {
         self.start_recording(cx);
+        let block = self
+            .update_editor(window, cx, |vim, editor, _, _| {
+                editor
+                    .selections
+                    .block
+                    .filter(|_| vim.mode == Mode::VisualBlock)
+            })
+            .flatten();
         self.switch_mode(Mode::Insert, false, window, cx);
         self.update_editor(window, cx, |_, editor, window, cx| {
             editor.change_selections(Default::default(), window, cx, |s| {
-                s.move_cursors_with(|map, cursor, _| (right(map, cursor, 1), SelectionGoal::None));
+                // Like Vim, append after the block on every line it spans, padding the lines
+                // ending before it with spaces, or at the end of every line after `$`.
+                if let Some(block) = block {
+                    let column = if block.to_line_end {
+                        u32::MAX
+                    } else {
+                        block.columns().end
+                    };
+                    s.select_block(BlockSelection {
+                        tail: DisplayPoint::new(*block.rows().start(), column),
+                        head: DisplayPoint::new(*block.rows().end(), column),
+                        to_line_end: block.to_line_end,
+                    });
+                } else {
+                    s.move_cursors_with(|map, cursor, _| {
+                        (right(map, cursor, 1), SelectionGoal::None)
+                    });
+                }
             });
         });
     }
@@ -198,6 +198,7 @@ impl Vim {
                     len: text.len() - initial_len,
                     is_entire_line: false,
                     first_line_indent: buffer.indent_size_for_line(MultiBufferRow(start.row)).len,
+                    is_block: false,
                 });
             }
         }
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use collections::HashMap;
 use editor::{
-    Bias, DisplayPoint, Editor, SelectionEffects,
+    Bias, BlockSelection, DisplayPoint, Editor, SelectionEffects,
     display_map::{DisplaySnapshot, ToDisplayPoint},
     movement,
 };
@@ -204,6 +204,10 @@ impl Vim {
                     motion.move_point(map, point, goal, times, &text_layout_details)
                 })
             } else {
+                let block = editor
+                    .selections
+                    .block
+                    .filter(|_| vim.mode == Mode::VisualBlock);
                 editor.change_selections(Default::default(), window, cx, |s| {
                     s.move_with(|map, selection| {
                         let was_reversed = selection.reversed;
@@ -257,7 +261,17 @@ impl Vim {
                         } else if !was_reversed && selection.reversed {
                             selection.end = movement::right(map, selection.end);
                         }
-                    })
+                    });
+
+                    // After `$`, the block extends to the end of every line it spans, including
+                    // the ones ending before its start.
+                    if let Some(block) = block {
+                        s.set_block(BlockSelection {
+                            tail: DisplayPoint::new(block.tail.row(), block.columns().start),
+                            head: DisplayPoint::new(block.head.row(), u32::MAX),
+                            to_line_end: true,
+                        });
+                    }
                 });
             }
         });
@@ -338,6 +352,7 @@ impl Vim {
             }
 
             let mut selections = Vec::new();
+            let mut columns = u32::MAX..0;
             let mut row = tail.row();
 
             loop {
@@ -357,6 +372,8 @@ impl Vim {
                 }
 
                 if positions.start <= laid_out_line.width {
+                    columns.start = columns.start.min(start.column());
+                    columns.end = columns.end.max(end.column());
                     let selection = Selection {
                         id: s.new_selection_id(),
                         start: start.to_point(map),
@@ -379,6 +396,17 @@ impl Vim {
                 }
             }
             s.select(selections);
+            if columns.start <= columns.end {
+                let (tail_column, head_column) = if is_reversed {
+                    (columns.end, columns.start)
+                } else {
+                    (columns.start, columns.end)
+                };
+                s.set_block(BlockSelection::new(
+                    DisplayPoint::new(tail.row(), tail_column),
+                    DisplayPoint::new(head.row(), head_column),
+                ));
+            }
         })
     }
 
@@ -1473,6 +1501,45 @@ mod test {
         });
     }
 
+    #[gpui::test]
+    async fn test_visual_block_append(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        // Lines ending before the block are padded up to its end.
+        cx.set_state(
+            indoc! {"
+                Theˇ quick
+                fox
+                the lazy dog"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("l ctrl-v j j shift-a ,");
+        cx.assert_state(
+            indoc! {"
+                The q,ˇuick
+                fox  ,ˇ
+                the l,ˇazy dog"},
+            Mode::Insert,
+        );
+
+        // After `$`, text is appended to the end of every line.
+        cx.set_state(
+            indoc! {"
+                Theˇ quick
+                fox
+                the lazy dog"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("l ctrl-v j j $ shift-a !");
+        cx.assert_state(
+            indoc! {"
+                The quick!ˇ
+                fox!ˇ
+                the lazy dog!ˇ"},
+            Mode::Insert,
+        );
+    }
+
     #[gpui::test]
     async fn test_visual_object(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -39,6 +39,10 @@ pub struct SearchOptions {
     pub case: bool,
     pub word: bool,
     pub regex: bool,
+    /// Specifies whether the item can be searched for syntax patterns.
+    pub structural: bool,
+    /// Specifies whether matches can be restricted to comments, strings or code.
+    pub syntax: bool,
     /// Specifies whether the  supports search & replace.
     pub replacement: bool,
     pub selection: bool,
@@ -53,6 +57,8 @@ pub trait SearchableItem: Item + EventEmitter<SearchEvent> {
             case: true,
             word: true,
             regex: true,
+            structural: false,
+            syntax: false,
             replacement: true,
             selection: true,
             find_in_results: false,
@@ -1,14 +1,23 @@
-use std::process::ExitStatus;
+use std::{cell::RefCell, process::ExitStatus, rc::Rc, sync::Arc};
 
-use anyhow::Result;
-use gpui::{AppContext, Context, Entity, Task};
+use anyhow::{Result, anyhow};
+use collections::HashMap;
+use futures::{
+    FutureExt as _, future,
+    future::{LocalBoxFuture, Shared},
+};
+use gpui::{AppContext, AsyncWindowContext, Context, Entity, Task, WeakEntity};
 use language::Buffer;
 use project::{TaskSourceKind, WorktreeId};
 use remote::ConnectionState;
-use task::{DebugScenario, ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
+use task::{
+    DebugScenario, DependsOrder, Problem, ResolvedTask, SpawnInTerminal, TaskContext, TaskGraph,
+    TaskId, TaskTemplate, match_problems,
+};
 use ui::Window;
+use util::ResultExt as _;
 
-use crate::Workspace;
+use crate::{Event, Workspace};
 
 impl Workspace {
     pub fn schedule_task(
@@ -55,6 +64,12 @@ impl Workspace {
         cx: &mut Context<Workspace>,
     ) {
         let spawn_in_terminal = resolved_task.resolved.clone();
+        let task_with_dependencies =
+            (!resolved_task.original_task().depends_on.is_empty()).then(|| resolved_task.clone());
+        let worktree_id = match &task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => None,
+        };
         if !omit_history {
             if let Some(debugger_provider) = self.debugger_provider.as_ref() {
                 debugger_provider.task_scheduled(cx);
@@ -71,22 +86,73 @@ impl Workspace {
             });
         }
 
-        if let Some(terminal_provider) = self.terminal_provider.as_ref() {
-            let task_status = terminal_provider.spawn(spawn_in_terminal, window, cx);
-            cx.background_spawn(async move {
-                match task_status.await {
-                    Some(Ok(status)) => {
-                        if status.success() {
-                            log::debug!("Task spawn succeeded");
-                        } else {
-                            log::debug!("Task spawn failed, code: {:?}", status.code());
-                        }
-                    }
-                    Some(Err(e)) => log::error!("Task spawn failed: {e}"),
-                    None => log::debug!("Task spawn got cancelled"),
+        if let Some(task) = task_with_dependencies {
+            self.schedule_task_with_dependencies(task, worktree_id, window, cx);
+        } else {
+            let task_status = self.spawn_resolved_task(spawn_in_terminal, window, cx);
+            cx.background_spawn(log_task_status(task_status)).detach();
+        }
+    }
+
+    /// Runs the task after the tasks it depends on, which are looked up by label among the worktree's and global tasks.
+    /// A task that several others depend on runs once, and no task runs after any of its dependencies fails.
+    fn schedule_task_with_dependencies(
+        &mut self,
+        task: ResolvedTask,
+        worktree_id: Option<WorktreeId>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let project = self.project.read(cx);
+        let Some(task_inventory) = project.task_store().read(cx).task_inventory().cloned() else {
+            return;
+        };
+        let worktree_id = worktree_id.or_else(|| {
+            project
+                .visible_worktrees(cx)
+                .next()
+                .map(|worktree| worktree.read(cx).id())
+        });
+        let templates = task_inventory
+            .read(cx)
+            .list_tasks(None, None, worktree_id, cx);
+        cx.spawn_in(window, async move |workspace, cx| {
+            let templates = templates
+                .await
+                .into_iter()
+                .map(|(kind, template)| (kind.to_id_base(), template))
+                .collect::<Vec<_>>();
+            let result = match TaskGraph::resolve(task, &templates) {
+                Ok(task_graph) => {
+                    run_task_graph(workspace.clone(), task_graph, &Rc::default(), cx.clone())
+                        .await
+                        .map_err(|e| anyhow!("{e:#}"))
                 }
-            })
-            .detach();
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                log::error!("{e:#}");
+                workspace
+                    .update(cx, |workspace, cx| workspace.show_error(&e, cx))
+                    .ok();
+            }
+        })
+        .detach();
+    }
+
+    /// Spawns the task in a terminal, processing its output if it needs to, and returns its exit status.
+    fn spawn_resolved_task(
+        &mut self,
+        spawn_in_terminal: SpawnInTerminal,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Option<Result<ExitStatus>>> {
+        if spawn_in_terminal.test_results.is_some()
+            || !spawn_in_terminal.problem_matchers.is_empty()
+        {
+            self.spawn_task_processing_output(spawn_in_terminal, window, cx)
+        } else {
+            self.spawn_in_terminal(spawn_in_terminal, window, cx)
         }
     }
 
@@ -111,6 +177,84 @@ impl Workspace {
         }
     }
 
+    /// Spawns a task whose output is used once it finishes: turned into diagnostics with its problem matchers,
+    /// and if it reports test results, emitted with [`Event::TestTaskFinished`].
+    pub fn spawn_task_with_output(
+        self: &mut Workspace,
+        spawn_in_terminal: SpawnInTerminal,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let task_status = self.spawn_task_processing_output(spawn_in_terminal, window, cx);
+        cx.background_spawn(log_task_status(task_status)).detach();
+    }
+
+    fn spawn_task_processing_output(
+        &mut self,
+        spawn_in_terminal: SpawnInTerminal,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Option<Result<ExitStatus>>> {
+        let Some(terminal_provider) = self.terminal_provider.as_ref() else {
+            return Task::ready(None);
+        };
+        let task_output =
+            terminal_provider.spawn_with_output(spawn_in_terminal.clone(), window, cx);
+        let matches_problems =
+            !spawn_in_terminal.problem_matchers.is_empty() && self.project.read(cx).is_local();
+        if matches_problems {
+            self.update_task_diagnostics(&spawn_in_terminal, Vec::new(), cx);
+        }
+        cx.spawn(async move |workspace, cx| match task_output.await {
+            Some(Ok((exit_status, output))) => {
+                let problems = if matches_problems {
+                    let problem_matchers = spawn_in_terminal.problem_matchers.clone();
+                    let output = output.clone();
+                    cx.background_spawn(async move { match_problems(&problem_matchers, &output) })
+                        .await
+                } else {
+                    Vec::new()
+                };
+                workspace
+                    .update(cx, |workspace, cx| {
+                        if matches_problems {
+                            workspace.update_task_diagnostics(&spawn_in_terminal, problems, cx);
+                        }
+                        if spawn_in_terminal.test_results.is_some() {
+                            cx.emit(Event::TestTaskFinished {
+                                task: spawn_in_terminal,
+                                exit_status,
+                                output,
+                            })
+                        }
+                    })
+                    .ok();
+                Some(Ok(exit_status))
+            }
+            Some(Err(e)) => Some(Err(e)),
+            None => None,
+        })
+    }
+
+    fn update_task_diagnostics(
+        &mut self,
+        spawn_in_terminal: &SpawnInTerminal,
+        problems: Vec<Problem>,
+        cx: &mut Context<Workspace>,
+    ) {
+        let lsp_store = self.project.read(cx).lsp_store();
+        lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store
+                .update_task_diagnostics(
+                    &spawn_in_terminal.label,
+                    spawn_in_terminal.cwd.as_deref(),
+                    problems,
+                    cx,
+                )
+                .log_err();
+        });
+    }
+
     pub fn spawn_in_terminal(
         self: &mut Workspace,
         spawn_in_terminal: SpawnInTerminal,
@@ -124,3 +268,79 @@ impl Workspace {
         }
     }
 }
+
+async fn log_task_status(task_status: Task<Option<Result<ExitStatus>>>) {
+    match task_status.await {
+        Some(Ok(status)) => {
+            if status.success() {
+                log::debug!("Task spawn succeeded");
+            } else {
+                log::debug!("Task spawn failed, code: {:?}", status.code());
+            }
+        }
+        Some(Err(e)) => log::error!("Task spawn failed: {e}"),
+        None => log::debug!("Task spawn got cancelled"),
+    }
+}
+
+/// A run of a task in a graph, shared by all the tasks that depend on it.
+type SharedTaskRun = Shared<LocalBoxFuture<'static, Result<(), Arc<anyhow::Error>>>>;
+
+/// Runs the dependencies of the task in the graph, then the task itself if they all succeed.
+/// Tasks already started for another part of the graph, keyed by their id in `runs`, aren't run again.
+fn run_task_graph(
+    workspace: WeakEntity<Workspace>,
+    task_graph: TaskGraph,
+    runs: &Rc<RefCell<HashMap<TaskId, SharedTaskRun>>>,
+    mut cx: AsyncWindowContext,
+) -> SharedTaskRun {
+    if let Some(run) = runs.borrow().get(&task_graph.task.id) {
+        return run.clone();
+    }
+
+    let TaskGraph {
+        task,
+        order,
+        dependencies,
+    } = task_graph;
+    let id = task.id.clone();
+    let dependencies = dependencies
+        .into_iter()
+        .map(|dependency| run_task_graph(workspace.clone(), dependency, runs, cx.clone()))
+        .collect::<Vec<_>>();
+    let run = async move {
+        // A failed dependency fails its dependents before they start, and dropping the
+        // remaining runs of a parallel group keeps their dependents from starting.
+        match order {
+            DependsOrder::Sequence => {
+                for dependency in dependencies {
+                    dependency.await?;
+                }
+            }
+            DependsOrder::Parallel => {
+                future::try_join_all(dependencies).await?;
+            }
+        }
+        if task.original_task().is_compound() {
+            return Ok(());
+        }
+
+        let label = task.display_label().to_string();
+        let task_status = workspace
+            .update_in(&mut cx, |workspace, window, cx| {
+                workspace.spawn_resolved_task(task.resolved, window, cx)
+            })
+            .map_err(Arc::new)?;
+        match task_status.await {
+            Some(Ok(status)) if status.success() => Ok(()),
+            Some(Ok(status)) => Err(anyhow!("Task `{label}` failed with {status}")),
+            Some(Err(e)) => Err(e.context(format!("Failed to spawn task `{label}`"))),
+            None => Err(anyhow!("Task `{label}` got cancelled")),
+        }
+        .map_err(Arc::new)
+    }
+    .boxed_local()
+    .shared();
+    runs.borrow_mut().insert(id, run.clone());
+    run
+}
@@ -137,6 +137,14 @@ pub trait TerminalProvider {
         window: &mut Window,
         cx: &mut App,
     ) -> Task<Option<Result<ExitStatus>>>;
+
+    /// Spawns the task like [`TerminalProvider::spawn`], also returning the contents of its terminal once it finishes.
+    fn spawn_with_output(
+        &self,
+        task: SpawnInTerminal,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Option<Result<(ExitStatus, String)>>>;
 }
 
 pub trait DebuggerProvider {
@@ -1006,6 +1014,11 @@ pub enum Event {
     ZoomChanged,
     ModalOpened,
     ClearActivityIndicator,
+    TestTaskFinished {
+        task: SpawnInTerminal,
+        exit_status: ExitStatus,
+        output: String,
+    },
 }
 
 #[derive(Debug)]
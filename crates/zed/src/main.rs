@@ -596,6 +596,7 @@ pub fn main() {
         file_finder::init(cx);
         tab_switcher::init(cx);
         outline::init(cx);
+        undo_tree::init(cx);
         project_symbols::init(cx);
         project_panel::init(cx);
         outline_panel::init(cx);
@@ -610,6 +611,8 @@ pub fn main() {
         toolchain_selector::init(cx);
         theme_selector::init(cx);
         language_tools::init(cx);
+        log_viewer::init(cx);
+        location_list::init(cx);
         call::init(app_state.client.clone(), app_state.user_store.clone(), cx);
         notifications::init(app_state.client.clone(), app_state.user_store.clone(), cx);
         collab_ui::init(&app_state, cx);
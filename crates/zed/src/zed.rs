@@ -31,6 +31,7 @@ use gpui::{
 };
 use image_viewer::ImageInfo;
 use language_tools::lsp_tool::{self, LspTool};
+use location_list::LocationListPanel;
 use migrate::{MigrationBanner, MigrationEvent, MigrationNotification, MigrationType};
 use migrator::{migrate_keymap, migrate_settings};
 pub use open_listener::*;
@@ -56,6 +57,7 @@ use settings::{
 use std::path::PathBuf;
 use std::sync::atomic::{self, AtomicBool};
 use std::{borrow::Cow, path::Path, sync::Arc};
+use tasks_ui::test_results_panel::TestResultsPanel;
 use terminal_view::terminal_panel::{self, TerminalPanel};
 use theme::{ActiveTheme, ThemeSettings};
 use ui::{PopoverMenuHandle, prelude::*};
@@ -338,6 +340,7 @@ pub fn initialize_workspace(
         let active_toolchain_language =
             cx.new(|cx| toolchain_selector::ActiveToolchain::new(workspace, window, cx));
         let vim_mode_indicator = cx.new(|cx| vim::ModeIndicator::new(window, cx));
+        let large_file_indicator = cx.new(|_| editor::LargeFileIndicator::new());
         let image_info = cx.new(|_cx| ImageInfo::new(workspace));
 
         let lsp_tool_menu_handle = PopoverMenuHandle::default();
@@ -356,6 +359,7 @@ pub fn initialize_workspace(
             status_bar.add_left_item(lsp_tool, window, cx);
             status_bar.add_left_item(diagnostic_summary, window, cx);
             status_bar.add_left_item(activity_indicator, window, cx);
+            status_bar.add_right_item(large_file_indicator, window, cx);
             status_bar.add_right_item(edit_prediction_button, window, cx);
             status_bar.add_right_item(active_buffer_language, window, cx);
             status_bar.add_right_item(active_toolchain_language, window, cx);
@@ -503,7 +507,9 @@ fn initialize_panels(
             workspace_handle.clone(),
             cx.clone(),
         );
+        let test_results_panel = TestResultsPanel::load(workspace_handle.clone(), cx);
         let debug_panel = DebugPanel::load(workspace_handle.clone(), cx);
+        let location_list_panel = LocationListPanel::load(workspace_handle.clone(), cx);
 
         let (
             project_panel,
@@ -514,6 +520,8 @@ fn initialize_panels(
             chat_panel,
             notification_panel,
             debug_panel,
+            test_results_panel,
+            location_list_panel,
         ) = futures::try_join!(
             project_panel,
             outline_panel,
@@ -523,6 +531,8 @@ fn initialize_panels(
             chat_panel,
             notification_panel,
             debug_panel,
+            test_results_panel,
+            location_list_panel,
         )?;
 
         workspace_handle.update_in(cx, |workspace, window, cx| {
@@ -534,6 +544,8 @@ fn initialize_panels(
             workspace.add_panel(chat_panel, window, cx);
             workspace.add_panel(notification_panel, window, cx);
             workspace.add_panel(debug_panel, window, cx);
+            workspace.add_panel(test_results_panel, window, cx);
+            workspace.add_panel(location_list_panel, window, cx);
         })?;
 
         let is_assistant2_enabled = !cfg!(test);
@@ -4329,6 +4341,8 @@ mod tests {
                 "keymap_editor",
                 "keystroke_input",
                 "language_selector",
+                "location_list",
+                "log_viewer",
                 "lsp_tool",
                 "markdown",
                 "menu",
@@ -4356,6 +4370,7 @@ mod tests {
                 "theme_selector",
                 "toast",
                 "toolchain",
+                "undo_tree",
                 "variable_list",
                 "vim",
                 "welcome",